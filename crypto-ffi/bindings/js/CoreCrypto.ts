@@ -200,8 +200,8 @@ export enum WirePolicy {
  */
 export interface CustomConfiguration {
     /**
-     * Duration in seconds after which we will automatically force a self_update commit
-     * Note: This isn't currently implemented
+     * Duration in seconds after which the own LeafNode has to be updated.
+     * See {@link CoreCrypto.conversationsDueForKeyRotation}
      */
     keyRotationSpan?: number;
     /**
@@ -342,11 +342,14 @@ export enum RatchetTreeType {
      */
     Full = 0x01,
     /**
-     * Contains the difference since previous epoch (not yet implemented)
+     * Contains the difference since previous epoch. Rebuild the complete GroupInfo with {@link CoreCrypto.rebuildGroupInfo}.
+     * The encoding is versioned by {@link CoreCrypto.ratchetTreeDeltaVersion}
      */
     Delta = 0x02,
     /**
-     * To define (not yet implemented)
+     * GroupInfo without ratchet tree, which is served separately. Get it with {@link CoreCrypto.exportRatchetTree}
+     * and supply it when joining, where it is checked against the tree hash of the GroupInfo. Only the conversations
+     * created or joined once it is configured leave the ratchet tree out, including in their Welcome messages
      */
     ByRef = 0x03,
 }
@@ -495,6 +498,11 @@ export interface DecryptedMessage {
      * Client identifier of the sender of the message being decrypted. Only present for application messages.
      */
     senderClientId?: ClientId;
+    /**
+     * Additional authenticated data bound to the message by its sender, see {@link CoreCrypto.encryptMessageWithAad}.
+     * Only present for application messages.
+     */
+    aad?: Uint8Array;
     /**
      * true when the decrypted message resulted in an epoch change i.e. it was a commit
      */
@@ -515,6 +523,25 @@ export interface DecryptedMessage {
      * New CRL distribution points that appeared by the introduction of a new credential
      */
     crlNewDistributionPoints?: string[];
+    /**
+     * Only set when the decrypted message is a commit ending the conversation with a ReInit proposal. Its members are
+     * expected to move to the successor, see {@link CoreCrypto.joinReinitSuccessor}
+     */
+    reinit?: ReInit;
+}
+
+/**
+ * Parameters of the conversation replacing one ended by a ReInit proposal
+ */
+export interface ReInit {
+    /**
+     * Identifier of the successor conversation
+     */
+    groupId: ConversationId;
+    /**
+     * Ciphersuite of the successor conversation
+     */
+    ciphersuite: Ciphersuite;
 }
 
 /**
@@ -541,6 +568,10 @@ export interface BufferedDecryptedMessage {
      * see {@link DecryptedMessage.senderClientId}
      */
     senderClientId?: ClientId;
+    /**
+     * see {@link DecryptedMessage.aad}
+     */
+    aad?: Uint8Array;
     /**
      * see {@link DecryptedMessage.hasEpochChanged}
      */
@@ -553,6 +584,155 @@ export interface BufferedDecryptedMessage {
      * see {@link DecryptedMessage.crlNewDistributionPoints}
      */
     crlNewDistributionPoints?: string[];
+    /**
+     * see {@link DecryptedMessage.reinit}
+     */
+    reinit?: ReInit;
+}
+
+/**
+ * Outcome of decrypting one message of a batch, see {@link CoreCrypto.decryptMessages}
+ */
+export interface BatchDecryptedMessage {
+    /**
+     * The decrypted message, when decryption succeeded
+     */
+    message?: DecryptedMessage;
+    /**
+     * The error which prevented this message from being decrypted, if any
+     */
+    error?: CoreCryptoError | Error;
+}
+
+/**
+ * Whether a member is part of the current epoch and whether pending proposals change that
+ */
+export enum MemberStatus {
+    /**
+     * Member of the current epoch
+     */
+    Active = 1,
+    /**
+     * Member of the current epoch whom a pending proposal removes
+     */
+    PendingRemoval = 2,
+    /**
+     * Added by a pending proposal, not a member of the current epoch yet
+     */
+    PendingAddition = 3,
+}
+
+/**
+ * A member of a conversation, see {@link CoreCrypto.getConversationRoster}
+ */
+export interface ConversationMember {
+    /**
+     * Identifier of the client
+     */
+    clientId: ClientId;
+    /**
+     * Position of the member in the ratchet tree. Not set for pending additions
+     */
+    leafIndex?: number;
+    /**
+     * Type of the member's Credential
+     */
+    credentialType: CredentialType;
+    /**
+     * Public key the member signs its messages with
+     */
+    signatureKey: Uint8Array;
+    /**
+     * Ciphersuites supported by the member
+     */
+    ciphersuites: number[];
+    /**
+     * Extension types supported by the member on top of the default ones
+     */
+    extensions: number[];
+    /**
+     * Epoch in which the member was added. Not set when this happened before we joined the conversation
+     * or for pending additions
+     */
+    addedInEpoch?: number;
+    /**
+     * Whether the member is part of the current epoch
+     */
+    status: MemberStatus;
+}
+
+/**
+ * A message received for a future epoch, buffered until the commit for that epoch arrives.
+ * See {@link CoreCrypto.listPendingMessages}
+ */
+export interface BufferedMessage {
+    /**
+     * Conversation the message belongs to
+     */
+    conversationId: ConversationId;
+    /**
+     * Epoch the message was sent in
+     */
+    epoch: number;
+    /**
+     * Unix timestamp in seconds at which the message was buffered
+     */
+    receivedAt: number;
+    /**
+     * The message, as received from the Delivery Service
+     */
+    message: Uint8Array;
+}
+
+/**
+ * An application message of a conversation shared with devices joining it later.
+ * See {@link CoreCrypto.importHistory}
+ */
+export interface HistoryMessage {
+    /**
+     * Epoch the message was sent in
+     */
+    epoch: number;
+    /**
+     * Client ID of the sender of the message. Only as trustworthy as the member who shared the archive
+     */
+    senderClientId: ClientId;
+    /**
+     * Unix timestamp in seconds at which the message was recorded
+     */
+    receivedAt: number;
+    /**
+     * The decrypted message
+     */
+    message: Uint8Array;
+}
+
+/**
+ * How an epoch authenticator is rendered by {@link CoreCrypto.safetyNumber}
+ */
+export enum SafetyNumberFormat {
+    /**
+     * Groups of 5 digits separated by a space
+     */
+    Numeric = 1,
+    /**
+     * Emojis separated by a space
+     */
+    Emoji = 2,
+}
+
+/**
+ * The epoch authenticator of a past or current epoch, see {@link CoreCrypto.epochAuthenticatorHistory}
+ */
+export interface EpochAuthenticator {
+    /**
+     * The epoch this authenticator belongs to
+     */
+    epoch: number;
+    /**
+     * The epoch authenticator itself
+     */
+    authenticator: Uint8Array;
 }
 
 /**
@@ -622,6 +802,60 @@ function normalizeEnum<T>(enumType: T, value: number): T[keyof T] {
     return enumAsDiscriminant;
 }
 
+const mapDecryptedMessage = (
+    ffiDecryptedMessage: CoreCryptoFfiTypes.DecryptedMessage
+): DecryptedMessage => {
+    const ffiCommitDelay = ffiDecryptedMessage.commit_delay;
+
+    let commitDelay = undefined;
+    if (typeof ffiCommitDelay === "number" && ffiCommitDelay >= 0) {
+        commitDelay = ffiCommitDelay * 1000;
+    }
+
+    const identity = mapWireIdentity(ffiDecryptedMessage.identity);
+
+    const ret: DecryptedMessage = {
+        message: ffiDecryptedMessage.message,
+        proposals: ffiDecryptedMessage.proposals,
+        isActive: ffiDecryptedMessage.is_active,
+        senderClientId: ffiDecryptedMessage.sender_client_id,
+        aad: ffiDecryptedMessage.aad,
+        commitDelay,
+        identity,
+        hasEpochChanged: ffiDecryptedMessage.has_epoch_changed,
+        bufferedMessages: ffiDecryptedMessage.buffered_messages?.map(
+            mapBufferedDecryptedMessage
+        ),
+        crlNewDistributionPoints:
+            ffiDecryptedMessage.crl_new_distribution_points,
+        reinit: mapReInit(ffiDecryptedMessage.reinit),
+    };
+
+    return ret;
+};
+
+const mapBufferedDecryptedMessage = (
+    m: CoreCryptoFfiTypes.BufferedDecryptedMessage
+): BufferedDecryptedMessage => ({
+    message: m.message,
+    proposals: m.proposals,
+    isActive: m.is_active,
+    senderClientId: m.sender_client_id,
+    aad: m.aad,
+    commitDelay: m.commit_delay,
+    identity: mapWireIdentity(m.identity),
+    hasEpochChanged: m.has_epoch_changed,
+    crlNewDistributionPoints: m.crl_new_distribution_points,
+    reinit: mapReInit(m.reinit),
+});
+
+const mapReInit = (
+    ffiReInit?: CoreCryptoFfiTypes.ReInit
+): ReInit | undefined =>
+    ffiReInit
+        ? { groupId: ffiReInit.group_id, ciphersuite: ffiReInit.ciphersuite }
+        : undefined;
+
 const mapWireIdentity = (
     ffiIdentity?: CoreCryptoFfiTypes.WireIdentity
 ): WireIdentity | undefined => {
@@ -657,6 +891,24 @@ const mapX509Identity = (
     };
 };
 
+const mapHistoryMessage = (
+    ffiMessage: CoreCryptoFfiTypes.HistoryMessage
+): HistoryMessage => ({
+    epoch: Number(ffiMessage.epoch),
+    senderClientId: ffiMessage.sender_client_id,
+    receivedAt: Number(ffiMessage.received_at),
+    message: ffiMessage.message,
+});
+
+const mapBufferedMessage = (
+    ffiMessage: CoreCryptoFfiTypes.BufferedMessage
+): BufferedMessage => ({
+    conversationId: ffiMessage.conversation_id,
+    epoch: Number(ffiMessage.epoch),
+    receivedAt: Number(ffiMessage.received_at),
+    message: ffiMessage.message,
+});
+
 export interface AcmeDirectory {
     /**
      * URL for fetching a new nonce. Use this only for creating a new account.
@@ -719,6 +971,24 @@ export interface ProposalBundle {
     crlNewDistributionPoints?: string[];
 }
 
+/**
+ * An application specific GroupContext extension
+ */
+export interface AppExtension {
+    /**
+     * Extension type, outside of the ones defined by MLS
+     *
+     * @readonly
+     */
+    extensionType: number;
+    /**
+     * Content of the extension
+     *
+     * @readonly
+     */
+    data: Uint8Array;
+}
+
 export interface WelcomeBundle {
     /**
      * Conversation ID
@@ -732,6 +1002,12 @@ export interface WelcomeBundle {
      * @readonly
      */
     crlNewDistributionPoints?: string[];
+    /**
+     * Messages of the conversation which were received before the Welcome, decrypted
+     *
+     * @readonly
+     */
+    bufferedMessages?: BufferedDecryptedMessage[];
 }
 
 /**
@@ -750,6 +1026,14 @@ export enum ProposalType {
      * This allows to propose to update the client keying material (i.e. keypackage rotation) and the group root key
      */
     Update,
+    /**
+     * This allows to propose the injection of an external pre-shared key in the key schedule of the next epoch
+     */
+    ExternalPsk,
+    /**
+     * This allows to propose ending the conversation in favor of a successor, e.g. with another ciphersuite
+     */
+    ReInit,
 }
 
 /**
@@ -782,6 +1066,26 @@ export interface RemoveProposalArgs extends ProposalArgs {
     clientId: ClientId;
 }
 
+/**
+ * Arguments for a proposal of type `ExternalPsk`
+ */
+export interface ExternalPskProposalArgs extends ProposalArgs {
+    /**
+     * Id of a PSK previously registered with {@link CoreCrypto.registerExternalPsk}
+     */
+    pskId: Uint8Array;
+}
+
+/**
+ * Arguments for a proposal of type `ReInit`
+ */
+export interface ReInitProposalArgs extends ProposalArgs {
+    /**
+     * Parameters of the successor conversation
+     */
+    reinit: ReInit;
+}
+
 /**
  * MLS External Proposal type
  */
@@ -1060,6 +1364,38 @@ export class CoreCrypto {
         );
     }
 
+    /**
+     * Exports the whole MLS state of this client (credentials, conversations, KeyPackages, PKI environment...) to move it
+     * to another device without rejoining every conversation. Once imported elsewhere, this device must not be used anymore
+     *
+     * @param passphrase - passphrase the snapshot is encrypted with
+     *
+     * @returns the encrypted snapshot
+     */
+    async exportSnapshot(passphrase: string): Promise<Uint8Array> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.export_snapshot(passphrase)
+        );
+    }
+
+    /**
+     * Initializes the MLS client from a snapshot created by {@link CoreCrypto.exportSnapshot}, in place of
+     * {@link CoreCrypto.mlsInit}. This is only possible on a keystore which does not hold any client yet
+     *
+     * @param snapshot - the encrypted snapshot
+     * @param passphrase - passphrase the snapshot was encrypted with
+     *
+     * @returns the client ID of the imported client
+     */
+    async importSnapshot(
+        snapshot: Uint8Array,
+        passphrase: string
+    ): Promise<ClientId> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.import_snapshot(snapshot, passphrase)
+        );
+    }
+
     /** @hidden */
     private constructor(cc: CoreCryptoFfiTypes.CoreCrypto) {
         this.#cc = cc;
@@ -1093,6 +1429,42 @@ export class CoreCrypto {
         await CoreCryptoError.asyncMapErr(this.#cc.close());
     }
 
+    /**
+     * Starts a keystore transaction: until {@link CoreCrypto.commitTransaction} is called, everything the following
+     * operations persist (decrypted messages, commits, merges, buffered messages...) is staged in memory and then
+     * written to the keystore all at once. Use it to process a batch of notifications atomically.
+     */
+    async beginTransaction(): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(this.#cc.begin_transaction());
+    }
+
+    /**
+     * Atomically persists everything staged since {@link CoreCrypto.beginTransaction}
+     */
+    async commitTransaction(): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(this.#cc.commit_transaction());
+    }
+
+    /**
+     * Discards everything staged since {@link CoreCrypto.beginTransaction} and reloads conversations from the keystore
+     */
+    async rollbackTransaction(): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(this.#cc.rollback_transaction());
+    }
+
+    /**
+     * Re-encrypts the {@link https://developer.mozilla.org/en-US/docs/Web/API/IndexedDB_API | IndexedDB} database under a new key,
+     * e.g. after the user reset their passcode. Once this resolves, {@link CoreCrypto} has to be instantiated with `newKey`.
+     *
+     * Persist `newKey` before calling this. If this gets interrupted (e.g. the tab is closed), instantiate {@link CoreCrypto}
+     * with `newKey`: it picks up where it stopped without losing data. Instantiating it with the former key fails until then.
+     *
+     * @param newKey - the new key of the database
+     */
+    async rekey(newKey: string): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(this.#cc.rekey(newKey));
+    }
+
     /**
      * Registers the callbacks for CoreCrypto to use in order to gain additional information
      *
@@ -1166,63 +1538,391 @@ export class CoreCrypto {
     }
 
     /**
-     * Returns the current epoch of a conversation
+     * Creates a subconversation of an existing conversation, e.g. for a conference call, and adds some of its members
+     * to it. Only members of the parent conversation at its current epoch are able to join.
      *
-     * @returns the epoch of the conversation
+     * **CAUTION**: {@link CoreCrypto.commitAccepted} **HAS TO** be called afterward **ONLY IF** the Delivery Service responds
+     * '200 OK' to the {@link CommitBundle} upload.
      *
-     * @example
-     * ```ts
-     *  const cc = await CoreCrypto.init({ databaseName: "test", key: "test", clientId: "test" });
-     *  const encoder = new TextEncoder();
-     *  console.log(await cc.conversationEpoch(encoder.encode("my super chat")))
-     * ```
-     */
-    async conversationEpoch(conversationId: ConversationId): Promise<number> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#cc.conversation_epoch(conversationId)
-        );
-    }
-
-    /**
-     * Returns the ciphersuite of a conversation
+     * @param parentId - conversation identifier of the parent conversation
+     * @param conversationId - conversation identifier of the subconversation
+     * @param keyPackages - KeyPackages of the clients to add, who all have to be members of the parent conversation
      *
-     * @returns the ciphersuite of the conversation
+     * @returns A {@link MemberAddedMessages}
      */
-    async conversationCiphersuite(conversationId: ConversationId): Promise<Ciphersuite> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#cc.conversation_ciphersuite(conversationId)
-        );
-    }
+    async createSubconversation(
+        parentId: ConversationId,
+        conversationId: ConversationId,
+        keyPackages: Uint8Array[]
+    ): Promise<MemberAddedMessages> {
+        try {
+            const ffiRet: CoreCryptoFfiTypes.MemberAddedMessages =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.create_subconversation(
+                        parentId,
+                        conversationId,
+                        keyPackages
+                    )
+                );
 
-    /**
-     * Wipes and destroys the local storage of a given conversation / MLS group
-     *
-     * @param conversationId - The ID of the conversation to remove
-     */
-    async wipeConversation(conversationId: ConversationId): Promise<void> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#cc.wipe_conversation(conversationId)
-        );
+            const gi = ffiRet.group_info;
+
+            const ret: MemberAddedMessages = {
+                welcome: ffiRet.welcome,
+                commit: ffiRet.commit,
+                groupInfo: {
+                    encryptionType: gi.encryption_type,
+                    ratchetTreeType: gi.ratchet_tree_type,
+                    payload: gi.payload,
+                },
+                crlNewDistributionPoints: ffiRet.crl_new_distribution_points,
+            };
+
+            return ret;
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
     }
 
     /**
-     * Creates a new conversation with the current client being the sole member
-     * You will want to use {@link CoreCrypto.addClientsToConversation} afterwards to add clients to this conversation
+     * Joins a subconversation created with {@link CoreCrypto.createSubconversation}. The local parent conversation
+     * has to be at the same epoch as when the subconversation was created.
      *
-     * @param conversationId - The conversation ID; You can either make them random or let the backend attribute MLS group IDs
-     * @param creatorCredentialType - kind of credential the creator wants to create the group with
+     * @param parentId - conversation identifier of the parent conversation
+     * @param welcomeMessage - TLS-serialized MLS Welcome message of the subconversation
      * @param configuration - configuration of the MLS group
-     * @param configuration.ciphersuite - The {@link Ciphersuite} that is chosen to be the group's
-     * @param configuration.externalSenders - Array of Client IDs that are qualified as external senders within the group
-     * @param configuration.custom - {@link CustomConfiguration}
+     * @returns The conversation ID of the subconversation
      */
-    async createConversation(
-        conversationId: ConversationId,
-        creatorCredentialType: CredentialType,
-        configuration: ConversationConfiguration = {}
-    ) {
+    async joinSubconversation(
+        parentId: ConversationId,
+        welcomeMessage: Uint8Array,
+        configuration: CustomConfiguration = {}
+    ): Promise<WelcomeBundle> {
         try {
-            const {
+            const { keyRotationSpan, wirePolicy } = configuration || {};
+            const config = new CustomConfigurationFfi(
+                keyRotationSpan,
+                wirePolicy
+            );
+            const ffiRet: CoreCryptoFfiTypes.WelcomeBundle =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.join_subconversation(
+                        parentId,
+                        welcomeMessage,
+                        config
+                    )
+                );
+
+            const ret: WelcomeBundle = {
+                id: ffiRet.id,
+                crlNewDistributionPoints: ffiRet.crl_new_distribution_points,
+                bufferedMessages: ffiRet.buffered_messages?.map(
+                    mapBufferedDecryptedMessage
+                ),
+            };
+
+            return ret;
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
+    }
+
+    /**
+     * Ends a conversation with a commit holding a single ReInit proposal. Once it is accepted, the conversation cannot
+     * be used anymore and its members are expected to move to the successor created with
+     * {@link CoreCrypto.createReinitSuccessor}.
+     *
+     * **CAUTION**: {@link CoreCrypto.commitAccepted} **HAS TO** be called afterward **ONLY IF** the Delivery Service responds
+     * '200 OK' to the {@link CommitBundle} upload.
+     *
+     * @param conversationId - The ID of the conversation to end
+     * @param reinit - Parameters of the successor conversation
+     *
+     * @returns A {@link CommitBundle}
+     */
+    async reinitConversation(
+        conversationId: ConversationId,
+        reinit: ReInit
+    ): Promise<CommitBundle> {
+        try {
+            const ffiRet: CoreCryptoFfiTypes.CommitBundle =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.reinit_conversation(
+                        conversationId,
+                        reinit.groupId,
+                        reinit.ciphersuite
+                    )
+                );
+
+            const gi = ffiRet.group_info;
+
+            const ret: CommitBundle = {
+                welcome: ffiRet.welcome,
+                commit: ffiRet.commit,
+                groupInfo: {
+                    encryptionType: gi.encryption_type,
+                    ratchetTreeType: gi.ratchet_tree_type,
+                    payload: gi.payload,
+                },
+            };
+
+            return ret;
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
+    }
+
+    /**
+     * Creates the successor of a conversation ended by a ReInit commit and adds some of its members to it. Only
+     * members of the ended conversation are able to join.
+     *
+     * **CAUTION**: {@link CoreCrypto.commitAccepted} **HAS TO** be called afterward on the successor **ONLY IF** the
+     * Delivery Service responds '200 OK' to the {@link CommitBundle} upload.
+     *
+     * @param conversationId - The ID of the ended conversation
+     * @param reinit - Parameters of the successor, as committed in the ended conversation
+     * @param keyPackages - KeyPackages of the clients to add, for the ciphersuite of the successor
+     *
+     * @returns A {@link MemberAddedMessages}
+     */
+    async createReinitSuccessor(
+        conversationId: ConversationId,
+        reinit: ReInit,
+        keyPackages: Uint8Array[]
+    ): Promise<MemberAddedMessages> {
+        try {
+            const ffiRet: CoreCryptoFfiTypes.MemberAddedMessages =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.create_reinit_successor(
+                        conversationId,
+                        reinit.groupId,
+                        reinit.ciphersuite,
+                        keyPackages
+                    )
+                );
+
+            const gi = ffiRet.group_info;
+
+            const ret: MemberAddedMessages = {
+                welcome: ffiRet.welcome,
+                commit: ffiRet.commit,
+                groupInfo: {
+                    encryptionType: gi.encryption_type,
+                    ratchetTreeType: gi.ratchet_tree_type,
+                    payload: gi.payload,
+                },
+                crlNewDistributionPoints: ffiRet.crl_new_distribution_points,
+            };
+
+            return ret;
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
+    }
+
+    /**
+     * Joins the successor created with {@link CoreCrypto.createReinitSuccessor}. The local conversation has to be the
+     * one ended by the ReInit commit, see {@link DecryptedMessage.reinit}.
+     *
+     * @param conversationId - The ID of the ended conversation
+     * @param welcomeMessage - TLS-serialized MLS Welcome message of the successor
+     * @param configuration - configuration of the MLS group
+     * @returns The conversation ID of the successor
+     */
+    async joinReinitSuccessor(
+        conversationId: ConversationId,
+        welcomeMessage: Uint8Array,
+        configuration: CustomConfiguration = {}
+    ): Promise<WelcomeBundle> {
+        try {
+            const { keyRotationSpan, wirePolicy } = configuration || {};
+            const config = new CustomConfigurationFfi(
+                keyRotationSpan,
+                wirePolicy
+            );
+            const ffiRet: CoreCryptoFfiTypes.WelcomeBundle =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.join_reinit_successor(
+                        conversationId,
+                        welcomeMessage,
+                        config
+                    )
+                );
+
+            const ret: WelcomeBundle = {
+                id: ffiRet.id,
+                crlNewDistributionPoints: ffiRet.crl_new_distribution_points,
+                bufferedMessages: ffiRet.buffered_messages?.map(
+                    mapBufferedDecryptedMessage
+                ),
+            };
+
+            return ret;
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
+    }
+
+    /**
+     * Lists the subconversations of a conversation. They are wiped along with it by {@link CoreCrypto.wipeConversation}
+     *
+     * @param conversationId - conversation identifier of the parent conversation
+     * @returns The IDs of the subconversations
+     */
+    async listSubconversations(
+        conversationId: ConversationId
+    ): Promise<ConversationId[]> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.list_subconversations(conversationId)
+        );
+    }
+
+    /**
+     * Returns the current epoch of a conversation
+     *
+     * @returns the epoch of the conversation
+     *
+     * @example
+     * ```ts
+     *  const cc = await CoreCrypto.init({ databaseName: "test", key: "test", clientId: "test" });
+     *  const encoder = new TextEncoder();
+     *  console.log(await cc.conversationEpoch(encoder.encode("my super chat")))
+     * ```
+     */
+    async conversationEpoch(conversationId: ConversationId): Promise<number> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.conversation_epoch(conversationId)
+        );
+    }
+
+    /**
+     * Returns the ciphersuite of a conversation
+     *
+     * @returns the ciphersuite of the conversation
+     */
+    async conversationCiphersuite(conversationId: ConversationId): Promise<Ciphersuite> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.conversation_ciphersuite(conversationId)
+        );
+    }
+
+    /**
+     * Returns the epoch authenticator of the current epoch of a conversation. It is identical for all
+     * the members of this epoch and can be compared out-of-band to prove they share the same view of the group.
+     *
+     * @param conversationId - The ID of the conversation
+     *
+     * @returns the epoch authenticator
+     */
+    async epochAuthenticator(conversationId: ConversationId): Promise<Uint8Array> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.epoch_authenticator(conversationId)
+        );
+    }
+
+    /**
+     * Renders the epoch authenticator of the current epoch of a conversation as a safety number
+     * meant to be compared by humans
+     *
+     * @param conversationId - The ID of the conversation
+     * @param format - Whether digits or emojis are used
+     * @param length - The number of digit groups or emojis
+     *
+     * @returns the safety number, identical for all the members of the current epoch
+     *
+     * @example
+     * ```ts
+     *  const cc = await CoreCrypto.init({ databaseName: "test", key: "test", clientId: "test" });
+     *  const encoder = new TextEncoder();
+     *  console.log(await cc.safetyNumber(encoder.encode("my super chat"), SafetyNumberFormat.Emoji, 8))
+     * ```
+     */
+    async safetyNumber(
+        conversationId: ConversationId,
+        format: SafetyNumberFormat = SafetyNumberFormat.Numeric,
+        length: number = 6
+    ): Promise<string> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.safety_number(conversationId, format, length)
+        );
+    }
+
+    /**
+     * Returns the epoch authenticators of the last epochs of a conversation, from the oldest to the current one.
+     * Useful to explain a safety number mismatch noticed after the fact.
+     *
+     * @param conversationId - The ID of the conversation
+     */
+    async epochAuthenticatorHistory(
+        conversationId: ConversationId
+    ): Promise<EpochAuthenticator[]> {
+        const history: { epoch: number; authenticator: number[] }[] =
+            await CoreCryptoError.asyncMapErr(
+                this.#cc.epoch_authenticator_history(conversationId)
+            );
+        return history.map(({ epoch, authenticator }) => ({
+            epoch,
+            authenticator: new Uint8Array(authenticator),
+        }));
+    }
+
+    /**
+     * Wipes and destroys the local storage of a given conversation / MLS group
+     *
+     * @param conversationId - The ID of the conversation to remove
+     */
+    async wipeConversation(conversationId: ConversationId): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.wipe_conversation(conversationId)
+        );
+    }
+
+    /**
+     * Creates a proposal removing the current client from the conversation. Once it has been sent to the other
+     * members, call {@link CoreCrypto.leaveProposalSent} to wipe the conversation locally. The remaining members
+     * will commit it like any other Remove proposal.
+     *
+     * @param conversationId - The ID of the conversation to leave
+     *
+     * @returns A {@link ProposalBundle} to fan out to the other members
+     */
+    async leaveConversation(
+        conversationId: ConversationId
+    ): Promise<ProposalBundle> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.leave_conversation(conversationId)
+        );
+    }
+
+    /**
+     * Wipes the conversation locally once the proposal created by {@link CoreCrypto.leaveConversation} has been sent
+     *
+     * @param conversationId - The ID of the conversation being left
+     */
+    async leaveProposalSent(conversationId: ConversationId): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.leave_proposal_sent(conversationId)
+        );
+    }
+
+    /**
+     * Creates a new conversation with the current client being the sole member
+     * You will want to use {@link CoreCrypto.addClientsToConversation} afterwards to add clients to this conversation
+     *
+     * @param conversationId - The conversation ID; You can either make them random or let the backend attribute MLS group IDs
+     * @param creatorCredentialType - kind of credential the creator wants to create the group with
+     * @param configuration - configuration of the MLS group
+     * @param configuration.ciphersuite - The {@link Ciphersuite} that is chosen to be the group's
+     * @param configuration.externalSenders - Array of Client IDs that are qualified as external senders within the group
+     * @param configuration.custom - {@link CustomConfiguration}
+     */
+    async createConversation(
+        conversationId: ConversationId,
+        creatorCredentialType: CredentialType,
+        configuration: ConversationConfiguration = {}
+    ) {
+        try {
+            const {
                 ciphersuite,
                 externalSenders,
                 custom = {},
@@ -1273,40 +1973,42 @@ export class CoreCrypto {
                     this.#cc.decrypt_message(conversationId, payload)
                 );
 
-            const ffiCommitDelay = ffiDecryptedMessage.commit_delay;
-
-            let commitDelay = undefined;
-            if (typeof ffiCommitDelay === "number" && ffiCommitDelay >= 0) {
-                commitDelay = ffiCommitDelay * 1000;
-            }
+            return mapDecryptedMessage(ffiDecryptedMessage);
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
+    }
 
-            const identity = mapWireIdentity(ffiDecryptedMessage.identity);
-
-            const ret: DecryptedMessage = {
-                message: ffiDecryptedMessage.message,
-                proposals: ffiDecryptedMessage.proposals,
-                isActive: ffiDecryptedMessage.is_active,
-                senderClientId: ffiDecryptedMessage.sender_client_id,
-                commitDelay,
-                identity,
-                hasEpochChanged: ffiDecryptedMessage.has_epoch_changed,
-                bufferedMessages: ffiDecryptedMessage.buffered_messages?.map(
-                    (m) => ({
-                        message: m.message,
-                        proposals: m.proposals,
-                        isActive: m.is_active,
-                        senderClientId: m.sender_client_id,
-                        commitDelay: m.commit_delay,
-                        identity: mapWireIdentity(m.identity),
-                        hasEpochChanged: m.has_epoch_changed,
-                        crlNewDistributionPoints: m.crl_new_distribution_points,
-                    })
-                ),
-                crlNewDistributionPoints:
-                    ffiDecryptedMessage.crl_new_distribution_points,
-            };
+    /**
+     * Decrypts a batch of messages, typically the backlog fetched after having been offline. Messages are grouped by
+     * conversation and each conversation is persisted once, after all of its messages have been processed. Within a
+     * conversation, application messages and proposals are processed before the commit closing their epoch.
+     * Each conversation is processed in its own keystore transaction, so this cannot be called while a transaction
+     * is in progress.
+     *
+     * An error for a message does not prevent the other ones from being decrypted. The same errors as in
+     * {@link CoreCrypto.decryptMessage} should be caught & ignored.
+     *
+     * @param messages - pairs of conversation id and encrypted message, in the order they were received
+     *
+     * @returns one {@link BatchDecryptedMessage} per message, in the same order as the input
+     */
+    async decryptMessages(
+        messages: [ConversationId, Uint8Array][]
+    ): Promise<BatchDecryptedMessage[]> {
+        try {
+            const ffiResults: CoreCryptoFfiTypes.BatchDecryptedMessage[] =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.decrypt_messages(
+                        messages.map(([conversationId]) => conversationId),
+                        messages.map(([, payload]) => payload)
+                    )
+                );
 
-            return ret;
+            return ffiResults.map((r) => ({
+                message: r.message ? mapDecryptedMessage(r.message) : undefined,
+                error: r.error ? CoreCryptoError.build(r.error) : undefined,
+            }));
         } catch (e) {
             throw CoreCryptoError.fromStdError(e as Error);
         }
@@ -1329,6 +2031,27 @@ export class CoreCrypto {
         );
     }
 
+    /**
+     * Same as {@link CoreCrypto.encryptMessage} but also binds additional data to the message. It is sent in clear
+     * alongside the ciphertext, e.g. for the Delivery Service to route the message, while being authenticated.
+     * Recipients get it back in {@link DecryptedMessage.aad}
+     *
+     * @param conversationId - The ID of the conversation
+     * @param message - The plaintext message to encrypt
+     * @param aad - The additional authenticated data
+     *
+     * @returns The encrypted payload for the given group. This needs to be fanned out to the other members of the group.
+     */
+    async encryptMessageWithAad(
+        conversationId: ConversationId,
+        message: Uint8Array,
+        aad: Uint8Array
+    ): Promise<Uint8Array> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.encrypt_message_with_aad(conversationId, message, aad)
+        );
+    }
+
     /**
      * Ingest a TLS-serialized MLS welcome message to join an existing MLS group
      *
@@ -1358,6 +2081,52 @@ export class CoreCrypto {
             const ret: WelcomeBundle = {
                 id: ffiRet.id,
                 crlNewDistributionPoints: ffiRet.crl_new_distribution_points,
+                bufferedMessages: ffiRet.buffered_messages?.map(
+                    mapBufferedDecryptedMessage
+                ),
+            };
+
+            return ret;
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
+    }
+
+    /**
+     * Same as {@link CoreCrypto.processWelcomeMessage} when the GroupInfo in the Welcome does not carry the ratchet
+     * tree, e.g. when the committer uses {@link RatchetTreeType.ByRef}
+     *
+     * @param welcomeMessage - TLS-serialized MLS Welcome message
+     * @param ratchetTree - TLS-serialized ratchet tree of the group, served by the Delivery Service
+     * @param configuration - configuration of the MLS group
+     * @returns The conversation ID of the newly joined group. You can use the same ID to decrypt/encrypt messages
+     */
+    async processWelcomeMessageWithRatchetTree(
+        welcomeMessage: Uint8Array,
+        ratchetTree: Uint8Array,
+        configuration: CustomConfiguration = {}
+    ): Promise<WelcomeBundle> {
+        try {
+            const { keyRotationSpan, wirePolicy } = configuration || {};
+            const config = new CustomConfigurationFfi(
+                keyRotationSpan,
+                wirePolicy
+            );
+            const ffiRet: CoreCryptoFfiTypes.WelcomeBundle =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.process_welcome_message_with_ratchet_tree(
+                        welcomeMessage,
+                        ratchetTree,
+                        config
+                    )
+                );
+
+            const ret: WelcomeBundle = {
+                id: ffiRet.id,
+                crlNewDistributionPoints: ffiRet.crl_new_distribution_points,
+                bufferedMessages: ffiRet.buffered_messages?.map(
+                    mapBufferedDecryptedMessage
+                ),
             };
 
             return ret;
@@ -1419,39 +2188,130 @@ export class CoreCrypto {
         );
     }
 
-    /**
-     * Prunes local KeyPackages after making sure they also have been deleted on the backend side
-     * You should only use this after {@link CoreCrypto.e2eiRotateAll}
-     *
-     * @param refs - KeyPackage references to delete obtained from a {RotateBundle}
-     */
-    async deleteKeypackages(refs: Uint8Array[]): Promise<void> {
-        return await CoreCryptoError.asyncMapErr(
-            this.#cc.delete_keypackages(refs)
-        );
+    /**
+     * Prunes local KeyPackages after making sure they also have been deleted on the backend side
+     * You should only use this after {@link CoreCrypto.e2eiRotateAll}
+     *
+     * @param refs - KeyPackage references to delete obtained from a {RotateBundle}
+     */
+    async deleteKeypackages(refs: Uint8Array[]): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.delete_keypackages(refs)
+        );
+    }
+
+    /**
+     * Adds new clients to a conversation, assuming the current client has the right to add new clients to the conversation.
+     *
+     * **CAUTION**: {@link CoreCrypto.commitAccepted} **HAS TO** be called afterward **ONLY IF** the Delivery Service responds
+     * '200 OK' to the {@link CommitBundle} upload. It will "merge" the commit locally i.e. increment the local group
+     * epoch, use new encryption secrets etc...
+     *
+     * @param conversationId - The ID of the conversation
+     * @param keyPackages - KeyPackages of the new clients to add
+     *
+     * @returns A {@link CommitBundle}
+     */
+    async addClientsToConversation(
+        conversationId: ConversationId,
+        keyPackages: Uint8Array[]
+    ): Promise<MemberAddedMessages> {
+        try {
+            const ffiRet: CoreCryptoFfiTypes.MemberAddedMessages =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.add_clients_to_conversation(
+                        conversationId,
+                        keyPackages
+                    )
+                );
+
+            const gi = ffiRet.group_info;
+
+            const ret: MemberAddedMessages = {
+                welcome: ffiRet.welcome,
+                commit: ffiRet.commit,
+                groupInfo: {
+                    encryptionType: gi.encryption_type,
+                    ratchetTreeType: gi.ratchet_tree_type,
+                    payload: gi.payload,
+                },
+                crlNewDistributionPoints: ffiRet.crl_new_distribution_points,
+            };
+
+            return ret;
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
+    }
+
+    /**
+     * Removes the provided clients from a conversation; Assuming those clients exist and the current client is allowed
+     * to do so, otherwise this operation does nothing.
+     *
+     * **CAUTION**: {@link CoreCrypto.commitAccepted} **HAS TO** be called afterward **ONLY IF** the Delivery Service responds
+     * '200 OK' to the {@link CommitBundle} upload. It will "merge" the commit locally i.e. increment the local group
+     * epoch, use new encryption secrets etc...
+     *
+     * @param conversationId - The ID of the conversation
+     * @param clientIds - Array of Client IDs to remove.
+     *
+     * @returns A {@link CommitBundle}
+     */
+    async removeClientsFromConversation(
+        conversationId: ConversationId,
+        clientIds: ClientId[]
+    ): Promise<CommitBundle> {
+        try {
+            const ffiRet: CoreCryptoFfiTypes.CommitBundle =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.remove_clients_from_conversation(
+                        conversationId,
+                        clientIds
+                    )
+                );
+
+            const gi = ffiRet.group_info;
+
+            const ret: CommitBundle = {
+                welcome: ffiRet.welcome,
+                commit: ffiRet.commit,
+                groupInfo: {
+                    encryptionType: gi.encryption_type,
+                    ratchetTreeType: gi.ratchet_tree_type,
+                    payload: gi.payload,
+                },
+            };
+
+            return ret;
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
     }
 
     /**
-     * Adds new clients to a conversation, assuming the current client has the right to add new clients to the conversation.
+     * Adds all the devices of a single user to a conversation. Every KeyPackage has to belong to this user.
      *
      * **CAUTION**: {@link CoreCrypto.commitAccepted} **HAS TO** be called afterward **ONLY IF** the Delivery Service responds
      * '200 OK' to the {@link CommitBundle} upload. It will "merge" the commit locally i.e. increment the local group
      * epoch, use new encryption secrets etc...
      *
      * @param conversationId - The ID of the conversation
-     * @param keyPackages - KeyPackages of the new clients to add
+     * @param userId - The ID of the user, e.g. `bd4c7053-1c5a-4020-9559-cd7bf7961954`
+     * @param keyPackages - KeyPackages of the devices of this user
      *
-     * @returns A {@link CommitBundle}
+     * @returns A {@link MemberAddedMessages}
      */
-    async addClientsToConversation(
+    async addUserToConversation(
         conversationId: ConversationId,
+        userId: string,
         keyPackages: Uint8Array[]
     ): Promise<MemberAddedMessages> {
         try {
             const ffiRet: CoreCryptoFfiTypes.MemberAddedMessages =
                 await CoreCryptoError.asyncMapErr(
-                    this.#cc.add_clients_to_conversation(
+                    this.#cc.add_user_to_conversation(
                         conversationId,
+                        userId,
                         keyPackages
                     )
                 );
@@ -1476,28 +2336,28 @@ export class CoreCrypto {
     }
 
     /**
-     * Removes the provided clients from a conversation; Assuming those clients exist and the current client is allowed
-     * to do so, otherwise this operation does nothing.
+     * Removes all the devices of a user from a conversation. When removing the current user, only its other devices
+     * are removed.
      *
      * **CAUTION**: {@link CoreCrypto.commitAccepted} **HAS TO** be called afterward **ONLY IF** the Delivery Service responds
      * '200 OK' to the {@link CommitBundle} upload. It will "merge" the commit locally i.e. increment the local group
      * epoch, use new encryption secrets etc...
      *
      * @param conversationId - The ID of the conversation
-     * @param clientIds - Array of Client IDs to remove.
+     * @param userId - The ID of the user, e.g. `bd4c7053-1c5a-4020-9559-cd7bf7961954`
      *
      * @returns A {@link CommitBundle}
      */
-    async removeClientsFromConversation(
+    async removeUserFromConversation(
         conversationId: ConversationId,
-        clientIds: ClientId[]
+        userId: string
     ): Promise<CommitBundle> {
         try {
             const ffiRet: CoreCryptoFfiTypes.CommitBundle =
                 await CoreCryptoError.asyncMapErr(
-                    this.#cc.remove_clients_from_conversation(
+                    this.#cc.remove_user_from_conversation(
                         conversationId,
-                        clientIds
+                        userId
                     )
                 );
 
@@ -1557,6 +2417,126 @@ export class CoreCrypto {
         }
     }
 
+    /**
+     * Lists the conversations whose `keyRotationSpan` (see {@link CustomConfiguration}) has passed since the own
+     * LeafNode was last updated
+     *
+     * @returns The IDs of the conversations due for a key rotation
+     */
+    async conversationsDueForKeyRotation(): Promise<ConversationId[]> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.conversations_due_for_key_rotation()
+        );
+    }
+
+    /**
+     * Creates an update commit for every conversation returned by {@link CoreCrypto.conversationsDueForKeyRotation}.
+     * Conversations with a pending commit are skipped.
+     *
+     * **CAUTION**: like for {@link CoreCrypto.updateKeyingMaterial}, {@link CoreCrypto.commitAccepted} **HAS TO** be
+     * called for each conversation afterward **ONLY IF** the Delivery Service responds '200 OK' to the commit upload
+     *
+     * @returns A {@link CommitBundle} for each conversation, indexed by the hex encoded conversation ID
+     */
+    async updateKeyingMaterialWhenDue(): Promise<Map<string, CommitBundle>> {
+        try {
+            const ffiRet: Map<string, CoreCryptoFfiTypes.CommitBundle> =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.update_keying_material_when_due()
+                );
+
+            const ret = new Map<string, CommitBundle>();
+            ffiRet.forEach((ffiCommit, id) => {
+                const gi = ffiCommit.group_info;
+                ret.set(id, {
+                    welcome: ffiCommit.welcome,
+                    commit: ffiCommit.commit,
+                    groupInfo: {
+                        encryptionType: gi.encryption_type,
+                        ratchetTreeType: gi.ratchet_tree_type,
+                        payload: gi.payload,
+                    },
+                });
+            });
+
+            return ret;
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
+    }
+
+    /**
+     * Changes the key rotation span of a conversation, for example one created before key rotations were tracked
+     *
+     * @param conversationId - The ID of the conversation
+     * @param keyRotationSpan - Duration in seconds after which the own LeafNode has to be updated. Disabled when undefined
+     */
+    async setKeyRotationSpan(
+        conversationId: ConversationId,
+        keyRotationSpan?: number
+    ): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.set_key_rotation_span(conversationId, keyRotationSpan)
+        );
+    }
+
+    /**
+     * Registers an external pre-shared key, to be later referenced in conversations of the given ciphersuite.
+     * Registering again the same `pskId` replaces the previous secret.
+     *
+     * @param ciphersuite - of the conversations the PSK will be used in
+     * @param pskId - identifier of the PSK, shared with the other parties alongside the secret
+     * @param psk - the secret itself
+     */
+    async registerExternalPsk(
+        ciphersuite: Ciphersuite,
+        pskId: Uint8Array,
+        psk: Uint8Array
+    ): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.register_external_psk(ciphersuite, pskId, psk)
+        );
+    }
+
+    /**
+     * Commits a PreSharedKey proposal for each of the given external PSKs, along with the other pending proposals.
+     * All the members have to have registered these PSKs with {@link CoreCrypto.registerExternalPsk} to process the commit.
+     *
+     * **CAUTION**: {@link CoreCrypto.commitAccepted} **HAS TO** be called afterwards **ONLY IF** the Delivery Service responds
+     * '200 OK' to the {@link CommitBundle} upload. It will "merge" the commit locally i.e. increment the local group
+     * epoch, use new encryption secrets etc...
+     *
+     * @param conversationId - The ID of the conversation
+     * @param pskIds - ids of the PSKs to inject in the key schedule
+     *
+     * @returns A {@link CommitBundle}
+     */
+    async commitExternalPsks(
+        conversationId: ConversationId,
+        pskIds: Uint8Array[]
+    ): Promise<CommitBundle> {
+        try {
+            const ffiRet: CoreCryptoFfiTypes.CommitBundle =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.commit_external_psks(conversationId, pskIds)
+                );
+
+            const gi = ffiRet.group_info;
+
+            return {
+                welcome: ffiRet.welcome,
+                commit: ffiRet.commit,
+                groupInfo: {
+                    encryptionType: gi.encryption_type,
+                    ratchetTreeType: gi.ratchet_tree_type,
+                    payload: gi.payload,
+                },
+            };
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
+    }
+
     /**
      * Commits the local pending proposals and returns the {@link CommitBundle} object containing what can result from this operation.
      *
@@ -1601,13 +2581,18 @@ export class CoreCrypto {
      * Creates a new proposal for the provided Conversation ID
      *
      * @param proposalType - The type of proposal, see {@link ProposalType}
-     * @param args - The arguments of the proposal, see {@link ProposalArgs}, {@link AddProposalArgs} or {@link RemoveProposalArgs}
+     * @param args - The arguments of the proposal, see {@link ProposalArgs}, {@link AddProposalArgs}, {@link RemoveProposalArgs}, {@link ExternalPskProposalArgs} or {@link ReInitProposalArgs}
      *
      * @returns A {@link ProposalBundle} containing the Proposal and its reference in order to roll it back if necessary
      */
     async newProposal(
         proposalType: ProposalType,
-        args: ProposalArgs | AddProposalArgs | RemoveProposalArgs
+        args:
+            | ProposalArgs
+            | AddProposalArgs
+            | RemoveProposalArgs
+            | ExternalPskProposalArgs
+            | ReInitProposalArgs
     ): Promise<ProposalBundle> {
         switch (proposalType) {
             case ProposalType.Add: {
@@ -1641,6 +2626,34 @@ export class CoreCrypto {
                     this.#cc.new_update_proposal(args.conversationId)
                 );
             }
+            case ProposalType.ExternalPsk: {
+                if (!(args as ExternalPskProposalArgs).pskId) {
+                    throw new Error(
+                        "pskId is not contained in the proposal arguments"
+                    );
+                }
+                return await CoreCryptoError.asyncMapErr(
+                    this.#cc.new_external_psk_proposal(
+                        args.conversationId,
+                        (args as ExternalPskProposalArgs).pskId
+                    )
+                );
+            }
+            case ProposalType.ReInit: {
+                const reinit = (args as ReInitProposalArgs).reinit;
+                if (!reinit) {
+                    throw new Error(
+                        "reinit is not contained in the proposal arguments"
+                    );
+                }
+                return await CoreCryptoError.asyncMapErr(
+                    this.#cc.new_reinit_proposal(
+                        args.conversationId,
+                        reinit.groupId,
+                        reinit.ciphersuite
+                    )
+                );
+            }
             default:
                 throw new Error("Invalid proposal type!");
         }
@@ -1721,105 +2734,392 @@ export class CoreCrypto {
                     ffiInitMessage.crl_new_distribution_points,
             };
 
-            return ret;
-        } catch (e) {
-            throw CoreCryptoError.fromStdError(e as Error);
-        }
+            return ret;
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
+    }
+
+    /**
+     * Same as {@link CoreCrypto.joinByExternalCommit} for a {@link RatchetTreeType.ByRef} GroupInfo
+     *
+     * @param groupInfo - the payload of a {@link GroupInfoBundle} with {@link RatchetTreeType.ByRef}
+     * @param ratchetTree - the TLS-serialized ratchet tree fetched from the Delivery Service, checked against the tree hash of the GroupInfo
+     * @param credentialType - kind of Credential to use for joining this group
+     * @param configuration - configuration of the MLS group
+     * @returns see {@link ConversationInitBundle}
+     */
+    async joinByExternalCommitWithRatchetTree(
+        groupInfo: Uint8Array,
+        ratchetTree: Uint8Array,
+        credentialType: CredentialType,
+        configuration: CustomConfiguration = {}
+    ): Promise<ConversationInitBundle> {
+        try {
+            const { keyRotationSpan, wirePolicy } = configuration || {};
+            const config = new CustomConfigurationFfi(
+                keyRotationSpan,
+                wirePolicy
+            );
+            const ffiInitMessage: CoreCryptoFfiTypes.ConversationInitBundle =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.join_by_external_commit_with_ratchet_tree(
+                        groupInfo,
+                        ratchetTree,
+                        config,
+                        credentialType
+                    )
+                );
+
+            const gi = ffiInitMessage.group_info;
+
+            const ret: ConversationInitBundle = {
+                conversationId: ffiInitMessage.conversation_id,
+                commit: ffiInitMessage.commit,
+                groupInfo: {
+                    encryptionType: gi.encryption_type,
+                    ratchetTreeType: gi.ratchet_tree_type,
+                    payload: gi.payload,
+                },
+                crlNewDistributionPoints:
+                    ffiInitMessage.crl_new_distribution_points,
+            };
+
+            return ret;
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
+    }
+
+    /**
+     * This merges the commit generated by {@link CoreCrypto.joinByExternalCommit}, persists the group permanently
+     * and deletes the temporary one. This step makes the group operational and ready to encrypt/decrypt message
+     *
+     * @param conversationId - The ID of the conversation
+     * @returns eventually decrypted buffered messages if any
+     */
+    async mergePendingGroupFromExternalCommit(
+        conversationId: ConversationId
+    ): Promise<BufferedDecryptedMessage[] | undefined> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.merge_pending_group_from_external_commit(conversationId)
+        );
+    }
+
+    /**
+     * In case the external commit generated by {@link CoreCrypto.joinByExternalCommit} is rejected by the Delivery Service, and we
+     * want to abort this external commit once for all, we can wipe out the pending group from the keystore in order
+     * not to waste space
+     *
+     * @param conversationId - The ID of the conversation
+     */
+    async clearPendingGroupFromExternalCommit(
+        conversationId: ConversationId
+    ): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.clear_pending_group_from_external_commit(conversationId)
+        );
+    }
+
+    /**
+     * Allows to mark the latest commit produced as "accepted" and be able to safely merge it into the local group state
+     *
+     * @param conversationId - The group's ID
+     * @returns the messages from current epoch which had been buffered, if any
+     */
+    async commitAccepted(
+        conversationId: ConversationId
+    ): Promise<BufferedDecryptedMessage[] | undefined> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.commit_accepted(conversationId)
+        );
+    }
+
+    /**
+     * Allows to remove a pending proposal (rollback). Use this when backend rejects the proposal you just sent e.g. if permissions have changed meanwhile.
+     *
+     * **CAUTION**: only use this when you had an explicit response from the Delivery Service
+     * e.g. 403 or 409. Do not use otherwise e.g. 5xx responses, timeout etc…
+     *
+     * @param conversationId - The group's ID
+     * @param proposalRef - A reference to the proposal to delete. You get one when using {@link CoreCrypto.newProposal}
+     */
+    async clearPendingProposal(
+        conversationId: ConversationId,
+        proposalRef: ProposalRef
+    ): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.clear_pending_proposal(conversationId, proposalRef)
+        );
+    }
+
+    /**
+     * Allows to remove a pending commit (rollback). Use this when backend rejects the commit you just sent e.g. if permissions have changed meanwhile.
+     *
+     * **CAUTION**: only use this when you had an explicit response from the Delivery Service
+     * e.g. 403. Do not use otherwise e.g. 5xx responses, timeout etc..
+     * **DO NOT** use when Delivery Service responds 409, pending state will be renewed
+     * in {@link CoreCrypto.decryptMessage}
+     *
+     * @param conversationId - The group's ID
+     */
+    async clearPendingCommit(conversationId: ConversationId): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.clear_pending_commit(conversationId)
+        );
+    }
+
+    /**
+     * Derives a new key from the group
+     *
+     * @param conversationId - The group's ID
+     * @param keyLength - the length of the key to be derived. If the value is higher than the
+     * bounds of `u16` or the context hash * 255, an error will be returned
+     *
+     * @returns A `Uint8Array` representing the derived key
+     */
+    async exportSecretKey(
+        conversationId: ConversationId,
+        keyLength: number
+    ): Promise<Uint8Array> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.export_secret_key(conversationId, keyLength)
+        );
+    }
+
+    /**
+     * Configures how the GroupInfos returned in commits, external commits and credential rotations
+     * are encrypted. They are left in plaintext when neither `key` nor `deriveFromConversation` is supplied.
+     *
+     * @param key - 32 bytes key used to encrypt the GroupInfo of every conversation in a JWE
+     * @param deriveFromConversation - when no `key` is supplied, derive one for each conversation
+     */
+    async setGroupInfoEncryption(
+        key?: Uint8Array,
+        deriveFromConversation: boolean = false
+    ): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.set_group_info_encryption(key, deriveFromConversation)
+        );
     }
 
     /**
-     * This merges the commit generated by {@link CoreCrypto.joinByExternalCommit}, persists the group permanently
-     * and deletes the temporary one. This step makes the group operational and ready to encrypt/decrypt message
+     * Returns the key encrypting the GroupInfos of commits created in the current epoch when
+     * the encryption key is derived from the conversation
      *
-     * @param conversationId - The ID of the conversation
-     * @returns eventually decrypted buffered messages if any
+     * @param conversationId - The group's ID
+     *
+     * @returns A `Uint8Array` representing the key
      */
-    async mergePendingGroupFromExternalCommit(
+    async exportGroupInfoEncryptionKey(
         conversationId: ConversationId
-    ): Promise<BufferedDecryptedMessage[] | undefined> {
+    ): Promise<Uint8Array> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.merge_pending_group_from_external_commit(conversationId)
+            this.#cc.export_group_info_encryption_key(conversationId)
         );
     }
 
     /**
-     * In case the external commit generated by {@link CoreCrypto.joinByExternalCommit} is rejected by the Delivery Service, and we
-     * want to abort this external commit once for all, we can wipe out the pending group from the keystore in order
-     * not to waste space
+     * Configures which representation of the ratchet tree the GroupInfos returned in commits carry
      *
-     * @param conversationId - The ID of the conversation
+     * @param ratchetTreeType - see {@link RatchetTreeType}. Defaults to {@link RatchetTreeType.Full}
      */
-    async clearPendingGroupFromExternalCommit(
-        conversationId: ConversationId
+    async setGroupInfoRatchetTreeType(
+        ratchetTreeType: RatchetTreeType
     ): Promise<void> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.clear_pending_group_from_external_commit(conversationId)
+            this.#cc.set_group_info_ratchet_tree_type(ratchetTreeType)
         );
     }
 
     /**
-     * Allows to mark the latest commit produced as "accepted" and be able to safely merge it into the local group state
+     * Configures how many messages received for a future epoch can be buffered per conversation, and for how long.
+     * When buffering a message evicts older ones, decrypting it fails with an error telling how many were evicted
      *
-     * @param conversationId - The group's ID
-     * @returns the messages from current epoch which had been buffered, if any
+     * @param maxCount - maximum number of buffered messages per conversation. Defaults to 100
+     * @param maxUnknownConversations - maximum number of conversations we are not a member of yet which can have
+     * buffered messages. Defaults to 20
+     * @param maxAgeSecs - time in seconds after which a buffered message expires, as an unsigned 32-bit integer.
+     * `undefined` keeps them. Defaults to a day
      */
-    async commitAccepted(
-        conversationId: ConversationId
-    ): Promise<BufferedDecryptedMessage[] | undefined> {
+    async setPendingMessagesLimits(
+        maxCount: number,
+        maxUnknownConversations: number,
+        maxAgeSecs?: number
+    ): Promise<void> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.commit_accepted(conversationId)
+            this.#cc.set_pending_messages_limits(
+                maxCount,
+                maxUnknownConversations,
+                maxAgeSecs
+            )
         );
     }
 
     /**
-     * Allows to remove a pending proposal (rollback). Use this when backend rejects the proposal you just sent e.g. if permissions have changed meanwhile.
+     * Lists the messages of a conversation buffered while waiting for the commit of their epoch
      *
-     * **CAUTION**: only use this when you had an explicit response from the Delivery Service
-     * e.g. 403 or 409. Do not use otherwise e.g. 5xx responses, timeout etc…
+     * @param conversationId - The group's ID
+     *
+     * @returns the buffered messages, oldest first
+     */
+    async listPendingMessages(
+        conversationId: ConversationId
+    ): Promise<BufferedMessage[]> {
+        const messages: CoreCryptoFfiTypes.BufferedMessage[] =
+            await CoreCryptoError.asyncMapErr(
+                this.#cc.list_pending_messages(conversationId)
+            );
+        return messages.map(mapBufferedMessage);
+    }
+
+    /**
+     * Drops the buffered messages of every conversation which expired or exceed the configured limits.
+     * Meant to be called periodically, see {@link CoreCrypto.setPendingMessagesLimits}
+     *
+     * @returns the evicted messages
+     */
+    async clearExpiredPendingMessages(): Promise<BufferedMessage[]> {
+        const messages: CoreCryptoFfiTypes.BufferedMessage[] =
+            await CoreCryptoError.asyncMapErr(
+                this.#cc.clear_expired_pending_messages()
+            );
+        return messages.map(mapBufferedMessage);
+    }
+
+    /**
+     * Drops all the messages of a conversation buffered while waiting for the commit of their epoch
      *
      * @param conversationId - The group's ID
-     * @param proposalRef - A reference to the proposal to delete. You get one when using {@link CoreCrypto.newProposal}
+     *
+     * @returns the dropped messages
      */
-    async clearPendingProposal(
+    async clearPendingMessages(
+        conversationId: ConversationId
+    ): Promise<BufferedMessage[]> {
+        const messages: CoreCryptoFfiTypes.BufferedMessage[] =
+            await CoreCryptoError.asyncMapErr(
+                this.#cc.clear_pending_messages(conversationId)
+            );
+        return messages.map(mapBufferedMessage);
+    }
+
+    /**
+     * Starts recording the messages of a conversation so that they can be shared with devices joining it later.
+     * Calling it again only updates the limits
+     *
+     * @param conversationId - The group's ID
+     * @param maxEpochs - number of epochs, the current one included, whose messages are shared. Defaults to 100
+     * @param maxMessages - maximum number of shared messages. Defaults to 1000
+     * @param maxAgeSecs - time after which a message is no longer shared, `undefined` to keep them. Defaults to 30 days
+     */
+    async enableHistorySharing(
         conversationId: ConversationId,
-        proposalRef: ProposalRef
+        maxEpochs: number = 100,
+        maxMessages: number = 1000,
+        maxAgeSecs: number | undefined = 30 * 24 * 60 * 60
     ): Promise<void> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.clear_pending_proposal(conversationId, proposalRef)
+            this.#cc.enable_history_sharing(
+                conversationId,
+                maxEpochs,
+                maxMessages,
+                maxAgeSecs
+            )
         );
     }
 
     /**
-     * Allows to remove a pending commit (rollback). Use this when backend rejects the commit you just sent e.g. if permissions have changed meanwhile.
+     * Stops recording the messages of a conversation and deletes the recorded ones
      *
-     * **CAUTION**: only use this when you had an explicit response from the Delivery Service
-     * e.g. 403. Do not use otherwise e.g. 5xx responses, timeout etc..
-     * **DO NOT** use when Delivery Service responds 409, pending state will be renewed
-     * in {@link CoreCrypto.decryptMessage}
+     * @param conversationId - The group's ID
+     */
+    async disableHistorySharing(conversationId: ConversationId): Promise<void> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.disable_history_sharing(conversationId)
+        );
+    }
+
+    /**
+     * Packages the recorded history of a conversation in an archive encrypted under a secret of the current epoch
      *
      * @param conversationId - The group's ID
+     *
+     * @returns the encrypted archive
      */
-    async clearPendingCommit(conversationId: ConversationId): Promise<void> {
+    async exportHistory(conversationId: ConversationId): Promise<Uint8Array> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.clear_pending_commit(conversationId)
+            this.#cc.export_history(conversationId)
         );
     }
 
     /**
-     * Derives a new key from the group
+     * Exports the history of a conversation and encrypts it as an application message, with `"wire history archive"`
+     * as additional authenticated data. Receivers pass the decrypted message to {@link CoreCrypto.importHistory}. Meant to be sent right after a new device joined the conversation
      *
      * @param conversationId - The group's ID
-     * @param keyLength - the length of the key to be derived. If the value is higher than the
-     * bounds of `u16` or the context hash * 255, an error will be returned
      *
-     * @returns A `Uint8Array` representing the derived key
+     * @returns the encrypted message
      */
-    async exportSecretKey(
+    async shareHistory(conversationId: ConversationId): Promise<Uint8Array> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.share_history(conversationId)
+        );
+    }
+
+    /**
+     * Opens a history archive. It has to be imported while in the epoch it was created in, usually right after
+     * decrypting the message carrying it
+     *
+     * The messages, their senders included, are not authenticated by whoever originally sent them: they are exactly as
+     * trustworthy as the member who shared the archive, i.e. the sender of the message carrying it.
+     *
+     * @param conversationId - The group's ID
+     * @param archive - the archive, see {@link CoreCrypto.exportHistory}
+     *
+     * @returns the messages of the archive, oldest first
+     */
+    async importHistory(
         conversationId: ConversationId,
-        keyLength: number
+        archive: Uint8Array
+    ): Promise<HistoryMessage[]> {
+        const messages: CoreCryptoFfiTypes.HistoryMessage[] =
+            await CoreCryptoError.asyncMapErr(
+                this.#cc.import_history(conversationId, archive)
+            );
+        return messages.map(mapHistoryMessage);
+    }
+
+    /**
+     * Rebuilds the complete GroupInfo out of a {@link RatchetTreeType.Delta} one
+     *
+     * @param delta - the payload of a {@link GroupInfoBundle} with {@link RatchetTreeType.Delta}, encrypted or not
+     * @param previousGroupInfo - the complete GroupInfo of the previous epoch
+     * @param key - key to decrypt the payload with, when it is encrypted
+     *
+     * @returns the complete GroupInfo, which can in turn be used to rebuild the one of the next epoch
+     */
+    async rebuildGroupInfo(
+        delta: Uint8Array,
+        previousGroupInfo: Uint8Array,
+        key?: Uint8Array
     ): Promise<Uint8Array> {
         return await CoreCryptoError.asyncMapErr(
-            this.#cc.export_secret_key(conversationId, keyLength)
+            this.#cc.rebuild_group_info(delta, previousGroupInfo, key)
+        );
+    }
+
+    /**
+     * Returns the TLS-serialized ratchet tree of the current epoch of a conversation. With {@link RatchetTreeType.ByRef},
+     * upload it to the Delivery Service alongside the GroupInfo once the commit has been accepted
+     *
+     * @param conversationId - The group's ID
+     */
+    async exportRatchetTree(
+        conversationId: ConversationId
+    ): Promise<Uint8Array> {
+        return await CoreCryptoError.asyncMapErr(
+            this.#cc.export_ratchet_tree(conversationId)
         );
     }
 
@@ -1839,6 +3139,157 @@ export class CoreCrypto {
         );
     }
 
+    /**
+     * Replaces the external senders of a conversation e.g. when the Delivery Service rotated its removal key
+     *
+     * @param conversationId - The group's ID
+     * @param externalSenders - keys of the external senders, either JWK or raw
+     *
+     * **CAUTION**: {@link CoreCrypto.commitAccepted} **HAS TO** be called afterward **ONLY IF** the Delivery Service responds
+     * '200 OK' to the {@link CommitBundle} upload.
+     *
+     * @returns A {@link CommitBundle}
+     */
+    async updateExternalSenders(
+        conversationId: ConversationId,
+        externalSenders: Uint8Array[]
+    ): Promise<CommitBundle> {
+        try {
+            const ffiRet: CoreCryptoFfiTypes.CommitBundle =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.update_external_senders(
+                        conversationId,
+                        externalSenders
+                    )
+                );
+
+            const gi = ffiRet.group_info;
+
+            const ret: CommitBundle = {
+                welcome: ffiRet.welcome,
+                commit: ffiRet.commit,
+                groupInfo: {
+                    encryptionType: gi.encryption_type,
+                    ratchetTreeType: gi.ratchet_tree_type,
+                    payload: gi.payload,
+                },
+            };
+
+            return ret;
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
+    }
+
+    /**
+     * Adds extension and proposal types to the capabilities every member of a conversation is required to support
+     *
+     * @param conversationId - The group's ID
+     * @param extensionTypes - extension types to require
+     * @param proposalTypes - proposal types to require
+     *
+     * **CAUTION**: {@link CoreCrypto.commitAccepted} **HAS TO** be called afterward **ONLY IF** the Delivery Service responds
+     * '200 OK' to the {@link CommitBundle} upload.
+     *
+     * @returns A {@link CommitBundle}
+     */
+    async addRequiredCapabilities(
+        conversationId: ConversationId,
+        extensionTypes: number[],
+        proposalTypes: number[]
+    ): Promise<CommitBundle> {
+        try {
+            const ffiRet: CoreCryptoFfiTypes.CommitBundle =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.add_required_capabilities(
+                        conversationId,
+                        Uint16Array.of(...extensionTypes),
+                        Uint16Array.of(...proposalTypes)
+                    )
+                );
+
+            const gi = ffiRet.group_info;
+
+            const ret: CommitBundle = {
+                welcome: ffiRet.welcome,
+                commit: ffiRet.commit,
+                groupInfo: {
+                    encryptionType: gi.encryption_type,
+                    ratchetTreeType: gi.ratchet_tree_type,
+                    payload: gi.payload,
+                },
+            };
+
+            return ret;
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
+    }
+
+    /**
+     * Replaces the application specific extensions of a conversation. Their types have to be advertised in the
+     * capabilities of every member.
+     *
+     * @param conversationId - The group's ID
+     * @param appExtensions - the new extensions. An empty list removes them all
+     *
+     * **CAUTION**: {@link CoreCrypto.commitAccepted} **HAS TO** be called afterward **ONLY IF** the Delivery Service responds
+     * '200 OK' to the {@link CommitBundle} upload.
+     *
+     * @returns A {@link CommitBundle}
+     */
+    async updateAppExtensions(
+        conversationId: ConversationId,
+        appExtensions: AppExtension[]
+    ): Promise<CommitBundle> {
+        try {
+            const ffiRet: CoreCryptoFfiTypes.CommitBundle =
+                await CoreCryptoError.asyncMapErr(
+                    this.#cc.update_app_extensions(
+                        conversationId,
+                        Uint16Array.of(
+                            ...appExtensions.map((e) => e.extensionType)
+                        ),
+                        appExtensions.map((e) => e.data)
+                    )
+                );
+
+            const gi = ffiRet.group_info;
+
+            const ret: CommitBundle = {
+                welcome: ffiRet.welcome,
+                commit: ffiRet.commit,
+                groupInfo: {
+                    encryptionType: gi.encryption_type,
+                    ratchetTreeType: gi.ratchet_tree_type,
+                    payload: gi.payload,
+                },
+            };
+
+            return ret;
+        } catch (e) {
+            throw CoreCryptoError.fromStdError(e as Error);
+        }
+    }
+
+    /**
+     * Lists the application specific extensions of a conversation, see {@link CoreCrypto.updateAppExtensions}
+     *
+     * @param conversationId - The group's ID
+     */
+    async appExtensions(
+        conversationId: ConversationId
+    ): Promise<AppExtension[]> {
+        const ffiRet: CoreCryptoFfiTypes.AppExtension[] =
+            await CoreCryptoError.asyncMapErr(
+                this.#cc.app_extensions(conversationId)
+            );
+        return ffiRet.map((e) => ({
+            extensionType: e.extension_type,
+            data: e.data,
+        }));
+    }
+
     /**
      * Returns all clients from group's members
      *
@@ -1852,6 +3303,35 @@ export class CoreCrypto {
         );
     }
 
+    /**
+     * Returns a detailed view of the members of a conversation, followed by the clients pending proposals would add
+     *
+     * @param conversationId - The group's ID
+     *
+     * @returns the members ordered by leaf index, then the pending additions
+     */
+    async getConversationRoster(
+        conversationId: ConversationId
+    ): Promise<ConversationMember[]> {
+        const roster: CoreCryptoFfiTypes.ConversationMember[] =
+            await CoreCryptoError.asyncMapErr(
+                this.#cc.get_conversation_roster(conversationId)
+            );
+        return roster.map((m) => ({
+            clientId: m.client_id,
+            leafIndex: m.leaf_index,
+            credentialType: normalizeEnum(CredentialType, m.credential_type),
+            signatureKey: m.signature_key,
+            ciphersuites: Array.from(m.ciphersuites),
+            extensions: Array.from(m.extensions),
+            addedInEpoch:
+                m.added_in_epoch !== undefined
+                    ? Number(m.added_in_epoch)
+                    : undefined,
+            status: normalizeEnum(MemberStatus, m.status),
+        }));
+    }
+
     /**
      * Allows {@link CoreCrypto} to act as a CSPRNG provider
      * @note The underlying CSPRNG algorithm is ChaCha20 and takes in account the external seed provider either at init time or provided with {@link CoreCrypto.reseedRng}
@@ -2458,6 +3938,17 @@ export class CoreCrypto {
         this.#assertModuleLoaded();
         return CoreCryptoFfi.version();
     }
+
+    /**
+     * Returns the version of the encoding of {@link RatchetTreeType.Delta} GroupInfos produced and understood by this
+     * client. Deltas with any other version are rejected by {@link CoreCrypto.rebuildGroupInfo}.
+     *
+     * @returns The ratchet tree delta version
+     */
+    static ratchetTreeDeltaVersion(): number {
+        this.#assertModuleLoaded();
+        return CoreCryptoFfi.ratchet_tree_delta_version();
+    }
 }
 
 type JsonRawData = Uint8Array;
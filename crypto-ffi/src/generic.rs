@@ -25,8 +25,8 @@ use core_crypto::{
         ClientIdentifier, CryptoError, E2eIdentityError, EntropySeed, KeyPackageIn, KeyPackageRef,
         MlsBufferedConversationDecryptMessage, MlsCentral, MlsCentralConfiguration, MlsCiphersuite, MlsCommitBundle,
         MlsConversationConfiguration, MlsConversationCreationMessage, MlsConversationDecryptMessage,
-        MlsConversationInitBundle, MlsCustomConfiguration, MlsGroupInfoBundle, MlsProposalBundle, MlsRotateBundle,
-        VerifiableGroupInfo,
+        MlsConversationInitBundle, MlsCustomConfiguration, MlsGroupInfoBundle, MlsGroupInfoEncryption,
+        MlsGroupInfoEncryptionKey, MlsMessageIn, MlsProposalBundle, MlsReInit, MlsRotateBundle,
     },
     MlsError,
 };
@@ -43,6 +43,12 @@ pub fn version() -> String {
     VERSION.to_string()
 }
 
+/// See [core_crypto::prelude::RATCHET_TREE_DELTA_VERSION]
+#[uniffi::export]
+pub fn ratchet_tree_delta_version() -> u8 {
+    core_crypto::prelude::RATCHET_TREE_DELTA_VERSION
+}
+
 #[derive(Debug, thiserror::Error, uniffi::Error)]
 pub enum CoreCryptoError {
     #[error(transparent)]
@@ -121,6 +127,12 @@ impl From<Ciphersuite> for MlsCiphersuite {
     }
 }
 
+impl From<MlsCiphersuite> for Ciphersuite {
+    fn from(cs: MlsCiphersuite) -> Self {
+        Self(cs.into())
+    }
+}
+
 impl UniffiCustomTypeConverter for Ciphersuite {
     type Builtin = u16;
 
@@ -226,19 +238,40 @@ impl TryFrom<MlsConversationCreationMessage> for MemberAddedMessages {
     }
 }
 
+#[derive(Debug, Clone, uniffi::Record)]
+/// An application specific GroupContext extension, see [core_crypto::mls::MlsCentral::update_app_extensions]
+pub struct AppExtension {
+    pub extension_type: u16,
+    pub data: Vec<u8>,
+}
+
 #[derive(Debug, uniffi::Record)]
 /// see [core_crypto::prelude::MlsConversationCreationMessage]
 pub struct WelcomeBundle {
     pub id: ConversationId,
     pub crl_new_distribution_points: Option<Vec<String>>,
+    pub buffered_messages: Option<Vec<BufferedDecryptedMessage>>,
 }
 
-impl From<core_crypto::prelude::WelcomeBundle> for WelcomeBundle {
-    fn from(w: core_crypto::prelude::WelcomeBundle) -> Self {
-        Self {
+impl TryFrom<core_crypto::prelude::WelcomeBundle> for WelcomeBundle {
+    type Error = CoreCryptoError;
+
+    fn try_from(w: core_crypto::prelude::WelcomeBundle) -> Result<Self, Self::Error> {
+        let buffered_messages = if let Some(bm) = w.buffered_messages {
+            let bm = bm
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<CoreCryptoResult<Vec<_>>>()?;
+            Some(bm)
+        } else {
+            None
+        };
+
+        Ok(Self {
             id: w.id,
             crl_new_distribution_points: w.crl_new_distribution_points.into(),
-        }
+            buffered_messages,
+        })
     }
 }
 
@@ -294,10 +327,10 @@ impl From<MlsGroupInfoEncryptionType> for core_crypto::prelude::MlsGroupInfoEncr
 pub enum MlsRatchetTreeType {
     /// Plain old and complete `GroupInfo`
     Full = 1,
-    /// Contains `GroupInfo` changes since previous epoch (not yet implemented)
-    /// (see [draft](https://github.com/rohan-wire/ietf-drafts/blob/main/mahy-mls-ratchet-tree-delta/draft-mahy-mls-ratchet-tree-delta.md))
+    /// Contains `GroupInfo` changes since previous epoch, in a versioned encoding which deviates from the
+    /// [draft](https://github.com/rohan-wire/ietf-drafts/blob/main/mahy-mls-ratchet-tree-delta/draft-mahy-mls-ratchet-tree-delta.md)
     Delta = 2,
-    /// TODO: to define
+    /// `GroupInfo` without ratchet tree, which is served separately
     ByRef = 3,
 }
 
@@ -418,10 +451,12 @@ pub struct DecryptedMessage {
     pub is_active: bool,
     pub commit_delay: Option<u64>,
     pub sender_client_id: Option<ClientId>,
+    pub aad: Option<Vec<u8>>,
     pub has_epoch_changed: bool,
     pub identity: WireIdentity,
     pub buffered_messages: Option<Vec<BufferedDecryptedMessage>>,
     pub crl_new_distribution_points: Option<Vec<String>>,
+    pub reinit: Option<ReInit>,
 }
 
 #[derive(Debug, uniffi::Record)]
@@ -432,9 +467,191 @@ pub struct BufferedDecryptedMessage {
     pub is_active: bool,
     pub commit_delay: Option<u64>,
     pub sender_client_id: Option<ClientId>,
+    pub aad: Option<Vec<u8>>,
     pub has_epoch_changed: bool,
     pub identity: WireIdentity,
     pub crl_new_distribution_points: Option<Vec<String>>,
+    pub reinit: Option<ReInit>,
+}
+
+#[derive(Debug, Clone, uniffi::Record)]
+/// See [core_crypto::prelude::MlsReInit]
+pub struct ReInit {
+    pub group_id: Vec<u8>,
+    pub ciphersuite: Ciphersuite,
+}
+
+impl From<MlsReInit> for ReInit {
+    fn from(from: MlsReInit) -> Self {
+        Self {
+            group_id: from.group_id,
+            ciphersuite: from.ciphersuite.into(),
+        }
+    }
+}
+
+impl From<ReInit> for MlsReInit {
+    fn from(from: ReInit) -> Self {
+        Self {
+            group_id: from.group_id,
+            ciphersuite: from.ciphersuite.into(),
+        }
+    }
+}
+
+#[derive(Debug, uniffi::Record)]
+/// A message to decrypt as part of a batch, see [core_crypto::mls::MlsCentral::decrypt_messages]
+pub struct BatchedMessage {
+    pub conversation_id: Vec<u8>,
+    pub payload: Vec<u8>,
+}
+
+#[derive(Debug, uniffi::Record)]
+/// Outcome of decrypting one message of a batch, see [core_crypto::mls::MlsCentral::decrypt_messages]
+pub struct BatchDecryptedMessage {
+    pub message: Option<DecryptedMessage>,
+    /// Set when this message could not be decrypted
+    pub error: Option<String>,
+}
+
+impl From<CoreCryptoResult<DecryptedMessage>> for BatchDecryptedMessage {
+    fn from(from: CoreCryptoResult<DecryptedMessage>) -> Self {
+        match from {
+            Ok(message) => Self {
+                message: Some(message),
+                error: None,
+            },
+            Err(e) => Self {
+                message: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, uniffi::Enum)]
+#[repr(u8)]
+/// see [core_crypto::prelude::SafetyNumberFormat]
+pub enum SafetyNumberFormat {
+    /// Groups of 5 digits
+    Numeric = 1,
+    /// Emojis
+    Emoji = 2,
+}
+
+impl SafetyNumberFormat {
+    fn with_length(self, length: u8) -> core_crypto::prelude::SafetyNumberFormat {
+        match self {
+            Self::Numeric => core_crypto::prelude::SafetyNumberFormat::Numeric { groups: length },
+            Self::Emoji => core_crypto::prelude::SafetyNumberFormat::Emoji { count: length },
+        }
+    }
+}
+
+#[derive(Debug, uniffi::Record)]
+/// see [core_crypto::prelude::MlsEpochAuthenticator]
+pub struct EpochAuthenticator {
+    pub epoch: u64,
+    pub authenticator: Vec<u8>,
+}
+
+impl From<core_crypto::prelude::MlsEpochAuthenticator> for EpochAuthenticator {
+    fn from(from: core_crypto::prelude::MlsEpochAuthenticator) -> Self {
+        Self {
+            epoch: from.epoch,
+            authenticator: from.authenticator,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+#[repr(u8)]
+/// see [core_crypto::prelude::MlsMemberStatus]
+pub enum MemberStatus {
+    /// Member of the current epoch
+    Active = 1,
+    /// Member of the current epoch whom a pending proposal removes
+    PendingRemoval = 2,
+    /// Added by a pending proposal, not a member of the current epoch yet
+    PendingAddition = 3,
+}
+
+impl From<core_crypto::prelude::MlsMemberStatus> for MemberStatus {
+    fn from(status: core_crypto::prelude::MlsMemberStatus) -> Self {
+        match status {
+            core_crypto::prelude::MlsMemberStatus::Active => Self::Active,
+            core_crypto::prelude::MlsMemberStatus::PendingRemoval => Self::PendingRemoval,
+            core_crypto::prelude::MlsMemberStatus::PendingAddition => Self::PendingAddition,
+        }
+    }
+}
+
+#[derive(Debug, uniffi::Record)]
+/// see [core_crypto::prelude::MlsConversationMember]
+pub struct ConversationMember {
+    pub client_id: ClientId,
+    pub leaf_index: Option<u32>,
+    pub credential_type: MlsCredentialType,
+    pub signature_key: Vec<u8>,
+    pub ciphersuites: Vec<u16>,
+    pub extensions: Vec<u16>,
+    pub added_in_epoch: Option<u64>,
+    pub status: MemberStatus,
+}
+
+impl From<core_crypto::prelude::MlsConversationMember> for ConversationMember {
+    fn from(member: core_crypto::prelude::MlsConversationMember) -> Self {
+        Self {
+            client_id: ClientId(member.client_id),
+            leaf_index: member.leaf_index,
+            credential_type: member.credential_type.into(),
+            signature_key: member.signature_key,
+            ciphersuites: member.ciphersuites,
+            extensions: member.extensions,
+            added_in_epoch: member.added_in_epoch,
+            status: member.status.into(),
+        }
+    }
+}
+
+#[derive(Debug, uniffi::Record)]
+/// see [core_crypto::prelude::MlsBufferedMessage]
+pub struct BufferedMessage {
+    pub conversation_id: Vec<u8>,
+    pub epoch: u64,
+    pub received_at: u64,
+    pub message: Vec<u8>,
+}
+
+impl From<core_crypto::prelude::MlsBufferedMessage> for BufferedMessage {
+    fn from(from: core_crypto::prelude::MlsBufferedMessage) -> Self {
+        Self {
+            conversation_id: from.conversation_id,
+            epoch: from.epoch,
+            received_at: from.received_at,
+            message: from.message,
+        }
+    }
+}
+
+#[derive(Debug, uniffi::Record)]
+/// see [core_crypto::prelude::MlsHistoryMessage]
+pub struct HistoryMessage {
+    pub epoch: u64,
+    pub sender_client_id: ClientId,
+    pub received_at: u64,
+    pub message: Vec<u8>,
+}
+
+impl From<core_crypto::prelude::MlsHistoryMessage> for HistoryMessage {
+    fn from(from: core_crypto::prelude::MlsHistoryMessage) -> Self {
+        Self {
+            epoch: from.epoch,
+            sender_client_id: ClientId(from.sender_client_id),
+            received_at: from.received_at,
+            message: from.message,
+        }
+    }
 }
 
 impl TryFrom<MlsConversationDecryptMessage> for DecryptedMessage {
@@ -463,10 +680,12 @@ impl TryFrom<MlsConversationDecryptMessage> for DecryptedMessage {
             is_active: from.is_active,
             commit_delay: from.delay,
             sender_client_id: from.sender_client_id.map(ClientId),
+            aad: from.aad,
             has_epoch_changed: from.has_epoch_changed,
             identity: from.identity.into(),
             buffered_messages,
             crl_new_distribution_points: from.crl_new_distribution_points.into(),
+            reinit: from.reinit.map(Into::into),
         })
     }
 }
@@ -487,9 +706,11 @@ impl TryFrom<MlsBufferedConversationDecryptMessage> for BufferedDecryptedMessage
             is_active: from.is_active,
             commit_delay: from.delay,
             sender_client_id: from.sender_client_id.map(ClientId),
+            aad: from.aad,
             has_epoch_changed: from.has_epoch_changed,
             identity: from.identity.into(),
             crl_new_distribution_points: from.crl_new_distribution_points.into(),
+            reinit: from.reinit.map(Into::into),
         })
     }
 }
@@ -891,6 +1112,22 @@ impl CoreCrypto {
             .await?)
     }
 
+    /// See [core_crypto::mls::MlsCentral::export_snapshot]
+    pub async fn export_snapshot(&self, passphrase: String) -> CoreCryptoResult<Vec<u8>> {
+        Ok(self.central.lock().await.export_snapshot(&passphrase).await?)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::import_snapshot]
+    pub async fn import_snapshot(&self, snapshot: Vec<u8>, passphrase: String) -> CoreCryptoResult<ClientId> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .import_snapshot(&snapshot, &passphrase)
+            .await
+            .map(ClientId)?)
+    }
+
     /// See [core_crypto::mls::MlsCentral::restore_from_disk]
     pub async fn restore_from_disk(&self) -> CoreCryptoResult<()> {
         let mut central = self.central.lock().await;
@@ -911,6 +1148,26 @@ impl CoreCrypto {
         Ok(())
     }
 
+    /// See [core_crypto::mls::MlsCentral::begin_transaction]
+    pub async fn begin_transaction(&self) -> CoreCryptoResult<()> {
+        Ok(self.central.lock().await.begin_transaction().await?)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::commit_transaction]
+    pub async fn commit_transaction(&self) -> CoreCryptoResult<()> {
+        Ok(self.central.lock().await.commit_transaction().await?)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::rollback_transaction]
+    pub async fn rollback_transaction(&self) -> CoreCryptoResult<()> {
+        Ok(self.central.lock().await.rollback_transaction().await?)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::rekey]
+    pub async fn rekey(&self, new_key: String) -> CoreCryptoResult<()> {
+        Ok(self.central.lock().await.rekey(&new_key).await?)
+    }
+
     /// See [core_crypto::mls::MlsCentral::close]
     pub async fn unload(self: std::sync::Arc<Self>) -> CoreCryptoResult<()> {
         if let Some(cc) = std::sync::Arc::into_inner(self) {
@@ -1050,19 +1307,74 @@ impl CoreCrypto {
         Ok(Ciphersuite::from(core_crypto::prelude::CiphersuiteName::from(cs)))
     }
 
+    /// See [core_crypto::mls::MlsCentral::epoch_authenticator]
+    pub async fn epoch_authenticator(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<Vec<u8>> {
+        Ok(self.central.lock().await.epoch_authenticator(&conversation_id).await?)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::safety_number]
+    ///
+    /// `length` is the number of digit groups or emojis
+    pub async fn safety_number(
+        &self,
+        conversation_id: Vec<u8>,
+        format: SafetyNumberFormat,
+        length: u8,
+    ) -> CoreCryptoResult<String> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .safety_number(&conversation_id, format.with_length(length))
+            .await?)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::epoch_authenticator_history]
+    pub async fn epoch_authenticator_history(
+        &self,
+        conversation_id: Vec<u8>,
+    ) -> CoreCryptoResult<Vec<EpochAuthenticator>> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .epoch_authenticator_history(&conversation_id)
+            .await?
+            .into_iter()
+            .map(EpochAuthenticator::from)
+            .collect())
+    }
+
     /// See [core_crypto::mls::MlsCentral::process_raw_welcome_message]
     pub async fn process_welcome_message(
         &self,
         welcome_message: Vec<u8>,
         custom_configuration: CustomConfiguration,
     ) -> CoreCryptoResult<WelcomeBundle> {
-        Ok(self
-            .central
+        self.central
             .lock()
             .await
             .process_raw_welcome_message(welcome_message, custom_configuration.into())
             .await?
-            .into())
+            .try_into()
+    }
+
+    /// See [core_crypto::mls::MlsCentral::process_welcome_message_with_ratchet_tree]
+    pub async fn process_welcome_message_with_ratchet_tree(
+        &self,
+        welcome_message: Vec<u8>,
+        ratchet_tree: Vec<u8>,
+        custom_configuration: CustomConfiguration,
+    ) -> CoreCryptoResult<WelcomeBundle> {
+        let welcome_message = MlsMessageIn::tls_deserialize(&mut welcome_message.as_slice())
+            .map_err(MlsError::from)
+            .map_err(CryptoError::from)?;
+        self.central
+            .lock()
+            .await
+            .process_welcome_message_with_ratchet_tree(welcome_message, &ratchet_tree, custom_configuration.into())
+            .await?
+            .try_into()
     }
 
     /// See [core_crypto::mls::MlsCentral::add_members_to_conversation]
@@ -1103,95 +1415,351 @@ impl CoreCrypto {
             .try_into()
     }
 
-    /// See [core_crypto::mls::MlsCentral::mark_conversation_as_child_of]
-    pub async fn mark_conversation_as_child_of(&self, child_id: Vec<u8>, parent_id: Vec<u8>) -> CoreCryptoResult<()> {
-        Ok(self
-            .central
-            .lock()
-            .await
-            .mark_conversation_as_child_of(&child_id, &parent_id)
-            .await?)
-    }
+    /// See [core_crypto::mls::MlsCentral::add_user_to_conversation]
+    pub async fn add_user_to_conversation(
+        &self,
+        conversation_id: Vec<u8>,
+        user_id: String,
+        key_packages: Vec<Vec<u8>>,
+    ) -> CoreCryptoResult<MemberAddedMessages> {
+        let key_packages = key_packages
+            .into_iter()
+            .map(|kp| {
+                KeyPackageIn::tls_deserialize(&mut kp.as_slice()).map_err(|e| CoreCryptoError::CryptoError {
+                    error: CryptoError::MlsError(e.into()),
+                })
+            })
+            .collect::<CoreCryptoResult<Vec<_>>>()?;
 
-    /// See [core_crypto::mls::MlsCentral::update_keying_material]
-    pub async fn update_keying_material(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<CommitBundle> {
         self.central
             .lock()
             .await
-            .update_keying_material(&conversation_id)
+            .add_user_to_conversation(&conversation_id, &user_id, key_packages)
             .await?
             .try_into()
     }
 
-    /// See [core_crypto::mls::MlsCentral::commit_pending_proposals]
-    pub async fn commit_pending_proposals(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<Option<CommitBundle>> {
+    /// See [core_crypto::mls::MlsCentral::remove_user_from_conversation]
+    pub async fn remove_user_from_conversation(
+        &self,
+        conversation_id: Vec<u8>,
+        user_id: String,
+    ) -> CoreCryptoResult<CommitBundle> {
         self.central
             .lock()
             .await
-            .commit_pending_proposals(&conversation_id)
-            .await
-            .transpose()
-            .map(|r| r?.try_into())
-            .transpose()
+            .remove_user_from_conversation(&conversation_id, &user_id)
+            .await?
+            .try_into()
     }
 
-    /// see [core_crypto::mls::MlsCentral::wipe_conversation]
-    pub async fn wipe_conversation(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<()> {
-        Ok(self.central.lock().await.wipe_conversation(&conversation_id).await?)
+    /// See [core_crypto::mls::MlsCentral::mark_conversation_as_child_of]
+    pub async fn mark_conversation_as_child_of(&self, child_id: Vec<u8>, parent_id: Vec<u8>) -> CoreCryptoResult<()> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .mark_conversation_as_child_of(&child_id, &parent_id)
+            .await?)
     }
 
-    /// See [core_crypto::mls::MlsCentral::decrypt_message]
-    pub async fn decrypt_message(
+    /// See [core_crypto::mls::MlsCentral::create_subconversation]
+    pub async fn create_subconversation(
         &self,
+        parent_id: Vec<u8>,
         conversation_id: Vec<u8>,
-        payload: Vec<u8>,
-    ) -> CoreCryptoResult<DecryptedMessage> {
-        let raw_decrypted_message = self
-            .central
+        key_packages: Vec<Vec<u8>>,
+    ) -> CoreCryptoResult<MemberAddedMessages> {
+        let key_packages = key_packages
+            .into_iter()
+            .map(|kp| {
+                KeyPackageIn::tls_deserialize(&mut kp.as_slice()).map_err(|e| CoreCryptoError::CryptoError {
+                    error: CryptoError::MlsError(e.into()),
+                })
+            })
+            .collect::<CoreCryptoResult<Vec<_>>>()?;
+
+        self.central
             .lock()
             .await
-            .decrypt_message(&conversation_id, payload)
-            .await?;
-
-        let decrypted_message: DecryptedMessage = raw_decrypted_message.try_into()?;
+            .create_subconversation(&parent_id, &conversation_id, key_packages)
+            .await?
+            .try_into()
+    }
 
-        Ok(decrypted_message)
+    /// See [core_crypto::mls::MlsCentral::join_subconversation]
+    pub async fn join_subconversation(
+        &self,
+        parent_id: Vec<u8>,
+        welcome_message: Vec<u8>,
+        custom_configuration: CustomConfiguration,
+    ) -> CoreCryptoResult<WelcomeBundle> {
+        let welcome_message = MlsMessageIn::tls_deserialize(&mut welcome_message.as_slice())
+            .map_err(MlsError::from)
+            .map_err(CryptoError::from)?;
+        self.central
+            .lock()
+            .await
+            .join_subconversation(&parent_id, welcome_message, custom_configuration.into())
+            .await?
+            .try_into()
     }
 
-    /// See [core_crypto::mls::MlsCentral::encrypt_message]
-    pub async fn encrypt_message(&self, conversation_id: Vec<u8>, message: Vec<u8>) -> CoreCryptoResult<Vec<u8>> {
+    /// See [core_crypto::mls::MlsCentral::list_subconversations]
+    pub async fn list_subconversations(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<Vec<Vec<u8>>> {
         Ok(self
             .central
             .lock()
             .await
-            .encrypt_message(&conversation_id, message)
+            .list_subconversations(&conversation_id)
             .await?)
     }
 
-    /// See [core_crypto::mls::MlsCentral::conversation_exists]
-    pub async fn conversation_exists(&self, conversation_id: Vec<u8>) -> bool {
-        self.central.lock().await.conversation_exists(&conversation_id).await
-    }
-
-    /// See [core_crypto::mls::MlsCentral::new_add_proposal]
-    pub async fn new_add_proposal(
+    /// See [core_crypto::mls::MlsCentral::reinit_conversation]
+    pub async fn reinit_conversation(
         &self,
         conversation_id: Vec<u8>,
-        keypackage: Vec<u8>,
-    ) -> CoreCryptoResult<ProposalBundle> {
-        let kp = KeyPackageIn::tls_deserialize(&mut keypackage.as_slice())
-            .map_err(MlsError::from)
-            .map_err(CryptoError::from)?;
+        reinit: ReInit,
+    ) -> CoreCryptoResult<CommitBundle> {
         self.central
             .lock()
             .await
-            .new_add_proposal(&conversation_id, kp.into())
+            .reinit_conversation(&conversation_id, reinit.into())
             .await?
             .try_into()
     }
 
-    /// See [core_crypto::mls::MlsCentral::new_update_proposal]
-    pub async fn new_update_proposal(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<ProposalBundle> {
+    /// See [core_crypto::mls::MlsCentral::create_reinit_successor]
+    pub async fn create_reinit_successor(
+        &self,
+        conversation_id: Vec<u8>,
+        reinit: ReInit,
+        key_packages: Vec<Vec<u8>>,
+    ) -> CoreCryptoResult<MemberAddedMessages> {
+        let key_packages = key_packages
+            .into_iter()
+            .map(|kp| {
+                KeyPackageIn::tls_deserialize(&mut kp.as_slice()).map_err(|e| CoreCryptoError::CryptoError {
+                    error: CryptoError::MlsError(e.into()),
+                })
+            })
+            .collect::<CoreCryptoResult<Vec<_>>>()?;
+
+        self.central
+            .lock()
+            .await
+            .create_reinit_successor(&conversation_id, reinit.into(), key_packages)
+            .await?
+            .try_into()
+    }
+
+    /// See [core_crypto::mls::MlsCentral::join_reinit_successor]
+    pub async fn join_reinit_successor(
+        &self,
+        conversation_id: Vec<u8>,
+        welcome_message: Vec<u8>,
+        custom_configuration: CustomConfiguration,
+    ) -> CoreCryptoResult<WelcomeBundle> {
+        let welcome_message = MlsMessageIn::tls_deserialize(&mut welcome_message.as_slice())
+            .map_err(MlsError::from)
+            .map_err(CryptoError::from)?;
+        self.central
+            .lock()
+            .await
+            .join_reinit_successor(&conversation_id, welcome_message, custom_configuration.into())
+            .await?
+            .try_into()
+    }
+
+    /// See [core_crypto::mls::MlsCentral::update_keying_material]
+    pub async fn update_keying_material(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<CommitBundle> {
+        self.central
+            .lock()
+            .await
+            .update_keying_material(&conversation_id)
+            .await?
+            .try_into()
+    }
+
+    /// See [core_crypto::mls::MlsCentral::conversations_due_for_key_rotation]
+    pub async fn conversations_due_for_key_rotation(&self) -> CoreCryptoResult<Vec<Vec<u8>>> {
+        Ok(self.central.lock().await.conversations_due_for_key_rotation().await?)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::update_keying_material_when_due]
+    ///
+    /// Conversation ids are hex encoded because uniffi only supports `HashMap<String, T>`
+    pub async fn update_keying_material_when_due(&self) -> CoreCryptoResult<HashMap<String, CommitBundle>> {
+        let commits = self.central.lock().await.update_keying_material_when_due().await?;
+        commits
+            .into_iter()
+            .map(|(id, commit)| CoreCryptoResult::Ok((hex::encode(id), CommitBundle::try_from(commit)?)))
+            .collect()
+    }
+
+    /// See [core_crypto::mls::MlsCentral::set_key_rotation_span]
+    pub async fn set_key_rotation_span(
+        &self,
+        conversation_id: Vec<u8>,
+        key_rotation_span: Option<std::time::Duration>,
+    ) -> CoreCryptoResult<()> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .set_key_rotation_span(&conversation_id, key_rotation_span)
+            .await?)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::register_external_psk]
+    pub async fn register_external_psk(
+        &self,
+        ciphersuite: Ciphersuite,
+        psk_id: Vec<u8>,
+        psk: Vec<u8>,
+    ) -> CoreCryptoResult<()> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .register_external_psk(ciphersuite.into(), psk_id, psk)
+            .await?)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::commit_external_psks]
+    pub async fn commit_external_psks(
+        &self,
+        conversation_id: Vec<u8>,
+        psk_ids: Vec<Vec<u8>>,
+    ) -> CoreCryptoResult<CommitBundle> {
+        self.central
+            .lock()
+            .await
+            .commit_external_psks(&conversation_id, psk_ids)
+            .await?
+            .try_into()
+    }
+
+    /// See [core_crypto::mls::MlsCentral::commit_pending_proposals]
+    pub async fn commit_pending_proposals(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<Option<CommitBundle>> {
+        self.central
+            .lock()
+            .await
+            .commit_pending_proposals(&conversation_id)
+            .await
+            .transpose()
+            .map(|r| r?.try_into())
+            .transpose()
+    }
+
+    /// see [core_crypto::mls::MlsCentral::wipe_conversation]
+    pub async fn wipe_conversation(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<()> {
+        Ok(self.central.lock().await.wipe_conversation(&conversation_id).await?)
+    }
+
+    /// see [core_crypto::mls::MlsCentral::leave_conversation]
+    pub async fn leave_conversation(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<ProposalBundle> {
+        self.central
+            .lock()
+            .await
+            .leave_conversation(&conversation_id)
+            .await?
+            .try_into()
+    }
+
+    /// see [core_crypto::mls::MlsCentral::leave_proposal_sent]
+    pub async fn leave_proposal_sent(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<()> {
+        Ok(self.central.lock().await.leave_proposal_sent(&conversation_id).await?)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::decrypt_message]
+    pub async fn decrypt_message(
+        &self,
+        conversation_id: Vec<u8>,
+        payload: Vec<u8>,
+    ) -> CoreCryptoResult<DecryptedMessage> {
+        let raw_decrypted_message = self
+            .central
+            .lock()
+            .await
+            .decrypt_message(&conversation_id, payload)
+            .await?;
+
+        let decrypted_message: DecryptedMessage = raw_decrypted_message.try_into()?;
+
+        Ok(decrypted_message)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::decrypt_messages]
+    pub async fn decrypt_messages(
+        &self,
+        messages: Vec<BatchedMessage>,
+    ) -> CoreCryptoResult<Vec<BatchDecryptedMessage>> {
+        let messages = messages.into_iter().map(|m| (m.conversation_id, m.payload)).collect();
+        Ok(self
+            .central
+            .lock()
+            .await
+            .decrypt_messages(messages)
+            .await?
+            .into_iter()
+            .map(|r| {
+                r.map_err(CoreCryptoError::from)
+                    .and_then(DecryptedMessage::try_from)
+                    .into()
+            })
+            .collect())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::encrypt_message]
+    pub async fn encrypt_message(&self, conversation_id: Vec<u8>, message: Vec<u8>) -> CoreCryptoResult<Vec<u8>> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .encrypt_message(&conversation_id, message)
+            .await?)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::encrypt_message_with_aad]
+    pub async fn encrypt_message_with_aad(
+        &self,
+        conversation_id: Vec<u8>,
+        message: Vec<u8>,
+        aad: Vec<u8>,
+    ) -> CoreCryptoResult<Vec<u8>> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .encrypt_message_with_aad(&conversation_id, message, aad)
+            .await?)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::conversation_exists]
+    pub async fn conversation_exists(&self, conversation_id: Vec<u8>) -> bool {
+        self.central.lock().await.conversation_exists(&conversation_id).await
+    }
+
+    /// See [core_crypto::mls::MlsCentral::new_add_proposal]
+    pub async fn new_add_proposal(
+        &self,
+        conversation_id: Vec<u8>,
+        keypackage: Vec<u8>,
+    ) -> CoreCryptoResult<ProposalBundle> {
+        let kp = KeyPackageIn::tls_deserialize(&mut keypackage.as_slice())
+            .map_err(MlsError::from)
+            .map_err(CryptoError::from)?;
+        self.central
+            .lock()
+            .await
+            .new_add_proposal(&conversation_id, kp.into())
+            .await?
+            .try_into()
+    }
+
+    /// See [core_crypto::mls::MlsCentral::new_update_proposal]
+    pub async fn new_update_proposal(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<ProposalBundle> {
         self.central
             .lock()
             .await
@@ -1214,6 +1782,34 @@ impl CoreCrypto {
             .try_into()
     }
 
+    /// See [core_crypto::mls::MlsCentral::new_external_psk_proposal]
+    pub async fn new_external_psk_proposal(
+        &self,
+        conversation_id: Vec<u8>,
+        psk_id: Vec<u8>,
+    ) -> CoreCryptoResult<ProposalBundle> {
+        self.central
+            .lock()
+            .await
+            .new_external_psk_proposal(&conversation_id, psk_id)
+            .await?
+            .try_into()
+    }
+
+    /// See [core_crypto::mls::MlsCentral::new_reinit_proposal]
+    pub async fn new_reinit_proposal(
+        &self,
+        conversation_id: Vec<u8>,
+        reinit: ReInit,
+    ) -> CoreCryptoResult<ProposalBundle> {
+        self.central
+            .lock()
+            .await
+            .new_reinit_proposal(&conversation_id, reinit.into())
+            .await?
+            .try_into()
+    }
+
     /// See [core_crypto::mls::MlsCentral::new_external_add_proposal]
     pub async fn new_external_add_proposal(
         &self,
@@ -1245,13 +1841,32 @@ impl CoreCrypto {
         custom_configuration: CustomConfiguration,
         credential_type: MlsCredentialType,
     ) -> CoreCryptoResult<ConversationInitBundle> {
-        let group_info = VerifiableGroupInfo::tls_deserialize(&mut group_info.as_slice())
-            .map_err(MlsError::from)
-            .map_err(CryptoError::from)?;
+        let mut central = self.central.lock().await;
+        let group_info = central.open_group_info(&group_info, None)?;
+        central
+            .join_by_external_commit(group_info, custom_configuration.into(), credential_type.into())
+            .await?
+            .try_into()
+    }
+
+    /// See [core_crypto::mls::MlsCentral::join_by_external_commit_with_ratchet_tree]
+    pub async fn join_by_external_commit_with_ratchet_tree(
+        &self,
+        group_info: Vec<u8>,
+        ratchet_tree: Vec<u8>,
+        custom_configuration: CustomConfiguration,
+        credential_type: MlsCredentialType,
+    ) -> CoreCryptoResult<ConversationInitBundle> {
         self.central
             .lock()
             .await
-            .join_by_external_commit(group_info, custom_configuration.into(), credential_type.into())
+            .join_by_external_commit_with_ratchet_tree(
+                &group_info,
+                &ratchet_tree,
+                None,
+                custom_configuration.into(),
+                credential_type.into(),
+            )
             .await?
             .try_into()
     }
@@ -1354,6 +1969,19 @@ impl CoreCrypto {
             .map(|cids| cids.into_iter().map(ClientId).collect())?)
     }
 
+    /// See [core_crypto::mls::MlsCentral::get_conversation_roster]
+    pub async fn get_conversation_roster(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<Vec<ConversationMember>> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .get_conversation_roster(&conversation_id)
+            .await?
+            .into_iter()
+            .map(ConversationMember::from)
+            .collect())
+    }
+
     /// See [core_crypto::mls::MlsCentral::export_secret_key]
     pub async fn export_secret_key(&self, conversation_id: Vec<u8>, key_length: u32) -> CoreCryptoResult<Vec<u8>> {
         Ok(self
@@ -1364,10 +1992,245 @@ impl CoreCrypto {
             .await?)
     }
 
+    /// See [core_crypto::mls::MlsCentral::set_group_info_encryption]. When `key` is supplied it is
+    /// used for every conversation, otherwise `derive_from_conversation` derives one per conversation.
+    /// Supplying neither disables the encryption.
+    pub async fn set_group_info_encryption(
+        &self,
+        key: Option<Vec<u8>>,
+        derive_from_conversation: bool,
+    ) -> CoreCryptoResult<()> {
+        let encryption = match (key, derive_from_conversation) {
+            (Some(key), _) => Some(MlsGroupInfoEncryption::Key(key.try_into()?)),
+            (None, true) => Some(MlsGroupInfoEncryption::Derived),
+            (None, false) => None,
+        };
+        self.central.lock().await.set_group_info_encryption(encryption);
+        Ok(())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::export_group_info_encryption_key]
+    pub async fn export_group_info_encryption_key(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<Vec<u8>> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .export_group_info_encryption_key(&conversation_id)
+            .await?)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::set_group_info_ratchet_tree_type]
+    pub async fn set_group_info_ratchet_tree_type(
+        &self,
+        ratchet_tree_type: MlsRatchetTreeType,
+    ) -> CoreCryptoResult<()> {
+        self.central
+            .lock()
+            .await
+            .set_group_info_ratchet_tree_type(ratchet_tree_type.into());
+        Ok(())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::set_pending_messages_limits]
+    ///
+    /// `max_age_secs` set to `None` disables the expiry of buffered messages
+    pub async fn set_pending_messages_limits(
+        &self,
+        max_count: u32,
+        max_unknown_conversations: u32,
+        max_age_secs: Option<u32>,
+    ) -> CoreCryptoResult<()> {
+        self.central
+            .lock()
+            .await
+            .set_pending_messages_limits(core_crypto::prelude::MlsPendingMessagesLimits {
+                max_count: max_count as usize,
+                max_unknown_conversations: max_unknown_conversations as usize,
+                max_age: max_age_secs.map(|secs| std::time::Duration::from_secs(secs.into())),
+            });
+        Ok(())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::list_pending_messages]
+    pub async fn list_pending_messages(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<Vec<BufferedMessage>> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .list_pending_messages(&conversation_id)
+            .await?
+            .into_iter()
+            .map(BufferedMessage::from)
+            .collect())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::clear_expired_pending_messages]
+    pub async fn clear_expired_pending_messages(&self) -> CoreCryptoResult<Vec<BufferedMessage>> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .clear_expired_pending_messages()
+            .await?
+            .into_iter()
+            .map(BufferedMessage::from)
+            .collect())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::clear_pending_messages]
+    pub async fn clear_pending_messages(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<Vec<BufferedMessage>> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .clear_pending_messages(&conversation_id)
+            .await?
+            .into_iter()
+            .map(BufferedMessage::from)
+            .collect())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::enable_history_sharing]
+    ///
+    /// `max_age_secs` set to `None` disables the expiry of shared messages
+    pub async fn enable_history_sharing(
+        &self,
+        conversation_id: Vec<u8>,
+        max_epochs: u64,
+        max_messages: u32,
+        max_age_secs: Option<u64>,
+    ) -> CoreCryptoResult<()> {
+        let limits = core_crypto::prelude::MlsHistorySharingLimits {
+            max_epochs,
+            max_messages: max_messages as usize,
+            max_age: max_age_secs.map(std::time::Duration::from_secs),
+        };
+        Ok(self
+            .central
+            .lock()
+            .await
+            .enable_history_sharing(&conversation_id, limits)
+            .await?)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::disable_history_sharing]
+    pub async fn disable_history_sharing(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<()> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .disable_history_sharing(&conversation_id)
+            .await?)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::export_history]
+    pub async fn export_history(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<Vec<u8>> {
+        Ok(self.central.lock().await.export_history(&conversation_id).await?)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::share_history]
+    pub async fn share_history(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<Vec<u8>> {
+        Ok(self.central.lock().await.share_history(&conversation_id).await?)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::import_history]
+    pub async fn import_history(
+        &self,
+        conversation_id: Vec<u8>,
+        archive: Vec<u8>,
+    ) -> CoreCryptoResult<Vec<HistoryMessage>> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .import_history(&conversation_id, archive)
+            .await?
+            .into_iter()
+            .map(HistoryMessage::from)
+            .collect())
+    }
+
+    /// See [core_crypto::mls::MlsCentral::export_ratchet_tree]
+    pub async fn export_ratchet_tree(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<Vec<u8>> {
+        Ok(self.central.lock().await.export_ratchet_tree(&conversation_id).await?)
+    }
+
+    /// See [core_crypto::mls::MlsCentral::rebuild_group_info]
+    pub async fn rebuild_group_info(
+        &self,
+        delta: Vec<u8>,
+        previous_group_info: Vec<u8>,
+        key: Option<Vec<u8>>,
+    ) -> CoreCryptoResult<Vec<u8>> {
+        let key = key.map(MlsGroupInfoEncryptionKey::try_from).transpose()?;
+        Ok(self
+            .central
+            .lock()
+            .await
+            .rebuild_group_info(&delta, &previous_group_info, key)?)
+    }
+
     /// See [core_crypto::mls::MlsCentral::get_external_sender]
     pub async fn get_external_sender(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<Vec<u8>> {
         Ok(self.central.lock().await.get_external_sender(&conversation_id).await?)
     }
+
+    /// See [core_crypto::mls::MlsCentral::update_external_senders]
+    pub async fn update_external_senders(
+        &self,
+        conversation_id: Vec<u8>,
+        external_senders: Vec<Vec<u8>>,
+    ) -> CoreCryptoResult<CommitBundle> {
+        self.central
+            .lock()
+            .await
+            .update_external_senders(&conversation_id, external_senders)
+            .await?
+            .try_into()
+    }
+
+    /// See [core_crypto::mls::MlsCentral::add_required_capabilities]
+    pub async fn add_required_capabilities(
+        &self,
+        conversation_id: Vec<u8>,
+        extension_types: Vec<u16>,
+        proposal_types: Vec<u16>,
+    ) -> CoreCryptoResult<CommitBundle> {
+        self.central
+            .lock()
+            .await
+            .add_required_capabilities(&conversation_id, extension_types, proposal_types)
+            .await?
+            .try_into()
+    }
+
+    /// See [core_crypto::mls::MlsCentral::update_app_extensions]
+    pub async fn update_app_extensions(
+        &self,
+        conversation_id: Vec<u8>,
+        app_extensions: Vec<AppExtension>,
+    ) -> CoreCryptoResult<CommitBundle> {
+        let app_extensions = app_extensions.into_iter().map(|e| (e.extension_type, e.data)).collect();
+        self.central
+            .lock()
+            .await
+            .update_app_extensions(&conversation_id, app_extensions)
+            .await?
+            .try_into()
+    }
+
+    /// See [core_crypto::mls::MlsCentral::app_extensions]
+    pub async fn app_extensions(&self, conversation_id: Vec<u8>) -> CoreCryptoResult<Vec<AppExtension>> {
+        Ok(self
+            .central
+            .lock()
+            .await
+            .app_extensions(&conversation_id)
+            .await?
+            .into_iter()
+            .map(|(extension_type, data)| AppExtension { extension_type, data })
+            .collect())
+    }
 }
 
 #[derive(Debug, Copy, Clone, uniffi::Enum)]
@@ -1843,13 +2706,9 @@ impl CoreCrypto {
         group_info: Vec<u8>,
         credential_type: MlsCredentialType,
     ) -> CoreCryptoResult<E2eiConversationState> {
-        let group_info = VerifiableGroupInfo::tls_deserialize(&mut group_info.as_slice())
-            .map_err(MlsError::from)
-            .map_err(CryptoError::from)?;
-        Ok(self
-            .central
-            .lock()
-            .await
+        let central = self.central.lock().await;
+        let group_info = central.open_group_info(&group_info, None)?;
+        Ok(central
             .get_credential_in_use(group_info, credential_type.into())
             .await?
             .into())
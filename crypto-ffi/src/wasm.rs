@@ -566,6 +566,15 @@ impl TryFrom<MlsConversationInitBundle> for ConversationInitBundle {
     }
 }
 
+#[wasm_bindgen]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+/// An application specific GroupContext extension, see [core_crypto::mls::MlsCentral::update_app_extensions]
+pub struct AppExtension {
+    pub extension_type: u16,
+    #[wasm_bindgen(getter_with_clone)]
+    pub data: Vec<u8>,
+}
+
 #[wasm_bindgen]
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 /// see [core_crypto::prelude::WelcomeBundle]
@@ -574,6 +583,8 @@ pub struct WelcomeBundle {
     id: ConversationId,
     /// New CRL Distribution of members of this group
     crl_new_distribution_points: Option<Vec<String>>,
+    /// Messages of the conversation received before the Welcome, decrypted
+    buffered_messages: Option<Vec<BufferedDecryptedMessage>>,
 }
 
 #[wasm_bindgen]
@@ -589,14 +600,34 @@ impl WelcomeBundle {
             .clone()
             .map(|crl_dp| crl_dp.iter().cloned().map(JsValue::from).collect::<js_sys::Array>())
     }
+
+    #[wasm_bindgen(getter)]
+    pub fn buffered_messages(&self) -> Option<js_sys::Array> {
+        self.buffered_messages
+            .clone()
+            .map(|bm| bm.iter().cloned().map(JsValue::from).collect::<js_sys::Array>())
+    }
 }
 
-impl From<core_crypto::prelude::WelcomeBundle> for WelcomeBundle {
-    fn from(w: core_crypto::prelude::WelcomeBundle) -> Self {
-        Self {
+impl TryFrom<core_crypto::prelude::WelcomeBundle> for WelcomeBundle {
+    type Error = CoreCryptoError;
+
+    fn try_from(w: core_crypto::prelude::WelcomeBundle) -> Result<Self, Self::Error> {
+        let buffered_messages = if let Some(bm) = w.buffered_messages {
+            let bm = bm
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<WasmCryptoResult<Vec<_>>>()?;
+            Some(bm)
+        } else {
+            None
+        };
+
+        Ok(Self {
             id: w.id,
             crl_new_distribution_points: w.crl_new_distribution_points.into(),
-        }
+            buffered_messages,
+        })
     }
 }
 
@@ -611,12 +642,46 @@ pub struct DecryptedMessage {
     /// Commit delay hint (in milliseconds) to prevent clients from hammering the server with epoch changes
     commit_delay: Option<u32>,
     sender_client_id: Option<Vec<u8>>,
+    /// Additional authenticated data bound to an application message
+    aad: Option<Vec<u8>>,
     /// true when the decrypted message resulted in an epoch change i.e. it was a commit
     has_epoch_changed: bool,
     identity: WireIdentity,
     buffered_messages: Option<Vec<BufferedDecryptedMessage>>,
     /// New CRL Distribution of members of this group
     crl_new_distribution_points: Option<Vec<String>>,
+    /// Set when the conversation ended with a ReInit commit, see [core_crypto::prelude::MlsReInit]
+    reinit: Option<ReInit>,
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+/// see [core_crypto::prelude::MlsReInit]
+pub struct ReInit {
+    group_id: Vec<u8>,
+    ciphersuite: Ciphersuite,
+}
+
+impl From<MlsReInit> for ReInit {
+    fn from(from: MlsReInit) -> Self {
+        Self {
+            group_id: from.group_id,
+            ciphersuite: from.ciphersuite.into(),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl ReInit {
+    #[wasm_bindgen(getter)]
+    pub fn group_id(&self) -> Uint8Array {
+        Uint8Array::from(self.group_id.as_slice())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn ciphersuite(&self) -> Ciphersuite {
+        self.ciphersuite
+    }
 }
 
 impl TryFrom<MlsConversationDecryptMessage> for DecryptedMessage {
@@ -651,10 +716,12 @@ impl TryFrom<MlsConversationDecryptMessage> for DecryptedMessage {
             is_active: from.is_active,
             commit_delay,
             sender_client_id: from.sender_client_id.map(ClientId::into),
+            aad: from.aad,
             has_epoch_changed: from.has_epoch_changed,
             identity: from.identity.into(),
             buffered_messages,
             crl_new_distribution_points: from.crl_new_distribution_points.into(),
+            reinit: from.reinit.map(Into::into),
         })
     }
 }
@@ -698,6 +765,15 @@ impl DecryptedMessage {
         }
     }
 
+    #[wasm_bindgen(getter)]
+    pub fn aad(&self) -> JsValue {
+        if let Some(aad) = &self.aad {
+            Uint8Array::from(aad.as_slice()).into()
+        } else {
+            JsValue::NULL
+        }
+    }
+
     #[wasm_bindgen(getter)]
     pub fn has_epoch_changed(&self) -> bool {
         self.has_epoch_changed
@@ -721,6 +797,11 @@ impl DecryptedMessage {
             .clone()
             .map(|crl_dp| crl_dp.iter().cloned().map(JsValue::from).collect::<js_sys::Array>())
     }
+
+    #[wasm_bindgen(getter)]
+    pub fn reinit(&self) -> Option<ReInit> {
+        self.reinit.clone()
+    }
 }
 
 #[wasm_bindgen]
@@ -732,10 +813,12 @@ pub struct BufferedDecryptedMessage {
     is_active: bool,
     commit_delay: Option<u32>,
     sender_client_id: Option<Vec<u8>>,
+    aad: Option<Vec<u8>>,
     has_epoch_changed: bool,
     identity: WireIdentity,
     /// New CRL Distribution of members of this group
     crl_new_distribution_points: Option<Vec<String>>,
+    reinit: Option<ReInit>,
 }
 
 impl TryFrom<MlsBufferedConversationDecryptMessage> for BufferedDecryptedMessage {
@@ -760,9 +843,11 @@ impl TryFrom<MlsBufferedConversationDecryptMessage> for BufferedDecryptedMessage
             is_active: from.is_active,
             commit_delay,
             sender_client_id: from.sender_client_id.map(ClientId::into),
+            aad: from.aad,
             has_epoch_changed: from.has_epoch_changed,
             identity: from.identity.into(),
             crl_new_distribution_points: from.crl_new_distribution_points.into(),
+            reinit: from.reinit.map(Into::into),
         })
     }
 }
@@ -806,6 +891,15 @@ impl BufferedDecryptedMessage {
         }
     }
 
+    #[wasm_bindgen(getter)]
+    pub fn aad(&self) -> JsValue {
+        if let Some(aad) = &self.aad {
+            Uint8Array::from(aad.as_slice()).into()
+        } else {
+            JsValue::NULL
+        }
+    }
+
     #[wasm_bindgen(getter)]
     pub fn has_epoch_changed(&self) -> bool {
         self.has_epoch_changed
@@ -822,6 +916,281 @@ impl BufferedDecryptedMessage {
             .clone()
             .map(|crl_dp| crl_dp.iter().cloned().map(JsValue::from).collect::<js_sys::Array>())
     }
+
+    #[wasm_bindgen(getter)]
+    pub fn reinit(&self) -> Option<ReInit> {
+        self.reinit.clone()
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+/// Outcome of decrypting one message of a batch, see [core_crypto::mls::MlsCentral::decrypt_messages]
+pub struct BatchDecryptedMessage {
+    message: Option<DecryptedMessage>,
+    /// Set when this message could not be decrypted. Same format as the errors thrown by the other methods
+    error: Option<String>,
+}
+
+impl From<CoreCryptoResult<DecryptedMessage>> for BatchDecryptedMessage {
+    fn from(from: CoreCryptoResult<DecryptedMessage>) -> Self {
+        match from {
+            Ok(message) => Self {
+                message: Some(message),
+                error: None,
+            },
+            Err(e) => Self {
+                message: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl BatchDecryptedMessage {
+    #[wasm_bindgen(getter)]
+    pub fn message(&self) -> Option<DecryptedMessage> {
+        self.message.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+/// see [core_crypto::prelude::MlsMemberStatus]
+pub enum MemberStatus {
+    /// Member of the current epoch
+    Active = 1,
+    /// Member of the current epoch whom a pending proposal removes
+    PendingRemoval = 2,
+    /// Added by a pending proposal, not a member of the current epoch yet
+    PendingAddition = 3,
+}
+
+impl From<MlsMemberStatus> for MemberStatus {
+    fn from(status: MlsMemberStatus) -> Self {
+        match status {
+            MlsMemberStatus::Active => Self::Active,
+            MlsMemberStatus::PendingRemoval => Self::PendingRemoval,
+            MlsMemberStatus::PendingAddition => Self::PendingAddition,
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+/// see [core_crypto::prelude::MlsConversationMember]
+pub struct ConversationMember {
+    client_id: Vec<u8>,
+    leaf_index: Option<u32>,
+    credential_type: CredentialType,
+    signature_key: Vec<u8>,
+    ciphersuites: Vec<u16>,
+    extensions: Vec<u16>,
+    added_in_epoch: Option<u64>,
+    status: MemberStatus,
+}
+
+impl From<MlsConversationMember> for ConversationMember {
+    fn from(member: MlsConversationMember) -> Self {
+        Self {
+            client_id: member.client_id.to_vec(),
+            leaf_index: member.leaf_index,
+            credential_type: member.credential_type.into(),
+            signature_key: member.signature_key,
+            ciphersuites: member.ciphersuites,
+            extensions: member.extensions,
+            added_in_epoch: member.added_in_epoch,
+            status: member.status.into(),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl ConversationMember {
+    #[wasm_bindgen(getter)]
+    pub fn client_id(&self) -> Uint8Array {
+        Uint8Array::from(self.client_id.as_slice())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn leaf_index(&self) -> Option<u32> {
+        self.leaf_index
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn credential_type(&self) -> CredentialType {
+        self.credential_type
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn signature_key(&self) -> Uint8Array {
+        Uint8Array::from(self.signature_key.as_slice())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn ciphersuites(&self) -> Vec<u16> {
+        self.ciphersuites.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn extensions(&self) -> Vec<u16> {
+        self.extensions.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn added_in_epoch(&self) -> Option<u64> {
+        self.added_in_epoch
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn status(&self) -> MemberStatus {
+        self.status
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+/// see [core_crypto::prelude::MlsBufferedMessage]
+pub struct BufferedMessage {
+    conversation_id: Vec<u8>,
+    epoch: u64,
+    received_at: u64,
+    message: Vec<u8>,
+}
+
+impl From<MlsBufferedMessage> for BufferedMessage {
+    fn from(from: MlsBufferedMessage) -> Self {
+        Self {
+            conversation_id: from.conversation_id,
+            epoch: from.epoch,
+            received_at: from.received_at,
+            message: from.message,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl BufferedMessage {
+    #[wasm_bindgen(getter)]
+    pub fn conversation_id(&self) -> Uint8Array {
+        Uint8Array::from(self.conversation_id.as_slice())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn received_at(&self) -> u64 {
+        self.received_at
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn message(&self) -> Uint8Array {
+        Uint8Array::from(self.message.as_slice())
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+/// see [core_crypto::prelude::MlsHistoryMessage]
+pub struct HistoryMessage {
+    epoch: u64,
+    sender_client_id: Vec<u8>,
+    received_at: u64,
+    message: Vec<u8>,
+}
+
+impl From<MlsHistoryMessage> for HistoryMessage {
+    fn from(from: MlsHistoryMessage) -> Self {
+        Self {
+            epoch: from.epoch,
+            sender_client_id: from.sender_client_id.to_vec(),
+            received_at: from.received_at,
+            message: from.message,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl HistoryMessage {
+    #[wasm_bindgen(getter)]
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn sender_client_id(&self) -> Uint8Array {
+        Uint8Array::from(self.sender_client_id.as_slice())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn received_at(&self) -> u64 {
+        self.received_at
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn message(&self) -> Uint8Array {
+        Uint8Array::from(self.message.as_slice())
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+/// see [core_crypto::prelude::SafetyNumberFormat]
+pub enum SafetyNumberFormat {
+    /// Groups of 5 digits
+    Numeric = 1,
+    /// Emojis
+    Emoji = 2,
+}
+
+impl SafetyNumberFormat {
+    fn with_length(self, length: u8) -> core_crypto::prelude::SafetyNumberFormat {
+        match self {
+            Self::Numeric => core_crypto::prelude::SafetyNumberFormat::Numeric { groups: length },
+            Self::Emoji => core_crypto::prelude::SafetyNumberFormat::Emoji { count: length },
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+/// see [core_crypto::prelude::MlsEpochAuthenticator]
+pub struct EpochAuthenticator {
+    epoch: u64,
+    authenticator: Vec<u8>,
+}
+
+impl From<MlsEpochAuthenticator> for EpochAuthenticator {
+    fn from(from: MlsEpochAuthenticator) -> Self {
+        Self {
+            epoch: from.epoch,
+            authenticator: from.authenticator,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl EpochAuthenticator {
+    #[wasm_bindgen(getter)]
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn authenticator(&self) -> Uint8Array {
+        Uint8Array::from(self.authenticator.as_slice())
+    }
 }
 
 #[wasm_bindgen]
@@ -1270,6 +1639,11 @@ impl CoreCrypto {
         crate::VERSION.into()
     }
 
+    /// see [core_crypto::prelude::RATCHET_TREE_DELTA_VERSION]
+    pub fn ratchet_tree_delta_version() -> u8 {
+        core_crypto::prelude::RATCHET_TREE_DELTA_VERSION
+    }
+
     /// see [core_crypto::mls::MlsCentral::try_new]
     pub async fn _internal_new(
         path: String,
@@ -1407,6 +1781,44 @@ impl CoreCrypto {
         )
     }
 
+    /// Returns [`WasmCryptoResult<Uint8Array>`]
+    ///
+    /// See [core_crypto::mls::MlsCentral::export_snapshot]
+    pub fn export_snapshot(&self, passphrase: String) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let snapshot = this
+                    .read()
+                    .await
+                    .export_snapshot(&passphrase)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(Uint8Array::from(snapshot.as_slice()).into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns [`WasmCryptoResult<Uint8Array>`]
+    ///
+    /// See [core_crypto::mls::MlsCentral::import_snapshot]
+    pub fn import_snapshot(&self, snapshot: Box<[u8]>, passphrase: String) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let client_id = this
+                    .write()
+                    .await
+                    .import_snapshot(&snapshot, &passphrase)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(Uint8Array::from(client_id.as_slice()).into())
+            }
+            .err_into(),
+        )
+    }
+
     /// Returns the Arc strong ref count
     pub fn has_outstanding_refs(&self) -> bool {
         std::sync::Arc::strong_count(&self.inner) > 1
@@ -1490,39 +1902,107 @@ impl CoreCrypto {
 
     /// Returns: [`WasmCryptoResult<()>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::callbacks]
-    pub fn set_callbacks(&self, callbacks: CoreCryptoWasmCallbacks) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::begin_transaction]
+    pub fn begin_transaction(&self) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                this.write().await.callbacks(std::sync::Arc::new(callbacks));
-
+                this.write()
+                    .await
+                    .begin_transaction()
+                    .await
+                    .map_err(CoreCryptoError::from)?;
                 WasmCryptoResult::Ok(JsValue::UNDEFINED)
             }
             .err_into(),
         )
     }
 
-    pub fn set_logger(&self, logger: CoreCryptoWasmLogger, level: CoreCryptoLogLevel) {
-        fmt::fmt()
-            .with_max_level(LevelFilter::from(level))
-            .with_writer(logger)
-            .init()
-    }
-
-    /// Returns:: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    /// Returns: [`WasmCryptoResult<()>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::client_public_key]
-    pub fn client_public_key(&self, ciphersuite: Ciphersuite, credential_type: CredentialType) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::commit_transaction]
+    pub fn commit_transaction(&self) -> Promise {
         let this = self.inner.clone();
-        let ciphersuite: CiphersuiteName = ciphersuite.into();
         future_to_promise(
             async move {
-                let cc = this.read().await;
-                let pk = cc
-                    .client_public_key(ciphersuite.into(), credential_type.into())
+                this.write()
+                    .await
+                    .commit_transaction()
+                    .await
                     .map_err(CoreCryptoError::from)?;
-                WasmCryptoResult::Ok(Uint8Array::from(pk.as_slice()).into())
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::rollback_transaction]
+    pub fn rollback_transaction(&self) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                this.write()
+                    .await
+                    .rollback_transaction()
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::rekey]
+    pub fn rekey(&self, new_key: String) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                this.read().await.rekey(&new_key).await.map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::callbacks]
+    pub fn set_callbacks(&self, callbacks: CoreCryptoWasmCallbacks) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                this.write().await.callbacks(std::sync::Arc::new(callbacks));
+
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+            }
+            .err_into(),
+        )
+    }
+
+    pub fn set_logger(&self, logger: CoreCryptoWasmLogger, level: CoreCryptoLogLevel) {
+        fmt::fmt()
+            .with_max_level(LevelFilter::from(level))
+            .with_writer(logger)
+            .init()
+    }
+
+    /// Returns:: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::client_public_key]
+    pub fn client_public_key(&self, ciphersuite: Ciphersuite, credential_type: CredentialType) -> Promise {
+        let this = self.inner.clone();
+        let ciphersuite: CiphersuiteName = ciphersuite.into();
+        future_to_promise(
+            async move {
+                let cc = this.read().await;
+                let pk = cc
+                    .client_public_key(ciphersuite.into(), credential_type.into())
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(Uint8Array::from(pk.as_slice()).into())
             }
             .err_into(),
         )
@@ -1698,6 +2178,68 @@ impl CoreCrypto {
         )
     }
 
+    /// Returns: [`WasmCryptoResult<Uint8Array>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::epoch_authenticator]
+    pub fn epoch_authenticator(&self, conversation_id: ConversationId) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let authenticator = this
+                    .write()
+                    .await
+                    .epoch_authenticator(&conversation_id)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(Uint8Array::from(authenticator.as_slice()).into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<String>`]
+    ///
+    /// `length` is the number of digit groups or emojis
+    ///
+    /// see [core_crypto::mls::MlsCentral::safety_number]
+    pub fn safety_number(&self, conversation_id: ConversationId, format: SafetyNumberFormat, length: u8) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let safety_number = this
+                    .write()
+                    .await
+                    .safety_number(&conversation_id, format.with_length(length))
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(safety_number.into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<Vec<EpochAuthenticator>>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::epoch_authenticator_history]
+    pub fn epoch_authenticator_history(&self, conversation_id: ConversationId) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let history = this
+                    .write()
+                    .await
+                    .epoch_authenticator_history(&conversation_id)
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .into_iter()
+                    .map(EpochAuthenticator::from)
+                    .collect::<Vec<_>>();
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&history)?)
+            }
+            .err_into(),
+        )
+    }
+
     /// Returns: [`bool`]
     ///
     /// see [core_crypto::mls::MlsCentral::conversation_exists]
@@ -1732,7 +2274,39 @@ impl CoreCrypto {
                     .process_raw_welcome_message(welcome_message.into(), custom_configuration.into())
                     .await
                     .map_err(CoreCryptoError::from)?;
-                let bundle: WelcomeBundle = bundle.into();
+                let bundle: WelcomeBundle = bundle.try_into()?;
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&bundle)?)
+            }
+            .err_into(),
+        )
+    }
+
+    #[allow(clippy::boxed_local)]
+    /// Returns: [`WasmCryptoResult<WelcomeBundle>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::process_welcome_message_with_ratchet_tree]
+    pub fn process_welcome_message_with_ratchet_tree(
+        &self,
+        welcome_message: Box<[u8]>,
+        ratchet_tree: Box<[u8]>,
+        custom_configuration: CustomConfiguration,
+    ) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let welcome_message = MlsMessageIn::tls_deserialize(&mut welcome_message.as_ref())
+                    .map_err(|e| CoreCryptoError(WasmError::CryptoError(CryptoError::MlsError(e.into()))))?;
+                let bundle = this
+                    .write()
+                    .await
+                    .process_welcome_message_with_ratchet_tree(
+                        welcome_message,
+                        &ratchet_tree,
+                        custom_configuration.into(),
+                    )
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                let bundle: WelcomeBundle = bundle.try_into()?;
                 WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&bundle)?)
             }
             .err_into(),
@@ -1802,21 +2376,33 @@ impl CoreCrypto {
         )
     }
 
-    /// Returns: [`WasmCryptoResult<()>`]
+    /// Returns: [`WasmCryptoResult<MemberAddedMessages>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::mark_conversation_as_child_of]
-    pub fn mark_conversation_as_child_of(&self, child_id: Box<[u8]>, parent_id: Box<[u8]>) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::add_user_to_conversation]
+    pub fn add_user_to_conversation(
+        &self,
+        conversation_id: ConversationId,
+        user_id: String,
+        key_packages: Box<[Uint8Array]>,
+    ) -> Promise {
         let this = self.inner.clone();
 
         future_to_promise(
             async move {
-                let mut central = this.write().await;
-                central
-                    .mark_conversation_as_child_of(&child_id.into(), &parent_id.into())
-                    .await
-                    .map_err(CoreCryptoError::from)?;
+                let key_packages = key_packages
+                    .iter()
+                    .map(|kp| {
+                        KeyPackageIn::tls_deserialize(&mut kp.to_vec().as_slice())
+                            .map_err(|e| CoreCryptoError(WasmError::CryptoError(CryptoError::MlsError(e.into()))))
+                    })
+                    .collect::<CoreCryptoResult<Vec<_>>>()?;
 
-                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+                let mut central = this.write().await;
+                let commit = central
+                    .add_user_to_conversation(&conversation_id, &user_id, key_packages)
+                    .await?;
+                let commit: MemberAddedMessages = commit.try_into()?;
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
             }
             .err_into(),
         )
@@ -1824,15 +2410,15 @@ impl CoreCrypto {
 
     /// Returns: [`WasmCryptoResult<CommitBundle>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::update_keying_material]
-    pub fn update_keying_material(&self, conversation_id: ConversationId) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::remove_user_from_conversation]
+    pub fn remove_user_from_conversation(&self, conversation_id: ConversationId, user_id: String) -> Promise {
         let this = self.inner.clone();
 
         future_to_promise(
             async move {
                 let mut central = this.write().await;
                 let commit = central
-                    .update_keying_material(&conversation_id)
+                    .remove_user_from_conversation(&conversation_id, &user_id)
                     .await
                     .map_err(CoreCryptoError::from)?;
 
@@ -1844,217 +2430,265 @@ impl CoreCrypto {
         )
     }
 
-    /// see [core_crypto::mls::MlsCentral::commit_pending_proposals]
-    pub fn commit_pending_proposals(&self, conversation_id: ConversationId) -> Promise {
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::mark_conversation_as_child_of]
+    pub fn mark_conversation_as_child_of(&self, child_id: Box<[u8]>, parent_id: Box<[u8]>) -> Promise {
         let this = self.inner.clone();
 
         future_to_promise(
             async move {
                 let mut central = this.write().await;
-                let commit: Option<CommitBundle> = central
-                    .commit_pending_proposals(&conversation_id)
-                    .await?
-                    .map(|c| c.try_into())
-                    .transpose()?;
+                central
+                    .mark_conversation_as_child_of(&child_id.into(), &parent_id.into())
+                    .await
+                    .map_err(CoreCryptoError::from)?;
 
-                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<()>`]
+    /// Returns: [`WasmCryptoResult<MemberAddedMessages>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::wipe_conversation]
-    pub fn wipe_conversation(&self, conversation_id: ConversationId) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::create_subconversation]
+    pub fn create_subconversation(
+        &self,
+        parent_id: ConversationId,
+        conversation_id: ConversationId,
+        key_packages: Box<[Uint8Array]>,
+    ) -> Promise {
         let this = self.inner.clone();
+
         future_to_promise(
             async move {
+                let key_packages = key_packages
+                    .iter()
+                    .map(|kp| {
+                        KeyPackageIn::tls_deserialize(&mut kp.to_vec().as_slice())
+                            .map_err(|e| CoreCryptoError(WasmError::CryptoError(CryptoError::MlsError(e.into()))))
+                    })
+                    .collect::<CoreCryptoResult<Vec<_>>>()?;
+
                 let mut central = this.write().await;
-                central
-                    .wipe_conversation(&conversation_id)
-                    .await
-                    .map_err(CoreCryptoError::from)?;
-                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+                let commit = central
+                    .create_subconversation(&parent_id, &conversation_id, key_packages)
+                    .await?;
+                let commit: MemberAddedMessages = commit.try_into()?;
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<DecryptedMessage>`]
+    /// Returns: [`WasmCryptoResult<WelcomeBundle>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::decrypt_message]
-    pub fn decrypt_message(&self, conversation_id: ConversationId, payload: Box<[u8]>) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::join_subconversation]
+    pub fn join_subconversation(
+        &self,
+        parent_id: ConversationId,
+        welcome_message: Box<[u8]>,
+        custom_configuration: CustomConfiguration,
+    ) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let raw_decrypted_message = this
+                let welcome_message = MlsMessageIn::tls_deserialize(&mut welcome_message.as_ref())
+                    .map_err(|e| CoreCryptoError(WasmError::CryptoError(CryptoError::MlsError(e.into()))))?;
+                let bundle = this
                     .write()
                     .await
-                    .decrypt_message(&conversation_id.to_vec(), payload)
+                    .join_subconversation(&parent_id, welcome_message, custom_configuration.into())
                     .await
                     .map_err(CoreCryptoError::from)?;
-
-                let decrypted_message = DecryptedMessage::try_from(raw_decrypted_message)?;
-
-                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&decrypted_message)?)
+                let bundle: WelcomeBundle = bundle.try_into()?;
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&bundle)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<Uint8Array>`]
+    /// Returns: [`WasmCryptoResult<Vec<ConversationId>>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::encrypt_message]
-    pub fn encrypt_message(&self, conversation_id: ConversationId, message: Box<[u8]>) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::list_subconversations]
+    pub fn list_subconversations(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let ciphertext = this
-                    .write()
+                let ids = this
+                    .read()
                     .await
-                    .encrypt_message(&conversation_id.to_vec(), message)
+                    .list_subconversations(&conversation_id)
                     .await
-                    .map(|ciphertext| Uint8Array::from(ciphertext.as_slice()))
                     .map_err(CoreCryptoError::from)?;
-
-                WasmCryptoResult::Ok(ciphertext.into())
+                let js_ids = js_sys::Array::from_iter(
+                    ids.into_iter()
+                        .map(|id| js_sys::Uint8Array::from(id.as_slice()))
+                        .map(JsValue::from),
+                );
+                WasmCryptoResult::Ok(js_ids.into())
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    /// Returns: [`WasmCryptoResult<CommitBundle>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::new_add_proposal]
-    pub fn new_add_proposal(&self, conversation_id: ConversationId, keypackage: Box<[u8]>) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::reinit_conversation]
+    pub fn reinit_conversation(
+        &self,
+        conversation_id: ConversationId,
+        group_id: ConversationId,
+        ciphersuite: Ciphersuite,
+    ) -> Promise {
         let this = self.inner.clone();
+        let reinit = MlsReInit {
+            group_id,
+            ciphersuite: ciphersuite.into(),
+        };
         future_to_promise(
             async move {
-                let kp = KeyPackageIn::tls_deserialize(&mut keypackage.as_ref())
-                    .map_err(MlsError::from)
-                    .map_err(CryptoError::from)
-                    .map_err(CoreCryptoError::from)?;
-
-                let proposal: ProposalBundle = this
+                let commit: CommitBundle = this
                     .write()
                     .await
-                    .new_add_proposal(&conversation_id.to_vec(), kp.into())
+                    .reinit_conversation(&conversation_id, reinit)
                     .await
                     .map_err(CoreCryptoError::from)?
                     .try_into()?;
-
-                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&proposal)?)
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    /// Returns: [`WasmCryptoResult<MemberAddedMessages>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::new_update_proposal]
-    pub fn new_update_proposal(&self, conversation_id: ConversationId) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::create_reinit_successor]
+    pub fn create_reinit_successor(
+        &self,
+        conversation_id: ConversationId,
+        group_id: ConversationId,
+        ciphersuite: Ciphersuite,
+        key_packages: Box<[Uint8Array]>,
+    ) -> Promise {
         let this = self.inner.clone();
+        let reinit = MlsReInit {
+            group_id,
+            ciphersuite: ciphersuite.into(),
+        };
         future_to_promise(
             async move {
-                let proposal: ProposalBundle = this
+                let key_packages = key_packages
+                    .iter()
+                    .map(|kp| {
+                        KeyPackageIn::tls_deserialize(&mut kp.to_vec().as_slice())
+                            .map_err(|e| CoreCryptoError(WasmError::CryptoError(CryptoError::MlsError(e.into()))))
+                    })
+                    .collect::<CoreCryptoResult<Vec<_>>>()?;
+
+                let commit: MemberAddedMessages = this
                     .write()
                     .await
-                    .new_update_proposal(&conversation_id.to_vec())
+                    .create_reinit_successor(&conversation_id, reinit, key_packages)
                     .await?
                     .try_into()?;
-
-                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&proposal)?)
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    /// Returns: [`WasmCryptoResult<WelcomeBundle>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::new_remove_proposal]
-    pub fn new_remove_proposal(&self, conversation_id: ConversationId, client_id: FfiClientId) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::join_reinit_successor]
+    pub fn join_reinit_successor(
+        &self,
+        conversation_id: ConversationId,
+        welcome_message: Box<[u8]>,
+        custom_configuration: CustomConfiguration,
+    ) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let proposal: ProposalBundle = this
+                let welcome_message = MlsMessageIn::tls_deserialize(&mut welcome_message.as_ref())
+                    .map_err(|e| CoreCryptoError(WasmError::CryptoError(CryptoError::MlsError(e.into()))))?;
+                let bundle: WelcomeBundle = this
                     .write()
                     .await
-                    .new_remove_proposal(&conversation_id.to_vec(), client_id.into())
+                    .join_reinit_successor(&conversation_id, welcome_message, custom_configuration.into())
                     .await
                     .map_err(CoreCryptoError::from)?
                     .try_into()?;
-
-                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&proposal)?)
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&bundle)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    /// Returns: [`WasmCryptoResult<CommitBundle>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::new_external_add_proposal]
-    pub fn new_external_add_proposal(
-        &self,
-        conversation_id: ConversationId,
-        epoch: u32,
-        ciphersuite: Ciphersuite,
-        credential_type: CredentialType,
-    ) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::update_keying_material]
+    pub fn update_keying_material(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
-        let ciphersuite: CiphersuiteName = ciphersuite.into();
+
         future_to_promise(
             async move {
-                let proposal_bytes = this
-                    .write()
-                    .await
-                    .new_external_add_proposal(
-                        conversation_id.to_vec(),
-                        u64::from(epoch).into(),
-                        ciphersuite.into(),
-                        credential_type.into(),
-                    )
+                let mut central = this.write().await;
+                let commit = central
+                    .update_keying_material(&conversation_id)
                     .await
-                    .map_err(CoreCryptoError::from)?
-                    .to_bytes()
-                    .map(|bytes| Uint8Array::from(bytes.as_slice()))
-                    .map_err(MlsError::from)
-                    .map_err(CryptoError::from)
                     .map_err(CoreCryptoError::from)?;
 
-                WasmCryptoResult::Ok(proposal_bytes.into())
+                let commit: CommitBundle = commit.try_into()?;
+
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
             }
             .err_into(),
         )
     }
 
-    #[allow(clippy::boxed_local)]
-    /// Returns: [`WasmCryptoResult<ConversationInitBundle>`]
+    /// Returns: [`WasmCryptoResult<Array<Uint8Array>>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::join_by_external_commit]
-    pub fn join_by_external_commit(
-        &self,
-        group_info: Box<[u8]>,
-        custom_configuration: CustomConfiguration,
-        credential_type: CredentialType,
-    ) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::conversations_due_for_key_rotation]
+    pub fn conversations_due_for_key_rotation(&self) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let group_info = VerifiableGroupInfo::tls_deserialize(&mut group_info.as_ref())
-                    .map_err(MlsError::from)
-                    .map_err(CryptoError::from)
+                let ids = this
+                    .read()
+                    .await
+                    .conversations_due_for_key_rotation()
+                    .await
                     .map_err(CoreCryptoError::from)?;
+                let js_ids = js_sys::Array::from_iter(
+                    ids.into_iter()
+                        .map(|id| js_sys::Uint8Array::from(id.as_slice()))
+                        .map(JsValue::from),
+                );
+                WasmCryptoResult::Ok(js_ids.into())
+            }
+            .err_into(),
+        )
+    }
 
-                let result: ConversationInitBundle = this
+    /// Returns: [`WasmCryptoResult<Map<string, CommitBundle>>`] where conversation ids are hex encoded
+    ///
+    /// see [core_crypto::mls::MlsCentral::update_keying_material_when_due]
+    pub fn update_keying_material_when_due(&self) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let commits = this
                     .write()
                     .await
-                    .join_by_external_commit(group_info, custom_configuration.into(), credential_type.into())
+                    .update_keying_material_when_due()
                     .await
                     .map_err(CoreCryptoError::from)?
-                    .try_into()?;
-
-                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&result)?)
+                    .into_iter()
+                    .map(|(id, commit)| WasmCryptoResult::Ok((hex::encode(id), CommitBundle::try_from(commit)?)))
+                    .collect::<WasmCryptoResult<HashMap<String, CommitBundle>>>()?;
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commits)?)
             }
             .err_into(),
         )
@@ -2062,26 +2696,17 @@ impl CoreCrypto {
 
     /// Returns: [`WasmCryptoResult<()>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::merge_pending_group_from_external_commit]
-    pub fn merge_pending_group_from_external_commit(&self, conversation_id: ConversationId) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::set_key_rotation_span]
+    pub fn set_key_rotation_span(&self, conversation_id: ConversationId, key_rotation_span: Option<u32>) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                if let Some(decrypted_messages) = this
-                    .write()
+                let key_rotation_span = key_rotation_span.map(|span| std::time::Duration::from_secs(span as u64));
+                this.write()
                     .await
-                    .merge_pending_group_from_external_commit(&conversation_id)
+                    .set_key_rotation_span(&conversation_id, key_rotation_span)
                     .await
-                    .map_err(CoreCryptoError::from)?
-                {
-                    let messages = decrypted_messages
-                        .into_iter()
-                        .map(TryInto::try_into)
-                        .collect::<WasmCryptoResult<Vec<BufferedDecryptedMessage>>>()?;
-
-                    return WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&messages)?);
-                }
-
+                    .map_err(CoreCryptoError::from)?;
                 WasmCryptoResult::Ok(JsValue::UNDEFINED)
             }
             .err_into(),
@@ -2090,14 +2715,15 @@ impl CoreCrypto {
 
     /// Returns: [`WasmCryptoResult<()>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::clear_pending_group_from_external_commit]
-    pub fn clear_pending_group_from_external_commit(&self, conversation_id: ConversationId) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::register_external_psk]
+    pub fn register_external_psk(&self, ciphersuite: Ciphersuite, psk_id: Box<[u8]>, psk: Box<[u8]>) -> Promise {
         let this = self.inner.clone();
+        let ciphersuite: CiphersuiteName = ciphersuite.into();
         future_to_promise(
             async move {
-                this.write()
+                this.read()
                     .await
-                    .clear_pending_group_from_external_commit(&conversation_id)
+                    .register_external_psk(ciphersuite.into(), psk_id.into(), psk.into())
                     .await
                     .map_err(CoreCryptoError::from)?;
                 WasmCryptoResult::Ok(JsValue::UNDEFINED)
@@ -2106,161 +2732,630 @@ impl CoreCrypto {
         )
     }
 
-    /// see [core_crypto::mls::MlsCentral::commit_accepted]
-    pub fn commit_accepted(&self, conversation_id: ConversationId) -> Promise {
+    /// Returns: [`WasmCryptoResult<CommitBundle>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::commit_external_psks]
+    pub fn commit_external_psks(&self, conversation_id: ConversationId, psk_ids: Box<[Uint8Array]>) -> Promise {
         let this = self.inner.clone();
+        let psk_ids = psk_ids.iter().map(|id| id.to_vec()).collect::<Vec<_>>();
         future_to_promise(
             async move {
-                if let Some(decrypted_messages) = this
+                let commit: CommitBundle = this
                     .write()
                     .await
-                    .commit_accepted(&conversation_id)
+                    .commit_external_psks(&conversation_id, psk_ids)
                     .await
                     .map_err(CoreCryptoError::from)?
-                {
-                    let messages = decrypted_messages
-                        .into_iter()
-                        .map(TryInto::try_into)
-                        .collect::<WasmCryptoResult<Vec<BufferedDecryptedMessage>>>()?;
-
-                    return WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&messages)?);
-                }
+                    .try_into()?;
 
-                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
             }
             .err_into(),
         )
     }
 
-    /// see [core_crypto::mls::MlsCentral::clear_pending_proposal]
-    pub fn clear_pending_proposal(&self, conversation_id: ConversationId, proposal_ref: Box<[u8]>) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::commit_pending_proposals]
+    pub fn commit_pending_proposals(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
+
         future_to_promise(
             async move {
-                this.write()
-                    .await
-                    .clear_pending_proposal(&conversation_id.to_vec(), proposal_ref.to_vec().into())
-                    .await
-                    .map_err(CoreCryptoError::from)?;
+                let mut central = this.write().await;
+                let commit: Option<CommitBundle> = central
+                    .commit_pending_proposals(&conversation_id)
+                    .await?
+                    .map(|c| c.try_into())
+                    .transpose()?;
 
-                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
             }
             .err_into(),
         )
     }
 
-    /// see [core_crypto::mls::MlsCentral::clear_pending_commit]
-    pub fn clear_pending_commit(&self, conversation_id: ConversationId) -> Promise {
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::wipe_conversation]
+    pub fn wipe_conversation(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                this.write()
-                    .await
-                    .clear_pending_commit(&conversation_id.to_vec())
+                let mut central = this.write().await;
+                central
+                    .wipe_conversation(&conversation_id)
                     .await
                     .map_err(CoreCryptoError::from)?;
-
                 WasmCryptoResult::Ok(JsValue::UNDEFINED)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    /// Returns: [`WasmCryptoResult<ProposalBundle>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::random_bytes]
-    pub fn random_bytes(&self, len: usize) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::leave_conversation]
+    pub fn leave_conversation(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let bytes = this.read().await.random_bytes(len).map_err(CoreCryptoError::from)?;
-                WasmCryptoResult::Ok(Uint8Array::from(bytes.as_slice()).into())
+                let proposal: ProposalBundle = this
+                    .write()
+                    .await
+                    .leave_conversation(&conversation_id.to_vec())
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .try_into()?;
+
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&proposal)?)
             }
             .err_into(),
         )
     }
 
-    #[allow(rustdoc::broken_intra_doc_links)]
     /// Returns: [`WasmCryptoResult<()>`]
     ///
-    /// see [mls_crypto_provider::MlsCryptoProvider::reseed]
-    pub fn reseed_rng(&self, seed: Box<[u8]>) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::leave_proposal_sent]
+    pub fn leave_proposal_sent(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let seed = EntropySeed::try_from_slice(&seed)
-                    .map_err(CryptoError::from)
+                let mut central = this.write().await;
+                central
+                    .leave_proposal_sent(&conversation_id.to_vec())
+                    .await
                     .map_err(CoreCryptoError::from)?;
-
-                this.write().await.provider_mut().reseed(Some(seed));
                 WasmCryptoResult::Ok(JsValue::UNDEFINED)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<()>`]
+    /// Returns: [`WasmCryptoResult<DecryptedMessage>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::try_new]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_init(&self) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::decrypt_message]
+    pub fn decrypt_message(&self, conversation_id: ConversationId, payload: Box<[u8]>) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
-
         future_to_promise(
             async move {
-                proteus_impl! { errcode_dest => {
-                    this.write().await.proteus_init().await.map_err(CoreCryptoError::from)?;
-                    WasmCryptoResult::Ok(JsValue::UNDEFINED)
-                } or throw WasmCryptoResult<_> }
+                let raw_decrypted_message = this
+                    .write()
+                    .await
+                    .decrypt_message(&conversation_id.to_vec(), payload)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+
+                let decrypted_message = DecryptedMessage::try_from(raw_decrypted_message)?;
+
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&decrypted_message)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<()>`]
+    /// Returns: [`WasmCryptoResult<Vec<BatchDecryptedMessage>>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::session_from_prekey]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_session_from_prekey(&self, session_id: String, prekey: Box<[u8]>) -> Promise {
+    /// `conversation_ids` and `payloads` are parallel arrays, in the order the messages were received
+    ///
+    /// see [core_crypto::mls::MlsCentral::decrypt_messages]
+    pub fn decrypt_messages(&self, conversation_ids: Box<[Uint8Array]>, payloads: Box<[Uint8Array]>) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
-
         future_to_promise(
             async move {
-                proteus_impl! { errcode_dest => {
-                    this.write().await.proteus_session_from_prekey(&session_id, &prekey).await.map_err(CoreCryptoError::from)?;
-                    WasmCryptoResult::Ok(JsValue::UNDEFINED)
-                } or throw WasmCryptoResult<_> }
+                if conversation_ids.len() != payloads.len() {
+                    return Err(CoreCryptoError(WasmError::CryptoError(CryptoError::ConsumerError)));
+                }
+                let messages = conversation_ids
+                    .iter()
+                    .map(|id| id.to_vec())
+                    .zip(payloads.iter().map(|p| p.to_vec()))
+                    .collect();
+                let results = this
+                    .write()
+                    .await
+                    .decrypt_messages(messages)
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .into_iter()
+                    .map(|r| {
+                        r.map_err(CoreCryptoError::from)
+                            .and_then(DecryptedMessage::try_from)
+                            .into()
+                    })
+                    .collect::<Vec<BatchDecryptedMessage>>();
+
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&results)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<()>`]
+    /// Returns: [`WasmCryptoResult<Uint8Array>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::session_from_message]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_session_from_message(&self, session_id: String, envelope: Box<[u8]>) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::encrypt_message]
+    pub fn encrypt_message(&self, conversation_id: ConversationId, message: Box<[u8]>) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
-
         future_to_promise(
             async move {
-                proteus_impl! { errcode_dest => {
-                    let (_, payload) = this.write().await.proteus_session_from_message(&session_id, &envelope).await.map_err(CoreCryptoError::from)?;
-                    WasmCryptoResult::Ok(Uint8Array::from(payload.as_slice()).into())
-                } or throw WasmCryptoResult<_> }
+                let ciphertext = this
+                    .write()
+                    .await
+                    .encrypt_message(&conversation_id.to_vec(), message)
+                    .await
+                    .map(|ciphertext| Uint8Array::from(ciphertext.as_slice()))
+                    .map_err(CoreCryptoError::from)?;
+
+                WasmCryptoResult::Ok(ciphertext.into())
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<()>`]
-    ///
-    /// **Note**: This isn't usually needed as persisting sessions happens automatically when decrypting/encrypting messages and initializing Sessions
+    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::session_save]
+    /// see [core_crypto::mls::MlsCentral::encrypt_message_with_aad]
+    pub fn encrypt_message_with_aad(
+        &self,
+        conversation_id: ConversationId,
+        message: Box<[u8]>,
+        aad: Box<[u8]>,
+    ) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let ciphertext = this
+                    .write()
+                    .await
+                    .encrypt_message_with_aad(&conversation_id.to_vec(), message, aad)
+                    .await
+                    .map(|ciphertext| Uint8Array::from(ciphertext.as_slice()))
+                    .map_err(CoreCryptoError::from)?;
+
+                WasmCryptoResult::Ok(ciphertext.into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::new_add_proposal]
+    pub fn new_add_proposal(&self, conversation_id: ConversationId, keypackage: Box<[u8]>) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let kp = KeyPackageIn::tls_deserialize(&mut keypackage.as_ref())
+                    .map_err(MlsError::from)
+                    .map_err(CryptoError::from)
+                    .map_err(CoreCryptoError::from)?;
+
+                let proposal: ProposalBundle = this
+                    .write()
+                    .await
+                    .new_add_proposal(&conversation_id.to_vec(), kp.into())
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .try_into()?;
+
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&proposal)?)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::new_update_proposal]
+    pub fn new_update_proposal(&self, conversation_id: ConversationId) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let proposal: ProposalBundle = this
+                    .write()
+                    .await
+                    .new_update_proposal(&conversation_id.to_vec())
+                    .await?
+                    .try_into()?;
+
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&proposal)?)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::new_remove_proposal]
+    pub fn new_remove_proposal(&self, conversation_id: ConversationId, client_id: FfiClientId) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let proposal: ProposalBundle = this
+                    .write()
+                    .await
+                    .new_remove_proposal(&conversation_id.to_vec(), client_id.into())
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .try_into()?;
+
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&proposal)?)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<ProposalBundle>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::new_external_psk_proposal]
+    pub fn new_external_psk_proposal(&self, conversation_id: ConversationId, psk_id: Box<[u8]>) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let proposal: ProposalBundle = this
+                    .write()
+                    .await
+                    .new_external_psk_proposal(&conversation_id.to_vec(), psk_id.into())
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .try_into()?;
+
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&proposal)?)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<ProposalBundle>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::new_reinit_proposal]
+    pub fn new_reinit_proposal(
+        &self,
+        conversation_id: ConversationId,
+        group_id: ConversationId,
+        ciphersuite: Ciphersuite,
+    ) -> Promise {
+        let this = self.inner.clone();
+        let reinit = MlsReInit {
+            group_id,
+            ciphersuite: ciphersuite.into(),
+        };
+        future_to_promise(
+            async move {
+                let proposal: ProposalBundle = this
+                    .write()
+                    .await
+                    .new_reinit_proposal(&conversation_id.to_vec(), reinit)
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .try_into()?;
+
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&proposal)?)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::new_external_add_proposal]
+    pub fn new_external_add_proposal(
+        &self,
+        conversation_id: ConversationId,
+        epoch: u32,
+        ciphersuite: Ciphersuite,
+        credential_type: CredentialType,
+    ) -> Promise {
+        let this = self.inner.clone();
+        let ciphersuite: CiphersuiteName = ciphersuite.into();
+        future_to_promise(
+            async move {
+                let proposal_bytes = this
+                    .write()
+                    .await
+                    .new_external_add_proposal(
+                        conversation_id.to_vec(),
+                        u64::from(epoch).into(),
+                        ciphersuite.into(),
+                        credential_type.into(),
+                    )
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .to_bytes()
+                    .map(|bytes| Uint8Array::from(bytes.as_slice()))
+                    .map_err(MlsError::from)
+                    .map_err(CryptoError::from)
+                    .map_err(CoreCryptoError::from)?;
+
+                WasmCryptoResult::Ok(proposal_bytes.into())
+            }
+            .err_into(),
+        )
+    }
+
+    #[allow(clippy::boxed_local)]
+    /// Returns: [`WasmCryptoResult<ConversationInitBundle>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::join_by_external_commit]
+    pub fn join_by_external_commit(
+        &self,
+        group_info: Box<[u8]>,
+        custom_configuration: CustomConfiguration,
+        credential_type: CredentialType,
+    ) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let mut central = this.write().await;
+                let group_info = central
+                    .open_group_info(&group_info, None)
+                    .map_err(CoreCryptoError::from)?;
+
+                let result: ConversationInitBundle = central
+                    .join_by_external_commit(group_info, custom_configuration.into(), credential_type.into())
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .try_into()?;
+
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&result)?)
+            }
+            .err_into(),
+        )
+    }
+
+    #[allow(clippy::boxed_local)]
+    /// Returns: [`WasmCryptoResult<ConversationInitBundle>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::join_by_external_commit_with_ratchet_tree]
+    pub fn join_by_external_commit_with_ratchet_tree(
+        &self,
+        group_info: Box<[u8]>,
+        ratchet_tree: Box<[u8]>,
+        custom_configuration: CustomConfiguration,
+        credential_type: CredentialType,
+    ) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let result: ConversationInitBundle = this
+                    .write()
+                    .await
+                    .join_by_external_commit_with_ratchet_tree(
+                        &group_info,
+                        &ratchet_tree,
+                        None,
+                        custom_configuration.into(),
+                        credential_type.into(),
+                    )
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                    .try_into()?;
+
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&result)?)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::merge_pending_group_from_external_commit]
+    pub fn merge_pending_group_from_external_commit(&self, conversation_id: ConversationId) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                if let Some(decrypted_messages) = this
+                    .write()
+                    .await
+                    .merge_pending_group_from_external_commit(&conversation_id)
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                {
+                    let messages = decrypted_messages
+                        .into_iter()
+                        .map(TryInto::try_into)
+                        .collect::<WasmCryptoResult<Vec<BufferedDecryptedMessage>>>()?;
+
+                    return WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&messages)?);
+                }
+
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::clear_pending_group_from_external_commit]
+    pub fn clear_pending_group_from_external_commit(&self, conversation_id: ConversationId) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                this.write()
+                    .await
+                    .clear_pending_group_from_external_commit(&conversation_id)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+            }
+            .err_into(),
+        )
+    }
+
+    /// see [core_crypto::mls::MlsCentral::commit_accepted]
+    pub fn commit_accepted(&self, conversation_id: ConversationId) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                if let Some(decrypted_messages) = this
+                    .write()
+                    .await
+                    .commit_accepted(&conversation_id)
+                    .await
+                    .map_err(CoreCryptoError::from)?
+                {
+                    let messages = decrypted_messages
+                        .into_iter()
+                        .map(TryInto::try_into)
+                        .collect::<WasmCryptoResult<Vec<BufferedDecryptedMessage>>>()?;
+
+                    return WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&messages)?);
+                }
+
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+            }
+            .err_into(),
+        )
+    }
+
+    /// see [core_crypto::mls::MlsCentral::clear_pending_proposal]
+    pub fn clear_pending_proposal(&self, conversation_id: ConversationId, proposal_ref: Box<[u8]>) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                this.write()
+                    .await
+                    .clear_pending_proposal(&conversation_id.to_vec(), proposal_ref.to_vec().into())
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+            }
+            .err_into(),
+        )
+    }
+
+    /// see [core_crypto::mls::MlsCentral::clear_pending_commit]
+    pub fn clear_pending_commit(&self, conversation_id: ConversationId) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                this.write()
+                    .await
+                    .clear_pending_commit(&conversation_id.to_vec())
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::random_bytes]
+    pub fn random_bytes(&self, len: usize) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let bytes = this.read().await.random_bytes(len).map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(Uint8Array::from(bytes.as_slice()).into())
+            }
+            .err_into(),
+        )
+    }
+
+    #[allow(rustdoc::broken_intra_doc_links)]
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [mls_crypto_provider::MlsCryptoProvider::reseed]
+    pub fn reseed_rng(&self, seed: Box<[u8]>) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let seed = EntropySeed::try_from_slice(&seed)
+                    .map_err(CryptoError::from)
+                    .map_err(CoreCryptoError::from)?;
+
+                this.write().await.provider_mut().reseed(Some(seed));
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::try_new]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_init(&self) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    this.write().await.proteus_init().await.map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(JsValue::UNDEFINED)
+                } or throw WasmCryptoResult<_> }
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::session_from_prekey]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_session_from_prekey(&self, session_id: String, prekey: Box<[u8]>) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    this.write().await.proteus_session_from_prekey(&session_id, &prekey).await.map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(JsValue::UNDEFINED)
+                } or throw WasmCryptoResult<_> }
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::session_from_message]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_session_from_message(&self, session_id: String, envelope: Box<[u8]>) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    let (_, payload) = this.write().await.proteus_session_from_message(&session_id, &envelope).await.map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(Uint8Array::from(payload.as_slice()).into())
+                } or throw WasmCryptoResult<_> }
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// **Note**: This isn't usually needed as persisting sessions happens automatically when decrypting/encrypting messages and initializing Sessions
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::session_save]
     #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
     pub fn proteus_session_save(&self, session_id: String) -> Promise {
         let this = self.inner.clone();
@@ -2269,7 +3364,238 @@ impl CoreCrypto {
         future_to_promise(
             async move {
                 proteus_impl! { errcode_dest => {
-                    this.write().await.proteus_session_save(&session_id).await.map_err(CoreCryptoError::from)?;
+                    this.write().await.proteus_session_save(&session_id).await.map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(JsValue::UNDEFINED)
+                } or throw WasmCryptoResult<_> }
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::session_delete]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_session_delete(&self, session_id: String) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    this.write().await.proteus_session_delete(&session_id).await.map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(JsValue::UNDEFINED)
+                } or throw WasmCryptoResult<_> }
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<bool>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::session_exists]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_session_exists(&self, session_id: String) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    let exists = this.write().await.proteus_session_exists(&session_id).await.map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(JsValue::from_bool(exists))
+                } or throw WasmCryptoResult<_> }
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::decrypt]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_decrypt(&self, session_id: String, ciphertext: Box<[u8]>) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    let cleartext = this.write().await.proteus_decrypt(&session_id, &ciphertext).await.map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(Uint8Array::from(cleartext.as_slice()).into())
+                } or throw WasmCryptoResult<_> }
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::encrypt]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_encrypt(&self, session_id: String, plaintext: Box<[u8]>) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    let encrypted = this.write().await.proteus_encrypt(&session_id, &plaintext).await.map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(Uint8Array::from(encrypted.as_slice()).into())
+                } or throw WasmCryptoResult<_> }
+            }.err_into()
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<js_sys::Map<string, Uint8Array>>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::encrypt_batched]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_encrypt_batched(&self, sessions: Box<[js_sys::JsString]>, plaintext: Box<[u8]>) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    let session_ids: Vec<String> = sessions.iter().map(String::from).collect();
+                    let batch = this.write().await.proteus_encrypt_batched(session_ids.as_slice(), &plaintext).await.map_err(CoreCryptoError::from)?;
+                    let js_obj = js_sys::Map::new();
+                    for (key, payload) in batch.into_iter() {
+                        js_obj.set(&js_sys::JsString::from(key).into(), &Uint8Array::from(payload.as_slice()));
+                    }
+                    WasmCryptoResult::Ok(js_obj.into())
+                } or throw WasmCryptoResult<_> }
+            }.err_into()
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<Uint8Array>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::new_prekey]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_new_prekey(&self, prekey_id: u16) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    let prekey_raw = this.read().await.proteus_new_prekey(prekey_id).await.map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(Uint8Array::from(prekey_raw.as_slice()).into())
+                } or throw WasmCryptoResult<_> }
+            }.err_into()
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<ProteusAutoPrekeyBundle>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::new_prekey]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_new_prekey_auto(&self) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    let (id, pkb) = this.read().await.proteus_new_prekey_auto().await.map_err(CoreCryptoError::from)?;
+                    WasmCryptoResult::Ok(ProteusAutoPrekeyBundle { id, pkb }.into())
+                } or throw WasmCryptoResult<_> }
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns [`WasmCryptoResult<Uint8Array>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::last_resort_prekey]
+    pub fn proteus_last_resort_prekey(&self) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        future_to_promise(async move {
+            proteus_impl! { errcode_dest => {
+                let last_resort_pkbundle = this.read().await.proteus_last_resort_prekey().await.map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(Uint8Array::from(last_resort_pkbundle.as_slice()).into())
+            } or throw WasmCryptoResult<_> }
+        }.err_into())
+    }
+
+    /// Returns: [`WasmCryptoResult<u16>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::last_resort_prekey_id]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_last_resort_prekey_id() -> WasmCryptoResult<u16> {
+        proteus_impl! {{
+            Ok(core_crypto::CoreCrypto::proteus_last_resort_prekey_id())
+        } or throw WasmCryptoResult<_> }
+    }
+
+    /// Returns: [`WasmCryptoResult<String>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::fingerprint]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub async fn proteus_fingerprint(&self) -> WasmCryptoResult<String> {
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        proteus_impl! { errcode_dest => {
+            self.inner.read().await.proteus_fingerprint().map_err(CoreCryptoError::from).map(Into::into)
+        } or throw WasmCryptoResult<_> }
+    }
+
+    /// Returns: [`WasmCryptoResult<String>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::fingerprint_local]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub async fn proteus_fingerprint_local(&self, session_id: String) -> WasmCryptoResult<String> {
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        proteus_impl! { errcode_dest => {
+            self.inner
+                .write()
+                .await
+                .proteus_fingerprint_local(&session_id)
+                .await
+                .map_err(CoreCryptoError::from)
+                .map(Into::into)
+        } or throw WasmCryptoResult<_> }
+    }
+
+    /// Returns: [`WasmCryptoResult<String>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::fingerprint_remote]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub async fn proteus_fingerprint_remote(&self, session_id: String) -> WasmCryptoResult<String> {
+        let errcode_dest = self.proteus_last_error_code.clone();
+
+        proteus_impl! { errcode_dest => {
+            self.inner.write().await.proteus_fingerprint_remote(&session_id).await
+                .map_err(CoreCryptoError::from).map(Into::into)
+        } or throw WasmCryptoResult<_> }
+    }
+
+    /// Returns: [`WasmCryptoResult<String>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCproteus_fingerprint_prekeybundle]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_fingerprint_prekeybundle(prekey: Box<[u8]>) -> WasmCryptoResult<String> {
+        proteus_impl!({
+            core_crypto::proteus::ProteusCentral::fingerprint_prekeybundle(&prekey)
+                .map_err(Into::into).map(Into::into)
+        } or throw WasmCryptoResult<_>)
+    }
+
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::proteus::ProteusCentral::cryptobox_migrate]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_cryptobox_migrate(&self, path: String) -> Promise {
+        let this = self.inner.clone();
+        let errcode_dest = self.proteus_last_error_code.clone();
+        future_to_promise(
+            async move {
+                proteus_impl! { errcode_dest => {
+                    this.read().await.proteus_cryptobox_migrate(&path).await.map_err(CoreCryptoError::from)?;
                     WasmCryptoResult::Ok(JsValue::UNDEFINED)
                 } or throw WasmCryptoResult<_> }
             }
@@ -2277,288 +3603,473 @@ impl CoreCrypto {
         )
     }
 
+    /// Returns: [`WasmCryptoResult<u32>`]
+    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
+    pub fn proteus_last_error_code(&self) -> Promise {
+        let errcode = self.proteus_last_error_code.clone();
+        future_to_promise(
+            async move {
+                proteus_impl! {{
+                    let prev_value: u32 = *(errcode.read().await);
+                    let mut errcode_val = errcode.write().await;
+                    *errcode_val = 0;
+
+                    WasmCryptoResult::Ok(prev_value.into())
+                } or throw WasmCryptoResult<_> }
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<Vec<u8>>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::export_secret_key]
+    pub fn export_secret_key(&self, conversation_id: ConversationId, key_length: usize) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let key = this
+                    .write()
+                    .await
+                    .export_secret_key(&conversation_id.to_vec(), key_length)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(Uint8Array::from(key.as_slice()).into())
+            }
+            .err_into(),
+        )
+    }
+
+    #[allow(clippy::boxed_local)]
     /// Returns: [`WasmCryptoResult<()>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::session_delete]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_session_delete(&self, session_id: String) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::set_group_info_encryption]. When `key` is supplied it is
+    /// used for every conversation, otherwise `derive_from_conversation` derives one per conversation.
+    /// Supplying neither disables the encryption.
+    pub fn set_group_info_encryption(&self, key: Option<Box<[u8]>>, derive_from_conversation: bool) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
+        future_to_promise(
+            async move {
+                let encryption = match (key, derive_from_conversation) {
+                    (Some(key), _) => Some(MlsGroupInfoEncryption::Key(
+                        key.to_vec().try_into().map_err(CoreCryptoError::from)?,
+                    )),
+                    (None, true) => Some(MlsGroupInfoEncryption::Derived),
+                    (None, false) => None,
+                };
+                this.write().await.set_group_info_encryption(encryption);
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+            }
+            .err_into(),
+        )
+    }
 
+    /// Returns: [`WasmCryptoResult<Vec<u8>>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::export_group_info_encryption_key]
+    pub fn export_group_info_encryption_key(&self, conversation_id: ConversationId) -> Promise {
+        let this = self.inner.clone();
         future_to_promise(
             async move {
-                proteus_impl! { errcode_dest => {
-                    this.write().await.proteus_session_delete(&session_id).await.map_err(CoreCryptoError::from)?;
-                    WasmCryptoResult::Ok(JsValue::UNDEFINED)
-                } or throw WasmCryptoResult<_> }
+                let key = this
+                    .write()
+                    .await
+                    .export_group_info_encryption_key(&conversation_id.to_vec())
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(Uint8Array::from(key.as_slice()).into())
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<bool>`]
+    /// Returns: [`WasmCryptoResult<()>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::session_exists]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_session_exists(&self, session_id: String) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::set_group_info_ratchet_tree_type]
+    pub fn set_group_info_ratchet_tree_type(&self, ratchet_tree_type: u8) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
+        future_to_promise(
+            async move {
+                let ratchet_tree_type = match ratchet_tree_type {
+                    1 => MlsRatchetTreeType::Full,
+                    2 => MlsRatchetTreeType::Delta,
+                    3 => MlsRatchetTreeType::ByRef,
+                    _ => return Err(CoreCryptoError(WasmError::EnumError)),
+                };
+                this.write().await.set_group_info_ratchet_tree_type(ratchet_tree_type);
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+            }
+            .err_into(),
+        )
+    }
 
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// `max_age_secs` set to `undefined` disables the expiry of buffered messages
+    ///
+    /// see [core_crypto::mls::MlsCentral::set_pending_messages_limits]
+    pub fn set_pending_messages_limits(
+        &self,
+        max_count: u32,
+        max_unknown_conversations: u32,
+        max_age_secs: Option<u32>,
+    ) -> Promise {
+        let this = self.inner.clone();
         future_to_promise(
             async move {
-                proteus_impl! { errcode_dest => {
-                    let exists = this.write().await.proteus_session_exists(&session_id).await.map_err(CoreCryptoError::from)?;
-                    WasmCryptoResult::Ok(JsValue::from_bool(exists))
-                } or throw WasmCryptoResult<_> }
+                this.write()
+                    .await
+                    .set_pending_messages_limits(MlsPendingMessagesLimits {
+                        max_count: max_count as usize,
+                        max_unknown_conversations: max_unknown_conversations as usize,
+                        max_age: max_age_secs.map(|secs| std::time::Duration::from_secs(secs.into())),
+                    });
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    /// Returns: [`WasmCryptoResult<js_sys::Array<BufferedMessage>>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::decrypt]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_decrypt(&self, session_id: String, ciphertext: Box<[u8]>) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::list_pending_messages]
+    pub fn list_pending_messages(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
+        future_to_promise(
+            async move {
+                let messages = this
+                    .read()
+                    .await
+                    .list_pending_messages(&conversation_id.to_vec())
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                let messages =
+                    js_sys::Array::from_iter(messages.into_iter().map(BufferedMessage::from).map(JsValue::from));
+                WasmCryptoResult::Ok(messages.into())
+            }
+            .err_into(),
+        )
+    }
 
+    /// Returns: [`WasmCryptoResult<js_sys::Array<BufferedMessage>>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::clear_expired_pending_messages]
+    pub fn clear_expired_pending_messages(&self) -> Promise {
+        let this = self.inner.clone();
         future_to_promise(
             async move {
-                proteus_impl! { errcode_dest => {
-                    let cleartext = this.write().await.proteus_decrypt(&session_id, &ciphertext).await.map_err(CoreCryptoError::from)?;
-                    WasmCryptoResult::Ok(Uint8Array::from(cleartext.as_slice()).into())
-                } or throw WasmCryptoResult<_> }
+                let messages = this
+                    .write()
+                    .await
+                    .clear_expired_pending_messages()
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                let messages =
+                    js_sys::Array::from_iter(messages.into_iter().map(BufferedMessage::from).map(JsValue::from));
+                WasmCryptoResult::Ok(messages.into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<js_sys::Array<BufferedMessage>>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::clear_pending_messages]
+    pub fn clear_pending_messages(&self, conversation_id: ConversationId) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let messages = this
+                    .write()
+                    .await
+                    .clear_pending_messages(&conversation_id.to_vec())
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                let messages =
+                    js_sys::Array::from_iter(messages.into_iter().map(BufferedMessage::from).map(JsValue::from));
+                WasmCryptoResult::Ok(messages.into())
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// `max_age_secs` set to `undefined` disables the expiry of shared messages
+    ///
+    /// see [core_crypto::mls::MlsCentral::enable_history_sharing]
+    pub fn enable_history_sharing(
+        &self,
+        conversation_id: ConversationId,
+        max_epochs: u32,
+        max_messages: u32,
+        max_age_secs: Option<u32>,
+    ) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let limits = MlsHistorySharingLimits {
+                    max_epochs: max_epochs.into(),
+                    max_messages: max_messages as usize,
+                    max_age: max_age_secs.map(|secs| std::time::Duration::from_secs(secs.into())),
+                };
+                this.write()
+                    .await
+                    .enable_history_sharing(&conversation_id.to_vec(), limits)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<()>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::disable_history_sharing]
+    pub fn disable_history_sharing(&self, conversation_id: ConversationId) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                this.write()
+                    .await
+                    .disable_history_sharing(&conversation_id.to_vec())
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(JsValue::UNDEFINED)
+            }
+            .err_into(),
+        )
+    }
+
+    /// Returns: [`WasmCryptoResult<Uint8Array>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::export_history]
+    pub fn export_history(&self, conversation_id: ConversationId) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let archive = this
+                    .write()
+                    .await
+                    .export_history(&conversation_id.to_vec())
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(Uint8Array::from(archive.as_slice()).into())
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<js_sys::Uint8Array>`]
+    /// Returns: [`WasmCryptoResult<Uint8Array>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::encrypt]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_encrypt(&self, session_id: String, plaintext: Box<[u8]>) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::share_history]
+    pub fn share_history(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
-
         future_to_promise(
             async move {
-                proteus_impl! { errcode_dest => {
-                    let encrypted = this.write().await.proteus_encrypt(&session_id, &plaintext).await.map_err(CoreCryptoError::from)?;
-                    WasmCryptoResult::Ok(Uint8Array::from(encrypted.as_slice()).into())
-                } or throw WasmCryptoResult<_> }
-            }.err_into()
+                let ciphertext = this
+                    .write()
+                    .await
+                    .share_history(&conversation_id.to_vec())
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(Uint8Array::from(ciphertext.as_slice()).into())
+            }
+            .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<js_sys::Map<string, Uint8Array>>`]
+    #[allow(clippy::boxed_local)]
+    /// Returns: [`WasmCryptoResult<js_sys::Array<HistoryMessage>>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::encrypt_batched]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_encrypt_batched(&self, sessions: Box<[js_sys::JsString]>, plaintext: Box<[u8]>) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::import_history]
+    pub fn import_history(&self, conversation_id: ConversationId, archive: Box<[u8]>) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
-
         future_to_promise(
             async move {
-                proteus_impl! { errcode_dest => {
-                    let session_ids: Vec<String> = sessions.iter().map(String::from).collect();
-                    let batch = this.write().await.proteus_encrypt_batched(session_ids.as_slice(), &plaintext).await.map_err(CoreCryptoError::from)?;
-                    let js_obj = js_sys::Map::new();
-                    for (key, payload) in batch.into_iter() {
-                        js_obj.set(&js_sys::JsString::from(key).into(), &Uint8Array::from(payload.as_slice()));
-                    }
-                    WasmCryptoResult::Ok(js_obj.into())
-                } or throw WasmCryptoResult<_> }
-            }.err_into()
+                let messages = this
+                    .write()
+                    .await
+                    .import_history(&conversation_id.to_vec(), archive)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                let messages =
+                    js_sys::Array::from_iter(messages.into_iter().map(HistoryMessage::from).map(JsValue::from));
+                WasmCryptoResult::Ok(messages.into())
+            }
+            .err_into(),
         )
     }
 
     /// Returns: [`WasmCryptoResult<Uint8Array>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::new_prekey]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_new_prekey(&self, prekey_id: u16) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::export_ratchet_tree]
+    pub fn export_ratchet_tree(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
-
         future_to_promise(
             async move {
-                proteus_impl! { errcode_dest => {
-                    let prekey_raw = this.read().await.proteus_new_prekey(prekey_id).await.map_err(CoreCryptoError::from)?;
-                    WasmCryptoResult::Ok(Uint8Array::from(prekey_raw.as_slice()).into())
-                } or throw WasmCryptoResult<_> }
-            }.err_into()
+                let ratchet_tree = this
+                    .write()
+                    .await
+                    .export_ratchet_tree(&conversation_id)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(Uint8Array::from(ratchet_tree.as_slice()).into())
+            }
+            .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<ProteusAutoPrekeyBundle>`]
+    #[allow(clippy::boxed_local)]
+    /// Returns: [`WasmCryptoResult<Vec<u8>>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::new_prekey]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_new_prekey_auto(&self) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::rebuild_group_info]
+    pub fn rebuild_group_info(
+        &self,
+        delta: Box<[u8]>,
+        previous_group_info: Box<[u8]>,
+        key: Option<Box<[u8]>>,
+    ) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
         future_to_promise(
             async move {
-                proteus_impl! { errcode_dest => {
-                    let (id, pkb) = this.read().await.proteus_new_prekey_auto().await.map_err(CoreCryptoError::from)?;
-                    WasmCryptoResult::Ok(ProteusAutoPrekeyBundle { id, pkb }.into())
-                } or throw WasmCryptoResult<_> }
+                let key = key
+                    .map(|key| MlsGroupInfoEncryptionKey::try_from(key.to_vec()))
+                    .transpose()
+                    .map_err(CoreCryptoError::from)?;
+                let group_info = this
+                    .read()
+                    .await
+                    .rebuild_group_info(&delta, &previous_group_info, key)
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(Uint8Array::from(group_info.as_slice()).into())
             }
             .err_into(),
         )
     }
 
-    /// Returns [`WasmCryptoResult<Uint8Array>`]
+    /// Returns: [`WasmCryptoResult<Vec<u8>>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::last_resort_prekey]
-    pub fn proteus_last_resort_prekey(&self) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::get_external_sender]
+    pub fn get_external_sender(&self, id: ConversationId) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
-
-        future_to_promise(async move {
-            proteus_impl! { errcode_dest => {
-                let last_resort_pkbundle = this.read().await.proteus_last_resort_prekey().await.map_err(CoreCryptoError::from)?;
-                WasmCryptoResult::Ok(Uint8Array::from(last_resort_pkbundle.as_slice()).into())
-            } or throw WasmCryptoResult<_> }
-        }.err_into())
-    }
-
-    /// Returns: [`WasmCryptoResult<u16>`]
-    ///
-    /// see [core_crypto::proteus::ProteusCentral::last_resort_prekey_id]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_last_resort_prekey_id() -> WasmCryptoResult<u16> {
-        proteus_impl! {{
-            Ok(core_crypto::CoreCrypto::proteus_last_resort_prekey_id())
-        } or throw WasmCryptoResult<_> }
-    }
-
-    /// Returns: [`WasmCryptoResult<String>`]
-    ///
-    /// see [core_crypto::proteus::ProteusCentral::fingerprint]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub async fn proteus_fingerprint(&self) -> WasmCryptoResult<String> {
-        let errcode_dest = self.proteus_last_error_code.clone();
-
-        proteus_impl! { errcode_dest => {
-            self.inner.read().await.proteus_fingerprint().map_err(CoreCryptoError::from).map(Into::into)
-        } or throw WasmCryptoResult<_> }
-    }
-
-    /// Returns: [`WasmCryptoResult<String>`]
-    ///
-    /// see [core_crypto::proteus::ProteusCentral::fingerprint_local]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub async fn proteus_fingerprint_local(&self, session_id: String) -> WasmCryptoResult<String> {
-        let errcode_dest = self.proteus_last_error_code.clone();
-
-        proteus_impl! { errcode_dest => {
-            self.inner
-                .write()
-                .await
-                .proteus_fingerprint_local(&session_id)
-                .await
-                .map_err(CoreCryptoError::from)
-                .map(Into::into)
-        } or throw WasmCryptoResult<_> }
-    }
-
-    /// Returns: [`WasmCryptoResult<String>`]
-    ///
-    /// see [core_crypto::proteus::ProteusCentral::fingerprint_remote]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub async fn proteus_fingerprint_remote(&self, session_id: String) -> WasmCryptoResult<String> {
-        let errcode_dest = self.proteus_last_error_code.clone();
-
-        proteus_impl! { errcode_dest => {
-            self.inner.write().await.proteus_fingerprint_remote(&session_id).await
-                .map_err(CoreCryptoError::from).map(Into::into)
-        } or throw WasmCryptoResult<_> }
-    }
-
-    /// Returns: [`WasmCryptoResult<String>`]
-    ///
-    /// see [core_crypto::proteus::ProteusCproteus_fingerprint_prekeybundle]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_fingerprint_prekeybundle(prekey: Box<[u8]>) -> WasmCryptoResult<String> {
-        proteus_impl!({
-            core_crypto::proteus::ProteusCentral::fingerprint_prekeybundle(&prekey)
-                .map_err(Into::into).map(Into::into)
-        } or throw WasmCryptoResult<_>)
+        future_to_promise(
+            async move {
+                let ext_sender = this
+                    .write()
+                    .await
+                    .get_external_sender(&id.to_vec())
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                WasmCryptoResult::Ok(Uint8Array::from(ext_sender.as_slice()).into())
+            }
+            .err_into(),
+        )
     }
 
-    /// Returns: [`WasmCryptoResult<()>`]
+    /// Returns: [`WasmCryptoResult<CommitBundle>`]
     ///
-    /// see [core_crypto::proteus::ProteusCentral::cryptobox_migrate]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_cryptobox_migrate(&self, path: String) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::update_external_senders]
+    pub fn update_external_senders(
+        &self,
+        conversation_id: ConversationId,
+        external_senders: Box<[Uint8Array]>,
+    ) -> Promise {
         let this = self.inner.clone();
-        let errcode_dest = self.proteus_last_error_code.clone();
         future_to_promise(
             async move {
-                proteus_impl! { errcode_dest => {
-                    this.read().await.proteus_cryptobox_migrate(&path).await.map_err(CoreCryptoError::from)?;
-                    WasmCryptoResult::Ok(JsValue::UNDEFINED)
-                } or throw WasmCryptoResult<_> }
+                let external_senders = external_senders.iter().map(|s| s.to_vec()).collect();
+                let commit = this
+                    .write()
+                    .await
+                    .update_external_senders(&conversation_id, external_senders)
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                let commit: CommitBundle = commit.try_into()?;
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<u32>`]
-    #[cfg_attr(not(feature = "proteus"), allow(unused_variables))]
-    pub fn proteus_last_error_code(&self) -> Promise {
-        let errcode = self.proteus_last_error_code.clone();
+    /// Returns: [`WasmCryptoResult<CommitBundle>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::add_required_capabilities]
+    pub fn add_required_capabilities(
+        &self,
+        conversation_id: ConversationId,
+        extension_types: Box<[u16]>,
+        proposal_types: Box<[u16]>,
+    ) -> Promise {
+        let this = self.inner.clone();
         future_to_promise(
             async move {
-                proteus_impl! {{
-                    let prev_value: u32 = *(errcode.read().await);
-                    let mut errcode_val = errcode.write().await;
-                    *errcode_val = 0;
-
-                    WasmCryptoResult::Ok(prev_value.into())
-                } or throw WasmCryptoResult<_> }
+                let commit = this
+                    .write()
+                    .await
+                    .add_required_capabilities(&conversation_id, extension_types.into(), proposal_types.into())
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                let commit: CommitBundle = commit.try_into()?;
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<Vec<u8>>`]
+    /// Returns: [`WasmCryptoResult<CommitBundle>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::export_secret_key]
-    pub fn export_secret_key(&self, conversation_id: ConversationId, key_length: usize) -> Promise {
+    /// `extension_types` and `data` are paired up by index
+    ///
+    /// see [core_crypto::mls::MlsCentral::update_app_extensions]
+    pub fn update_app_extensions(
+        &self,
+        conversation_id: ConversationId,
+        extension_types: Box<[u16]>,
+        data: Box<[Uint8Array]>,
+    ) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let key = this
+                if extension_types.len() != data.len() {
+                    return Err(CoreCryptoError(WasmError::CryptoError(CryptoError::ConsumerError)));
+                }
+                let app_extensions = extension_types
+                    .iter()
+                    .copied()
+                    .zip(data.iter().map(|d| d.to_vec()))
+                    .collect();
+                let commit = this
                     .write()
                     .await
-                    .export_secret_key(&conversation_id.to_vec(), key_length)
+                    .update_app_extensions(&conversation_id, app_extensions)
                     .await
                     .map_err(CoreCryptoError::from)?;
-                WasmCryptoResult::Ok(Uint8Array::from(key.as_slice()).into())
+                let commit: CommitBundle = commit.try_into()?;
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&commit)?)
             }
             .err_into(),
         )
     }
 
-    /// Returns: [`WasmCryptoResult<Vec<u8>>`]
+    /// Returns: [`WasmCryptoResult<Vec<AppExtension>>`]
     ///
-    /// see [core_crypto::mls::MlsCentral::get_external_sender]
-    pub fn get_external_sender(&self, id: ConversationId) -> Promise {
+    /// see [core_crypto::mls::MlsCentral::app_extensions]
+    pub fn app_extensions(&self, conversation_id: ConversationId) -> Promise {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let ext_sender = this
+                let app_extensions = this
                     .write()
                     .await
-                    .get_external_sender(&id.to_vec())
+                    .app_extensions(&conversation_id)
                     .await
-                    .map_err(CoreCryptoError::from)?;
-                WasmCryptoResult::Ok(Uint8Array::from(ext_sender.as_slice()).into())
+                    .map_err(CoreCryptoError::from)?
+                    .into_iter()
+                    .map(|(extension_type, data)| AppExtension { extension_type, data })
+                    .collect::<Vec<_>>();
+                WasmCryptoResult::Ok(serde_wasm_bindgen::to_value(&app_extensions)?)
             }
             .err_into(),
         )
@@ -2588,6 +4099,27 @@ impl CoreCrypto {
             .err_into(),
         )
     }
+
+    /// Returns: [`WasmCryptoResult<js_sys::Array<ConversationMember>>`]
+    ///
+    /// see [core_crypto::mls::MlsCentral::get_conversation_roster]
+    pub fn get_conversation_roster(&self, conversation_id: ConversationId) -> Promise {
+        let this = self.inner.clone();
+        future_to_promise(
+            async move {
+                let roster = this
+                    .write()
+                    .await
+                    .get_conversation_roster(&conversation_id.to_vec())
+                    .await
+                    .map_err(CoreCryptoError::from)?;
+                let roster =
+                    js_sys::Array::from_iter(roster.into_iter().map(ConversationMember::from).map(JsValue::from));
+                WasmCryptoResult::Ok(roster.into())
+            }
+            .err_into(),
+        )
+    }
 }
 
 // End-to-end identity methods
@@ -2955,14 +4487,12 @@ impl CoreCrypto {
         let this = self.inner.clone();
         future_to_promise(
             async move {
-                let group_info = VerifiableGroupInfo::tls_deserialize(&mut group_info.as_ref())
-                    .map_err(MlsError::from)
-                    .map_err(CryptoError::from)
+                let central = this.read().await;
+                let group_info = central
+                    .open_group_info(&group_info, None)
                     .map_err(CoreCryptoError::from)?;
 
-                let state: E2eiConversationState = this
-                    .write()
-                    .await
+                let state: E2eiConversationState = central
                     .get_credential_in_use(group_info, credential_type.into())
                     .await
                     .map(Into::into)
@@ -1,6 +1,6 @@
 use crate::{
     mls::credential::ext::CredentialExt,
-    prelude::{ConversationId, CryptoResult, MlsCentral, MlsConversation, MlsCredentialType},
+    prelude::{ConversationId, CryptoResult, MlsCentral, MlsConversation, MlsCredentialType, MlsGroupInfoIn},
     MlsError,
 };
 
@@ -40,12 +40,13 @@ impl MlsCentral {
         conversation_lock.e2ei_conversation_state(&self.mls_backend).await
     }
 
-    /// Verifies a Group state before joining it
+    /// Verifies a Group state before joining it. The GroupInfo can be encrypted in a JWE, see [MlsGroupInfoIn]
     #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
     pub async fn e2ei_verify_group_state(
         &self,
-        group_info: VerifiableGroupInfo,
+        group_info: impl Into<MlsGroupInfoIn>,
     ) -> CryptoResult<E2eiConversationState> {
+        let group_info = self.resolve_group_info(group_info.into())?;
         self.mls_backend
             .authentication_service()
             .refresh_time_of_interest()
@@ -1,9 +1,8 @@
 use crate::{e2e_identity::CrlRegistration, prelude::MlsCentral, CryptoError, CryptoResult};
-use core_crypto_keystore::entities::{E2eiAcmeCA, E2eiCrl, E2eiIntermediateCert, EntityBase, UniqueEntity};
+use core_crypto_keystore::entities::{E2eiAcmeCA, E2eiCrl, E2eiIntermediateCert};
 use mls_crypto_provider::MlsCryptoProvider;
 use openmls_traits::OpenMlsCryptoProvider;
 use std::collections::HashSet;
-use std::ops::DerefMut;
 use wire_e2e_identity::prelude::x509::{
     extract_crl_uris, extract_expiration_from_crl,
     revocation::{PkiEnvironment, PkiEnvironmentParams},
@@ -108,13 +107,10 @@ impl MlsCentral {
     /// * `trust_anchor_pem` - PEM certificate to anchor as a Trust Root
     #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
     pub async fn e2ei_register_acme_ca(&self, trust_anchor_pem: String) -> CryptoResult<()> {
-        {
-            let mut conn = self.mls_backend.key_store().borrow_conn().await?;
-            if E2eiAcmeCA::find_unique(&mut conn).await.is_ok() {
-                return Err(CryptoError::E2eiError(
-                    super::E2eIdentityError::TrustAnchorAlreadyRegistered,
-                ));
-            }
+        if self.mls_backend.key_store().find_unique::<E2eiAcmeCA>().await.is_ok() {
+            return Err(CryptoError::E2eiError(
+                super::E2eIdentityError::TrustAnchorAlreadyRegistered,
+            ));
         }
 
         let pki_env = PkiEnvironment::init(PkiEnvironmentParams {
@@ -137,9 +133,7 @@ impl MlsCentral {
         // Save DER repr in keystore
         let cert_der = PkiEnvironment::encode_cert_to_der(&root_cert).map_err(|e| CryptoError::E2eiError(e.into()))?;
         let acme_ca = E2eiAcmeCA { content: cert_der };
-        let mut conn = self.mls_backend.key_store().borrow_conn().await?;
-        acme_ca.replace(&mut conn).await?;
-        drop(conn);
+        self.mls_backend.key_store().replace_unique(acme_ca).await?;
 
         // To do that, tear down and recreate the pki env
         self.init_pki_env().await?;
@@ -176,7 +170,7 @@ impl MlsCentral {
         inter_ca: x509_cert::Certificate,
     ) -> CryptoResult<NewCrlDistributionPoint> {
         // TrustAnchor must have been registered at this point
-        let ta = E2eiAcmeCA::find_unique(self.mls_backend.key_store().borrow_conn().await?.deref_mut()).await?;
+        let ta = self.mls_backend.key_store().find_unique::<E2eiAcmeCA>().await?;
         let ta = x509_cert::Certificate::from_der(&ta.content)?;
 
         // the `/federation` endpoint from smallstep repeats the root CA
@@ -281,10 +275,9 @@ impl MlsCentral {
     #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
     pub(crate) async fn restore_pki_env(backend: &MlsCryptoProvider) -> CryptoResult<Option<PkiEnvironment>> {
         let keystore = backend.key_store();
-        let mut conn = keystore.borrow_conn().await?;
 
         let mut trust_roots = vec![];
-        let Ok(ta_raw) = E2eiAcmeCA::find_unique(&mut conn).await else {
+        let Ok(ta_raw) = keystore.find_unique::<E2eiAcmeCA>().await else {
             return Ok(None);
         };
 
@@ -292,7 +285,8 @@ impl MlsCentral {
             x509_cert::Certificate::from_der(&ta_raw.content).map(x509_cert::anchor::TrustAnchorChoice::Certificate)?,
         );
 
-        let intermediates = E2eiIntermediateCert::find_all(&mut conn, Default::default())
+        let intermediates = keystore
+            .find_all::<E2eiIntermediateCert>(Default::default())
             .await?
             .into_iter()
             .try_fold(vec![], |mut acc, inter| {
@@ -300,7 +294,8 @@ impl MlsCentral {
                 CryptoResult::Ok(acc)
             })?;
 
-        let crls = E2eiCrl::find_all(&mut conn, Default::default())
+        let crls = keystore
+            .find_all::<E2eiCrl>(Default::default())
             .await?
             .into_iter()
             .try_fold(vec![], |mut acc, crl| {
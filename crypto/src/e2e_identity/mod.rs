@@ -710,9 +710,12 @@ pub mod tests {
                     crate::e2e_identity::refresh_token::RefreshToken::from("initial-refresh-token".to_string());
                 let initial_refresh_token =
                     core_crypto_keystore::entities::E2eiRefreshToken::from(initial_refresh_token);
-                let mut conn = ctx.mls_central.mls_backend.key_store().borrow_conn().await?;
-                use core_crypto_keystore::entities::UniqueEntity as _;
-                initial_refresh_token.replace(&mut conn).await.unwrap();
+                ctx.mls_central
+                    .mls_backend
+                    .key_store()
+                    .replace_unique(initial_refresh_token)
+                    .await
+                    .unwrap();
             }
         }
 
@@ -3,10 +3,7 @@ use crate::{
     prelude::{E2eIdentityError, E2eIdentityResult, MlsCentral},
     CryptoError, CryptoResult,
 };
-use core_crypto_keystore::{
-    entities::{E2eiRefreshToken, UniqueEntity},
-    CryptoKeystoreResult,
-};
+use core_crypto_keystore::{entities::E2eiRefreshToken, CryptoKeystoreResult};
 use mls_crypto_provider::MlsCryptoProvider;
 use openmls_traits::OpenMlsCryptoProvider;
 use zeroize::Zeroize;
@@ -36,17 +33,19 @@ impl E2eiEnrollment {
         backend: &MlsCryptoProvider,
         rt: RefreshToken,
     ) -> CryptoKeystoreResult<()> {
-        let mut conn = backend.key_store().borrow_conn().await?;
         let rt = E2eiRefreshToken::from(rt);
-        rt.replace(&mut conn).await
+        backend.key_store().replace_unique(rt).await
     }
 }
 
 impl MlsCentral {
     #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
     pub(crate) async fn find_refresh_token(&self) -> CryptoResult<RefreshToken> {
-        let mut conn = self.mls_backend.key_store().borrow_conn().await?;
-        E2eiRefreshToken::find_unique(&mut conn).await?.try_into()
+        self.mls_backend
+            .key_store()
+            .find_unique::<E2eiRefreshToken>()
+            .await?
+            .try_into()
     }
 }
 
@@ -3,7 +3,6 @@ use std::collections::HashMap;
 use openmls::prelude::{KeyPackage, KeyPackageRef, MlsCredentialType as OpenMlsCredential};
 use openmls_traits::OpenMlsCryptoProvider;
 
-use core_crypto_keystore::{entities::MlsKeyPackage, CryptoKeystoreMls};
 use mls_crypto_provider::MlsCryptoProvider;
 
 use crate::e2e_identity::init_certificates::NewCrlDistributionPoint;
@@ -143,9 +142,9 @@ impl MlsCentral {
             .save_new_x509_credential_bundle(&self.mls_backend, cs.signature_algorithm(), cert_bundle)
             .await?;
 
-        let commits = self.e2ei_update_all(&new_cb).await?;
+        let commits = self.e2ei_update_all(cs, &new_cb).await?;
 
-        let key_package_refs_to_remove = self.find_key_packages_to_remove(&new_cb).await?;
+        let key_package_refs_to_remove = self.find_key_packages_to_remove(cs, &new_cb).await?;
 
         let new_key_packages = self
             .mls_client()?
@@ -161,9 +160,14 @@ impl MlsCentral {
     }
 
     #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
-    async fn find_key_packages_to_remove(&self, cb: &CredentialBundle) -> CryptoResult<Vec<KeyPackageRef>> {
-        let nb_kp = self.mls_backend.key_store().count::<MlsKeyPackage>().await?;
-        let kps: Vec<KeyPackage> = self.mls_backend.key_store().mls_fetch_keypackages(nb_kp as u32).await?;
+    async fn find_key_packages_to_remove(
+        &self,
+        cs: MlsCiphersuite,
+        cb: &CredentialBundle,
+    ) -> CryptoResult<Vec<KeyPackageRef>> {
+        // KeyPackages of other ciphersuites are bound to other credentials which are not being rotated
+        let kps = self.mls_client()?.find_all_keypackages(&self.mls_backend).await?;
+        let kps = kps.into_iter().map(|(_, kp)| kp).filter(|kp| kp.ciphersuite() == cs.0);
 
         let mut kp_refs = vec![];
 
@@ -192,6 +196,7 @@ impl MlsCentral {
     #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
     async fn e2ei_update_all(
         &mut self,
+        cs: MlsCiphersuite,
         cb: &CredentialBundle,
     ) -> CryptoResult<HashMap<ConversationId, MlsCommitBundle>> {
         let all_conversations = self.get_all_conversations().await?;
@@ -199,8 +204,13 @@ impl MlsCentral {
         let mut commits = HashMap::with_capacity(all_conversations.len());
         for conv in all_conversations {
             let mut conv = conv.write().await;
+            // conversations of other ciphersuites use a credential of their own
+            if conv.ciphersuite() != cs {
+                continue;
+            }
             let id = conv.id().clone();
-            let commit = conv.e2ei_rotate(&self.mls_backend, self.mls_client()?, cb).await?;
+            let mut commit = conv.e2ei_rotate(&self.mls_backend, self.mls_client()?, cb).await?;
+            commit.group_info = self.finalize_group_info(commit.group_info, Some(&*conv))?;
             let _ = commits.insert(id, commit);
         }
         Ok(commits)
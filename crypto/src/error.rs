@@ -34,6 +34,12 @@ pub enum CryptoError {
     /// This error is emitted when the requested client couldn't be found in MLS group
     #[error("Couldn't find client")]
     ClientNotFound(crate::prelude::ClientId),
+    /// No device of the requested user could be found in the MLS group
+    #[error("Couldn't find any device of user {0}")]
+    UserNotFound(String),
+    /// A KeyPackage supplied to add a user's devices belongs to another user
+    #[error("A KeyPackage does not belong to the user being added")]
+    KeyPackageUserMismatch(crate::prelude::ClientId),
     /// This error is emitted when a pending proposal couldn't be found in MLS group
     #[error("Couldn't find pending proposal {0}")]
     PendingProposalNotFound(crate::mls::proposal::MlsProposalRef),
@@ -134,6 +140,29 @@ pub enum CryptoError {
     /// Incoming message is for a future epoch. We will buffer it until the commit for that epoch arrives
     #[error("Incoming message is for a future epoch. We will buffer it until the commit for that epoch arrives")]
     BufferedFutureMessage,
+    /// History sharing has not been enabled for this conversation
+    #[error("History sharing has not been enabled for this conversation")]
+    HistorySharingDisabled,
+    /// A history archive could not be decrypted, either because it is malformed or because it was tampered with
+    #[error("Failed to decrypt the history archive")]
+    HistoryArchiveDecryptionError,
+    /// A snapshot can only be imported in a keystore which does not hold any client yet
+    #[error("A snapshot can only be imported in a keystore which does not hold any client yet")]
+    SnapshotImportIntoNonEmptyKeystore,
+    /// The snapshot was created by a newer version of CoreCrypto
+    #[error("Unsupported snapshot version {0}")]
+    UnsupportedSnapshotVersion(u8),
+    /// A snapshot could not be decrypted, either because the passphrase is wrong or because it was tampered with
+    #[error("Failed to decrypt the snapshot")]
+    SnapshotDecryptionError,
+    /// Incoming message is for a conversation we have not joined yet. We will buffer it until its Welcome
+    /// or our external commit gets merged
+    #[error("Incoming message is for a conversation we have not joined yet. We will buffer it until we join it")]
+    BufferedMessageForUnknownConversation,
+    /// Incoming message has been buffered, but the buffer of this conversation was full or held expired
+    /// messages. Those have been evicted and are returned here
+    #[error("Incoming message has been buffered, evicting {} older message(s)", .0.len())]
+    FutureMessageBufferOverflow(Vec<crate::prelude::MlsBufferedMessage>),
     /// Proteus Error Wrapper
     #[error(transparent)]
     ProteusError(#[from] ProteusError),
@@ -161,6 +190,9 @@ pub enum CryptoError {
     /// Parent group cannot be found
     #[error("The specified parent group has not been found in the keystore")]
     ParentGroupNotFound,
+    /// Tried to wipe a conversation we are leaving without having created the proposal removing ourselves
+    #[error("No pending proposal removing ourselves from this conversation was found")]
+    SelfRemoveProposalNotFound,
     /// Message epoch is too old
     #[error("The epoch in which message was encrypted is older than {MAX_PAST_EPOCHS}")]
     MessageEpochTooOld,
@@ -246,6 +278,27 @@ pub enum CryptoError {
     /// The group lacks an ExternalSender extension whereas it should have at least one
     #[error("The group lacks an ExternalSender extension whereas it should have at least one")]
     MissingExternalSenderExtension,
+    /// A member does not support the capabilities required by GroupContext extensions or the extensions themselves
+    #[error("A member does not support the capabilities required by the GroupContext extensions or the extensions themselves")]
+    UnsupportedGroupContextExtensions,
+    /// The GroupInfo is encrypted but no key is available to decrypt it
+    #[error("The GroupInfo is encrypted but no key is available to decrypt it")]
+    MissingGroupInfoEncryptionKey,
+    /// The encrypted GroupInfo is malformed or could not be decrypted with the supplied key
+    #[error("The encrypted GroupInfo is malformed or could not be decrypted with the supplied key")]
+    GroupInfoDecryptionError,
+    /// The GroupInfo or its ratchet tree delta could not be parsed
+    #[error("The GroupInfo or its ratchet tree delta could not be parsed")]
+    MalformedGroupInfo,
+    /// A ratchet tree delta can only be applied on the GroupInfo of the previous epoch of the same group
+    #[error("A ratchet tree delta can only be applied on the GroupInfo of the previous epoch of the same group")]
+    RatchetTreeDeltaEpochMismatch,
+    /// The ratchet tree delta has been encoded with a version this client does not support
+    #[error("The ratchet tree delta has an unsupported version: {0}")]
+    UnsupportedRatchetTreeDeltaVersion(u8),
+    /// A ReInit proposal has to be committed alone but the conversation has other pending proposals
+    #[error("A ReInit proposal cannot be committed along with other pending proposals")]
+    PendingProposalsPreventReInit,
     /// Not supported for the moment
     #[error("Not supported for the moment")]
     Unsupported,
@@ -331,6 +384,9 @@ pub enum MlsError {
     /// Propose remove members error
     #[error(transparent)]
     ProposeRemoveMemberError(#[from] openmls::prelude::ProposeRemoveMemberError),
+    /// Propose external PSK error
+    #[error(transparent)]
+    ProposePskError(#[from] openmls::prelude::ProposalError<core_crypto_keystore::CryptoKeystoreError>),
     /// Commit to pending proposals error
     #[error(transparent)]
     MlsCommitToPendingProposalsError(
@@ -383,6 +439,9 @@ pub enum MlsError {
     MlsUpdateExtensionsError(
         #[from] openmls::prelude::UpdateExtensionsError<core_crypto_keystore::CryptoKeystoreError>,
     ),
+    /// OpenMLS invalid extension error
+    #[error(transparent)]
+    MlsInvalidExtensionError(#[from] openmls::prelude::InvalidExtensionError),
     /// OpenMLS LeafNode validation error
     #[error(transparent)]
     MlsLeafNodeValidationError(#[from] openmls::prelude::LeafNodeValidationError),
@@ -392,6 +451,9 @@ pub enum MlsError {
     /// OpenMLS GroupInfo error
     #[error(transparent)]
     GroupInfoError(#[from] openmls::messages::group_info::GroupInfoError),
+    /// OpenMLS pre-shared key error
+    #[error(transparent)]
+    MlsPskError(#[from] openmls::schedule::errors::PskError),
 }
 
 #[derive(Debug, thiserror::Error, strum::IntoStaticStr)]
@@ -28,14 +28,12 @@ pub(crate) trait GroupStoreEntity: std::fmt::Debug {
     async fn fetch_from_id(
         id: &[u8],
         identity: Option<Self::IdentityType>,
-        keystore: &mut core_crypto_keystore::connection::KeystoreDatabaseConnection,
+        keystore: &core_crypto_keystore::Connection,
     ) -> CryptoResult<Option<Self>>
     where
         Self: Sized;
 
-    async fn fetch_all(
-        keystore: &mut core_crypto_keystore::connection::KeystoreDatabaseConnection,
-    ) -> CryptoResult<Vec<Self>>
+    async fn fetch_all(keystore: &core_crypto_keystore::Connection) -> CryptoResult<Vec<Self>>
     where
         Self: Sized;
 }
@@ -53,10 +51,9 @@ impl GroupStoreEntity for MlsConversation {
     async fn fetch_from_id(
         id: &[u8],
         _: Option<Self::IdentityType>,
-        keystore: &mut core_crypto_keystore::connection::KeystoreDatabaseConnection,
+        keystore: &core_crypto_keystore::Connection,
     ) -> crate::CryptoResult<Option<Self>> {
-        use core_crypto_keystore::entities::EntityBase as _;
-        let Some(store_value) = Self::RawStoreValue::find_one(keystore, &id.into()).await? else {
+        let Some(store_value) = keystore.find::<Self::RawStoreValue>(id).await? else {
             return Ok(None);
         };
 
@@ -69,12 +66,8 @@ impl GroupStoreEntity for MlsConversation {
         })
     }
 
-    async fn fetch_all(
-        keystore: &mut core_crypto_keystore::connection::KeystoreDatabaseConnection,
-    ) -> CryptoResult<Vec<Self>> {
-        use core_crypto_keystore::entities::EntityBase as _;
-
-        let all_conversations = Self::RawStoreValue::find_all(keystore, EntityFindParams::default());
+    async fn fetch_all(keystore: &core_crypto_keystore::Connection) -> CryptoResult<Vec<Self>> {
+        let all_conversations = keystore.find_all::<Self::RawStoreValue>(EntityFindParams::default());
         Ok(all_conversations
             .await?
             .iter()
@@ -100,10 +93,9 @@ impl GroupStoreEntity for crate::proteus::ProteusConversationSession {
     async fn fetch_from_id(
         id: &[u8],
         identity: Option<Self::IdentityType>,
-        keystore: &mut core_crypto_keystore::connection::KeystoreDatabaseConnection,
+        keystore: &core_crypto_keystore::Connection,
     ) -> crate::CryptoResult<Option<Self>> {
-        use core_crypto_keystore::entities::EntityBase as _;
-        let Some(store_value) = Self::RawStoreValue::find_one(keystore, &id.into()).await? else {
+        let Some(store_value) = keystore.find::<Self::RawStoreValue>(id).await? else {
             return Ok(None);
         };
 
@@ -120,9 +112,7 @@ impl GroupStoreEntity for crate::proteus::ProteusConversationSession {
         }))
     }
 
-    async fn fetch_all(
-        _keystore: &mut core_crypto_keystore::connection::KeystoreDatabaseConnection,
-    ) -> CryptoResult<Vec<Self>>
+    async fn fetch_all(_keystore: &core_crypto_keystore::Connection) -> CryptoResult<Vec<Self>>
     where
         Self: Sized,
     {
@@ -210,13 +200,8 @@ impl<V: GroupStoreEntity> GroupStore<V> {
             return Ok(Some(value.clone()));
         }
 
-        let mut keystore_connection = keystore
-            .borrow_conn()
-            .await
-            .map_err(|_| crate::CryptoError::LockPoisonError)?;
-
         // Not in store, fetch the thing in the keystore
-        let mut value = V::fetch_from_id(k, identity, &mut keystore_connection).await?;
+        let mut value = V::fetch_from_id(k, identity, keystore).await?;
         if let Some(value) = value.take() {
             let value_to_insert = std::sync::Arc::new(async_lock::RwLock::new(value));
             self.insert_prepped(k.to_vec(), value_to_insert.clone());
@@ -231,12 +216,7 @@ impl<V: GroupStoreEntity> GroupStore<V> {
         &mut self,
         keystore: &mut core_crypto_keystore::Connection,
     ) -> CryptoResult<Vec<GroupStoreValue<V>>> {
-        let mut keystore_connection = keystore
-            .borrow_conn()
-            .await
-            .map_err(|_| crate::CryptoError::LockPoisonError)?;
-
-        let all = V::fetch_all(&mut keystore_connection)
+        let all = V::fetch_all(keystore)
             .await?
             .into_iter()
             .map(|g| {
@@ -373,15 +353,13 @@ mod tests {
         async fn fetch_from_id(
             id: &[u8],
             _identity: Option<Self::IdentityType>,
-            _keystore: &mut core_crypto_keystore::connection::KeystoreDatabaseConnection,
+            _keystore: &core_crypto_keystore::Connection,
         ) -> crate::CryptoResult<Option<Self>> {
             let id = std::str::from_utf8(id)?;
             Ok(Some(id.into()))
         }
 
-        async fn fetch_all(
-            _keystore: &mut core_crypto_keystore::connection::KeystoreDatabaseConnection,
-        ) -> CryptoResult<Vec<Self>> {
+        async fn fetch_all(_keystore: &core_crypto_keystore::Connection) -> CryptoResult<Vec<Self>> {
             unreachable!()
         }
     }
@@ -83,11 +83,25 @@ pub mod prelude {
             client::*,
             config::MlsCentralConfiguration,
             conversation::{
+                buffer_messages::{
+                    MlsBufferedMessage, MlsPendingMessagesLimits, DEFAULT_PENDING_MESSAGES_MAX_AGE,
+                    DEFAULT_PENDING_MESSAGES_MAX_COUNT, DEFAULT_PENDING_MESSAGES_MAX_UNKNOWN_CONVERSATIONS,
+                },
                 commit::{MlsCommitBundle, MlsConversationCreationMessage},
                 config::{MlsConversationConfiguration, MlsCustomConfiguration, MlsWirePolicy},
                 decrypt::{MlsBufferedConversationDecryptMessage, MlsConversationDecryptMessage},
-                group_info::{GroupInfoPayload, MlsGroupInfoBundle, MlsGroupInfoEncryptionType, MlsRatchetTreeType},
+                epoch_authenticator::{MlsEpochAuthenticator, SafetyNumberFormat, EPOCH_AUTHENTICATOR_HISTORY_SIZE},
+                group_info::{
+                    GroupInfoPayload, MlsGroupInfoBundle, MlsGroupInfoEncryption, MlsGroupInfoEncryptionKey,
+                    MlsGroupInfoEncryptionType, MlsGroupInfoIn, MlsRatchetTreeType, RATCHET_TREE_DELTA_VERSION,
+                },
+                history_sharing::{
+                    MlsHistoryMessage, MlsHistorySharingLimits, DEFAULT_HISTORY_MAX_AGE, DEFAULT_HISTORY_MAX_EPOCHS,
+                    DEFAULT_HISTORY_MAX_MESSAGES, HISTORY_ARCHIVE_AAD,
+                },
                 proposal::MlsProposalBundle,
+                reinit::MlsReInit,
+                roster::{MlsConversationMember, MlsMemberStatus},
                 welcome::WelcomeBundle,
                 *,
             },
@@ -5,7 +5,10 @@
 //!
 //! Feel free to delete all of this when the issue is fixed on the DS side !
 
-use crate::prelude::{ConversationId, CryptoError, CryptoResult, MlsCentral, MlsConversationDecryptMessage};
+use crate::{
+    mls::conversation::key_rotation::now,
+    prelude::{ConversationId, CryptoError, CryptoResult, MlsCentral, MlsConversationDecryptMessage},
+};
 use core_crypto_keystore::entities::{MlsPendingMessage, PersistedMlsPendingGroup};
 
 impl MlsCentral {
@@ -17,12 +20,13 @@ impl MlsCentral {
     ) -> CryptoResult<MlsConversationDecryptMessage> {
         let keystore = self.mls_backend.borrow_keystore();
         let Ok(Some(pending_group)) = keystore.find::<PersistedMlsPendingGroup>(id).await else {
-            return Err(CryptoError::ConversationNotFound(id.clone()));
+            return self.handle_unknown_conversation_message(id, message).await;
         };
 
         let pending_msg = MlsPendingMessage {
             id: pending_group.id.clone(),
             message: message.as_ref().to_vec(),
+            received_at: now(),
         };
         keystore.save::<MlsPendingMessage>(pending_msg).await?;
         Err(CryptoError::UnmergedPendingGroup)
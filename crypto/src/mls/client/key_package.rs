@@ -20,13 +20,7 @@ use openmls::prelude::{Credential, CredentialWithKey, CryptoConfig, KeyPackage,
 use openmls_traits::OpenMlsCryptoProvider;
 use tls_codec::{Deserialize, Serialize};
 
-use core_crypto_keystore::{
-    connection::KeystoreDatabaseConnection,
-    entities::{
-        EntityBase, EntityFindParams, MlsCredential, MlsCredentialExt, MlsEncryptionKeyPair, MlsHpkePrivateKey,
-        MlsKeyPackage,
-    },
-};
+use core_crypto_keystore::entities::{EntityFindParams, MlsEncryptionKeyPair, MlsHpkePrivateKey, MlsKeyPackage};
 use mls_crypto_provider::MlsCryptoProvider;
 
 use crate::{
@@ -102,16 +96,21 @@ impl Client {
         backend: &MlsCryptoProvider,
     ) -> CryptoResult<Vec<KeyPackage>> {
         // Auto-prune expired keypackages on request
-        self.prune_keypackages(backend, &[]).await?;
-        use core_crypto_keystore::CryptoKeystoreMls as _;
+        self.prune_keypackages(backend, ciphersuite, &[]).await?;
 
-        let mut existing_kps = backend
-            .key_store()
-            .mls_fetch_keypackages::<KeyPackage>(count as u32)
+        let mut existing_kps = self
+            .find_all_keypackages(backend)
             .await?
             .into_iter()
+            .map(|(_, kp)| kp)
             // TODO: do this filtering in SQL when the schema is updated
-            .filter(|kp| kp.ciphersuite() == ciphersuite.0)
+            .filter(|kp| {
+                kp.ciphersuite() == ciphersuite.0
+                    && MlsCredentialType::from(kp.leaf_node().credential().credential_type()) == credential_type
+            })
+            // most recent first
+            .rev()
+            .take(count)
             .collect::<Vec<_>>();
 
         let kpb_count = existing_kps.len();
@@ -160,27 +159,17 @@ impl Client {
         ciphersuite: MlsCiphersuite,
         credential_type: MlsCredentialType,
     ) -> CryptoResult<usize> {
-        use core_crypto_keystore::entities::EntityBase as _;
-        let keystore = backend.key_store();
-
-        let mut conn = keystore.borrow_conn().await?;
-        let kps = MlsKeyPackage::find_all(&mut conn, EntityFindParams::default()).await?;
-
-        let valid_count = kps
+        let valid_count = self
+            .find_all_keypackages(backend)
+            .await?
             .into_iter()
-            .map(|kp| core_crypto_keystore::deser::<KeyPackage>(&kp.keypackage))
             // TODO: do this filtering in SQL when the schema is updated
-            .filter(|kp| {
-                kp.as_ref()
-                    .map(|b| b.ciphersuite() == ciphersuite.0 && MlsCredentialType::from(b.leaf_node().credential().credential_type()) == credential_type)
-                    .unwrap_or_default()
+            .filter(|(_, kp)| {
+                kp.ciphersuite() == ciphersuite.0
+                    && MlsCredentialType::from(kp.leaf_node().credential().credential_type()) == credential_type
+                    && !Self::is_mls_keypackage_expired(kp)
             })
-            .try_fold(0usize, |mut valid_count, kp| {
-                if !Self::is_mls_keypackage_expired(&kp?) {
-                    valid_count += 1;
-                }
-                CryptoResult::Ok(valid_count)
-            })?;
+            .count();
 
         Ok(valid_count)
     }
@@ -195,15 +184,21 @@ impl Client {
         !(lifetime.has_acceptable_range() && lifetime.is_valid())
     }
 
-    /// Prune the provided KeyPackageRefs from the keystore
+    /// Prune the expired KeyPackages of the given ciphersuite along with the provided KeyPackageRefs from the keystore.
+    /// KeyPackages of other ciphersuites are left untouched, even when referenced in `refs`.
     ///
     /// Warning: Despite this API being public, the caller should know what they're doing.
     /// Provided KeypackageRefs **will** be purged regardless of their expiration state, so please be wary of what you are doing if you directly call this API.
     /// This could result in still valid, uploaded keypackages being pruned from the system and thus being impossible to find when referenced in a future Welcome message.
-    pub async fn prune_keypackages(&self, backend: &MlsCryptoProvider, refs: &[KeyPackageRef]) -> CryptoResult<()> {
-        let mut conn = backend.key_store().borrow_conn().await?;
-        let kps = self.find_all_keypackages(&mut conn).await?;
-        let _ = self._prune_keypackages(&kps, &mut conn, refs).await?;
+    pub async fn prune_keypackages(
+        &self,
+        backend: &MlsCryptoProvider,
+        ciphersuite: MlsCiphersuite,
+        refs: &[KeyPackageRef],
+    ) -> CryptoResult<()> {
+        let mut kps = self.find_all_keypackages(backend).await?;
+        kps.retain(|(_, kp)| kp.ciphersuite() == ciphersuite.0);
+        let _ = self._prune_keypackages(&kps, backend, refs).await?;
         Ok(())
     }
 
@@ -213,9 +208,8 @@ impl Client {
         backend: &MlsCryptoProvider,
         refs: &[KeyPackageRef],
     ) -> CryptoResult<()> {
-        let mut conn = backend.key_store().borrow_conn().await?;
-        let kps = self.find_all_keypackages(&mut conn).await?;
-        let kp_to_delete = self._prune_keypackages(&kps, &mut conn, refs).await?;
+        let kps = self.find_all_keypackages(backend).await?;
+        let kp_to_delete = self._prune_keypackages(&kps, backend, refs).await?;
 
         // Let's group KeyPackages by Credential
         let mut grouped_kps = HashMap::<Vec<u8>, Vec<KeyPackageRef>>::new();
@@ -237,7 +231,7 @@ impl Client {
             let all_to_delete = kps.iter().all(|kpr| kp_to_delete.contains(&kpr.as_slice()));
             if all_to_delete {
                 // then delete this Credential
-                MlsCredential::delete_by_credential(&mut conn, credential.clone()).await?;
+                backend.key_store().remove_credential(credential).await?;
                 let credential = Credential::tls_deserialize(&mut credential.as_slice()).map_err(MlsError::from)?;
                 self.identities.remove(&credential)?;
             }
@@ -254,11 +248,10 @@ impl Client {
     async fn _prune_keypackages<'a>(
         &self,
         kps: &'a [(MlsKeyPackage, KeyPackage)],
-        conn: &mut KeystoreDatabaseConnection,
+        backend: &MlsCryptoProvider,
         refs: &[KeyPackageRef],
     ) -> Result<Vec<&'a [u8]>, CryptoError> {
-        use core_crypto_keystore::entities::EntityBase as _;
-
+        let keystore = backend.key_store();
         let kp_to_delete: Vec<_> = kps
             .iter()
             .filter_map(|(store_kp, kp)| {
@@ -276,9 +269,13 @@ impl Client {
 
         for (kp, kp_ref) in &kp_to_delete {
             // TODO: maybe rewrite this to optimize it. But honestly it's called so rarely and on a so tiny amount of data
-            MlsKeyPackage::delete(conn, &[kp_ref.as_slice().into()]).await?;
-            MlsHpkePrivateKey::delete(conn, &[kp.hpke_init_key().as_slice().into()]).await?;
-            MlsEncryptionKeyPair::delete(conn, &[kp.leaf_node().encryption_key().as_slice().into()]).await?;
+            keystore.remove::<MlsKeyPackage, _>(kp_ref.as_slice()).await?;
+            keystore
+                .remove::<MlsHpkePrivateKey, _>(kp.hpke_init_key().as_slice())
+                .await?;
+            keystore
+                .remove::<MlsEncryptionKeyPair, _>(kp.leaf_node().encryption_key().as_slice())
+                .await?;
         }
 
         let kp_to_delete = kp_to_delete
@@ -289,11 +286,14 @@ impl Client {
         Ok(kp_to_delete)
     }
 
-    async fn find_all_keypackages(
+    pub(crate) async fn find_all_keypackages(
         &self,
-        conn: &mut KeystoreDatabaseConnection,
+        backend: &MlsCryptoProvider,
     ) -> CryptoResult<Vec<(MlsKeyPackage, KeyPackage)>> {
-        let kps = MlsKeyPackage::find_all(conn, EntityFindParams::default()).await?;
+        let kps = backend
+            .key_store()
+            .find_all::<MlsKeyPackage>(EntityFindParams::default())
+            .await?;
 
         let kps = kps.into_iter().try_fold(vec![], |mut acc, raw_kp| {
             let kp = core_crypto_keystore::deser::<KeyPackage>(&raw_kp.keypackage)?;
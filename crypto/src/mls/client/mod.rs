@@ -31,10 +31,10 @@ use core_crypto_keystore::CryptoKeystoreError;
 use openmls::prelude::{Credential, CredentialType};
 use openmls_basic_credential::SignatureKeyPair;
 use openmls_traits::{crypto::OpenMlsCrypto, types::SignatureScheme, OpenMlsCryptoProvider};
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 use tls_codec::{Deserialize, Serialize};
 
-use core_crypto_keystore::entities::{EntityBase, EntityFindParams, MlsCredential, MlsSignatureKeyPair};
+use core_crypto_keystore::entities::{EntityFindParams, MlsCredential, MlsSignatureKeyPair};
 use identities::ClientIdentities;
 use mls_crypto_provider::MlsCryptoProvider;
 use tracing::{debug, Instrument};
@@ -60,6 +60,8 @@ impl Client {
     /// Initializes the client.
     /// If the client's cryptographic material is already stored in the keystore, it loads it
     /// Otherwise, it is being created.
+    /// Either way, every given ciphersuite ends up with a signature keypair and, unless it already has some,
+    /// `nb_key_package` KeyPackages.
     ///
     /// # Arguments
     /// * `identifier` - client identifier ; either a [ClientId] or a x509 certificate chain
@@ -77,20 +79,7 @@ impl Client {
     ) -> CryptoResult<Self> {
         let id = identifier.get_id()?;
 
-        let credentials = backend
-            .key_store()
-            .find_all::<MlsCredential>(EntityFindParams::default())
-            .in_current_span()
-            .await?;
-
-        let credentials = credentials
-            .into_iter()
-            .filter(|c| &c.id[..] == id.as_slice())
-            .try_fold(vec![], |mut acc, c| {
-                let credential = Credential::tls_deserialize(&mut c.credential.as_slice()).map_err(MlsError::from)?;
-                acc.push((credential, c.created_at));
-                CryptoResult::Ok(acc)
-            })?;
+        let credentials = Self::find_credentials(backend, id.as_ref()).in_current_span().await?;
 
         let client = if !credentials.is_empty() {
             let signature_schemes = ciphersuites
@@ -98,7 +87,15 @@ impl Client {
                 .map(|cs| cs.signature_algorithm())
                 .collect::<HashSet<_>>();
             match Self::load(backend, id.as_ref(), credentials, signature_schemes).await {
-                Ok(client) => client,
+                Ok(mut client) => {
+                    client
+                        .provision_missing_identities(identifier, backend, ciphersuites)
+                        .await?;
+                    client
+                        .provision_missing_ciphersuites(backend, ciphersuites, nb_key_package)
+                        .await?;
+                    client
+                }
                 Err(CryptoError::ClientSignatureNotFound) => {
                     debug!(nb_key_package, ciphersuites = ?ciphersuites, "Client signature not found. Generating client");
                     Self::generate(identifier, backend, ciphersuites, nb_key_package).await?
@@ -262,19 +259,96 @@ impl Client {
             client.save_identity(backend, Some(&id), sc, cb).await?;
         }
 
-        if nb_key_package != 0 {
-            for cs in ciphersuites {
-                let sc = cs.signature_algorithm();
-                let identity = client.identities.iter().filter(|(id_sc, _)| id_sc == &sc);
-                for (_, cb) in identity {
-                    client
-                        .request_key_packages(nb_key_package, *cs, cb.credential.credential_type().into(), backend)
-                        .await?;
+        for cs in ciphersuites {
+            client.provision_ciphersuite(backend, *cs, nb_key_package).await?;
+        }
+
+        Ok(client)
+    }
+
+    /// Generates `nb_key_package` KeyPackages for the given ciphersuite, for every credential type
+    /// this client holds a credential of with the matching signature scheme
+    async fn provision_ciphersuite(
+        &self,
+        backend: &MlsCryptoProvider,
+        cs: MlsCiphersuite,
+        nb_key_package: usize,
+    ) -> CryptoResult<()> {
+        if nb_key_package == 0 {
+            return Ok(());
+        }
+
+        let sc = cs.signature_algorithm();
+        let credential_types = self
+            .identities
+            .iter()
+            .filter(|(id_sc, _)| id_sc == &sc)
+            .map(|(_, cb)| MlsCredentialType::from(cb.credential.credential_type()))
+            .collect::<BTreeSet<_>>();
+        for ct in credential_types {
+            self.request_key_packages(nb_key_package, cs, ct, backend).await?;
+        }
+
+        Ok(())
+    }
+
+    /// When a client is loaded with ciphersuites whose signature scheme it holds no identity for, creates these
+    /// identities from `identifier` as [Client::generate] would have done. Basic credentials are shared by every
+    /// signature scheme (see [Client::load]) so this only happens to clients holding X509 credentials alone, since
+    /// a certificate is bound to the signature scheme of its keypair.
+    ///
+    /// # Errors
+    /// [CryptoError::E2eiEnrollmentNotDone] when `identifier` holds X509 certificates but none for one of these
+    /// signature schemes
+    async fn provision_missing_identities(
+        &mut self,
+        identifier: ClientIdentifier,
+        backend: &MlsCryptoProvider,
+        ciphersuites: &[MlsCiphersuite],
+    ) -> CryptoResult<()> {
+        let missing = ciphersuites
+            .iter()
+            .map(|cs| cs.signature_algorithm())
+            .filter(|sc| !self.identities.iter().any(|(id_sc, _)| id_sc == *sc))
+            .collect::<HashSet<_>>();
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let identifier = match identifier {
+            ClientIdentifier::X509(mut certs) => {
+                certs.retain(|sc, _| missing.contains(sc));
+                if certs.len() < missing.len() {
+                    return Err(CryptoError::E2eiEnrollmentNotDone);
                 }
+                ClientIdentifier::X509(certs)
             }
+            identifier => identifier,
+        };
+        debug!(signature_schemes = ?missing, "Provisioning missing identities");
+        for (sc, id, cb) in identifier.generate_credential_bundles(backend, missing)? {
+            self.save_identity(backend, Some(&id), sc, cb).await?;
         }
+        Ok(())
+    }
 
-        Ok(client)
+    /// When a client is loaded with ciphersuites it does not hold any KeyPackage for, typically because they have
+    /// been added to the configuration after the client got created, provisions KeyPackages for them as
+    /// [Client::generate] would have done
+    async fn provision_missing_ciphersuites(
+        &self,
+        backend: &MlsCryptoProvider,
+        ciphersuites: &[MlsCiphersuite],
+        nb_key_package: usize,
+    ) -> CryptoResult<()> {
+        let kps = self.find_all_keypackages(backend).await?;
+        for cs in ciphersuites {
+            if !kps.iter().any(|(_, kp)| kp.ciphersuite() == cs.0) {
+                debug!(ciphersuite = ?cs, nb_key_package, "Provisioning a ciphersuite");
+                self.provision_ciphersuite(backend, *cs, nb_key_package).await?;
+            }
+        }
+        Ok(())
     }
 
     /// Loads the client from the keystore.
@@ -310,22 +384,29 @@ impl Client {
             };
 
             for (credential, created_at) in &credentials {
-                match credential.mls_credential() {
+                let signature_key = match credential.mls_credential() {
                     openmls::prelude::MlsCredentialType::Basic(_) => {
                         if id.as_slice() != credential.identity() {
                             return Err(CryptoError::ImplementationError);
                         }
+                        signature_key.clone()
                     }
                     openmls::prelude::MlsCredentialType::X509(cert) => {
+                        // a certificate is bound to a single keypair, hence to a single signature scheme
                         let spk = cert.extract_public_key()?.ok_or(CryptoError::InternalMlsError)?;
-                        if signature_key.public() != spk {
-                            return Err(CryptoError::ImplementationError);
+                        let kp = store_skps
+                            .iter()
+                            .find(|skp| skp.pk == spk)
+                            .ok_or(CryptoError::ImplementationError)?;
+                        if kp.signature_scheme != (sc as u16) {
+                            continue;
                         }
+                        SignatureKeyPair::tls_deserialize(&mut kp.keypair.as_slice()).map_err(MlsError::from)?
                     }
                 };
                 let cb = CredentialBundle {
                     credential: credential.clone(),
-                    signature_key: signature_key.clone(),
+                    signature_key,
                     created_at: *created_at,
                 };
                 identities.push_credential_bundle(sc, cb)?;
@@ -339,6 +420,40 @@ impl Client {
         })
     }
 
+    /// Reloads the identities of this client from the keystore, e.g. once the writes of a transaction have been
+    /// discarded
+    #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
+    pub(crate) async fn reload_identities(&mut self, backend: &MlsCryptoProvider) -> CryptoResult<()> {
+        let credentials = Self::find_credentials(backend, &self.id).await?;
+        let signature_schemes = backend
+            .key_store()
+            .find_all::<MlsSignatureKeyPair>(EntityFindParams::default())
+            .await?
+            .into_iter()
+            .filter(|skp| skp.credential_id == self.id.as_slice())
+            .map(|skp| SignatureScheme::try_from(skp.signature_scheme).map_err(|_| CryptoError::ImplementationError))
+            .collect::<CryptoResult<HashSet<_>>>()?;
+        let keypackage_lifetime = self.keypackage_lifetime;
+        *self = Self::load(backend, &self.id, credentials, signature_schemes).await?;
+        self.keypackage_lifetime = keypackage_lifetime;
+        Ok(())
+    }
+
+    /// Finds the credentials of the given client along with their creation date
+    async fn find_credentials(backend: &MlsCryptoProvider, id: &ClientId) -> CryptoResult<Vec<(Credential, u64)>> {
+        backend
+            .key_store()
+            .find_all::<MlsCredential>(EntityFindParams::default())
+            .await?
+            .into_iter()
+            .filter(|c| &c.id[..] == id.as_slice())
+            .try_fold(vec![], |mut acc, c| {
+                let credential = Credential::tls_deserialize(&mut c.credential.as_slice()).map_err(MlsError::from)?;
+                acc.push((credential, c.created_at));
+                CryptoResult::Ok(acc)
+            })
+    }
+
     #[cfg_attr(not(test), tracing::instrument(err, skip(backend)))]
     async fn find_all_basic_credentials(backend: &MlsCryptoProvider) -> CryptoResult<Vec<Credential>> {
         let store_credentials = backend
@@ -366,7 +481,7 @@ impl Client {
         sc: SignatureScheme,
         mut cb: CredentialBundle,
     ) -> CryptoResult<CredentialBundle> {
-        let mut conn = backend.key_store().borrow_conn().await?;
+        let keystore = backend.key_store();
 
         let id = id.unwrap_or_else(|| self.id());
 
@@ -376,7 +491,7 @@ impl Client {
             credential,
             created_at: 0,
         };
-        let created_at = credential.insert(&mut conn).await?;
+        let created_at = keystore.insert(credential).await?;
 
         let sign_kp = MlsSignatureKeyPair::new(
             sc,
@@ -384,7 +499,7 @@ impl Client {
             cb.signature_key.tls_serialize_detached().map_err(MlsError::from)?,
             id.clone().into(),
         );
-        sign_kp.save(&mut conn).await.map_err(|e| match e {
+        keystore.save(sign_kp).await.map_err(|e| match e {
             CryptoKeystoreError::AlreadyExists => CryptoError::CredentialBundleConflict,
             _ => e.into(),
         })?;
@@ -494,12 +609,15 @@ impl Client {
     pub async fn find_keypackages(
         &self,
         backend: &MlsCryptoProvider,
+        cs: MlsCiphersuite,
     ) -> CryptoResult<Vec<openmls::prelude::KeyPackage>> {
-        use core_crypto_keystore::CryptoKeystoreMls as _;
-        let kps = backend
-            .key_store()
-            .mls_fetch_keypackages::<openmls::prelude::KeyPackage>(u32::MAX)
-            .await?;
+        let kps = self
+            .find_all_keypackages(backend)
+            .await?
+            .into_iter()
+            .map(|(_, kp)| kp)
+            .filter(|kp| kp.ciphersuite() == cs.0)
+            .collect();
         Ok(kps)
     }
 }
@@ -507,11 +625,17 @@ impl Client {
 #[cfg(test)]
 pub mod tests {
     use core_crypto_keystore::entities::{EntityFindParams, MlsSignatureKeyPair};
+    use std::collections::HashMap;
     use wasm_bindgen_test::*;
 
-    use crate::prelude::ClientId;
-    use crate::test_utils::*;
+    use crate::e2e_identity::id::QualifiedE2eiClientId;
+    use crate::prelude::{
+        CertificateBundle, ClientId, ClientIdentifier, CryptoError, MlsCiphersuite, MlsCredentialType,
+    };
+    use crate::test_utils::{x509::X509TestChain, *};
     use mls_crypto_provider::MlsCryptoProvider;
+    use openmls::prelude::Ciphersuite;
+    use openmls_traits::OpenMlsCryptoProvider;
 
     use super::Client;
 
@@ -588,4 +712,122 @@ pub mod tests {
             .await
         }
     }
+
+    #[cfg_attr(not(target_family = "wasm"), async_std::test)]
+    #[wasm_bindgen_test]
+    pub async fn can_provision_several_ciphersuites() {
+        const N: usize = 3;
+        let backend = MlsCryptoProvider::try_new_in_memory("test").await.unwrap();
+        let ct = MlsCredentialType::Basic;
+        let ed25519 = MlsCiphersuite::from(Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519);
+        let p256 = MlsCiphersuite::from(Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256);
+        // shares its signature scheme with `ed25519`
+        let chacha = MlsCiphersuite::from(Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519);
+        let p384 = MlsCiphersuite::from(Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384);
+        let identifier = ClientIdentifier::Basic("alice".into());
+
+        let client = Client::init(identifier.clone(), &[ed25519, p256], &backend, N)
+            .await
+            .unwrap();
+        for cs in [ed25519, p256] {
+            assert!(client
+                .find_most_recent_credential_bundle(cs.signature_algorithm(), ct)
+                .is_some());
+            assert_eq!(client.valid_keypackages_count(&backend, cs, ct).await.unwrap(), N);
+        }
+        for cs in [chacha, p384] {
+            assert_eq!(client.valid_keypackages_count(&backend, cs, ct).await.unwrap(), 0);
+        }
+
+        // ciphersuites added to the configuration afterwards are provisioned when the client is loaded again
+        let client = Client::init(identifier, &[ed25519, p256, chacha, p384], &backend, N)
+            .await
+            .unwrap();
+        for cs in [ed25519, p256, chacha, p384] {
+            assert!(client
+                .find_most_recent_credential_bundle(cs.signature_algorithm(), ct)
+                .is_some());
+            assert_eq!(client.valid_keypackages_count(&backend, cs, ct).await.unwrap(), N);
+            let kps = client.find_keypackages(&backend, cs).await.unwrap();
+            assert_eq!(kps.len(), N);
+            assert!(kps.iter().all(|kp| kp.ciphersuite() == cs.0));
+        }
+
+        // pruning only ever considers the KeyPackages of the given ciphersuite
+        let ed25519_refs = client
+            .find_keypackages(&backend, ed25519)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|kp| kp.hash_ref(backend.crypto()).unwrap())
+            .collect::<Vec<_>>();
+        client.prune_keypackages(&backend, p256, &ed25519_refs).await.unwrap();
+        assert_eq!(client.valid_keypackages_count(&backend, ed25519, ct).await.unwrap(), N);
+        client
+            .prune_keypackages(&backend, ed25519, &ed25519_refs)
+            .await
+            .unwrap();
+        assert_eq!(client.valid_keypackages_count(&backend, ed25519, ct).await.unwrap(), 0);
+        for cs in [p256, chacha, p384] {
+            assert_eq!(client.valid_keypackages_count(&backend, cs, ct).await.unwrap(), N);
+        }
+
+        // requesting KeyPackages of a ciphersuite never hands out the ones of another
+        let kps = client.request_key_packages(N, chacha, ct, &backend).await.unwrap();
+        assert_eq!(kps.len(), N);
+        assert!(kps.iter().all(|kp| kp.ciphersuite() == chacha.0));
+        assert_eq!(client.valid_keypackages_count(&backend, ed25519, ct).await.unwrap(), 0);
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), async_std::test)]
+    #[wasm_bindgen_test]
+    pub async fn should_provision_x509_identities_of_new_signature_schemes() {
+        const N: usize = 2;
+        let backend = MlsCryptoProvider::try_new_in_memory("test").await.unwrap();
+        let ct = MlsCredentialType::X509;
+        let ed25519 = MlsCiphersuite::from(Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519);
+        let p256 = MlsCiphersuite::from(Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256);
+        let ed25519_chain = X509TestChain::init_empty(ed25519.signature_algorithm());
+        let p256_chain = X509TestChain::init_empty(p256.signature_algorithm());
+        // both certificates identify the same client
+        let client_id = QualifiedE2eiClientId::generate();
+        let ed25519_cert = CertificateBundle::new(
+            "alice_wire",
+            "alice Smith",
+            Some(&client_id),
+            None,
+            ed25519_chain.find_local_intermediate_ca(),
+        );
+        let p256_cert = CertificateBundle::new(
+            "alice_wire",
+            "alice Smith",
+            Some(&client_id),
+            None,
+            p256_chain.find_local_intermediate_ca(),
+        );
+
+        let identifier = ClientIdentifier::X509(HashMap::from([(ed25519.signature_algorithm(), ed25519_cert.clone())]));
+        let client = Client::init(identifier.clone(), &[ed25519], &backend, N).await.unwrap();
+        assert_eq!(client.valid_keypackages_count(&backend, ed25519, ct).await.unwrap(), N);
+
+        // a certificate is bound to its signature scheme: without one for the new ciphersuite, nothing can be provisioned
+        assert!(matches!(
+            Client::init(identifier, &[ed25519, p256], &backend, N)
+                .await
+                .unwrap_err(),
+            CryptoError::E2eiEnrollmentNotDone
+        ));
+
+        let identifier = ClientIdentifier::X509(HashMap::from([
+            (ed25519.signature_algorithm(), ed25519_cert),
+            (p256.signature_algorithm(), p256_cert),
+        ]));
+        let client = Client::init(identifier, &[ed25519, p256], &backend, N).await.unwrap();
+        for cs in [ed25519, p256] {
+            assert!(client
+                .find_most_recent_credential_bundle(cs.signature_algorithm(), ct)
+                .is_some());
+            assert_eq!(client.valid_keypackages_count(&backend, cs, ct).await.unwrap(), N);
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use crate::{CryptoError, CryptoResult};
+use crate::{e2e_identity::id::WireQualifiedClientId, CryptoError, CryptoResult};
 
 /// Unique identifier of a User (human person holding some devices).
 /// This contradicts the initial design requirements of this project since it was supposed to be
@@ -10,8 +10,21 @@ use crate::{CryptoError, CryptoResult};
 #[derive(Debug, Clone, Copy, Eq, PartialEq, derive_more::Deref)]
 pub struct UserId<'a>(&'a [u8]);
 
-impl UserId<'_> {
+impl<'a> UserId<'a> {
     const USER_ID_DELIMITER: u8 = 58; // the char ':'
+
+    /// From a user id alone, e.g. `bd4c7053-1c5a-4020-9559-cd7bf7961954`
+    pub(crate) fn new(user_id: &'a str) -> Self {
+        Self(user_id.as_bytes())
+    }
+
+    /// Whether the given client is a device of this user
+    pub(crate) fn owns(&self, client_id: &[u8]) -> bool {
+        // client ids read from certificates have their user id encoded in base64url
+        let qualified = WireQualifiedClientId::try_from(client_id).ok();
+        let client_id = qualified.as_ref().map(|c| c.as_slice()).unwrap_or(client_id);
+        matches!(UserId::try_from(client_id), Ok(uid) if uid == *self)
+    }
 }
 
 impl<'a> TryFrom<&'a str> for UserId<'a> {
@@ -60,6 +73,16 @@ pub mod tests {
         assert_eq!(user_id, UserId("LcksJb74Tm6N12cDjFy7lQ".as_bytes()));
     }
 
+    #[async_std::test]
+    #[wasm_bindgen_test]
+    pub async fn should_own_devices_in_any_form() {
+        let user_id = UserId::new("bd4c7053-1c5a-4020-9559-cd7bf7961954");
+        assert!(user_id.owns(b"bd4c7053-1c5a-4020-9559-cd7bf7961954:4959bc6ab12f2846@wire.com"));
+        assert!(user_id.owns(b"vUxwUxxaQCCVWc1795YZVA:4959bc6ab12f2846@wire.com"));
+        assert!(!user_id.owns(b"a2fe1fd6-9dbd-4e6c-9a4f-ea3fb3d6b7d8:4959bc6ab12f2846@wire.com"));
+        assert!(!user_id.owns(b"bd4c7053-1c5a-4020-9559-cd7bf7961954"));
+    }
+
     #[async_std::test]
     #[wasm_bindgen_test]
     pub async fn should_fail_when_invalid() {
@@ -1,20 +1,89 @@
 //! This file is intended to fix some issues we have with the Delivery Service. Sometimes, clients
 //! receive for the next epoch before receiving the commit for this epoch.
 //!
+//! The buffer is bounded per conversation by [MlsPendingMessagesLimits]: past a maximum count the oldest
+//! messages are evicted and past a maximum age they expire.
+//!
 //! Feel free to delete all of this when the issue is fixed on the DS side !
 
 use crate::{
     group_store::GroupStoreValue,
+    mls::conversation::key_rotation::now,
     prelude::{
         decrypt::MlsBufferedConversationDecryptMessage, Client, ConversationId, CoreCryptoCallbacks, CryptoError,
         CryptoResult, MlsCentral, MlsConversation, MlsConversationDecryptMessage, MlsError,
     },
 };
-use core_crypto_keystore::entities::{EntityFindParams, MlsPendingMessage};
-use mls_crypto_provider::MlsCryptoProvider;
-use openmls::prelude::{MlsMessageIn, MlsMessageInBody};
+use core_crypto_keystore::{
+    entities::{EntityFindParams, MlsPendingMessage, PersistedMlsPendingGroup},
+    CryptoKeystoreMls as _,
+};
+use mls_crypto_provider::{CryptoKeystore, MlsCryptoProvider};
+use openmls::prelude::{MlsMessageIn, MlsMessageInBody, ProtocolMessage};
 use tls_codec::Deserialize;
-use tracing::{error, span, trace, Instrument, Level};
+use tracing::{error, span, trace, warn, Instrument, Level};
+
+/// Default maximum number of messages buffered per conversation
+pub const DEFAULT_PENDING_MESSAGES_MAX_COUNT: usize = 100;
+/// Default time after which a buffered message expires
+pub const DEFAULT_PENDING_MESSAGES_MAX_AGE: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+/// Default maximum number of conversations we are not a member of yet which can have buffered messages
+pub const DEFAULT_PENDING_MESSAGES_MAX_UNKNOWN_CONVERSATIONS: usize = 20;
+
+/// Bounds of the buffer of messages received for a future epoch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MlsPendingMessagesLimits {
+    /// Maximum number of messages buffered per conversation. Beyond that, the oldest ones are evicted
+    pub max_count: usize,
+    /// Maximum number of conversations we are not a member of yet which can have buffered messages, since
+    /// anyone can make up such conversations. Beyond that, the messages of the one whose oldest message was
+    /// buffered the longest ago are evicted
+    pub max_unknown_conversations: usize,
+    /// Time after which a buffered message expires. `None` keeps them until their epoch arrives
+    pub max_age: Option<std::time::Duration>,
+}
+
+impl Default for MlsPendingMessagesLimits {
+    fn default() -> Self {
+        Self {
+            max_count: DEFAULT_PENDING_MESSAGES_MAX_COUNT,
+            max_unknown_conversations: DEFAULT_PENDING_MESSAGES_MAX_UNKNOWN_CONVERSATIONS,
+            max_age: Some(DEFAULT_PENDING_MESSAGES_MAX_AGE),
+        }
+    }
+}
+
+/// A message received for a future epoch and buffered until the commit for that epoch arrives
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MlsBufferedMessage {
+    /// Conversation the message belongs to
+    pub conversation_id: ConversationId,
+    /// Epoch the message was sent in
+    pub epoch: u64,
+    /// Unix timestamp in seconds at which the message was buffered
+    pub received_at: u64,
+    /// TLS serialized message, as received from the Delivery Service
+    pub message: Vec<u8>,
+}
+
+impl TryFrom<MlsPendingMessage> for MlsBufferedMessage {
+    type Error = CryptoError;
+
+    fn try_from(mut pending: MlsPendingMessage) -> CryptoResult<Self> {
+        let msg = MlsMessageIn::tls_deserialize(&mut pending.message.as_slice()).map_err(MlsError::from)?;
+        let protocol_message = match msg.extract() {
+            MlsMessageInBody::PublicMessage(m) => ProtocolMessage::PublicMessage(m),
+            MlsMessageInBody::PrivateMessage(m) => ProtocolMessage::PrivateMessage(m),
+            _ => return Err(CryptoError::ConsumerError),
+        };
+        Ok(Self {
+            conversation_id: std::mem::take(&mut pending.id),
+            epoch: protocol_message.epoch().as_u64(),
+            received_at: pending.received_at,
+            message: std::mem::take(&mut pending.message),
+        })
+    }
+}
 
 impl MlsCentral {
     #[cfg_attr(not(test), tracing::instrument(err, skip(self, message), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
@@ -23,14 +92,250 @@ impl MlsCentral {
         id: &ConversationId,
         message: impl AsRef<[u8]>,
     ) -> CryptoResult<MlsConversationDecryptMessage> {
+        let evicted = self.buffer_pending_message(id, message).await?;
+        if evicted.is_empty() {
+            Err(CryptoError::BufferedFutureMessage)
+        } else {
+            Err(CryptoError::FutureMessageBufferOverflow(evicted))
+        }
+    }
+
+    /// Buffers a message for a conversation we are not a member of yet, most likely because the DS fanned
+    /// it out before the Welcome. It is replayed by [MlsCentral::process_welcome_message] or
+    /// [MlsCentral::merge_pending_group_from_external_commit] once we join the conversation.
+    ///
+    /// On top of the limits per conversation, the number of such conversations is bounded by
+    /// [MlsPendingMessagesLimits::max_unknown_conversations], so the evicted messages might belong to
+    /// another conversation.
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self, message), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
+    pub(crate) async fn handle_unknown_conversation_message(
+        &self,
+        id: &ConversationId,
+        message: impl AsRef<[u8]>,
+    ) -> CryptoResult<MlsConversationDecryptMessage> {
+        let msg = MlsMessageIn::tls_deserialize(&mut message.as_ref()).map_err(MlsError::from)?;
+        if !matches!(
+            msg.body_as_ref(),
+            MlsMessageInBody::PublicMessage(_) | MlsMessageInBody::PrivateMessage(_)
+        ) {
+            return Err(CryptoError::ConversationNotFound(id.clone()));
+        }
+
+        let mut evicted = self.evict_unknown_conversations(id).await?;
+        evicted.extend(self.buffer_pending_message(id, message).await?);
+        if evicted.is_empty() {
+            Err(CryptoError::BufferedMessageForUnknownConversation)
+        } else {
+            Err(CryptoError::FutureMessageBufferOverflow(evicted))
+        }
+    }
+
+    /// Makes room for the buffer of a conversation we are not a member of yet, by evicting the buffers of other
+    /// such conversations beyond [MlsPendingMessagesLimits::max_unknown_conversations]
+    ///
+    /// # Returns
+    /// The evicted messages
+    async fn evict_unknown_conversations(&self, id: &ConversationId) -> CryptoResult<Vec<MlsBufferedMessage>> {
+        if !self.find_pending_messages(id).await?.is_empty() {
+            return Ok(vec![]);
+        }
+
         let keystore = self.mls_backend.borrow_keystore();
+        let mut buffers: Vec<(Vec<u8>, Vec<MlsPendingMessage>)> = vec![];
+        for m in keystore
+            .find_all::<MlsPendingMessage>(EntityFindParams::default())
+            .await?
+        {
+            match buffers.iter_mut().find(|(id, _)| id == &m.id) {
+                Some((_, messages)) => messages.push(m),
+                None => buffers.push((m.id.clone(), vec![m])),
+            }
+        }
+        // messages buffered for a future epoch or for a pending external commit belong to conversations we know of
+        let mut unknown = vec![];
+        for (id, messages) in buffers {
+            let is_pending_group = keystore.find::<PersistedMlsPendingGroup>(&id).await?.is_some();
+            if !keystore.mls_group_exists(&id).await && !is_pending_group {
+                let oldest = messages.iter().map(|m| m.received_at).min().unwrap_or_default();
+                unknown.push((oldest, id, messages));
+            }
+        }
+        if unknown.len() < self.pending_messages_limits.max_unknown_conversations.max(1) {
+            return Ok(vec![]);
+        }
 
+        unknown.sort_by_key(|(oldest, ..)| *oldest);
+        let overflowing = unknown.len() + 1 - self.pending_messages_limits.max_unknown_conversations.max(1);
+        let mut evicted = vec![];
+        for (_, id, messages) in unknown.into_iter().take(overflowing) {
+            keystore.remove::<MlsPendingMessage, _>(&id).await?;
+            evicted.extend(messages);
+        }
+        evicted.into_iter().map(MlsBufferedMessage::try_from).collect()
+    }
+
+    /// Saves a message in the buffer of a conversation then enforces the [MlsPendingMessagesLimits]
+    ///
+    /// # Returns
+    /// The messages evicted to make room for it
+    async fn buffer_pending_message(
+        &self,
+        id: &ConversationId,
+        message: impl AsRef<[u8]>,
+    ) -> CryptoResult<Vec<MlsBufferedMessage>> {
         let pending_msg = MlsPendingMessage {
             id: id.clone(),
             message: message.as_ref().to_vec(),
+            received_at: now(),
         };
-        keystore.save::<MlsPendingMessage>(pending_msg).await?;
-        Err(CryptoError::BufferedFutureMessage)
+        self.mls_backend
+            .borrow_keystore()
+            .save::<MlsPendingMessage>(pending_msg)
+            .await?;
+        self.evict_pending_messages(id).await
+    }
+
+    /// Configures how many messages can be buffered per conversation while waiting for the commit of
+    /// their epoch, and for how long. Limits are enforced whenever a message gets buffered and by
+    /// [MlsCentral::clear_expired_pending_messages]. See [MlsPendingMessagesLimits::default] for the defaults.
+    pub fn set_pending_messages_limits(&mut self, limits: MlsPendingMessagesLimits) {
+        self.pending_messages_limits = limits;
+    }
+
+    /// Lists the messages of a conversation buffered while waiting for the commit of their epoch, oldest first
+    ///
+    /// # Arguments
+    /// * `id` - the group/conversation id
+    ///
+    /// # Errors
+    /// KeyStore errors or if a buffered message cannot be deserialized
+    #[cfg_attr(test, crate::idempotent)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
+    pub async fn list_pending_messages(&self, id: &ConversationId) -> CryptoResult<Vec<MlsBufferedMessage>> {
+        self.find_pending_messages(id)
+            .await?
+            .into_iter()
+            .map(MlsBufferedMessage::try_from)
+            .collect()
+    }
+
+    /// Drops the buffered messages of every conversation which expired or exceed the configured
+    /// [MlsPendingMessagesLimits]. Meant to be called periodically since messages are otherwise only
+    /// evicted when a new one gets buffered in the same conversation.
+    ///
+    /// # Returns
+    /// The evicted messages
+    ///
+    /// # Errors
+    /// KeyStore errors or if a buffered message cannot be deserialized
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self)))]
+    pub async fn clear_expired_pending_messages(&mut self) -> CryptoResult<Vec<MlsBufferedMessage>> {
+        let mut ids = self
+            .mls_backend
+            .borrow_keystore()
+            .find_all::<MlsPendingMessage>(EntityFindParams::default())
+            .await?
+            .into_iter()
+            .map(|m| m.id.clone())
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids.dedup();
+
+        let mut evicted = vec![];
+        for id in ids {
+            evicted.extend(self.evict_pending_messages(&id).await?);
+        }
+        Ok(evicted)
+    }
+
+    /// Drops all the messages of a conversation buffered while waiting for the commit of their epoch
+    ///
+    /// # Arguments
+    /// * `id` - the group/conversation id
+    ///
+    /// # Returns
+    /// The dropped messages
+    ///
+    /// # Errors
+    /// KeyStore errors or if a buffered message cannot be deserialized
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
+    pub async fn clear_pending_messages(&mut self, id: &ConversationId) -> CryptoResult<Vec<MlsBufferedMessage>> {
+        let cleared = self.find_pending_messages(id).await?;
+        if !cleared.is_empty() {
+            self.mls_backend
+                .borrow_keystore()
+                .remove::<MlsPendingMessage, _>(id)
+                .await?;
+        }
+        cleared.into_iter().map(MlsBufferedMessage::try_from).collect()
+    }
+
+    /// Buffered messages of a conversation, oldest first
+    async fn find_pending_messages(&self, id: &ConversationId) -> CryptoResult<Vec<MlsPendingMessage>> {
+        let mut messages = self
+            .mls_backend
+            .borrow_keystore()
+            .find_many::<MlsPendingMessage, _>(&[id])
+            .await?;
+        // stable sort so that messages buffered within the same second keep their order
+        messages.sort_by_key(|m| m.received_at);
+        Ok(messages)
+    }
+
+    /// Evicts the expired messages of a conversation then the oldest ones above the maximum count.
+    async fn evict_pending_messages(&self, id: &ConversationId) -> CryptoResult<Vec<MlsBufferedMessage>> {
+        let MlsPendingMessagesLimits { max_count, max_age, .. } = self.pending_messages_limits;
+        let mut kept = self.find_pending_messages(id).await?;
+
+        let now = now();
+        // The time messages buffered before it was recorded were received at is unknown, so they start
+        // expiring from now on
+        let mut stamped = false;
+        for m in kept.iter_mut().filter(|m| m.received_at == 0) {
+            m.received_at = now;
+            stamped = true;
+        }
+
+        let expired = max_age
+            .map(|max_age| {
+                kept.iter()
+                    .take_while(|m| m.received_at.saturating_add(max_age.as_secs()) < now)
+                    .count()
+            })
+            .unwrap_or_default();
+        let overflowing = (kept.len() - expired).saturating_sub(max_count);
+        let evicted = kept.drain(..expired + overflowing).collect::<Vec<_>>();
+        if evicted.is_empty() && !stamped {
+            return Ok(vec![]);
+        }
+
+        replace_pending_messages(self.mls_backend.borrow_keystore(), id, kept).await?;
+        evicted.into_iter().map(MlsBufferedMessage::try_from).collect()
+    }
+
+    /// Drops the buffered messages of a conversation sent before `epoch`. When joining from a Welcome,
+    /// messages of the epochs preceding ours cannot be decrypted and would fail the replay.
+    pub(crate) async fn discard_stale_pending_messages(&self, id: &ConversationId, epoch: u64) -> CryptoResult<()> {
+        let buffered = self
+            .find_pending_messages(id)
+            .await?
+            .into_iter()
+            .map(MlsBufferedMessage::try_from)
+            .collect::<CryptoResult<Vec<_>>>()?;
+        if buffered.iter().all(|m| m.epoch >= epoch) {
+            return Ok(());
+        }
+
+        let kept = buffered
+            .into_iter()
+            .filter(|m| m.epoch >= epoch)
+            .map(|m| MlsPendingMessage {
+                id: m.conversation_id,
+                message: m.message,
+                received_at: m.received_at,
+            })
+            .collect();
+        replace_pending_messages(self.mls_backend.borrow_keystore(), id, kept).await
     }
 
     #[cfg_attr(not(test), tracing::instrument(err, skip(self, conversation)))]
@@ -56,6 +361,40 @@ impl MlsCentral {
     }
 }
 
+/// Replaces the buffered messages of a conversation with `kept`. Since buffered messages cannot be deleted
+/// individually, they are all removed then the kept ones are saved again, in a keystore transaction so that
+/// none of them is lost midway. If a transaction is already in progress, it takes care of that.
+async fn replace_pending_messages(
+    keystore: &CryptoKeystore,
+    id: &[u8],
+    kept: Vec<MlsPendingMessage>,
+) -> CryptoResult<()> {
+    let in_transaction = keystore.is_in_transaction().await;
+    if !in_transaction {
+        keystore.begin_transaction().await?;
+    }
+
+    let replaced = async {
+        keystore.remove::<MlsPendingMessage, _>(id).await?;
+        for message in kept {
+            keystore.save(message).await?;
+        }
+        CryptoResult::Ok(())
+    }
+    .await;
+
+    if in_transaction {
+        return replaced;
+    }
+    match replaced {
+        Ok(()) => Ok(keystore.commit_transaction().await?),
+        Err(e) => {
+            keystore.rollback_transaction().await?;
+            Err(e)
+        }
+    }
+}
+
 impl MlsConversation {
     #[cfg_attr(target_family = "wasm", async_recursion::async_recursion(?Send))]
     #[cfg_attr(not(target_family = "wasm"), async_recursion::async_recursion)]
@@ -72,7 +411,8 @@ impl MlsConversation {
         let result = async move {
             let keystore = backend.borrow_keystore();
 
-            let group_id = self.id().as_slice();
+            let group_id = self.id().clone();
+            let group_id = group_id.as_slice();
             if is_rejoin {
                 // This means the external commit is about rejoining the group.
                 // This is most of the time a last resort measure (for example when a commit is dropped)
@@ -85,39 +425,64 @@ impl MlsConversation {
                 return Ok(None);
             }
 
-            let mut pending_messages = keystore
-                .find_all::<MlsPendingMessage>(EntityFindParams::default())
-                .await?
-                .into_iter()
-                .filter(|pm| pm.id == group_id)
-                .try_fold(vec![], |mut acc, m| {
-                    let msg = MlsMessageIn::tls_deserialize(&mut m.message.as_slice()).map_err(MlsError::from)?;
-                    let ct = match msg.body_as_ref() {
-                        MlsMessageInBody::PublicMessage(m) => Ok(m.content_type()),
-                        MlsMessageInBody::PrivateMessage(m) => Ok(m.content_type()),
-                        _ => Err(CryptoError::ConsumerError),
-                    }?;
-                    acc.push((ct as u8, msg));
-                    CryptoResult::Ok(acc)
-                })?;
-
-            // We want to restore application messages first, then Proposals & finally Commits
-            // luckily for us that's the exact same order as the [ContentType] enum
-            pending_messages.sort_by(|(a, _), (b, _)| a.cmp(b));
-
-            let mut decrypted_messages = Vec::with_capacity(pending_messages.len());
-            for (_, m) in pending_messages {
-                let parent_conversation = match &self.parent_id {
-                    Some(_) => Some(parent_conversation.ok_or(CryptoError::ParentGroupNotFound)?),
-                    _ => None,
+            let pending_messages = keystore.find_many::<MlsPendingMessage, _>(&[group_id]).await?;
+            if pending_messages.is_empty() {
+                return Ok(None);
+            }
+
+            let mut sorted = Vec::with_capacity(pending_messages.len());
+            for m in pending_messages {
+                let msg = match MlsMessageIn::tls_deserialize(&mut m.message.as_slice()) {
+                    Ok(msg) => msg,
+                    Err(e) => {
+                        warn!(error = %e, "Dropping a buffered message which cannot be deserialized");
+                        continue;
+                    }
                 };
+                let (protocol_message, ct) = match msg.clone().extract() {
+                    MlsMessageInBody::PublicMessage(pm) => {
+                        let ct = pm.content_type();
+                        (ProtocolMessage::PublicMessage(pm), ct)
+                    }
+                    MlsMessageInBody::PrivateMessage(pm) => {
+                        let ct = pm.content_type();
+                        (ProtocolMessage::PrivateMessage(pm), ct)
+                    }
+                    _ => {
+                        warn!("Dropping a buffered message which is neither a public nor a private message");
+                        continue;
+                    }
+                };
+                sorted.push(((protocol_message.epoch().as_u64(), ct as u8), msg, m));
+            }
+
+            // Messages buffered before joining might span several epochs: we replay them epoch by epoch and
+            // within an epoch, application messages first, then Proposals & finally Commits. Luckily for us
+            // that's the exact same order as the [ContentType] enum. The sort is stable so that messages of
+            // the same kind and epoch are replayed in the order they were received
+            sorted.sort_by_key(|(order, ..)| *order);
+
+            let parent_conversation = match &self.parent_id {
+                Some(_) => Some(parent_conversation.ok_or(CryptoError::ParentGroupNotFound)?),
+                _ => None,
+            };
+            let mut decrypted_messages = Vec::with_capacity(sorted.len());
+            let mut still_pending = vec![];
+            for (_, msg, pending) in sorted {
                 let restore_pending = false; // to prevent infinite recursion
                 let decrypted = self
-                    .decrypt_message(m, parent_conversation, client, backend, callbacks, restore_pending)
+                    .decrypt_message(msg, parent_conversation, client, backend, callbacks, restore_pending)
                     .in_current_span()
-                    .await?;
-                decrypted_messages.push(decrypted.into());
+                    .await;
+                match decrypted {
+                    Ok(decrypted) => decrypted_messages.push(decrypted.into()),
+                    // the commit of its epoch has not been received yet
+                    Err(CryptoError::BufferedFutureMessage) => still_pending.push(pending),
+                    // one message failing must not prevent the other ones from being replayed
+                    Err(e) => warn!(error = %e, "Dropping a buffered message which cannot be replayed"),
+                }
             }
+            replace_pending_messages(keystore, group_id, still_pending).await?;
 
             let decrypted_messages = (!decrypted_messages.is_empty()).then_some(decrypted_messages);
 
@@ -137,7 +502,8 @@ impl MlsConversation {
 
 #[cfg(test)]
 pub mod tests {
-    use crate::{test_utils::*, CryptoError};
+    use crate::{prelude::MlsPendingMessagesLimits, test_utils::*, CryptoError};
+    use core_crypto_keystore::entities::MlsPendingMessage;
     use wasm_bindgen_test::*;
 
     wasm_bindgen_test_configure!(run_in_browser);
@@ -429,4 +795,274 @@ pub mod tests {
             .await
         }
     }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_evict_oldest_messages_above_max_count(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+                    bob_central
+                        .mls_central
+                        .set_pending_messages_limits(MlsPendingMessagesLimits {
+                            max_count: 2,
+                            ..Default::default()
+                        });
+
+                    // Alice moves to the next epoch but Bob does not get the commit
+                    alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    let epoch = alice_central.mls_central.conversation_epoch(&id).await.unwrap();
+
+                    let mut messages = vec![];
+                    for i in 0..3u8 {
+                        let msg = alice_central.mls_central.encrypt_message(&id, [i]).await.unwrap();
+                        messages.push(msg);
+                    }
+                    for m in &messages[..2] {
+                        let decrypt = bob_central.mls_central.decrypt_message(&id, m).await;
+                        assert!(matches!(decrypt.unwrap_err(), CryptoError::BufferedFutureMessage));
+                    }
+
+                    // the buffer is full, the oldest message is evicted
+                    let decrypt = bob_central.mls_central.decrypt_message(&id, &messages[2]).await;
+                    let CryptoError::FutureMessageBufferOverflow(evicted) = decrypt.unwrap_err() else {
+                        panic!("the first message should have been evicted")
+                    };
+                    assert_eq!(evicted.len(), 1);
+                    assert_eq!(evicted[0].conversation_id, id);
+                    assert_eq!(evicted[0].epoch, epoch);
+                    assert_eq!(evicted[0].message, messages[0]);
+
+                    let buffered = bob_central.mls_central.list_pending_messages(&id).await.unwrap();
+                    let buffered = buffered.into_iter().map(|m| m.message).collect::<Vec<_>>();
+                    assert_eq!(buffered, messages[1..]);
+
+                    let cleared = bob_central.mls_central.clear_pending_messages(&id).await.unwrap();
+                    assert_eq!(cleared.len(), 2);
+                    assert_eq!(bob_central.mls_central.count_entities().await.pending_messages, 0);
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_bound_the_number_of_unknown_conversations(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    bob_central
+                        .mls_central
+                        .set_pending_messages_limits(MlsPendingMessagesLimits {
+                            max_unknown_conversations: 2,
+                            ..Default::default()
+                        });
+
+                    // Bob is a member of none of them
+                    let ids = [conversation_id(), conversation_id(), conversation_id()];
+                    let mut messages = vec![];
+                    for id in &ids {
+                        alice_central
+                            .mls_central
+                            .new_conversation(id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        let msg = alice_central.mls_central.encrypt_message(id, b"hello").await.unwrap();
+                        messages.push(msg);
+                    }
+
+                    for (id, msg) in ids[..2].iter().zip(&messages) {
+                        let decrypt = bob_central.mls_central.decrypt_message(id, msg).await;
+                        assert!(matches!(
+                            decrypt.unwrap_err(),
+                            CryptoError::BufferedMessageForUnknownConversation
+                        ));
+                    }
+                    // conversations which already have buffered messages are not bounded by this
+                    let decrypt = bob_central.mls_central.decrypt_message(&ids[0], &messages[0]).await;
+                    assert!(matches!(
+                        decrypt.unwrap_err(),
+                        CryptoError::BufferedMessageForUnknownConversation
+                    ));
+
+                    // the buffer of another conversation is evicted to make room for the new one
+                    let decrypt = bob_central.mls_central.decrypt_message(&ids[2], &messages[2]).await;
+                    let CryptoError::FutureMessageBufferOverflow(evicted) = decrypt.unwrap_err() else {
+                        panic!("the messages of another conversation should have been evicted")
+                    };
+                    assert!(!evicted.is_empty());
+                    let evicted_id = &evicted[0].conversation_id;
+                    assert!(ids[..2].contains(evicted_id));
+                    assert!(evicted.iter().all(|m| &m.conversation_id == evicted_id));
+
+                    assert!(bob_central
+                        .mls_central
+                        .list_pending_messages(evicted_id)
+                        .await
+                        .unwrap()
+                        .is_empty());
+                    assert_eq!(
+                        bob_central
+                            .mls_central
+                            .list_pending_messages(&ids[2])
+                            .await
+                            .unwrap()
+                            .len(),
+                        1
+                    );
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_expire_old_messages(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    let old = alice_central.mls_central.encrypt_message(&id, b"old").await.unwrap();
+                    let recent = alice_central.mls_central.encrypt_message(&id, b"recent").await.unwrap();
+
+                    // simulates a message buffered a long time ago
+                    bob_central
+                        .mls_central
+                        .mls_backend
+                        .borrow_keystore()
+                        .save(MlsPendingMessage {
+                            id: id.clone(),
+                            message: old.clone(),
+                            received_at: 1,
+                        })
+                        .await
+                        .unwrap();
+                    let decrypt = bob_central.mls_central.decrypt_message(&id, &recent).await;
+                    let CryptoError::FutureMessageBufferOverflow(evicted) = decrypt.unwrap_err() else {
+                        panic!("the old message should have expired")
+                    };
+                    assert_eq!(evicted.len(), 1);
+                    assert_eq!(evicted[0].message, old);
+                    assert_eq!(evicted[0].received_at, 1);
+
+                    // without max age, messages are kept until their epoch arrives
+                    bob_central
+                        .mls_central
+                        .set_pending_messages_limits(MlsPendingMessagesLimits {
+                            max_age: None,
+                            ..Default::default()
+                        });
+                    bob_central
+                        .mls_central
+                        .mls_backend
+                        .borrow_keystore()
+                        .save(MlsPendingMessage {
+                            id: id.clone(),
+                            message: old.clone(),
+                            received_at: 1,
+                        })
+                        .await
+                        .unwrap();
+                    let expired = bob_central.mls_central.clear_expired_pending_messages().await.unwrap();
+                    assert!(expired.is_empty());
+                    assert_eq!(bob_central.mls_central.count_entities().await.pending_messages, 2);
+
+                    bob_central
+                        .mls_central
+                        .set_pending_messages_limits(MlsPendingMessagesLimits::default());
+                    let expired = bob_central.mls_central.clear_expired_pending_messages().await.unwrap();
+                    assert_eq!(expired.len(), 1);
+                    assert_eq!(expired[0].message, old);
+                    let buffered = bob_central.mls_central.list_pending_messages(&id).await.unwrap();
+                    assert_eq!(buffered.len(), 1);
+                    assert_eq!(buffered[0].message, recent);
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_not_expire_messages_buffered_at_an_unknown_time(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    let legacy = alice_central.mls_central.encrypt_message(&id, b"legacy").await.unwrap();
+                    let recent = alice_central.mls_central.encrypt_message(&id, b"recent").await.unwrap();
+
+                    // messages buffered before the time they were received at was recorded
+                    bob_central
+                        .mls_central
+                        .mls_backend
+                        .borrow_keystore()
+                        .save(MlsPendingMessage {
+                            id: id.clone(),
+                            message: legacy.clone(),
+                            received_at: 0,
+                        })
+                        .await
+                        .unwrap();
+                    let decrypt = bob_central.mls_central.decrypt_message(&id, &recent).await;
+                    assert!(matches!(decrypt.unwrap_err(), CryptoError::BufferedFutureMessage));
+
+                    // they start expiring from now on
+                    let buffered = bob_central.mls_central.list_pending_messages(&id).await.unwrap();
+                    assert_eq!(buffered.len(), 2);
+                    assert_eq!(buffered[0].message, legacy);
+                    assert!(buffered.iter().all(|m| m.received_at > 0));
+                })
+            },
+        )
+        .await
+    }
 }
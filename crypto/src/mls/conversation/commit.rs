@@ -15,7 +15,10 @@ use crate::{
         crl::{extract_crl_uris_from_credentials, get_new_crl_distribution_points},
         CredentialBundle,
     },
-    prelude::{Client, ClientId, ConversationId, CryptoError, CryptoResult, MlsCentral, MlsError, MlsGroupInfoBundle},
+    prelude::{
+        user_id::UserId, Client, ClientId, ConversationId, CryptoError, CryptoResult, MlsCentral, MlsError,
+        MlsGroupInfoBundle,
+    },
 };
 use tracing::Instrument;
 
@@ -49,12 +52,13 @@ impl MlsCentral {
                 return Err(CryptoError::Unauthorized);
             }
         }
-        self.get_conversation(id)
-            .await?
-            .write()
-            .await
+        let conversation = self.get_conversation(id).await?;
+        let mut conversation = conversation.write().await;
+        let mut commit = conversation
             .add_members(self.mls_client()?, key_packages, &self.mls_backend)
-            .await
+            .await?;
+        commit.group_info = self.finalize_group_info(commit.group_info, Some(&*conversation))?;
+        Ok(commit)
     }
 
     /// Removes clients from the group/conversation.
@@ -83,12 +87,78 @@ impl MlsCentral {
                 return Err(CryptoError::Unauthorized);
             }
         }
-        self.get_conversation(id)
-            .await?
-            .write()
-            .await
+        let conversation = self.get_conversation(id).await?;
+        let mut conversation = conversation.write().await;
+        let mut commit = conversation
             .remove_members(self.mls_client()?, clients, &self.mls_backend)
+            .await?;
+        commit.group_info = self.finalize_group_info(commit.group_info, Some(&*conversation))?;
+        Ok(commit)
+    }
+
+    /// Adds the devices of a single user to the group/conversation
+    ///
+    /// # Arguments
+    /// * `id` - group/conversation id
+    /// * `user_id` - the user holding the devices, e.g. `bd4c7053-1c5a-4020-9559-cd7bf7961954`
+    /// * `key_packages` - one KeyPackage per device of this user to add
+    ///
+    /// # Errors
+    /// [CryptoError::KeyPackageUserMismatch] when a KeyPackage belongs to another user. Otherwise, same
+    /// as [MlsCentral::add_members_to_conversation]
+    #[cfg_attr(test, crate::idempotent)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self, key_packages), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
+    pub async fn add_user_to_conversation(
+        &mut self,
+        id: &ConversationId,
+        user_id: &str,
+        key_packages: Vec<KeyPackageIn>,
+    ) -> CryptoResult<MlsConversationCreationMessage> {
+        if key_packages.is_empty() {
+            return Err(CryptoError::ConsumerError);
+        }
+        let user_id = UserId::new(user_id);
+        if let Some(foreign) = key_packages
+            .iter()
+            .map(|kp| kp.credential().identity())
+            .find(|&client_id| !user_id.owns(client_id))
+        {
+            return Err(CryptoError::KeyPackageUserMismatch(foreign.into()));
+        }
+        self.add_members_to_conversation(id, key_packages).await
+    }
+
+    /// Removes every device of a user from the group/conversation. When removing our own user, only our
+    /// other devices are removed.
+    ///
+    /// # Arguments
+    /// * `id` - group/conversation id
+    /// * `user_id` - the user holding the devices, e.g. `bd4c7053-1c5a-4020-9559-cd7bf7961954`
+    ///
+    /// # Errors
+    /// [CryptoError::UserNotFound] when no (other) device of this user is a member of the group. Otherwise,
+    /// same as [MlsCentral::remove_members_from_conversation]
+    #[cfg_attr(test, crate::idempotent)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
+    pub async fn remove_user_from_conversation(
+        &mut self,
+        id: &ConversationId,
+        user_id: &str,
+    ) -> CryptoResult<MlsCommitBundle> {
+        let own_id = self.mls_client()?.id().clone();
+        let clients = self
+            .get_conversation(id)
+            .await?
+            .read()
             .await
+            .user_clients(UserId::new(user_id))
+            .into_iter()
+            .filter(|c| c != &own_id)
+            .collect::<Vec<_>>();
+        if clients.is_empty() {
+            return Err(CryptoError::UserNotFound(user_id.to_string()));
+        }
+        self.remove_members_from_conversation(id, &clients).await
     }
 
     /// Self updates the KeyPackage and automatically commits. Pending proposals will be commited
@@ -107,12 +177,13 @@ impl MlsCentral {
     #[cfg_attr(test, crate::idempotent)]
     #[cfg_attr(not(test), tracing::instrument(err, skip(self), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
     pub async fn update_keying_material(&mut self, id: &ConversationId) -> CryptoResult<MlsCommitBundle> {
-        self.get_conversation(id)
-            .await?
-            .write()
-            .await
+        let conversation = self.get_conversation(id).await?;
+        let mut conversation = conversation.write().await;
+        let mut commit = conversation
             .update_keying_material(self.mls_client()?, &self.mls_backend, None, None)
-            .await
+            .await?;
+        commit.group_info = self.finalize_group_info(commit.group_info, Some(&*conversation))?;
+        Ok(commit)
     }
 
     /// Commits all pending proposals of the group
@@ -128,12 +199,16 @@ impl MlsCentral {
     #[cfg_attr(test, crate::idempotent)]
     #[cfg_attr(not(test), tracing::instrument(err, skip(self), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
     pub async fn commit_pending_proposals(&mut self, id: &ConversationId) -> CryptoResult<Option<MlsCommitBundle>> {
-        self.get_conversation(id)
-            .await?
-            .write()
-            .await
+        let conversation = self.get_conversation(id).await?;
+        let mut conversation = conversation.write().await;
+        let Some(mut commit) = conversation
             .commit_pending_proposals(self.mls_client()?, &self.mls_backend)
-            .await
+            .await?
+        else {
+            return Ok(None);
+        };
+        commit.group_info = self.finalize_group_info(commit.group_info, Some(&*conversation))?;
+        Ok(Some(commit))
     }
 }
 
@@ -189,6 +264,16 @@ impl MlsConversation {
         })
     }
 
+    /// Client ids of all the devices of a user which are members of the group
+    pub(crate) fn user_clients(&self, user_id: UserId) -> Vec<ClientId> {
+        self.group
+            .members()
+            .map(|m| m.credential.identity().to_vec())
+            .filter(|client_id| user_id.owns(client_id))
+            .map(ClientId::from)
+            .collect()
+    }
+
     /// see [MlsCentral::remove_members_from_conversation]
     /// Note: this is not exposed publicly because authorization isn't handled at this level
     #[cfg_attr(test, crate::durable)]
@@ -721,6 +806,217 @@ pub mod tests {
         }
     }
 
+    pub mod users {
+        use super::*;
+
+        const ALICE_ANDROID: &str = "satICT30SbiIpjj1n-XQtA:7684f3f95a5e6848@world.com";
+        const ALICE_IOS: &str = "satICT30SbiIpjj1n-XQtA:7dfd976fc672c899@world.com";
+        const BOB_ANDROID: &str = "I_7X5oRAToKy9z_kvhDKKQ:8b1fd601510d102a@world.com";
+
+        #[async_std::test]
+        #[wasm_bindgen_test]
+        pub async fn should_add_all_devices_of_a_user() {
+            let case = TestCase::default_x509();
+            run_test_with_deterministic_client_ids(
+                case.clone(),
+                [
+                    [BOB_ANDROID, "bob_wire", "Bob Doe"],
+                    [ALICE_ANDROID, "alice_wire", "Alice Smith"],
+                    [ALICE_IOS, "alice_wire", "Alice Smith"],
+                ],
+                move |[mut bob_central, alice_android_central, alice_ios_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        bob_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+
+                        let alice_user_id = alice_android_central.mls_central.get_user_id();
+                        let key_packages = vec![
+                            alice_android_central.mls_central.rand_key_package(&case).await,
+                            alice_ios_central.mls_central.rand_key_package(&case).await,
+                        ];
+                        bob_central
+                            .mls_central
+                            .add_user_to_conversation(&id, &alice_user_id, key_packages)
+                            .await
+                            .unwrap();
+                        bob_central.mls_central.commit_accepted(&id).await.unwrap();
+
+                        let members = bob_central.mls_central.get_conversation_unchecked(&id).await.members();
+                        assert_eq!(members.len(), 3);
+                        assert!(members.contains_key(alice_android_central.mls_central.get_client_id().as_slice()));
+                        assert!(members.contains_key(alice_ios_central.mls_central.get_client_id().as_slice()));
+                    })
+                },
+            )
+            .await
+        }
+
+        #[async_std::test]
+        #[wasm_bindgen_test]
+        pub async fn should_not_add_devices_of_another_user() {
+            let case = TestCase::default_x509();
+            run_test_with_deterministic_client_ids(
+                case.clone(),
+                [
+                    [BOB_ANDROID, "bob_wire", "Bob Doe"],
+                    [ALICE_ANDROID, "alice_wire", "Alice Smith"],
+                    [ALICE_IOS, "alice_wire", "Alice Smith"],
+                ],
+                move |[mut bob_central, alice_android_central, _alice_ios_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        bob_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+
+                        let bob_user_id = bob_central.mls_central.get_user_id();
+                        let key_packages = vec![alice_android_central.mls_central.rand_key_package(&case).await];
+                        let add = bob_central
+                            .mls_central
+                            .add_user_to_conversation(&id, &bob_user_id, key_packages)
+                            .await;
+                        assert!(matches!(
+                            add.unwrap_err(),
+                            CryptoError::KeyPackageUserMismatch(c) if c == alice_android_central.mls_central.get_client_id()
+                        ));
+                        assert_eq!(
+                            bob_central.mls_central.get_conversation_unchecked(&id).await.members().len(),
+                            1
+                        );
+                    })
+                },
+            )
+            .await
+        }
+
+        #[async_std::test]
+        #[wasm_bindgen_test]
+        pub async fn should_remove_all_devices_of_a_user() {
+            let case = TestCase::default_x509();
+            run_test_with_deterministic_client_ids(
+                case.clone(),
+                [
+                    [BOB_ANDROID, "bob_wire", "Bob Doe"],
+                    [ALICE_ANDROID, "alice_wire", "Alice Smith"],
+                    [ALICE_IOS, "alice_wire", "Alice Smith"],
+                ],
+                move |[mut bob_central, mut alice_android_central, mut alice_ios_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        bob_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        bob_central
+                            .mls_central
+                            .invite_all(
+                                &case,
+                                &id,
+                                [
+                                    &mut alice_android_central.mls_central,
+                                    &mut alice_ios_central.mls_central,
+                                ],
+                            )
+                            .await
+                            .unwrap();
+
+                        let alice_user_id = alice_android_central.mls_central.get_user_id();
+                        let MlsCommitBundle { commit, .. } = bob_central
+                            .mls_central
+                            .remove_user_from_conversation(&id, &alice_user_id)
+                            .await
+                            .unwrap();
+                        bob_central.mls_central.commit_accepted(&id).await.unwrap();
+                        assert_eq!(
+                            bob_central
+                                .mls_central
+                                .get_conversation_unchecked(&id)
+                                .await
+                                .members()
+                                .len(),
+                            1
+                        );
+
+                        for alice_central in [&mut alice_android_central, &mut alice_ios_central] {
+                            let decrypted = alice_central
+                                .mls_central
+                                .decrypt_message(&id, commit.to_bytes().unwrap())
+                                .await
+                                .unwrap();
+                            assert!(!decrypted.is_active);
+                        }
+
+                        // nobody left to remove
+                        let remove = bob_central
+                            .mls_central
+                            .remove_user_from_conversation(&id, &alice_user_id)
+                            .await;
+                        assert!(matches!(remove.unwrap_err(), CryptoError::UserNotFound(u) if u == alice_user_id));
+                    })
+                },
+            )
+            .await
+        }
+
+        #[async_std::test]
+        #[wasm_bindgen_test]
+        pub async fn should_only_remove_our_other_devices() {
+            let case = TestCase::default_x509();
+            run_test_with_deterministic_client_ids(
+                case.clone(),
+                [
+                    [ALICE_ANDROID, "alice_wire", "Alice Smith"],
+                    [ALICE_IOS, "alice_wire", "Alice Smith"],
+                    [BOB_ANDROID, "bob_wire", "Bob Doe"],
+                ],
+                move |[mut alice_android_central, mut alice_ios_central, mut bob_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        alice_android_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        alice_android_central
+                            .mls_central
+                            .invite_all(
+                                &case,
+                                &id,
+                                [&mut alice_ios_central.mls_central, &mut bob_central.mls_central],
+                            )
+                            .await
+                            .unwrap();
+
+                        let alice_user_id = alice_android_central.mls_central.get_user_id();
+                        alice_android_central
+                            .mls_central
+                            .remove_user_from_conversation(&id, &alice_user_id)
+                            .await
+                            .unwrap();
+                        alice_android_central.mls_central.commit_accepted(&id).await.unwrap();
+
+                        let members = alice_android_central
+                            .mls_central
+                            .get_conversation_unchecked(&id)
+                            .await
+                            .members();
+                        assert_eq!(members.len(), 2);
+                        assert!(members.contains_key(alice_android_central.mls_central.get_client_id().as_slice()));
+                        assert!(!members.contains_key(alice_ios_central.mls_central.get_client_id().as_slice()));
+                    })
+                },
+            )
+            .await
+        }
+    }
+
     pub mod update_keying_material {
         use super::*;
 
@@ -31,7 +31,7 @@ use openmls_traits::OpenMlsCryptoProvider;
 use serde::{Deserialize, Serialize};
 use wire_e2e_identity::prelude::parse_json_jwk;
 
-use crate::prelude::{CryptoResult, E2eIdentityError, MlsCentral, MlsCiphersuite};
+use crate::prelude::{CryptoResult, E2eIdentityError, MlsCentral, MlsCiphersuite, MlsRatchetTreeType};
 use crate::MlsError;
 
 /// Sets the config in OpenMls for the oldest possible epoch(past current) that a message can be decrypted
@@ -107,6 +107,15 @@ impl MlsConversationConfiguration {
     /// Generates an `MlsGroupConfig` from this configuration
     #[inline(always)]
     pub fn as_openmls_default_configuration(&self) -> CryptoResult<openmls::group::MlsGroupConfig> {
+        self.as_openmls_configuration(MlsRatchetTreeType::Full)
+    }
+
+    /// Generates an `MlsGroupConfig` from this configuration. With [MlsRatchetTreeType::ByRef], the ratchet tree
+    /// extension is left out of the GroupInfos, including the ones in Welcome messages
+    pub(crate) fn as_openmls_configuration(
+        &self,
+        ratchet_tree_type: MlsRatchetTreeType,
+    ) -> CryptoResult<openmls::group::MlsGroupConfig> {
         let crypto_config = openmls::prelude::CryptoConfig {
             version: Self::DEFAULT_PROTOCOL_VERSION,
             ciphersuite: self.ciphersuite.into(),
@@ -122,7 +131,7 @@ impl MlsConversationConfiguration {
                 self.custom.out_of_order_tolerance,
                 self.custom.maximum_forward_distance,
             ))
-            .use_ratchet_tree_extension(true)
+            .use_ratchet_tree_extension(ratchet_tree_type != MlsRatchetTreeType::ByRef)
             .external_senders(self.external_senders.clone())
             .crypto_config(crypto_config)
             .build())
@@ -177,8 +186,8 @@ impl MlsConversationConfiguration {
 /// The configuration parameters for a group/conversation which are not handled natively by openmls
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MlsCustomConfiguration {
-    // TODO: Not implemented yet
-    /// Duration in seconds after which we will automatically force a self_update commit
+    /// Duration after which the own leaf has to be updated. Conversations for which it has passed are
+    /// returned by [crate::prelude::MlsCentral::conversations_due_for_key_rotation]
     pub key_rotation_span: Option<std::time::Duration>,
     /// Defines if handshake messages are encrypted or not
     pub wire_policy: MlsWirePolicy,
@@ -191,6 +200,12 @@ pub struct MlsCustomConfiguration {
     pub maximum_forward_distance: u32,
 }
 
+impl MlsCustomConfiguration {
+    /// Key rotation span given to the conversations created before the key rotation was tracked, when they are
+    /// restored from the keystore
+    pub const DEFAULT_KEY_ROTATION_SPAN: std::time::Duration = std::time::Duration::from_secs(90 * 24 * 60 * 60);
+}
+
 impl Default for MlsCustomConfiguration {
     fn default() -> Self {
         Self {
@@ -20,7 +20,6 @@ use openmls_traits::OpenMlsCryptoProvider;
 
 use tls_codec::Deserialize;
 
-use core_crypto_keystore::entities::MlsPendingMessage;
 use mls_crypto_provider::MlsCryptoProvider;
 
 use crate::{
@@ -37,7 +36,7 @@ use crate::{
         },
         ClientId, ConversationId, MlsCentral, MlsConversation,
     },
-    prelude::{E2eiConversationState, MlsProposalBundle, WireIdentity},
+    prelude::{E2eiConversationState, MlsProposalBundle, MlsReInit, WireIdentity},
     CoreCryptoCallbacks, CryptoError, CryptoResult, MlsError,
 };
 
@@ -58,6 +57,9 @@ pub struct MlsConversationDecryptMessage {
     pub delay: Option<u64>,
     /// [ClientId] of the sender of the message being decrypted. Only present for application messages.
     pub sender_client_id: Option<ClientId>,
+    /// Additional authenticated data the sender bound to the message (see [MlsCentral::encrypt_message_with_aad]).
+    /// Only present for application messages.
+    pub aad: Option<Vec<u8>>,
     /// Is the epoch changed after decrypting this message
     pub has_epoch_changed: bool,
     /// Identity claims present in the sender credential
@@ -69,6 +71,9 @@ pub struct MlsConversationDecryptMessage {
     pub buffered_messages: Option<Vec<MlsBufferedConversationDecryptMessage>>,
     /// New CRL distribution points that appeared by the introduction of a new credential
     pub crl_new_distribution_points: NewCrlDistributionPoint,
+    /// Only set when the decrypted message is a commit ending the conversation with a ReInit proposal. Its members
+    /// are expected to move to the successor, see [MlsCentral::join_reinit_successor]
+    pub reinit: Option<MlsReInit>,
 }
 
 /// Type safe recursion of [MlsConversationDecryptMessage]
@@ -85,11 +90,15 @@ pub struct MlsBufferedConversationDecryptMessage {
     /// see [MlsConversationDecryptMessage]
     pub sender_client_id: Option<ClientId>,
     /// see [MlsConversationDecryptMessage]
+    pub aad: Option<Vec<u8>>,
+    /// see [MlsConversationDecryptMessage]
     pub has_epoch_changed: bool,
     /// see [MlsConversationDecryptMessage]
     pub identity: WireIdentity,
     /// see [MlsConversationDecryptMessage]
     pub crl_new_distribution_points: NewCrlDistributionPoint,
+    /// see [MlsConversationDecryptMessage]
+    pub reinit: Option<MlsReInit>,
 }
 
 impl From<MlsConversationDecryptMessage> for MlsBufferedConversationDecryptMessage {
@@ -100,9 +109,11 @@ impl From<MlsConversationDecryptMessage> for MlsBufferedConversationDecryptMessa
             is_active: from.is_active,
             delay: from.delay,
             sender_client_id: from.sender_client_id,
+            aad: from.aad,
             has_epoch_changed: from.has_epoch_changed,
             identity: from.identity,
             crl_new_distribution_points: from.crl_new_distribution_points,
+            reinit: from.reinit,
         }
     }
 }
@@ -122,6 +133,26 @@ impl MlsConversation {
         backend: &MlsCryptoProvider,
         callbacks: Option<&dyn CoreCryptoCallbacks>,
         restore_pending: bool,
+    ) -> CryptoResult<MlsConversationDecryptMessage> {
+        let decrypted = self
+            .process_incoming_message(message, parent_conv, client, backend, callbacks, restore_pending)
+            .await?;
+
+        self.persist_group_when_changed(backend, false).await?;
+
+        Ok(decrypted)
+    }
+
+    /// Same as [MlsConversation::decrypt_message] but leaves it to the caller to persist the group
+    /// afterwards. Used to decrypt batches of messages with a single write to the keystore.
+    async fn process_incoming_message(
+        &mut self,
+        message: MlsMessageIn,
+        parent_conv: Option<&GroupStoreValue<MlsConversation>>,
+        client: &Client,
+        backend: &MlsCryptoProvider,
+        callbacks: Option<&dyn CoreCryptoCallbacks>,
+        restore_pending: bool,
     ) -> CryptoResult<MlsConversationDecryptMessage> {
         // handles the crooked case where we receive our own commits.
         // Since this would result in an error in openmls, we handle it here
@@ -139,19 +170,27 @@ impl MlsConversation {
         )?;
 
         let sender_client_id = credential.credential.identity().into();
+        let aad = message.aad().to_vec();
 
         let decrypted = match message.into_content() {
-            ProcessedMessageContent::ApplicationMessage(app_msg) => MlsConversationDecryptMessage {
-                app_msg: Some(app_msg.into_bytes()),
-                proposals: vec![],
-                is_active: true,
-                delay: None,
-                sender_client_id: Some(sender_client_id),
-                has_epoch_changed: false,
-                identity,
-                buffered_messages: None,
-                crl_new_distribution_points: None.into(),
-            },
+            ProcessedMessageContent::ApplicationMessage(app_msg) => {
+                let app_msg = app_msg.into_bytes();
+                self.record_history_message(backend, &sender_client_id, &app_msg, &aad)
+                    .await;
+                MlsConversationDecryptMessage {
+                    app_msg: Some(app_msg),
+                    proposals: vec![],
+                    is_active: true,
+                    delay: None,
+                    sender_client_id: Some(sender_client_id),
+                    aad: Some(aad),
+                    has_epoch_changed: false,
+                    identity,
+                    buffered_messages: None,
+                    crl_new_distribution_points: None.into(),
+                    reinit: None,
+                }
+            }
             ProcessedMessageContent::ProposalMessage(proposal) => {
                 let crl_dps = extract_crl_uris_from_proposals(&[proposal.proposal().clone()])?;
                 let crl_new_distribution_points = get_new_crl_distribution_points(backend, crl_dps).await?;
@@ -164,10 +203,12 @@ impl MlsConversation {
                     is_active: true,
                     delay: self.compute_next_commit_delay(),
                     sender_client_id: None,
+                    aad: None,
                     has_epoch_changed: false,
                     identity,
                     buffered_messages: None,
                     crl_new_distribution_points,
+                    reinit: None,
                 }
             }
             ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
@@ -202,11 +243,14 @@ impl MlsConversation {
 
                 // getting the pending has to be done before `merge_staged_commit` otherwise it's wiped out
                 let pending_commit = self.group.pending_commit().cloned();
+                let reinit = super::reinit::committed_reinit(&staged_commit)?;
 
                 self.group
                     .merge_staged_commit(backend, *staged_commit.clone())
                     .await
                     .map_err(MlsError::from)?;
+                self.record_epoch_authenticator(backend).await?;
+                self.record_member_epochs(backend).await?;
 
                 let (proposals_to_renew, needs_update) = Renew::renew(
                     &self.group.own_leaf_index(),
@@ -219,15 +263,8 @@ impl MlsConversation {
                     .await?;
 
                 let buffered_messages = if restore_pending {
-                    if let Some(pm) = self
-                        .restore_pending_messages(client, backend, callbacks, parent_conv, false)
+                    self.restore_pending_messages(client, backend, callbacks, parent_conv, false)
                         .await?
-                    {
-                        backend.key_store().remove::<MlsPendingMessage, _>(self.id()).await?;
-                        Some(pm)
-                    } else {
-                        None
-                    }
                 } else {
                     None
                 };
@@ -238,10 +275,12 @@ impl MlsConversation {
                     is_active: self.group.is_active(),
                     delay: self.compute_next_commit_delay(),
                     sender_client_id: None,
+                    aad: None,
                     has_epoch_changed: true,
                     identity,
                     buffered_messages,
                     crl_new_distribution_points,
+                    reinit,
                 }
             }
             ProcessedMessageContent::ExternalJoinProposalMessage(proposal) => {
@@ -257,16 +296,16 @@ impl MlsConversation {
                     is_active: true,
                     delay: self.compute_next_commit_delay(),
                     sender_client_id: None,
+                    aad: None,
                     has_epoch_changed: false,
                     identity,
                     buffered_messages: None,
                     crl_new_distribution_points,
+                    reinit: None,
                 }
             }
         };
 
-        self.persist_group_when_changed(backend, false).await?;
-
         Ok(decrypted)
     }
 
@@ -336,6 +375,8 @@ impl MlsConversation {
 
     #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
     async fn validate_commit(&self, commit: &StagedCommit, backend: &MlsCryptoProvider) -> CryptoResult<()> {
+        self.validate_committed_group_context_extensions(commit)?;
+
         if backend.authentication_service().is_env_setup().await {
             let credentials: Vec<_> = commit
                 .add_proposals()
@@ -375,7 +416,8 @@ impl MlsCentral {
     /// byte array. The delay will be `Some` when the message has a proposal
     ///
     /// # Errors
-    /// If the conversation can't be found, an error will be returned. Other errors are originating
+    /// If the conversation can't be found, the message is buffered until we join it and
+    /// [CryptoError::BufferedMessageForUnknownConversation] is returned. Other errors are originating
     /// from OpenMls and the KeyStore
     #[cfg_attr(not(test), tracing::instrument(err, skip(self, message), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
     pub async fn decrypt_message(
@@ -412,6 +454,187 @@ impl MlsCentral {
         }
         Ok(decrypt_message)
     }
+
+    /// Decrypts an ordered batch of messages, typically the backlog fetched from the Delivery Service
+    /// after having been offline for a while.
+    ///
+    /// Messages are grouped by conversation. Within a conversation they are reordered by epoch, then
+    /// application messages & proposals first and commits last, so that a commit arriving before
+    /// messages of the epoch it closes does not prevent decrypting them. Each conversation is processed
+    /// in its own keystore transaction and persisted only once, after all of its messages have been
+    /// processed. If that fails, nothing of the conversation is persisted and its messages are decrypted
+    /// one by one instead.
+    ///
+    /// # Arguments
+    /// * `messages` - pairs of conversation id and TLS-serialized message, in the order they were received
+    ///
+    /// # Return type
+    /// One result per input message, in the same order as the input. An error for a message, or for a
+    /// conversation, does not prevent the other ones from being decrypted.
+    ///
+    /// # Errors
+    /// [CryptoError::KeyStoreError] with a [core_crypto_keystore::CryptoKeystoreError::TransactionInProgress]
+    /// if a keystore transaction is already in progress, in which case nothing is decrypted
+    #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
+    pub async fn decrypt_messages(
+        &mut self,
+        messages: Vec<(ConversationId, Vec<u8>)>,
+    ) -> CryptoResult<Vec<CryptoResult<MlsConversationDecryptMessage>>> {
+        if self.mls_backend.key_store().is_in_transaction().await {
+            return Err(core_crypto_keystore::CryptoKeystoreError::TransactionInProgress.into());
+        }
+
+        let mut results = Vec::with_capacity(messages.len());
+        results.resize_with(messages.len(), || None);
+
+        let mut by_conversation: Vec<(ConversationId, Vec<(usize, Vec<u8>)>)> = vec![];
+        for (i, (id, message)) in messages.into_iter().enumerate() {
+            match by_conversation.iter_mut().find(|(c, _)| c == &id) {
+                Some((_, batch)) => batch.push((i, message)),
+                None => by_conversation.push((id, vec![(i, message)])),
+            }
+        }
+
+        for (id, batch) in by_conversation {
+            for (i, result) in self.decrypt_conversation_batch(&id, batch).await {
+                results[i] = Some(result);
+            }
+        }
+
+        Ok(results
+            .into_iter()
+            .map(|r| r.unwrap_or(Err(CryptoError::ImplementationError)))
+            .collect())
+    }
+
+    /// Decrypts the messages of a conversation in a keystore transaction, so that the group and the key
+    /// material OpenMLS writes or deletes along the way are persisted together.
+    async fn decrypt_conversation_batch(
+        &mut self,
+        id: &ConversationId,
+        batch: Vec<(usize, Vec<u8>)>,
+    ) -> Vec<(usize, CryptoResult<MlsConversationDecryptMessage>)> {
+        if self.begin_transaction().await.is_err() {
+            return self.decrypt_one_by_one(id, batch).await;
+        }
+        let processed = match self.process_conversation_batch(id, &batch).await {
+            Ok(results) => self.commit_transaction().await.map(|_| results),
+            Err(e) => self.rollback_transaction().await.and(Err(e)),
+        };
+        match processed {
+            Ok(results) => results,
+            Err(e) => {
+                // conversations have been reloaded from the keystore, none of the messages has been consumed
+                tracing::warn!(error = %e, "Failed decrypting a batch of messages, decrypting them one by one");
+                self.decrypt_one_by_one(id, batch).await
+            }
+        }
+    }
+
+    async fn decrypt_one_by_one(
+        &mut self,
+        id: &ConversationId,
+        batch: Vec<(usize, Vec<u8>)>,
+    ) -> Vec<(usize, CryptoResult<MlsConversationDecryptMessage>)> {
+        let mut results = Vec::with_capacity(batch.len());
+        for (i, message) in batch {
+            results.push((i, self.decrypt_message(id, message).await));
+        }
+        results
+    }
+
+    /// Decrypts the messages of a conversation and persists it once they have all been processed.
+    ///
+    /// # Errors
+    /// Errors which are not attributable to a single message, in which case the caller has to discard
+    /// everything persisted in the meantime
+    async fn process_conversation_batch(
+        &mut self,
+        id: &ConversationId,
+        batch: &[(usize, Vec<u8>)],
+    ) -> CryptoResult<Vec<(usize, CryptoResult<MlsConversationDecryptMessage>)>> {
+        let mut results = Vec::with_capacity(batch.len());
+
+        let Ok(conversation) = self.get_conversation(id).await else {
+            // the group might still be pending, let the regular path handle it
+            for (i, message) in batch {
+                results.push((*i, self.decrypt_message(id, message).await));
+            }
+            return Ok(results);
+        };
+        let parent_conversation = self.get_parent_conversation(&conversation).await?;
+
+        let mut sorted = Vec::with_capacity(batch.len());
+        for (i, raw) in batch {
+            let msg = match MlsMessageIn::tls_deserialize(&mut raw.as_slice()) {
+                Ok(msg) => msg,
+                Err(e) => {
+                    results.push((*i, Err(MlsError::from(e).into())));
+                    continue;
+                }
+            };
+            let (protocol_message, content_type) = match msg.clone().extract() {
+                MlsMessageInBody::PublicMessage(m) => {
+                    let ct = m.content_type();
+                    (ProtocolMessage::PublicMessage(m), ct)
+                }
+                MlsMessageInBody::PrivateMessage(m) => {
+                    let ct = m.content_type();
+                    (ProtocolMessage::PrivateMessage(m), ct)
+                }
+                _ => {
+                    let e = CryptoError::MlsError(ProcessMessageError::IncompatibleWireFormat.into());
+                    results.push((*i, Err(e)));
+                    continue;
+                }
+            };
+            let order = (protocol_message.epoch().as_u64(), content_type as u8);
+            sorted.push((order, *i, msg, raw));
+        }
+        // stable sort so that messages of the same kind and epoch keep their original order
+        sorted.sort_by_key(|(order, ..)| *order);
+
+        let mut future_messages = vec![];
+        let mut is_active = true;
+        {
+            let mut conv = conversation.write().await;
+            for (_, i, msg, raw) in sorted {
+                if !is_active {
+                    results.push((i, Err(CryptoError::ConversationNotFound(id.clone()))));
+                    continue;
+                }
+                let callbacks = self.callbacks.as_ref().map(|boxed| boxed.as_ref());
+                let decrypted = conv
+                    .process_incoming_message(
+                        msg,
+                        parent_conversation.as_ref(),
+                        self.mls_client()?,
+                        &self.mls_backend,
+                        callbacks,
+                        true,
+                    )
+                    .await;
+                match decrypted {
+                    Err(CryptoError::BufferedFutureMessage) => future_messages.push((i, raw)),
+                    Ok(decrypted) => {
+                        is_active = decrypted.is_active;
+                        results.push((i, Ok(decrypted)));
+                    }
+                    Err(e) => results.push((i, Err(e))),
+                }
+            }
+            conv.persist_group_when_changed(&self.mls_backend, false).await?;
+        }
+
+        for (i, raw) in future_messages {
+            results.push((i, self.handle_future_message(id, raw).await));
+        }
+
+        if !is_active {
+            self.wipe_conversation(id).await?;
+        }
+        Ok(results)
+    }
 }
 
 #[cfg(test)]
@@ -1699,4 +1922,162 @@ pub mod tests {
             .await
         }
     }
+
+    pub mod batch {
+        use super::*;
+
+        wasm_bindgen_test_configure!(run_in_browser);
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn should_decrypt_backlog_out_of_order(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob"],
+                move |[mut alice_central, mut bob_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        alice_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .invite_all(&case, &id, [&mut bob_central.mls_central])
+                            .await
+                            .unwrap();
+                        let epoch = bob_central.mls_central.conversation_epoch(&id).await.unwrap();
+
+                        let before = alice_central.mls_central.encrypt_message(&id, b"before").await.unwrap();
+                        let MlsCommitBundle { commit, .. } =
+                            alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                        alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                        let after = alice_central.mls_central.encrypt_message(&id, b"after").await.unwrap();
+
+                        // the DS fanned out the message of the new epoch first
+                        let batch = vec![
+                            (id.clone(), after),
+                            (id.clone(), commit.to_bytes().unwrap()),
+                            (id.clone(), before),
+                        ];
+                        let results = bob_central.mls_central.decrypt_messages(batch).await.unwrap();
+                        let [after, commit, before] = results.try_into().unwrap();
+
+                        assert_eq!(after.unwrap().app_msg.unwrap(), b"after");
+                        let commit = commit.unwrap();
+                        assert!(commit.has_epoch_changed);
+                        assert!(commit.buffered_messages.is_none());
+                        assert_eq!(before.unwrap().app_msg.unwrap(), b"before");
+
+                        assert_eq!(
+                            bob_central.mls_central.conversation_epoch(&id).await.unwrap(),
+                            epoch + 1
+                        );
+                        assert!(bob_central
+                            .mls_central
+                            .try_talk_to(&id, &mut alice_central.mls_central)
+                            .await
+                            .is_ok());
+                    })
+                },
+            )
+            .await
+        }
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn should_report_errors_per_message(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob"],
+                move |[mut alice_central, mut bob_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        alice_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .invite_all(&case, &id, [&mut bob_central.mls_central])
+                            .await
+                            .unwrap();
+
+                        let unknown_id = conversation_id();
+                        let msg = alice_central.mls_central.encrypt_message(&id, b"hello").await.unwrap();
+                        let batch = vec![
+                            (id.clone(), vec![0xFF; 8]),
+                            (unknown_id.clone(), msg.clone()),
+                            (id.clone(), msg),
+                        ];
+                        let results = bob_central.mls_central.decrypt_messages(batch).await.unwrap();
+                        let [garbage, unknown, msg] = results.try_into().unwrap();
+
+                        assert!(matches!(garbage.unwrap_err(), CryptoError::MlsError(_)));
+                        assert!(matches!(
+                            unknown.unwrap_err(),
+                            CryptoError::BufferedMessageForUnknownConversation
+                        ));
+                        assert_eq!(msg.unwrap().app_msg.unwrap(), b"hello");
+                    })
+                },
+            )
+            .await
+        }
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn should_not_decrypt_batch_within_transaction(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob"],
+                move |[mut alice_central, mut bob_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        alice_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .invite_all(&case, &id, [&mut bob_central.mls_central])
+                            .await
+                            .unwrap();
+
+                        let msg = alice_central.mls_central.encrypt_message(&id, b"hello").await.unwrap();
+                        bob_central.mls_central.begin_transaction().await.unwrap();
+                        let batch = vec![(id.clone(), msg.clone())];
+                        assert!(matches!(
+                            bob_central.mls_central.decrypt_messages(batch).await.unwrap_err(),
+                            CryptoError::KeyStoreError(
+                                core_crypto_keystore::CryptoKeystoreError::TransactionInProgress
+                            )
+                        ));
+                        bob_central.mls_central.rollback_transaction().await.unwrap();
+
+                        // the message has not been consumed
+                        let results = bob_central
+                            .mls_central
+                            .decrypt_messages(vec![(id.clone(), msg)])
+                            .await
+                            .unwrap();
+                        let [msg] = results.try_into().unwrap();
+                        assert_eq!(msg.unwrap().app_msg.unwrap(), b"hello");
+                        assert!(
+                            !bob_central
+                                .mls_central
+                                .mls_backend
+                                .key_store()
+                                .is_in_transaction()
+                                .await
+                        );
+                    })
+                },
+            )
+            .await
+        }
+    }
 }
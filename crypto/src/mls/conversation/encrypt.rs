@@ -18,19 +18,32 @@ use super::MlsConversation;
 /// Abstraction over a MLS group capable of encrypting a MLS message
 impl MlsConversation {
     /// see [MlsCentral::encrypt_message]
+    pub async fn encrypt_message(
+        &mut self,
+        client: &Client,
+        message: impl AsRef<[u8]>,
+        backend: &MlsCryptoProvider,
+    ) -> CryptoResult<Vec<u8>> {
+        self.encrypt_message_with_aad(client, message, &[], backend).await
+    }
+
+    /// see [MlsCentral::encrypt_message_with_aad]
     /// It is durable because encrypting increments the message generation
     #[cfg_attr(test, crate::durable)]
     #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
-    pub async fn encrypt_message(
+    pub async fn encrypt_message_with_aad(
         &mut self,
         client: &Client,
         message: impl AsRef<[u8]>,
+        aad: &[u8],
         backend: &MlsCryptoProvider,
     ) -> CryptoResult<Vec<u8>> {
         let signer = &self
             .find_current_credential_bundle(client)?
             .ok_or(CryptoError::IdentityInitializationError)?
             .signature_key;
+        // always set since openmls only resets it once a message has successfully been created
+        self.group.set_aad(aad);
         let encrypted = self
             .group
             .create_message(backend, signer, message.as_ref())
@@ -42,6 +55,8 @@ impl MlsConversation {
         let encrypted = encrypted.to_bytes().map_err(MlsError::from)?;
 
         self.persist_group_when_changed(backend, false).await?;
+        self.record_history_message(backend, client.id(), message.as_ref(), aad)
+            .await;
         Ok(encrypted)
     }
 }
@@ -73,6 +88,34 @@ impl MlsCentral {
             .encrypt_message(self.mls_client()?, message, &self.mls_backend)
             .await
     }
+
+    /// Same as [MlsCentral::encrypt_message] but also binds additional data to the message. It is sent in clear
+    /// alongside the ciphertext e.g. for the Delivery Service to route the message, while being authenticated: the
+    /// message can't be decrypted if it has been tampered with.
+    ///
+    /// # Arguments
+    /// * `conversation` - the group/conversation id
+    /// * `message` - the message as a byte array
+    /// * `aad` - the additional authenticated data, returned to the recipients in
+    ///   [crate::prelude::MlsConversationDecryptMessage::aad]
+    ///
+    /// # Errors
+    /// see [MlsCentral::encrypt_message]
+    #[cfg_attr(test, crate::idempotent)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
+    pub async fn encrypt_message_with_aad(
+        &mut self,
+        conversation: &ConversationId,
+        message: impl AsRef<[u8]>,
+        aad: impl AsRef<[u8]>,
+    ) -> CryptoResult<Vec<u8>> {
+        self.get_conversation(conversation)
+            .await?
+            .write()
+            .await
+            .encrypt_message_with_aad(self.mls_client()?, message, aad.as_ref(), &self.mls_backend)
+            .await
+    }
 }
 
 #[cfg(test)]
@@ -169,4 +212,82 @@ pub mod tests {
         )
         .await
     }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn can_encrypt_app_message_with_aad(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let msg = b"Hello bob";
+                    let aad = b"thread:42";
+                    let encrypted = alice_central
+                        .mls_central
+                        .encrypt_message_with_aad(&id, msg, aad)
+                        .await
+                        .unwrap();
+                    let decrypted = bob_central.mls_central.decrypt_message(&id, encrypted).await.unwrap();
+                    assert_eq!(decrypted.app_msg.unwrap(), msg);
+                    assert_eq!(decrypted.aad.unwrap(), aad);
+
+                    // the AAD does not leak into the next message
+                    let encrypted = alice_central.mls_central.encrypt_message(&id, msg).await.unwrap();
+                    let decrypted = bob_central.mls_central.decrypt_message(&id, encrypted).await.unwrap();
+                    assert!(decrypted.aad.unwrap().is_empty());
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_not_decrypt_message_with_tampered_aad(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let aad = b"expires:3600";
+                    let mut encrypted = alice_central
+                        .mls_central
+                        .encrypt_message_with_aad(&id, b"Hello bob", aad)
+                        .await
+                        .unwrap();
+                    // the AAD is sent in clear
+                    let position = encrypted.windows(aad.len()).position(|w| w == aad).unwrap();
+                    encrypted[position] ^= 1;
+
+                    assert!(bob_central.mls_central.decrypt_message(&id, encrypted).await.is_err());
+                })
+            },
+        )
+        .await
+    }
 }
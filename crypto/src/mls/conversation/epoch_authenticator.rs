@@ -0,0 +1,339 @@
+//! The epoch authenticator is a secret derived from the key schedule which is identical for all
+//! the members of a given epoch. Comparing it out-of-band, for example as a safety number, proves
+//! that both parties share the same view of the group.
+//!
+//! Since such a comparison usually happens some time after the fact, the authenticators of the
+//! last [EPOCH_AUTHENTICATOR_HISTORY_SIZE] epochs are kept in the keystore for each conversation.
+
+use core_crypto_keystore::entities::MlsEpochAuthenticators;
+use mls_crypto_provider::MlsCryptoProvider;
+use openmls_traits::OpenMlsCryptoProvider;
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::{ConversationId, CryptoError, CryptoResult, MlsCentral, MlsConversation, MlsError};
+
+/// How many past epoch authenticators are kept for each conversation, the current one included
+pub const EPOCH_AUTHENTICATOR_HISTORY_SIZE: usize = 10;
+
+/// The epoch authenticator of a past or current epoch
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MlsEpochAuthenticator {
+    /// The epoch this authenticator belongs to
+    pub epoch: u64,
+    /// The epoch authenticator itself
+    pub authenticator: Vec<u8>,
+}
+
+/// How an epoch authenticator is rendered into a safety number meant to be compared by humans
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SafetyNumberFormat {
+    /// Groups of 5 digits separated by a space, each one consuming 5 bytes of the epoch authenticator
+    Numeric {
+        /// The number of groups
+        groups: u8,
+    },
+    /// Emojis separated by a space, each one consuming 6 bits of the epoch authenticator
+    Emoji {
+        /// The number of emojis
+        count: u8,
+    },
+}
+
+impl Default for SafetyNumberFormat {
+    fn default() -> Self {
+        Self::Numeric { groups: 6 }
+    }
+}
+
+/// 64 emojis, each one standing for 6 bits. They are picked to be easy to tell apart
+const SAFETY_NUMBER_EMOJIS: [&str; 64] = [
+    "🐶", "🐱", "🦁", "🐎", "🦄", "🐷", "🐘", "🐰", "🐼", "🐓", "🐧", "🐢", "🐟", "🐙", "🦋", "🌷", "🌳", "🌵", "🍄",
+    "🌏", "🌙", "☁️", "🔥", "🍌", "🍎", "🍓", "🌽", "🍕", "🎂", "❤️", "😀", "🤖", "🎩", "👓", "🔧", "🎅", "👍", "☂️",
+    "⌛", "⏰", "🎁", "💡", "📕", "✏️", "📎", "✂️", "🔒", "🔑", "🔨", "☎️", "🏁", "🚂", "🚲", "✈️", "🚀", "🏆", "⚽",
+    "🎸", "🎺", "🔔", "⚓", "🎧", "📁", "📌",
+];
+
+impl SafetyNumberFormat {
+    /// Renders an epoch authenticator according to this format
+    ///
+    /// # Errors
+    /// [CryptoError::ConsumerError] when the epoch authenticator is too short for the requested length
+    pub fn format(&self, epoch_authenticator: &[u8]) -> CryptoResult<String> {
+        match *self {
+            Self::Numeric { groups } => {
+                let groups = groups as usize;
+                if groups == 0 || epoch_authenticator.len() < groups * 5 {
+                    return Err(CryptoError::ConsumerError);
+                }
+                Ok(epoch_authenticator
+                    .chunks_exact(5)
+                    .take(groups)
+                    .map(|chunk| {
+                        let n = chunk.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64);
+                        format!("{:05}", n % 100_000)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" "))
+            }
+            Self::Emoji { count } => {
+                let count = count as usize;
+                if count == 0 || epoch_authenticator.len() * 8 < count * 6 {
+                    return Err(CryptoError::ConsumerError);
+                }
+                Ok((0..count)
+                    .map(|i| {
+                        let bit = i * 6;
+                        let (byte, shift) = (bit / 8, bit % 8);
+                        // 6 bits may span over 2 bytes
+                        let hi = epoch_authenticator[byte] as u16;
+                        let lo = epoch_authenticator.get(byte + 1).copied().unwrap_or_default() as u16;
+                        let index = (((hi << 8) | lo) >> (10 - shift)) & 0x3F;
+                        SAFETY_NUMBER_EMOJIS[index as usize]
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" "))
+            }
+        }
+    }
+}
+
+impl MlsConversation {
+    /// Returns the epoch authenticator of the current epoch
+    pub fn epoch_authenticator(&self) -> Vec<u8> {
+        self.group.epoch_authenticator().as_slice().to_vec()
+    }
+
+    /// Appends the current epoch authenticator to the history of this conversation
+    pub(crate) async fn record_epoch_authenticator(&self, backend: &MlsCryptoProvider) -> CryptoResult<()> {
+        let mut history = self.epoch_authenticator_history(backend).await?;
+        let epoch = self.group.epoch().as_u64();
+        if history.last().map(|e| e.epoch) == Some(epoch) {
+            return Ok(());
+        }
+        history.push(MlsEpochAuthenticator {
+            epoch,
+            authenticator: self.epoch_authenticator(),
+        });
+        let overflow = history.len().saturating_sub(EPOCH_AUTHENTICATOR_HISTORY_SIZE);
+        history.drain(..overflow);
+
+        let authenticators = serde_json::to_vec(&history).map_err(MlsError::MlsKeystoreSerializationError)?;
+        let entity = MlsEpochAuthenticators {
+            id: self.id.clone(),
+            authenticators,
+        };
+        backend.key_store().save(entity).await?;
+        Ok(())
+    }
+
+    async fn epoch_authenticator_history(
+        &self,
+        backend: &MlsCryptoProvider,
+    ) -> CryptoResult<Vec<MlsEpochAuthenticator>> {
+        // conversations created before epoch authenticators were recorded have none
+        match backend.key_store().find::<MlsEpochAuthenticators>(&self.id).await? {
+            Some(entity) => {
+                Ok(serde_json::from_slice(&entity.authenticators).map_err(MlsError::MlsKeystoreSerializationError)?)
+            }
+            None => Ok(vec![]),
+        }
+    }
+}
+
+impl MlsCentral {
+    /// Returns the epoch authenticator of the current epoch of a conversation
+    ///
+    /// # Errors
+    /// If the conversation can't be found
+    #[cfg_attr(test, crate::idempotent)]
+    pub async fn epoch_authenticator(&mut self, id: &ConversationId) -> CryptoResult<Vec<u8>> {
+        Ok(self.get_conversation(id).await?.read().await.epoch_authenticator())
+    }
+
+    /// Returns the safety number of the current epoch of a conversation. All the members of the
+    /// current epoch compute the same one.
+    ///
+    /// # Arguments
+    /// * `id` - the conversation id
+    /// * `format` - how the epoch authenticator is rendered
+    ///
+    /// # Errors
+    /// If the conversation can't be found or if the format requires more bytes than the epoch authenticator has
+    #[cfg_attr(test, crate::idempotent)]
+    pub async fn safety_number(&mut self, id: &ConversationId, format: SafetyNumberFormat) -> CryptoResult<String> {
+        format.format(&self.epoch_authenticator(id).await?)
+    }
+
+    /// Returns the epoch authenticators of the last [EPOCH_AUTHENTICATOR_HISTORY_SIZE] epochs of a
+    /// conversation, from the oldest to the current one. Epochs which happened before the conversation
+    /// was joined are not part of it.
+    ///
+    /// # Errors
+    /// If the conversation can't be found or from the KeyStore
+    #[cfg_attr(test, crate::idempotent)]
+    pub async fn epoch_authenticator_history(
+        &mut self,
+        id: &ConversationId,
+    ) -> CryptoResult<Vec<MlsEpochAuthenticator>> {
+        self.get_conversation(id)
+            .await?
+            .read()
+            .await
+            .epoch_authenticator_history(&self.mls_backend)
+            .await
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use super::*;
+    use crate::test_utils::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn members_should_share_safety_number(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let alice_authenticator = alice_central.mls_central.epoch_authenticator(&id).await.unwrap();
+                    let bob_authenticator = bob_central.mls_central.epoch_authenticator(&id).await.unwrap();
+                    assert_eq!(alice_authenticator, bob_authenticator);
+
+                    for format in [SafetyNumberFormat::default(), SafetyNumberFormat::Emoji { count: 8 }] {
+                        let alice_number = alice_central.mls_central.safety_number(&id, format).await.unwrap();
+                        let bob_number = bob_central.mls_central.safety_number(&id, format).await.unwrap();
+                        assert_eq!(alice_number, bob_number);
+                    }
+
+                    // a new epoch has a new safety number
+                    let before = alice_central
+                        .mls_central
+                        .safety_number(&id, Default::default())
+                        .await
+                        .unwrap();
+                    alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    let after = alice_central
+                        .mls_central
+                        .safety_number(&id, Default::default())
+                        .await
+                        .unwrap();
+                    assert_ne!(before, after);
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_keep_bounded_history(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    // bob joined at epoch 1, the one before is not known to him
+                    let history = bob_central.mls_central.epoch_authenticator_history(&id).await.unwrap();
+                    assert_eq!(history.len(), 1);
+                    assert_eq!(history[0].epoch, 1);
+
+                    for _ in 0..EPOCH_AUTHENTICATOR_HISTORY_SIZE + 2 {
+                        let commit = alice_central
+                            .mls_central
+                            .update_keying_material(&id)
+                            .await
+                            .unwrap()
+                            .commit;
+                        alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                        bob_central
+                            .mls_central
+                            .decrypt_message(&id, commit.to_bytes().unwrap())
+                            .await
+                            .unwrap();
+                    }
+
+                    let alice_history = alice_central
+                        .mls_central
+                        .epoch_authenticator_history(&id)
+                        .await
+                        .unwrap();
+                    let bob_history = bob_central.mls_central.epoch_authenticator_history(&id).await.unwrap();
+                    assert_eq!(alice_history.len(), EPOCH_AUTHENTICATOR_HISTORY_SIZE);
+                    assert_eq!(alice_history, bob_history);
+
+                    let current_epoch = alice_central.mls_central.conversation_epoch(&id).await.unwrap();
+                    let current = alice_history.last().unwrap();
+                    assert_eq!(current.epoch, current_epoch);
+                    assert_eq!(
+                        current.authenticator,
+                        alice_central.mls_central.epoch_authenticator(&id).await.unwrap()
+                    );
+                    assert!(alice_history.windows(2).all(|w| w[0].epoch + 1 == w[1].epoch));
+                })
+            },
+        )
+        .await
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    pub fn safety_number_should_be_formatted() {
+        let authenticator = (0u8..32).collect::<Vec<_>>();
+
+        let numeric = SafetyNumberFormat::Numeric { groups: 2 }
+            .format(&authenticator)
+            .unwrap();
+        assert_eq!(
+            numeric,
+            format!("{:05} {:05}", 0x0001020304u64 % 100_000, 0x0506070809u64 % 100_000)
+        );
+
+        // 0x000102 is 0b000000_000000_000100_000010
+        let emoji = SafetyNumberFormat::Emoji { count: 4 }.format(&authenticator).unwrap();
+        assert_eq!(emoji, "🐶 🐶 🦄 🦁");
+
+        assert!(matches!(
+            SafetyNumberFormat::Numeric { groups: 7 }
+                .format(&authenticator)
+                .unwrap_err(),
+            CryptoError::ConsumerError
+        ));
+        assert!(matches!(
+            SafetyNumberFormat::Emoji { count: 43 }
+                .format(&authenticator)
+                .unwrap_err(),
+            CryptoError::ConsumerError
+        ));
+    }
+}
@@ -0,0 +1,492 @@
+//! The GroupContext extensions of a conversation (external senders, required capabilities and application
+//! specific extensions) are set when it is created. They can later be changed by a commit carrying a
+//! GroupContextExtensions proposal, which replaces them all at once.
+//!
+//! Every member has to support what the new extensions require (see
+//! [RFC 9420 section 7.2](https://www.rfc-editor.org/rfc/rfc9420.html#section-7.2)), which is verified before
+//! committing as well as when processing such a commit, against the membership resulting from it.
+
+use std::{collections::HashSet, ops::RangeInclusive};
+
+use openmls::prelude::{
+    Extension, ExtensionType, Extensions, LeafNode, Node, Proposal, ProposalType, RequiredCapabilitiesExtension,
+    StagedCommit, UnknownExtension,
+};
+use tracing::Instrument;
+
+use mls_crypto_provider::MlsCryptoProvider;
+
+use crate::prelude::{
+    Client, ConversationId, CryptoError, CryptoResult, MlsCentral, MlsCommitBundle, MlsConversation,
+    MlsConversationConfiguration, MlsError, MlsGroupInfoBundle,
+};
+
+/// Extension types members do not have to advertise (see [RFC 9420 section 17.3](https://www.rfc-editor.org/rfc/rfc9420.html#section-17.3))
+const DEFAULT_EXTENSION_TYPES: RangeInclusive<u16> = 0x0001..=0x0005;
+/// Proposal types members do not have to advertise (see [RFC 9420 section 17.4](https://www.rfc-editor.org/rfc/rfc9420.html#section-17.4))
+const DEFAULT_PROPOSAL_TYPES: RangeInclusive<u16> = 0x0001..=0x0007;
+
+impl MlsCentral {
+    /// Replaces the external senders of a conversation e.g. when the Delivery Service rotated its removal key
+    ///
+    /// # Arguments
+    /// * `id` - group/conversation id
+    /// * `external_senders` - as in [MlsCentral::set_raw_external_senders]
+    ///
+    /// # Errors
+    /// If a key can't be parsed or if a member does not support the new extensions. Other errors are KeyStore and
+    /// OpenMls errors
+    #[cfg_attr(test, crate::idempotent)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self, external_senders), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
+    pub async fn update_external_senders(
+        &mut self,
+        id: &ConversationId,
+        external_senders: Vec<Vec<u8>>,
+    ) -> CryptoResult<MlsCommitBundle> {
+        let mut cfg = MlsConversationConfiguration {
+            ciphersuite: self.conversation_ciphersuite(id).await?,
+            ..Default::default()
+        };
+        self.set_raw_external_senders(&mut cfg, external_senders)?;
+        let external_senders = Extension::ExternalSenders(cfg.external_senders);
+        self.update_group_context_extensions(id, |extensions| {
+            extensions.retain(|e| e.extension_type() != ExtensionType::ExternalSenders);
+            extensions.push(external_senders);
+            Ok(())
+        })
+        .await
+    }
+
+    /// Adds extension and proposal types to the capabilities every member of a conversation is required to support
+    ///
+    /// # Arguments
+    /// * `id` - group/conversation id
+    /// * `extension_types` - raw extension types to require
+    /// * `proposal_types` - raw proposal types to require
+    ///
+    /// # Errors
+    /// If a proposal type is unknown or if a member does not support the new capabilities. Other errors are KeyStore
+    /// and OpenMls errors
+    #[cfg_attr(test, crate::idempotent)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
+    pub async fn add_required_capabilities(
+        &mut self,
+        id: &ConversationId,
+        extension_types: Vec<u16>,
+        proposal_types: Vec<u16>,
+    ) -> CryptoResult<MlsCommitBundle> {
+        self.update_group_context_extensions(id, |extensions| {
+            let (mut required_extensions, mut required_proposals, required_credentials) = extensions
+                .iter()
+                .find_map(|e| match e {
+                    Extension::RequiredCapabilities(rc) => Some((
+                        rc.extension_types().to_vec(),
+                        rc.proposal_types().to_vec(),
+                        rc.credential_types().to_vec(),
+                    )),
+                    _ => None,
+                })
+                .unwrap_or_else(|| {
+                    (
+                        vec![],
+                        vec![],
+                        MlsConversationConfiguration::DEFAULT_SUPPORTED_CREDENTIALS.to_vec(),
+                    )
+                });
+            for extension_type in extension_types {
+                let extension_type = ExtensionType::from(extension_type);
+                if !required_extensions.contains(&extension_type) {
+                    required_extensions.push(extension_type);
+                }
+            }
+            for proposal_type in proposal_types {
+                let proposal_type = ProposalType::try_from(proposal_type).map_err(|_| CryptoError::ConsumerError)?;
+                if !required_proposals.contains(&proposal_type) {
+                    required_proposals.push(proposal_type);
+                }
+            }
+
+            extensions.retain(|e| e.extension_type() != ExtensionType::RequiredCapabilities);
+            extensions.push(Extension::RequiredCapabilities(RequiredCapabilitiesExtension::new(
+                &required_extensions,
+                &required_proposals,
+                &required_credentials,
+            )));
+            Ok(())
+        })
+        .await
+    }
+
+    /// Replaces the application specific extensions of a conversation i.e. the ones whose type is not defined by
+    /// MLS. Their types have to be advertised in the capabilities of every member.
+    ///
+    /// # Arguments
+    /// * `id` - group/conversation id
+    /// * `app_extensions` - raw extension types along with their content. An empty list removes them all
+    ///
+    /// # Errors
+    /// If one of the extension types is defined by MLS or if a member does not support it. Other errors are KeyStore
+    /// and OpenMls errors
+    #[cfg_attr(test, crate::idempotent)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self, app_extensions), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
+    pub async fn update_app_extensions(
+        &mut self,
+        id: &ConversationId,
+        app_extensions: Vec<(u16, Vec<u8>)>,
+    ) -> CryptoResult<MlsCommitBundle> {
+        if app_extensions
+            .iter()
+            .any(|(extension_type, _)| !matches!(ExtensionType::from(*extension_type), ExtensionType::Unknown(_)))
+        {
+            return Err(CryptoError::ConsumerError);
+        }
+        self.update_group_context_extensions(id, |extensions| {
+            extensions.retain(|e| !matches!(e.extension_type(), ExtensionType::Unknown(_)));
+            extensions.extend(
+                app_extensions
+                    .into_iter()
+                    .map(|(extension_type, data)| Extension::Unknown(extension_type, UnknownExtension(data))),
+            );
+            Ok(())
+        })
+        .await
+    }
+
+    /// Lists the application specific extensions of a conversation, see [MlsCentral::update_app_extensions]
+    ///
+    /// # Errors
+    /// If the conversation can't be found
+    #[cfg_attr(test, crate::idempotent)]
+    pub async fn app_extensions(&mut self, id: &ConversationId) -> CryptoResult<Vec<(u16, Vec<u8>)>> {
+        Ok(self
+            .get_conversation(id)
+            .await?
+            .read()
+            .await
+            .group
+            .group_context_extensions()
+            .iter()
+            .filter_map(|e| match e {
+                Extension::Unknown(extension_type, UnknownExtension(data)) => Some((*extension_type, data.clone())),
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Commits new GroupContext extensions, derived from the current ones. Pending proposals are committed as well.
+    ///
+    /// As for any other commit, [MlsCentral::commit_accepted] has to be called once the Delivery Service
+    /// acknowledged it.
+    ///
+    /// # Errors
+    /// If a member does not support the new extensions. Other errors are KeyStore and OpenMls errors
+    async fn update_group_context_extensions(
+        &mut self,
+        id: &ConversationId,
+        update: impl FnOnce(&mut Vec<Extension>) -> CryptoResult<()>,
+    ) -> CryptoResult<MlsCommitBundle> {
+        let conversation = self.get_conversation(id).await?;
+        let mut conversation = conversation.write().await;
+        let mut extensions = conversation
+            .group
+            .group_context_extensions()
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        update(&mut extensions)?;
+        let extensions = Extensions::from_vec(extensions).map_err(MlsError::from)?;
+
+        let mut commit = conversation
+            .update_extensions(self.mls_client()?, &self.mls_backend, extensions)
+            .await?;
+        commit.group_info = self.finalize_group_info(commit.group_info, Some(&*conversation))?;
+        Ok(commit)
+    }
+}
+
+impl MlsConversation {
+    /// Commits the given GroupContext extensions, replacing the current ones
+    #[cfg_attr(test, crate::durable)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
+    async fn update_extensions(
+        &mut self,
+        client: &Client,
+        backend: &MlsCryptoProvider,
+        extensions: Extensions,
+    ) -> CryptoResult<MlsCommitBundle> {
+        let ratchet_tree = self.group.export_ratchet_tree();
+        let members = ratchet_tree.iter().filter_map(|node| match node {
+            Some(Node::LeafNode(leaf_node)) => Some(leaf_node),
+            _ => None,
+        });
+        validate_group_context_extensions(&extensions, members)?;
+
+        let signer = &self
+            .find_most_recent_credential_bundle(client)?
+            .ok_or(CryptoError::IdentityInitializationError)?
+            .signature_key;
+        let (commit, welcome, group_info) = self
+            .group
+            .update_extensions(backend, signer, extensions)
+            .in_current_span()
+            .await
+            .map_err(MlsError::from)?;
+
+        // We should always have ratchet tree extension turned on hence GroupInfo should always be present
+        let group_info = group_info.ok_or(CryptoError::ImplementationError)?;
+        let group_info = MlsGroupInfoBundle::try_new_full_plaintext(group_info)?;
+
+        self.persist_group_when_changed(backend, false).await?;
+
+        Ok(MlsCommitBundle {
+            welcome,
+            commit,
+            group_info,
+        })
+    }
+
+    /// Verifies the GroupContext extensions a commit carries, if any, against the members resulting from it:
+    /// the members of the current epoch it does not remove and the ones it adds
+    pub(crate) fn validate_committed_group_context_extensions(&self, commit: &StagedCommit) -> CryptoResult<()> {
+        let Some(extensions) = commit
+            .queued_proposals()
+            .find_map(|proposal| match proposal.proposal() {
+                Proposal::GroupContextExtensions(gce) => Some(gce.extensions()),
+                _ => None,
+            })
+        else {
+            return Ok(());
+        };
+
+        let removed = commit
+            .remove_proposals()
+            .map(|remove| remove.remove_proposal().removed().u32())
+            .collect::<HashSet<_>>();
+        let ratchet_tree = self.group.export_ratchet_tree();
+        // leaves are the even nodes of the tree
+        let members = ratchet_tree
+            .iter()
+            .step_by(2)
+            .enumerate()
+            .filter(|(leaf_index, _)| !removed.contains(&(*leaf_index as u32)))
+            .filter_map(|(_, node)| match node {
+                Some(Node::LeafNode(leaf_node)) => Some(leaf_node),
+                _ => None,
+            });
+        let added = commit
+            .add_proposals()
+            .map(|add| add.add_proposal().key_package().leaf_node());
+        validate_group_context_extensions(extensions, members.chain(added))
+    }
+}
+
+/// Verifies that every given leaf supports the capabilities required by the given GroupContext extensions as well as
+/// the extensions themselves
+fn validate_group_context_extensions<'a>(
+    extensions: &Extensions,
+    leaf_nodes: impl IntoIterator<Item = &'a LeafNode>,
+) -> CryptoResult<()> {
+    let (mut required_extensions, required_proposals, required_credentials) = extensions
+        .required_capabilities()
+        .map(|rc| {
+            (
+                rc.extension_types().iter().map(|t| u16::from(*t)).collect::<Vec<_>>(),
+                rc.proposal_types().iter().map(|t| u16::from(*t)).collect::<Vec<_>>(),
+                rc.credential_types().iter().map(|t| u16::from(*t)).collect::<Vec<_>>(),
+            )
+        })
+        .unwrap_or_default();
+    required_extensions.extend(extensions.iter().map(|e| u16::from(e.extension_type())));
+
+    let supports = |supported: Vec<u16>, required: &[u16], defaults: &RangeInclusive<u16>| {
+        required.iter().all(|t| defaults.contains(t) || supported.contains(t))
+    };
+
+    for leaf_node in leaf_nodes {
+        let capabilities = leaf_node.capabilities();
+        let extensions = capabilities.extensions().iter().map(|t| u16::from(*t)).collect();
+        let proposals = capabilities.proposals().iter().map(|t| u16::from(*t)).collect();
+        let credentials = capabilities.credentials().iter().map(|t| u16::from(*t)).collect();
+        let supported = supports(extensions, &required_extensions, &DEFAULT_EXTENSION_TYPES)
+            && supports(proposals, &required_proposals, &DEFAULT_PROPOSAL_TYPES)
+            // no credential type is implicitly supported
+            && supports(credentials, &required_credentials, &(1..=0));
+        if !supported {
+            return Err(CryptoError::UnsupportedGroupContextExtensions);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use openmls::prelude::ExtensionType;
+    use openmls_traits::{crypto::OpenMlsCrypto, OpenMlsCryptoProvider};
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::MlsConversationDecryptMessage, test_utils::*, CryptoError};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_update_external_senders(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    let mut cfg = case.cfg.clone();
+                    cfg.external_senders = vec![alice_central.mls_central.rand_external_sender(&case)];
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, cfg)
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    // the Delivery Service rotated its key
+                    let (_sk, new_key) = alice_central
+                        .mls_central
+                        .mls_backend
+                        .crypto()
+                        .signature_key_gen(case.signature_scheme())
+                        .unwrap();
+                    let commit = alice_central
+                        .mls_central
+                        .update_external_senders(&id, vec![new_key.clone()])
+                        .await
+                        .unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    let MlsConversationDecryptMessage { is_active, .. } = bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    assert!(is_active);
+
+                    for central in [&mut alice_central.mls_central, &mut bob_central.mls_central] {
+                        assert_eq!(central.get_external_sender(&id).await.unwrap(), new_key);
+                    }
+                    assert!(alice_central
+                        .mls_central
+                        .try_talk_to(&id, &mut bob_central.mls_central)
+                        .await
+                        .is_ok());
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_add_required_capabilities(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    // ExternalSenders and GroupContextExtensions are supported by default
+                    let commit = alice_central
+                        .mls_central
+                        .add_required_capabilities(&id, vec![0x0005], vec![0x0007])
+                        .await
+                        .unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+
+                    let conversation = bob_central.mls_central.get_conversation_unchecked(&id).await;
+                    let capabilities = conversation
+                        .group
+                        .group_context_extensions()
+                        .required_capabilities()
+                        .unwrap();
+                    assert_eq!(capabilities.extension_types(), &[ExtensionType::ExternalSenders]);
+                    assert_eq!(capabilities.proposal_types().len(), 1);
+
+                    // no member advertises this extension type
+                    assert!(matches!(
+                        alice_central
+                            .mls_central
+                            .add_required_capabilities(&id, vec![0xff00], vec![])
+                            .await
+                            .unwrap_err(),
+                        CryptoError::UnsupportedGroupContextExtensions
+                    ));
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_not_set_app_extensions_unsupported_by_members(case: TestCase) {
+        run_test_with_client_ids(case.clone(), ["alice"], move |[mut alice_central]| {
+            Box::pin(async move {
+                let id = conversation_id();
+                alice_central
+                    .mls_central
+                    .new_conversation(&id, case.credential_type, case.cfg.clone())
+                    .await
+                    .unwrap();
+                let epoch = alice_central.mls_central.conversation_epoch(&id).await.unwrap();
+
+                // MLS extension types cannot be set this way
+                assert!(matches!(
+                    alice_central
+                        .mls_central
+                        .update_app_extensions(&id, vec![(0x0005, vec![])])
+                        .await
+                        .unwrap_err(),
+                    CryptoError::ConsumerError
+                ));
+                assert!(matches!(
+                    alice_central
+                        .mls_central
+                        .update_app_extensions(&id, vec![(0xff00, b"app".to_vec())])
+                        .await
+                        .unwrap_err(),
+                    CryptoError::UnsupportedGroupContextExtensions
+                ));
+                assert!(alice_central.mls_central.app_extensions(&id).await.unwrap().is_empty());
+                assert_eq!(alice_central.mls_central.conversation_epoch(&id).await.unwrap(), epoch);
+
+                // removing them all is always fine
+                alice_central
+                    .mls_central
+                    .update_app_extensions(&id, vec![])
+                    .await
+                    .unwrap();
+                alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                assert_eq!(
+                    alice_central.mls_central.conversation_epoch(&id).await.unwrap(),
+                    epoch + 1
+                );
+            })
+        })
+        .await
+    }
+}
@@ -1,7 +1,19 @@
-use openmls::prelude::{group_info::GroupInfo, MlsMessageOut};
+use base64::Engine as _;
+use mls_crypto_provider::MlsCryptoProvider;
+use openmls::prelude::{
+    group_info::{GroupInfo, VerifiableGroupInfo},
+    MlsMessageOut,
+};
+use openmls_traits::{crypto::OpenMlsCrypto, random::OpenMlsRand, types::AeadType, OpenMlsCryptoProvider};
 use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
 
-use crate::{CryptoResult, MlsError};
+use super::ratchet_tree_delta;
+pub use super::ratchet_tree_delta::RATCHET_TREE_DELTA_VERSION;
+use crate::{
+    prelude::{ConversationId, MlsCentral, MlsConversation},
+    CryptoError, CryptoResult, MlsError,
+};
 
 /// A [GroupInfo] with metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +39,409 @@ impl MlsGroupInfoBundle {
             payload: GroupInfoPayload::Plaintext(payload),
         })
     }
+
+    /// Encrypts the [GroupInfo] in a compact JWE (`alg: dir`, `enc: A256GCM`) with the given key.
+    /// Does nothing when the payload is already encrypted.
+    pub(crate) fn seal(self, key: &MlsGroupInfoEncryptionKey, backend: &MlsCryptoProvider) -> CryptoResult<Self> {
+        let GroupInfoPayload::Plaintext(plaintext) = self.payload else {
+            return Ok(self);
+        };
+        let jwe = jwe::seal(key, &plaintext, backend)?;
+        Ok(Self {
+            encryption_type: MlsGroupInfoEncryptionType::JweEncrypted,
+            ratchet_tree_type: self.ratchet_tree_type,
+            payload: GroupInfoPayload::Encrypted(jwe),
+        })
+    }
+
+    /// Replaces the ratchet tree of a complete and unencrypted [GroupInfo] by the changes since
+    /// `previous_ratchet_tree`. See [ratchet_tree_delta]
+    pub(crate) fn into_delta(self, previous_ratchet_tree: &[u8]) -> CryptoResult<Self> {
+        let (MlsRatchetTreeType::Full, GroupInfoPayload::Plaintext(group_info)) =
+            (self.ratchet_tree_type, &self.payload)
+        else {
+            return Ok(self);
+        };
+        let delta = ratchet_tree_delta::compute(group_info, previous_ratchet_tree)?;
+        Ok(Self {
+            encryption_type: self.encryption_type,
+            ratchet_tree_type: MlsRatchetTreeType::Delta,
+            payload: GroupInfoPayload::Plaintext(delta),
+        })
+    }
+
+    /// Whether the [GroupInfo] carries its ratchet tree, in the ratchet tree extension
+    fn has_ratchet_tree(&self) -> CryptoResult<bool> {
+        match &self.payload {
+            GroupInfoPayload::Plaintext(group_info) => ratchet_tree_delta::has_ratchet_tree(group_info),
+            GroupInfoPayload::Encrypted(_) => Ok(self.ratchet_tree_type != MlsRatchetTreeType::ByRef),
+        }
+    }
+
+    /// Marks a [GroupInfo] without ratchet tree extension as referencing its ratchet tree. The
+    /// reference is the tree hash in its GroupContext, which is covered by the signature.
+    fn into_by_ref(self) -> Self {
+        Self {
+            ratchet_tree_type: MlsRatchetTreeType::ByRef,
+            ..self
+        }
+    }
+
+    /// Seals the [GroupInfo] according to the [MlsGroupInfoEncryption] configured on [crate::prelude::MlsCentral]
+    pub(crate) fn seal_with(
+        self,
+        encryption: Option<&MlsGroupInfoEncryption>,
+        conversation: Option<&MlsConversation>,
+        backend: &MlsCryptoProvider,
+    ) -> CryptoResult<Self> {
+        match (encryption, conversation) {
+            (None, _) => Ok(self),
+            (Some(MlsGroupInfoEncryption::Key(key)), _) => self.seal(key, backend),
+            (Some(MlsGroupInfoEncryption::Derived), Some(conversation)) => {
+                let key = conversation.group_info_encryption_key(backend)?;
+                self.seal(&key, backend)
+            }
+            // e.g. an external commit: we do not know any secret of the epoch the GroupInfo comes from
+            (Some(MlsGroupInfoEncryption::Derived), None) => Err(CryptoError::MissingGroupInfoEncryptionKey),
+        }
+    }
+}
+
+impl MlsConversation {
+    const GROUP_INFO_ENCRYPTION_LABEL: &'static str = "wire group info encryption";
+
+    /// Content of the TLS serialized ratchet tree of the current epoch
+    pub(crate) fn ratchet_tree(&self) -> CryptoResult<Vec<u8>> {
+        use tls_codec::Serialize as _;
+
+        let ratchet_tree = self
+            .group
+            .export_ratchet_tree()
+            .tls_serialize_detached()
+            .map_err(MlsError::from)?;
+        Ok(ratchet_tree_delta::ratchet_tree_content(&ratchet_tree)?.to_vec())
+    }
+
+    /// Derives the key encrypting this group's [GroupInfo] in the current epoch.
+    /// See [crate::prelude::MlsCentral::export_group_info_encryption_key]
+    pub(crate) fn group_info_encryption_key(
+        &self,
+        backend: &MlsCryptoProvider,
+    ) -> CryptoResult<MlsGroupInfoEncryptionKey> {
+        let key = self
+            .group
+            .export_secret(
+                backend,
+                Self::GROUP_INFO_ENCRYPTION_LABEL,
+                &[],
+                MlsGroupInfoEncryptionKey::LENGTH,
+            )
+            .map_err(MlsError::from)?;
+        key.try_into()
+    }
+}
+
+impl MlsCentral {
+    /// Configures how the [GroupInfo]s returned by this client (in commits, external commits and
+    /// credential rotations) are encrypted. `None` (the default) leaves them in plaintext.
+    pub fn set_group_info_encryption(&mut self, encryption: Option<MlsGroupInfoEncryption>) {
+        self.group_info_encryption = encryption;
+    }
+
+    /// Returns the key encrypting the [GroupInfo]s of commits created in the current epoch of this
+    /// conversation when [MlsGroupInfoEncryption::Derived] is used. Share it with whoever needs to
+    /// read them e.g. a client about to join with an external commit.
+    ///
+    /// # Errors
+    /// OpenMls secret generation error or conversation not found
+    #[cfg_attr(test, crate::idempotent)]
+    pub async fn export_group_info_encryption_key(&mut self, id: &ConversationId) -> CryptoResult<Vec<u8>> {
+        let key = self
+            .get_conversation(id)
+            .await?
+            .read()
+            .await
+            .group_info_encryption_key(&self.mls_backend)?;
+        Ok(key.as_ref().to_vec())
+    }
+
+    /// Deserializes a [GroupInfo] wrapped in a MLS message, decrypting it first when it is a JWE.
+    ///
+    /// # Arguments
+    /// * `group_info` - either a TLS serialized [GroupInfo] or a JWE as found in [GroupInfoPayload::Encrypted]
+    /// * `key` - key to decrypt the JWE with. When `None`, the key from [MlsGroupInfoEncryption::Key] is used
+    ///
+    /// # Errors
+    /// When the JWE cannot be decrypted or when the [GroupInfo] cannot be deserialized
+    #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
+    pub fn open_group_info(
+        &self,
+        group_info: &[u8],
+        key: Option<MlsGroupInfoEncryptionKey>,
+    ) -> CryptoResult<VerifiableGroupInfo> {
+        use openmls::prelude::{MlsMessageIn, MlsMessageInBody};
+        use tls_codec::Deserialize as _;
+
+        let group_info = self.decrypt_group_info_payload(group_info, key)?;
+
+        // the GroupInfo can either be wrapped in a MLS message (like in a [GroupInfoPayload]) or not
+        match MlsMessageIn::tls_deserialize(&mut &group_info[..]).map(MlsMessageIn::extract) {
+            Ok(MlsMessageInBody::GroupInfo(gi)) => Ok(gi),
+            _ => Ok(VerifiableGroupInfo::tls_deserialize(&mut &group_info[..]).map_err(MlsError::from)?),
+        }
+    }
+
+    /// Deserializes a [MlsGroupInfoIn], decrypting it first when it is a JWE. See [MlsCentral::open_group_info]
+    pub(crate) fn resolve_group_info(&self, group_info: MlsGroupInfoIn) -> CryptoResult<VerifiableGroupInfo> {
+        match group_info {
+            MlsGroupInfoIn::Verifiable(group_info) => Ok(group_info),
+            MlsGroupInfoIn::Serialized { payload, key } => self.open_group_info(&payload, key),
+        }
+    }
+
+    /// Configures which representation of the ratchet tree the [GroupInfo]s returned in commits
+    /// carry. [MlsRatchetTreeType::Full] is the default.
+    ///
+    /// With [MlsRatchetTreeType::Delta], only the changes since the previous epoch are sent and the
+    /// complete [GroupInfo] has to be rebuilt with [MlsCentral::rebuild_group_info]. External
+    /// commits always carry the full ratchet tree since the joiner does not have any previous one.
+    ///
+    /// With [MlsRatchetTreeType::ByRef], the conversations created or joined afterwards leave the
+    /// ratchet tree out of their [GroupInfo]s, including the ones in Welcome messages. It has to be
+    /// served separately (see [MlsCentral::export_ratchet_tree]) and supplied when joining (see
+    /// [MlsCentral::join_by_external_commit_with_ratchet_tree] and
+    /// [MlsCentral::process_welcome_message_with_ratchet_tree]), where it is checked against the
+    /// tree hash of the GroupInfo. Conversations created before keep carrying their ratchet tree.
+    pub fn set_group_info_ratchet_tree_type(&mut self, ratchet_tree_type: MlsRatchetTreeType) {
+        self.group_info_ratchet_tree_type = ratchet_tree_type;
+    }
+
+    /// Returns the TLS serialized ratchet tree of the current epoch of a conversation. Once a commit
+    /// is merged, this is the ratchet tree its [MlsRatchetTreeType::ByRef] [GroupInfo] refers to.
+    ///
+    /// # Errors
+    /// If the conversation can't be found
+    #[cfg_attr(test, crate::idempotent)]
+    pub async fn export_ratchet_tree(&mut self, id: &ConversationId) -> CryptoResult<Vec<u8>> {
+        use tls_codec::Serialize as _;
+
+        Ok(self
+            .get_conversation(id)
+            .await?
+            .read()
+            .await
+            .group
+            .export_ratchet_tree()
+            .tls_serialize_detached()
+            .map_err(MlsError::from)?)
+    }
+
+    /// Rebuilds the complete [GroupInfo] out of a [MlsRatchetTreeType::Delta] one
+    ///
+    /// # Arguments
+    /// * `delta` - the payload of a [MlsGroupInfoBundle] with [MlsRatchetTreeType::Delta], encrypted or not
+    /// * `previous_group_info` - the complete [GroupInfo] of the previous epoch, wrapped in a MLS message
+    /// * `key` - key to decrypt the JWE with. When `None`, the key from [MlsGroupInfoEncryption::Key] is used
+    ///
+    /// # Return type
+    /// The complete [GroupInfo] wrapped in a MLS message, exactly as it has been signed. It can in
+    /// turn be used to rebuild the [GroupInfo] of the next epoch.
+    ///
+    /// # Errors
+    /// When the delta cannot be decrypted or parsed, when it has been encoded with another version
+    /// than [RATCHET_TREE_DELTA_VERSION] or when `previous_group_info` is not the [GroupInfo] of
+    /// the previous epoch
+    #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
+    pub fn rebuild_group_info(
+        &self,
+        delta: &[u8],
+        previous_group_info: &[u8],
+        key: Option<MlsGroupInfoEncryptionKey>,
+    ) -> CryptoResult<Vec<u8>> {
+        let delta = self.decrypt_group_info_payload(delta, key)?;
+        ratchet_tree_delta::apply(&delta, previous_group_info)
+    }
+
+    /// Shapes a freshly created [GroupInfo] according to the representation and encryption
+    /// configured for this client
+    pub(crate) fn finalize_group_info(
+        &self,
+        group_info: MlsGroupInfoBundle,
+        conversation: Option<&MlsConversation>,
+    ) -> CryptoResult<MlsGroupInfoBundle> {
+        // conversations created with [MlsRatchetTreeType::ByRef] never carry the ratchet tree, whatever is
+        // configured now
+        let group_info = match (self.group_info_ratchet_tree_type, conversation) {
+            _ if !group_info.has_ratchet_tree()? => group_info.into_by_ref(),
+            (MlsRatchetTreeType::Delta, Some(conversation)) => group_info.into_delta(&conversation.ratchet_tree()?)?,
+            _ => group_info,
+        };
+        group_info.seal_with(self.group_info_encryption.as_ref(), conversation, &self.mls_backend)
+    }
+
+    fn decrypt_group_info_payload<'a>(
+        &self,
+        payload: &'a [u8],
+        key: Option<MlsGroupInfoEncryptionKey>,
+    ) -> CryptoResult<std::borrow::Cow<'a, [u8]>> {
+        if !jwe::is_jwe(payload) {
+            return Ok(payload.into());
+        }
+        let key = match (key, &self.group_info_encryption) {
+            (Some(key), _) => key,
+            (None, Some(MlsGroupInfoEncryption::Key(key))) => key.clone(),
+            _ => return Err(CryptoError::MissingGroupInfoEncryptionKey),
+        };
+        Ok(jwe::open(&key, payload, &self.mls_backend)?.into())
+    }
+}
+
+/// How [GroupInfo]s produced by this client are encrypted
+#[derive(Debug, Clone)]
+pub enum MlsGroupInfoEncryption {
+    /// Encrypt with a key derived from the epoch in which the commit is created. Members can export
+    /// it with [crate::prelude::MlsCentral::export_group_info_encryption_key]. Since a client joining
+    /// with an external commit does not know any secret of the previous epoch, it cannot produce
+    /// [GroupInfo]s in this mode.
+    Derived,
+    /// Encrypt with a key supplied by the application, for every conversation
+    Key(MlsGroupInfoEncryptionKey),
+}
+
+/// A [GroupInfo] fetched from the Delivery Service, e.g. to join a group with
+#[derive(Debug, Clone)]
+pub enum MlsGroupInfoIn {
+    /// An already deserialized [GroupInfo]
+    Verifiable(VerifiableGroupInfo),
+    /// A [GroupInfo] as found in the payload of a [MlsGroupInfoBundle]
+    Serialized {
+        /// Either a TLS serialized [GroupInfo] or a JWE as found in [GroupInfoPayload::Encrypted]
+        payload: Vec<u8>,
+        /// Key to decrypt the JWE with. When `None`, the key from [MlsGroupInfoEncryption::Key] is used
+        key: Option<MlsGroupInfoEncryptionKey>,
+    },
+}
+
+impl From<VerifiableGroupInfo> for MlsGroupInfoIn {
+    fn from(group_info: VerifiableGroupInfo) -> Self {
+        Self::Verifiable(group_info)
+    }
+}
+
+/// AES-256-GCM key used to encrypt a [GroupInfo] into a JWE
+#[derive(Clone, Zeroize)]
+#[zeroize(drop)]
+pub struct MlsGroupInfoEncryptionKey([u8; Self::LENGTH]);
+
+impl MlsGroupInfoEncryptionKey {
+    /// Length in bytes of the key
+    pub const LENGTH: usize = 32;
+}
+
+impl std::fmt::Debug for MlsGroupInfoEncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("MlsGroupInfoEncryptionKey").field(&"[REDACTED]").finish()
+    }
+}
+
+impl TryFrom<Vec<u8>> for MlsGroupInfoEncryptionKey {
+    type Error = CryptoError;
+
+    fn try_from(mut value: Vec<u8>) -> CryptoResult<Self> {
+        let key = <[u8; Self::LENGTH]>::try_from(value.as_slice())
+            .map_err(|_| CryptoError::InvalidByteArrayError(Self::LENGTH));
+        value.zeroize();
+        Ok(Self(key?))
+    }
+}
+
+impl AsRef<[u8]> for MlsGroupInfoEncryptionKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Compact JWE serialization of a [GroupInfo] with direct encryption
+/// (see [RFC 7516](https://www.rfc-editor.org/rfc/rfc7516))
+pub(crate) mod jwe {
+    use super::*;
+
+    const PROTECTED_HEADER: &str = r#"{"alg":"dir","enc":"A256GCM","cty":"message/mls"}"#;
+    const IV_LENGTH: usize = 12;
+    const TAG_LENGTH: usize = 16;
+
+    #[derive(serde::Deserialize)]
+    struct ProtectedHeader {
+        alg: String,
+        enc: String,
+    }
+
+    /// A compact JWE always starts with a base64url encoded JSON object i.e. `eyJ` whereas a TLS
+    /// serialized MLS message starts with its protocol version
+    pub(crate) fn is_jwe(payload: &[u8]) -> bool {
+        payload.starts_with(b"eyJ")
+    }
+
+    pub(crate) fn seal(
+        key: &MlsGroupInfoEncryptionKey,
+        plaintext: &[u8],
+        backend: &MlsCryptoProvider,
+    ) -> CryptoResult<Vec<u8>> {
+        let b64 = base64::prelude::BASE64_URL_SAFE_NO_PAD;
+        let protected = b64.encode(PROTECTED_HEADER);
+        let iv = backend.rand().random_vec(IV_LENGTH)?;
+
+        let mut ciphertext = backend
+            .crypto()
+            .aead_encrypt(AeadType::Aes256Gcm, key.as_ref(), plaintext, &iv, protected.as_bytes())
+            .map_err(MlsError::from)?;
+        let tag = ciphertext.split_off(ciphertext.len() - TAG_LENGTH);
+
+        let jwe = format!(
+            "{protected}..{}.{}.{}",
+            b64.encode(iv),
+            b64.encode(ciphertext),
+            b64.encode(tag)
+        );
+        Ok(jwe.into_bytes())
+    }
+
+    pub(crate) fn open(
+        key: &MlsGroupInfoEncryptionKey,
+        jwe: &[u8],
+        backend: &MlsCryptoProvider,
+    ) -> CryptoResult<Vec<u8>> {
+        let b64 = base64::prelude::BASE64_URL_SAFE_NO_PAD;
+        let jwe = std::str::from_utf8(jwe).map_err(|_| CryptoError::GroupInfoDecryptionError)?;
+        let [protected, encrypted_key, iv, ciphertext, tag] = jwe.split('.').collect::<Vec<_>>()[..] else {
+            return Err(CryptoError::GroupInfoDecryptionError);
+        };
+
+        let header = b64
+            .decode(protected)
+            .map_err(|_| CryptoError::GroupInfoDecryptionError)?;
+        let header =
+            serde_json::from_slice::<ProtectedHeader>(&header).map_err(|_| CryptoError::GroupInfoDecryptionError)?;
+        if header.alg != "dir" || header.enc != "A256GCM" || !encrypted_key.is_empty() {
+            return Err(CryptoError::GroupInfoDecryptionError);
+        }
+
+        let decode = |part: &str| b64.decode(part).map_err(|_| CryptoError::GroupInfoDecryptionError);
+        let iv = decode(iv)?;
+        let mut ciphertext = decode(ciphertext)?;
+        ciphertext.extend(decode(tag)?);
+
+        backend
+            .crypto()
+            .aead_decrypt(
+                AeadType::Aes256Gcm,
+                key.as_ref(),
+                &ciphertext,
+                &iv,
+                protected.as_bytes(),
+            )
+            .map_err(|_| CryptoError::GroupInfoDecryptionError)
+    }
 }
 
 #[cfg(test)]
@@ -44,6 +459,7 @@ impl MlsGroupInfoBundle {
             GroupInfoPayload::Plaintext(gi) => {
                 openmls::prelude::MlsMessageIn::tls_deserialize(&mut gi.as_slice()).unwrap()
             }
+            GroupInfoPayload::Encrypted(_) => panic!("This payload is encrypted, open it first"),
         }
     }
 }
@@ -86,10 +502,12 @@ pub enum MlsGroupInfoEncryptionType {
 pub enum MlsRatchetTreeType {
     /// Plain old and complete [GroupInfo]
     Full = 1,
-    /// Contains [GroupInfo] changes since previous epoch (not yet implemented)
-    /// (see [draft](https://github.com/rohan-wire/ietf-drafts/blob/main/mahy-mls-ratchet-tree-delta/draft-mahy-mls-ratchet-tree-delta.md))
+    /// Contains [GroupInfo] changes since previous epoch, in an encoding versioned by
+    /// [RATCHET_TREE_DELTA_VERSION] which deviates from the
+    /// [draft](https://github.com/rohan-wire/ietf-drafts/blob/main/mahy-mls-ratchet-tree-delta/draft-mahy-mls-ratchet-tree-delta.md)
     Delta = 2,
-    /// TODO: to define
+    /// [GroupInfo] without ratchet tree, which is served separately and checked against the tree
+    /// hash of the GroupContext
     ByRef = 3,
 }
 
@@ -98,8 +516,8 @@ pub enum MlsRatchetTreeType {
 pub enum GroupInfoPayload {
     /// Unencrypted [GroupInfo]
     Plaintext(Vec<u8>),
-    // TODO: expose when fully implemented
-    // Encrypted(Vec<u8>),
+    /// [GroupInfo] encrypted in a compact JWE
+    Encrypted(Vec<u8>),
 }
 
 impl GroupInfoPayload {
@@ -107,6 +525,631 @@ impl GroupInfoPayload {
     pub fn bytes(self) -> Vec<u8> {
         match self {
             GroupInfoPayload::Plaintext(gi) => gi,
+            GroupInfoPayload::Encrypted(gi) => gi,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::{
+        mls::conversation::ratchet_tree_delta,
+        prelude::{MlsCiphersuite, MlsCommitBundle, MlsConversationInitBundle},
+        test_utils::*,
+    };
+
+    use super::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    pub mod encryption {
+        use super::*;
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn should_encrypt_group_info_with_supplied_key(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob"],
+                move |[mut alice_central, mut bob_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        alice_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+
+                        let key = alice_central
+                            .mls_central
+                            .random_bytes(MlsGroupInfoEncryptionKey::LENGTH)
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .set_group_info_encryption(Some(MlsGroupInfoEncryption::Key(
+                                key.clone().try_into().unwrap(),
+                            )));
+
+                        let MlsCommitBundle { group_info, .. } =
+                            alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                        alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                        assert_eq!(group_info.encryption_type, MlsGroupInfoEncryptionType::JweEncrypted);
+                        assert!(matches!(group_info.payload, GroupInfoPayload::Encrypted(_)));
+                        let jwe = group_info.payload.bytes();
+
+                        // Bob cannot read it without the key
+                        let missing_key = bob_central.mls_central.open_group_info(&jwe, None);
+                        assert!(matches!(
+                            missing_key.unwrap_err(),
+                            CryptoError::MissingGroupInfoEncryptionKey
+                        ));
+
+                        // nor with a wrong one
+                        let wrong_key = bob_central
+                            .mls_central
+                            .random_bytes(MlsGroupInfoEncryptionKey::LENGTH)
+                            .unwrap();
+                        let wrong_key = bob_central
+                            .mls_central
+                            .open_group_info(&jwe, Some(wrong_key.try_into().unwrap()));
+                        assert!(matches!(wrong_key.unwrap_err(), CryptoError::GroupInfoDecryptionError));
+
+                        let group_info = bob_central
+                            .mls_central
+                            .open_group_info(&jwe, Some(key.try_into().unwrap()))
+                            .unwrap();
+                        bob_central
+                            .mls_central
+                            .try_join_from_group_info(&case, &id, group_info, vec![&mut alice_central.mls_central])
+                            .await
+                            .unwrap();
+                    })
+                },
+            )
+            .await
+        }
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn should_encrypt_group_info_with_derived_key(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob"],
+                move |[mut alice_central, mut bob_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        alice_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .set_group_info_encryption(Some(MlsGroupInfoEncryption::Derived));
+
+                        let key = alice_central
+                            .mls_central
+                            .export_group_info_encryption_key(&id)
+                            .await
+                            .unwrap();
+                        let MlsCommitBundle { group_info, .. } =
+                            alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                        alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                        assert_eq!(group_info.encryption_type, MlsGroupInfoEncryptionType::JweEncrypted);
+
+                        // the key changes with the epoch
+                        let new_key = alice_central
+                            .mls_central
+                            .export_group_info_encryption_key(&id)
+                            .await
+                            .unwrap();
+                        assert_ne!(key, new_key);
+
+                        let group_info = bob_central
+                            .mls_central
+                            .open_group_info(&group_info.payload.bytes(), Some(key.try_into().unwrap()))
+                            .unwrap();
+                        bob_central
+                            .mls_central
+                            .try_join_from_group_info(&case, &id, group_info, vec![&mut alice_central.mls_central])
+                            .await
+                            .unwrap();
+                    })
+                },
+            )
+            .await
+        }
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn should_verify_and_join_from_encrypted_group_info(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob"],
+                move |[mut alice_central, mut bob_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        alice_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        let key = alice_central
+                            .mls_central
+                            .random_bytes(MlsGroupInfoEncryptionKey::LENGTH)
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .set_group_info_encryption(Some(MlsGroupInfoEncryption::Key(
+                                key.clone().try_into().unwrap(),
+                            )));
+                        let MlsCommitBundle { group_info, .. } =
+                            alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                        alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                        let jwe = group_info.payload.bytes();
+                        let encrypted = |key: &[u8]| MlsGroupInfoIn::Serialized {
+                            payload: jwe.clone(),
+                            key: Some(key.to_vec().try_into().unwrap()),
+                        };
+
+                        // a protected header which is not JSON
+                        let malformed = MlsGroupInfoIn::Serialized {
+                            payload: b"eyJhbGciOg....".to_vec(),
+                            key: Some(key.clone().try_into().unwrap()),
+                        };
+                        let malformed = bob_central.mls_central.e2ei_verify_group_state(malformed).await;
+                        assert!(matches!(malformed.unwrap_err(), CryptoError::GroupInfoDecryptionError));
+
+                        assert!(bob_central
+                            .mls_central
+                            .e2ei_verify_group_state(encrypted(&key))
+                            .await
+                            .is_ok());
+                        let MlsConversationInitBundle { commit, .. } = bob_central
+                            .mls_central
+                            .join_by_external_commit(encrypted(&key), case.custom_cfg(), case.credential_type)
+                            .await
+                            .unwrap();
+                        bob_central
+                            .mls_central
+                            .merge_pending_group_from_external_commit(&id)
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .decrypt_message(&id, commit.to_bytes().unwrap())
+                            .await
+                            .unwrap();
+                        assert!(bob_central
+                            .mls_central
+                            .try_talk_to(&id, &mut alice_central.mls_central)
+                            .await
+                            .is_ok());
+                    })
+                },
+            )
+            .await
+        }
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn should_encrypt_group_info_of_external_commit(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob"],
+                move |[mut alice_central, mut bob_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        alice_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        let group_info = alice_central.mls_central.get_group_info(&id).await;
+
+                        // Bob cannot derive a key from an epoch he is not part of
+                        bob_central
+                            .mls_central
+                            .set_group_info_encryption(Some(MlsGroupInfoEncryption::Derived));
+                        let derived = bob_central
+                            .mls_central
+                            .join_by_external_commit(group_info.clone(), case.custom_cfg(), case.credential_type)
+                            .await;
+                        assert!(matches!(
+                            derived.unwrap_err(),
+                            CryptoError::MissingGroupInfoEncryptionKey
+                        ));
+
+                        let key = bob_central
+                            .mls_central
+                            .random_bytes(MlsGroupInfoEncryptionKey::LENGTH)
+                            .unwrap();
+                        bob_central
+                            .mls_central
+                            .set_group_info_encryption(Some(MlsGroupInfoEncryption::Key(
+                                key.clone().try_into().unwrap(),
+                            )));
+                        let MlsConversationInitBundle { group_info, .. } = bob_central
+                            .mls_central
+                            .join_by_external_commit(group_info, case.custom_cfg(), case.credential_type)
+                            .await
+                            .unwrap();
+                        assert_eq!(group_info.encryption_type, MlsGroupInfoEncryptionType::JweEncrypted);
+
+                        let group_info = alice_central
+                            .mls_central
+                            .open_group_info(&group_info.payload.bytes(), Some(key.try_into().unwrap()))
+                            .unwrap();
+                        assert_eq!(MlsCiphersuite::from(group_info.ciphersuite()), case.ciphersuite());
+                    })
+                },
+            )
+            .await
+        }
+    }
+
+    pub mod delta {
+        use super::*;
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn rebuilt_group_info_should_match_full_one(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob", "charlie"],
+                move |[mut alice_central, mut bob_central, mut charlie_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        alice_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .invite_all(&case, &id, [&mut bob_central.mls_central])
+                            .await
+                            .unwrap();
+
+                        let MlsCommitBundle { commit, group_info, .. } =
+                            alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                        alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                        bob_central
+                            .mls_central
+                            .decrypt_message(&id, commit.to_bytes().unwrap())
+                            .await
+                            .unwrap();
+                        assert_eq!(group_info.ratchet_tree_type, MlsRatchetTreeType::Full);
+                        let mut previous_group_info = group_info.payload.bytes();
+
+                        alice_central
+                            .mls_central
+                            .set_group_info_ratchet_tree_type(MlsRatchetTreeType::Delta);
+                        for _ in 0..2 {
+                            let MlsCommitBundle { commit, group_info, .. } =
+                                alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                            alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                            bob_central
+                                .mls_central
+                                .decrypt_message(&id, commit.to_bytes().unwrap())
+                                .await
+                                .unwrap();
+                            assert_eq!(group_info.ratchet_tree_type, MlsRatchetTreeType::Delta);
+                            let delta = group_info.payload.bytes();
+                            assert!(delta.len() < previous_group_info.len());
+                            assert_eq!(delta[0], RATCHET_TREE_DELTA_VERSION);
+
+                            let mut next_version = delta.clone();
+                            next_version[0] += 1;
+                            let unsupported = charlie_central.mls_central.rebuild_group_info(
+                                &next_version,
+                                &previous_group_info,
+                                None,
+                            );
+                            assert!(matches!(
+                                unsupported.unwrap_err(),
+                                CryptoError::UnsupportedRatchetTreeDeltaVersion(v) if v == RATCHET_TREE_DELTA_VERSION + 1
+                            ));
+
+                            // a delta cannot claim a tree larger than the nodes it carries
+                            use tls_codec::DeserializeBytes as _;
+                            let (_, rest) = tls_codec::VLBytes::tls_deserialize_bytes(&delta[1..]).unwrap();
+                            let tree_size_offset = delta.len() - rest.len() + 4;
+                            let mut oversized = delta.clone();
+                            oversized[tree_size_offset..tree_size_offset + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+                            let oversized =
+                                charlie_central
+                                    .mls_central
+                                    .rebuild_group_info(&oversized, &previous_group_info, None);
+                            assert!(matches!(oversized.unwrap_err(), CryptoError::MalformedGroupInfo));
+
+                            let rebuilt = charlie_central
+                                .mls_central
+                                .rebuild_group_info(&delta, &previous_group_info, None)
+                                .unwrap();
+                            let ratchet_tree = ratchet_tree_delta::extract_ratchet_tree(&rebuilt).unwrap();
+                            let full_ratchet_tree = alice_central
+                                .mls_central
+                                .get_conversation_unchecked(&id)
+                                .await
+                                .ratchet_tree()
+                                .unwrap();
+                            assert_eq!(ratchet_tree, full_ratchet_tree);
+                            previous_group_info = rebuilt;
+                        }
+
+                        // the rebuilt GroupInfo is the one which has been signed
+                        let group_info = charlie_central
+                            .mls_central
+                            .open_group_info(&previous_group_info, None)
+                            .unwrap();
+                        charlie_central
+                            .mls_central
+                            .try_join_from_group_info(&case, &id, group_info, vec![&mut alice_central.mls_central])
+                            .await
+                            .unwrap();
+                    })
+                },
+            )
+            .await
+        }
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn should_only_rebuild_encrypted_delta_from_previous_epoch(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob"],
+                move |[mut alice_central, bob_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        alice_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+
+                        let MlsCommitBundle { group_info, .. } =
+                            alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                        alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                        let previous_group_info = group_info.payload.bytes();
+
+                        let key = alice_central
+                            .mls_central
+                            .random_bytes(MlsGroupInfoEncryptionKey::LENGTH)
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .set_group_info_encryption(Some(MlsGroupInfoEncryption::Key(
+                                key.clone().try_into().unwrap(),
+                            )));
+                        alice_central
+                            .mls_central
+                            .set_group_info_ratchet_tree_type(MlsRatchetTreeType::Delta);
+
+                        let mut deltas = vec![];
+                        for _ in 0..2 {
+                            let MlsCommitBundle { group_info, .. } =
+                                alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                            alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                            assert_eq!(group_info.encryption_type, MlsGroupInfoEncryptionType::JweEncrypted);
+                            assert_eq!(group_info.ratchet_tree_type, MlsRatchetTreeType::Delta);
+                            deltas.push(group_info.payload.bytes());
+                        }
+
+                        let missing_key =
+                            bob_central
+                                .mls_central
+                                .rebuild_group_info(&deltas[0], &previous_group_info, None);
+                        assert!(matches!(
+                            missing_key.unwrap_err(),
+                            CryptoError::MissingGroupInfoEncryptionKey
+                        ));
+
+                        let wrong_epoch = bob_central.mls_central.rebuild_group_info(
+                            &deltas[1],
+                            &previous_group_info,
+                            Some(key.clone().try_into().unwrap()),
+                        );
+                        assert!(matches!(
+                            wrong_epoch.unwrap_err(),
+                            CryptoError::RatchetTreeDeltaEpochMismatch
+                        ));
+
+                        let rebuilt = bob_central
+                            .mls_central
+                            .rebuild_group_info(&deltas[0], &previous_group_info, Some(key.try_into().unwrap()))
+                            .unwrap();
+                        assert!(bob_central.mls_central.open_group_info(&rebuilt, None).is_ok());
+                    })
+                },
+            )
+            .await
+        }
+    }
+
+    pub mod by_ref {
+        use super::*;
+        use crate::prelude::MlsConversationCreationMessage;
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn should_join_with_referenced_ratchet_tree(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob", "charlie"],
+                move |[mut alice_central, mut bob_central, mut charlie_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        alice_central
+                            .mls_central
+                            .set_group_info_ratchet_tree_type(MlsRatchetTreeType::ByRef);
+                        alice_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .invite_all(&case, &id, [&mut bob_central.mls_central])
+                            .await
+                            .unwrap();
+
+                        let MlsCommitBundle { commit, group_info, .. } =
+                            alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                        alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                        bob_central
+                            .mls_central
+                            .decrypt_message(&id, commit.to_bytes().unwrap())
+                            .await
+                            .unwrap();
+                        assert_eq!(group_info.ratchet_tree_type, MlsRatchetTreeType::ByRef);
+                        let group_info = group_info.payload.bytes();
+                        assert!(!ratchet_tree_delta::has_ratchet_tree(&group_info).unwrap());
+
+                        let ratchet_tree = alice_central.mls_central.export_ratchet_tree(&id).await.unwrap();
+
+                        let MlsConversationInitBundle {
+                            conversation_id,
+                            commit,
+                            group_info,
+                            ..
+                        } = charlie_central
+                            .mls_central
+                            .join_by_external_commit_with_ratchet_tree(
+                                &group_info,
+                                &ratchet_tree,
+                                None,
+                                case.custom_cfg(),
+                                case.credential_type,
+                            )
+                            .await
+                            .unwrap();
+                        assert_eq!(conversation_id, id);
+                        // Charlie did not configure ByRef
+                        assert_eq!(group_info.ratchet_tree_type, MlsRatchetTreeType::Full);
+                        charlie_central
+                            .mls_central
+                            .merge_pending_group_from_external_commit(&id)
+                            .await
+                            .unwrap();
+                        alice_central
+                            .mls_central
+                            .decrypt_message(&id, commit.to_bytes().unwrap())
+                            .await
+                            .unwrap();
+                        assert!(charlie_central
+                            .mls_central
+                            .try_talk_to(&id, &mut alice_central.mls_central)
+                            .await
+                            .is_ok());
+                    })
+                },
+            )
+            .await
+        }
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn should_join_by_welcome_with_ratchet_tree(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob"],
+                move |[mut alice_central, mut bob_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        alice_central
+                            .mls_central
+                            .set_group_info_ratchet_tree_type(MlsRatchetTreeType::ByRef);
+                        alice_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+
+                        let bob = bob_central.mls_central.rand_key_package(&case).await;
+                        let MlsConversationCreationMessage {
+                            welcome, group_info, ..
+                        } = alice_central
+                            .mls_central
+                            .add_members_to_conversation(&id, vec![bob])
+                            .await
+                            .unwrap();
+                        alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                        assert_eq!(group_info.ratchet_tree_type, MlsRatchetTreeType::ByRef);
+
+                        let ratchet_tree = alice_central.mls_central.export_ratchet_tree(&id).await.unwrap();
+                        bob_central
+                            .mls_central
+                            .process_welcome_message_with_ratchet_tree(welcome.into(), &ratchet_tree, case.custom_cfg())
+                            .await
+                            .unwrap();
+                        assert!(bob_central
+                            .mls_central
+                            .try_talk_to(&id, &mut alice_central.mls_central)
+                            .await
+                            .is_ok());
+                    })
+                },
+            )
+            .await
+        }
+
+        #[apply(all_cred_cipher)]
+        #[wasm_bindgen_test]
+        pub async fn should_fail_when_ratchet_tree_does_not_match_tree_hash(case: TestCase) {
+            run_test_with_client_ids(
+                case.clone(),
+                ["alice", "bob"],
+                move |[mut alice_central, mut bob_central]| {
+                    Box::pin(async move {
+                        let id = conversation_id();
+                        alice_central
+                            .mls_central
+                            .set_group_info_ratchet_tree_type(MlsRatchetTreeType::ByRef);
+                        alice_central
+                            .mls_central
+                            .new_conversation(&id, case.credential_type, case.cfg.clone())
+                            .await
+                            .unwrap();
+                        let previous_ratchet_tree = alice_central.mls_central.export_ratchet_tree(&id).await.unwrap();
+
+                        let MlsCommitBundle { group_info, .. } =
+                            alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                        alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                        let group_info = group_info.payload.bytes();
+
+                        let mismatch = bob_central
+                            .mls_central
+                            .join_by_external_commit_with_ratchet_tree(
+                                &group_info,
+                                &previous_ratchet_tree,
+                                None,
+                                case.custom_cfg(),
+                                case.credential_type,
+                            )
+                            .await;
+                        assert!(mismatch.is_err());
+                        assert!(!bob_central.mls_central.pending_group_exists(&id).await);
+
+                        let ratchet_tree = alice_central.mls_central.export_ratchet_tree(&id).await.unwrap();
+                        assert!(bob_central
+                            .mls_central
+                            .join_by_external_commit_with_ratchet_tree(
+                                &group_info,
+                                &ratchet_tree,
+                                None,
+                                case.custom_cfg(),
+                                case.credential_type,
+                            )
+                            .await
+                            .is_ok());
+                    })
+                },
+            )
+            .await
         }
     }
 }
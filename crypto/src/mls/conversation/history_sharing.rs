@@ -0,0 +1,588 @@
+//! Opt-in sharing of the history of a conversation with the devices joining it later. MLS gives a
+//! new member no way to decrypt what was sent before it joined, so the application messages of a
+//! conversation are recorded in the keystore once sharing is enabled, within [MlsHistorySharingLimits].
+//!
+//! An existing member packages them in an archive encrypted under a secret exported from the
+//! current epoch and sends it through the conversation (see [MlsCentral::share_history]). The new
+//! device imports it with [MlsCentral::import_history] while still in that epoch.
+
+use mls_crypto_provider::MlsCryptoProvider;
+use openmls_traits::{crypto::OpenMlsCrypto, random::OpenMlsRand, types::AeadType, OpenMlsCryptoProvider};
+use serde::{Deserialize, Serialize};
+
+use core_crypto_keystore::entities::MlsHistory;
+
+use crate::{
+    mls::conversation::key_rotation::now,
+    prelude::{ClientId, ConversationId, CryptoError, CryptoResult, MlsCentral, MlsConversation, MlsError},
+};
+
+/// Default number of epochs, the current one included, whose messages are shared
+pub const DEFAULT_HISTORY_MAX_EPOCHS: u64 = 100;
+/// Default maximum number of shared messages
+pub const DEFAULT_HISTORY_MAX_MESSAGES: usize = 1000;
+/// Default time after which a message is no longer shared
+pub const DEFAULT_HISTORY_MAX_AGE: std::time::Duration = std::time::Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Additional authenticated data of the application messages created by [MlsCentral::share_history].
+/// Recipients seeing it in [crate::prelude::MlsConversationDecryptMessage::aad] should pass the
+/// decrypted message to [MlsCentral::import_history]
+pub const HISTORY_ARCHIVE_AAD: &[u8] = b"wire history archive";
+
+/// How far back the history shared with new devices goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MlsHistorySharingLimits {
+    /// Number of epochs, the current one included, whose messages are shared
+    pub max_epochs: u64,
+    /// Maximum number of shared messages. Beyond that, the oldest ones are dropped
+    pub max_messages: usize,
+    /// Time after which a message is no longer shared. `None` keeps them as long as the other limits allow
+    pub max_age: Option<std::time::Duration>,
+}
+
+impl Default for MlsHistorySharingLimits {
+    fn default() -> Self {
+        Self {
+            max_epochs: DEFAULT_HISTORY_MAX_EPOCHS,
+            max_messages: DEFAULT_HISTORY_MAX_MESSAGES,
+            max_age: Some(DEFAULT_HISTORY_MAX_AGE),
+        }
+    }
+}
+
+/// An application message of a conversation, as recorded for history sharing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MlsHistoryMessage {
+    /// Epoch the message was sent in
+    pub epoch: u64,
+    /// [ClientId] of the sender of the message. For an imported message, this is only as trustworthy
+    /// as the member who shared the archive, see [MlsCentral::import_history]
+    pub sender_client_id: ClientId,
+    /// Unix timestamp in seconds at which the message was recorded
+    pub received_at: u64,
+    /// The decrypted message
+    pub message: Vec<u8>,
+}
+
+/// Serialized form of [MlsHistoryMessage]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct HistoryEntry {
+    epoch: u64,
+    sender_client_id: Vec<u8>,
+    received_at: u64,
+    message: Vec<u8>,
+}
+
+impl From<HistoryEntry> for MlsHistoryMessage {
+    fn from(entry: HistoryEntry) -> Self {
+        Self {
+            epoch: entry.epoch,
+            sender_client_id: entry.sender_client_id.into(),
+            received_at: entry.received_at,
+            message: entry.message,
+        }
+    }
+}
+
+/// What is persisted in [MlsHistory] for a conversation sharing its history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct History {
+    limits: MlsHistorySharingLimits,
+    entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    /// Drops the entries beyond the limits, `epoch` being the current epoch of the conversation
+    fn prune(&mut self, epoch: u64) {
+        let MlsHistorySharingLimits {
+            max_epochs,
+            max_messages,
+            max_age,
+        } = self.limits;
+        let now = now();
+        self.entries.retain(|e| {
+            let in_epochs = e.epoch.saturating_add(max_epochs) > epoch;
+            let in_age = max_age.is_none_or(|max_age| e.received_at.saturating_add(max_age.as_secs()) >= now);
+            in_epochs && in_age
+        });
+        let overflow = self.entries.len().saturating_sub(max_messages);
+        self.entries.drain(..overflow);
+    }
+}
+
+/// Binary layout of an archive: version, epoch the key was exported from, AES-GCM nonce, ciphertext
+mod archive {
+    use super::*;
+
+    const VERSION: u8 = 1;
+    const EPOCH_LENGTH: usize = 8;
+    pub(super) const IV_LENGTH: usize = 12;
+    const HEADER_LENGTH: usize = 1 + EPOCH_LENGTH + IV_LENGTH;
+
+    pub(super) fn seal(
+        id: &ConversationId,
+        epoch: u64,
+        key: &[u8],
+        iv: Vec<u8>,
+        plaintext: &[u8],
+        backend: &MlsCryptoProvider,
+    ) -> CryptoResult<Vec<u8>> {
+        let mut archive = Vec::with_capacity(HEADER_LENGTH + plaintext.len());
+        archive.push(VERSION);
+        archive.extend(epoch.to_be_bytes());
+        archive.extend(&iv);
+        let ciphertext = backend
+            .crypto()
+            .aead_encrypt(AeadType::Aes256Gcm, key, plaintext, &iv, &aad(id, epoch))
+            .map_err(MlsError::from)?;
+        archive.extend(ciphertext);
+        Ok(archive)
+    }
+
+    /// Epoch the key of an archive has been exported from
+    pub(super) fn epoch(archive: &[u8]) -> CryptoResult<u64> {
+        if archive.len() < HEADER_LENGTH || archive[0] != VERSION {
+            return Err(CryptoError::HistoryArchiveDecryptionError);
+        }
+        let epoch = archive[1..1 + EPOCH_LENGTH]
+            .try_into()
+            .map_err(|_| CryptoError::HistoryArchiveDecryptionError)?;
+        Ok(u64::from_be_bytes(epoch))
+    }
+
+    pub(super) fn open(
+        id: &ConversationId,
+        key: &[u8],
+        archive: &[u8],
+        backend: &MlsCryptoProvider,
+    ) -> CryptoResult<Vec<u8>> {
+        let epoch = epoch(archive)?;
+        let iv = &archive[1 + EPOCH_LENGTH..HEADER_LENGTH];
+        backend
+            .crypto()
+            .aead_decrypt(AeadType::Aes256Gcm, key, &archive[HEADER_LENGTH..], iv, &aad(id, epoch))
+            .map_err(|_| CryptoError::HistoryArchiveDecryptionError)
+    }
+
+    fn aad(id: &ConversationId, epoch: u64) -> Vec<u8> {
+        [&[VERSION][..], &epoch.to_be_bytes()[..], &id[..]].concat()
+    }
+}
+
+impl MlsConversation {
+    const HISTORY_SHARING_LABEL: &'static str = "wire history sharing";
+    const HISTORY_SHARING_KEY_LENGTH: usize = 32;
+
+    /// Records an application message when this conversation shares its history. Archives
+    /// themselves are never recorded. Failing to record a message does not fail sending or
+    /// receiving it, it is only left out of the shared history.
+    pub(crate) async fn record_history_message(
+        &self,
+        backend: &MlsCryptoProvider,
+        sender_client_id: &ClientId,
+        message: &[u8],
+        aad: &[u8],
+    ) {
+        if let Err(e) = self
+            .try_record_history_message(backend, sender_client_id, message, aad)
+            .await
+        {
+            tracing::warn!(error = %e, "Failed recording a message for history sharing");
+        }
+    }
+
+    async fn try_record_history_message(
+        &self,
+        backend: &MlsCryptoProvider,
+        sender_client_id: &ClientId,
+        message: &[u8],
+        aad: &[u8],
+    ) -> CryptoResult<()> {
+        if aad == HISTORY_ARCHIVE_AAD {
+            return Ok(());
+        }
+        let Some(mut history) = self.load_history(backend).await? else {
+            return Ok(());
+        };
+        let epoch = self.group.epoch().as_u64();
+        history.entries.push(HistoryEntry {
+            epoch,
+            sender_client_id: sender_client_id.to_vec(),
+            received_at: now(),
+            message: message.to_vec(),
+        });
+        history.prune(epoch);
+        self.save_history(backend, &history).await
+    }
+
+    /// Key encrypting the history archives created in the current epoch
+    fn history_archive_key(&self, backend: &MlsCryptoProvider) -> CryptoResult<Vec<u8>> {
+        Ok(self
+            .group
+            .export_secret(
+                backend,
+                Self::HISTORY_SHARING_LABEL,
+                &self.id,
+                Self::HISTORY_SHARING_KEY_LENGTH,
+            )
+            .map_err(MlsError::from)?)
+    }
+
+    async fn load_history(&self, backend: &MlsCryptoProvider) -> CryptoResult<Option<History>> {
+        match backend.key_store().find::<MlsHistory>(&self.id).await? {
+            Some(entity) => Ok(Some(
+                serde_json::from_slice(&entity.messages).map_err(MlsError::MlsKeystoreSerializationError)?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    async fn save_history(&self, backend: &MlsCryptoProvider, history: &History) -> CryptoResult<()> {
+        let messages = serde_json::to_vec(history).map_err(MlsError::MlsKeystoreSerializationError)?;
+        let entity = MlsHistory {
+            id: self.id.clone(),
+            messages,
+        };
+        backend.key_store().save(entity).await?;
+        Ok(())
+    }
+}
+
+impl MlsCentral {
+    /// Starts recording the application messages of a conversation so that they can be shared with
+    /// devices joining it later. Calling it again only updates the limits. Messages sent or received
+    /// before are not recorded.
+    ///
+    /// # Arguments
+    /// * `id` - the conversation id
+    /// * `limits` - how far back the shared history goes
+    ///
+    /// # Errors
+    /// If the conversation can't be found or from the KeyStore
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
+    pub async fn enable_history_sharing(
+        &mut self,
+        id: &ConversationId,
+        limits: MlsHistorySharingLimits,
+    ) -> CryptoResult<()> {
+        let conversation = self.get_conversation(id).await?;
+        let conversation = conversation.read().await;
+        let mut history = conversation.load_history(&self.mls_backend).await?.unwrap_or(History {
+            limits,
+            entries: vec![],
+        });
+        history.limits = limits;
+        history.prune(conversation.group.epoch().as_u64());
+        conversation.save_history(&self.mls_backend, &history).await
+    }
+
+    /// Stops recording the application messages of a conversation and deletes the recorded ones
+    ///
+    /// # Errors
+    /// If the conversation can't be found or from the KeyStore
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
+    pub async fn disable_history_sharing(&mut self, id: &ConversationId) -> CryptoResult<()> {
+        self.get_conversation(id).await?;
+        if self.mls_backend.key_store().find::<MlsHistory>(id).await?.is_some() {
+            self.mls_backend.key_store().remove::<MlsHistory, _>(id).await?;
+        }
+        Ok(())
+    }
+
+    /// Packages the recorded history of a conversation in an archive encrypted under a secret of the
+    /// current epoch. Any member of this epoch, including a device which just joined, can open it.
+    /// Use [MlsCentral::share_history] to send it through the conversation directly.
+    ///
+    /// # Arguments
+    /// * `id` - the conversation id
+    ///
+    /// # Errors
+    /// [CryptoError::HistorySharingDisabled] when history sharing has not been enabled for this conversation.
+    /// Otherwise if the conversation can't be found, from OpenMls or the KeyStore
+    #[cfg_attr(test, crate::idempotent)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
+    pub async fn export_history(&mut self, id: &ConversationId) -> CryptoResult<Vec<u8>> {
+        let conversation = self.get_conversation(id).await?;
+        let conversation = conversation.read().await;
+        let mut history = conversation
+            .load_history(&self.mls_backend)
+            .await?
+            .ok_or(CryptoError::HistorySharingDisabled)?;
+        let epoch = conversation.group.epoch().as_u64();
+        history.prune(epoch);
+
+        let plaintext = serde_json::to_vec(&history.entries).map_err(MlsError::MlsKeystoreSerializationError)?;
+        let key = conversation.history_archive_key(&self.mls_backend)?;
+        let iv = self.mls_backend.rand().random_vec(archive::IV_LENGTH)?;
+        archive::seal(id, epoch, &key, iv, &plaintext, &self.mls_backend)
+    }
+
+    /// Exports the history of a conversation (see [MlsCentral::export_history]) and encrypts it as an
+    /// application message, with [HISTORY_ARCHIVE_AAD] as additional authenticated data, to be sent
+    /// to the conversation right after a new device joined.
+    ///
+    /// # Errors
+    /// see [MlsCentral::export_history] and [MlsCentral::encrypt_message]
+    #[cfg_attr(test, crate::idempotent)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
+    pub async fn share_history(&mut self, id: &ConversationId) -> CryptoResult<Vec<u8>> {
+        let archive = self.export_history(id).await?;
+        self.encrypt_message_with_aad(id, archive, HISTORY_ARCHIVE_AAD).await
+    }
+
+    /// Opens a history archive created by [MlsCentral::export_history]. It has to be imported while
+    /// in the epoch it was created in, usually right after decrypting the message carrying it. When
+    /// this conversation shares its history too, the imported messages are recorded along with ours.
+    ///
+    /// The archive only proves that a member of the current epoch created it: the messages, their
+    /// senders included, are not authenticated by whoever originally sent them. They are exactly as
+    /// trustworthy as the member who shared the archive, i.e. the sender of the message carrying it.
+    ///
+    /// # Arguments
+    /// * `id` - the conversation id
+    /// * `archive` - the history archive
+    ///
+    /// # Return type
+    /// The messages of the archive, from the oldest to the most recent
+    ///
+    /// # Errors
+    /// [CryptoError::WrongEpoch] if the archive was not created in the current epoch,
+    /// [CryptoError::HistoryArchiveDecryptionError] if it can't be decrypted. Otherwise if the
+    /// conversation can't be found, from OpenMls or the KeyStore
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self, archive), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
+    pub async fn import_history(
+        &mut self,
+        id: &ConversationId,
+        archive: impl AsRef<[u8]>,
+    ) -> CryptoResult<Vec<MlsHistoryMessage>> {
+        let archive = archive.as_ref();
+        let conversation = self.get_conversation(id).await?;
+        let conversation = conversation.read().await;
+        let epoch = conversation.group.epoch().as_u64();
+        if archive::epoch(archive)? != epoch {
+            return Err(CryptoError::WrongEpoch);
+        }
+
+        let key = conversation.history_archive_key(&self.mls_backend)?;
+        let plaintext = archive::open(id, &key, archive, &self.mls_backend)?;
+        let entries = serde_json::from_slice::<Vec<HistoryEntry>>(&plaintext)
+            .map_err(|_| CryptoError::HistoryArchiveDecryptionError)?;
+
+        if let Some(mut history) = conversation.load_history(&self.mls_backend).await? {
+            let imported = entries
+                .iter()
+                .filter(|e| !history.entries.contains(e))
+                .cloned()
+                .collect::<Vec<_>>();
+            history.entries.extend(imported);
+            history.entries.sort_by_key(|e| e.epoch);
+            history.prune(epoch);
+            conversation.save_history(&self.mls_backend, &history).await?;
+        }
+
+        Ok(entries.into_iter().map(MlsHistoryMessage::from).collect())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use super::*;
+    use crate::test_utils::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn new_device_should_read_shared_history(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob", "charlie"],
+            move |[mut alice_central, mut bob_central, mut charlie_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .enable_history_sharing(&id, MlsHistorySharingLimits::default())
+                        .await
+                        .unwrap();
+
+                    let hello = alice_central.mls_central.encrypt_message(&id, b"hello").await.unwrap();
+                    bob_central.mls_central.decrypt_message(&id, hello).await.unwrap();
+                    let hi = bob_central.mls_central.encrypt_message(&id, b"hi").await.unwrap();
+                    alice_central.mls_central.decrypt_message(&id, hi).await.unwrap();
+
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut charlie_central.mls_central])
+                        .await
+                        .unwrap();
+                    let shared = alice_central.mls_central.share_history(&id).await.unwrap();
+                    let decrypted = charlie_central.mls_central.decrypt_message(&id, shared).await.unwrap();
+                    assert_eq!(decrypted.aad.as_deref(), Some(HISTORY_ARCHIVE_AAD));
+
+                    let history = charlie_central
+                        .mls_central
+                        .import_history(&id, decrypted.app_msg.unwrap())
+                        .await
+                        .unwrap();
+                    assert_eq!(history.len(), 2);
+                    assert_eq!(history[0].message, b"hello");
+                    assert_eq!(
+                        history[0].sender_client_id,
+                        alice_central.mls_central.client_id().unwrap()
+                    );
+                    assert_eq!(history[1].message, b"hi");
+                    assert_eq!(
+                        history[1].sender_client_id,
+                        bob_central.mls_central.client_id().unwrap()
+                    );
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn history_should_respect_limits(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .enable_history_sharing(
+                            &id,
+                            MlsHistorySharingLimits {
+                                max_epochs: 2,
+                                max_messages: 2,
+                                max_age: None,
+                            },
+                        )
+                        .await
+                        .unwrap();
+
+                    // too old an epoch
+                    alice_central.mls_central.encrypt_message(&id, b"0").await.unwrap();
+                    alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    // too many messages
+                    for m in [b"1", b"2", b"3"] {
+                        alice_central.mls_central.encrypt_message(&id, m).await.unwrap();
+                    }
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let archive = alice_central.mls_central.export_history(&id).await.unwrap();
+                    let history = bob_central.mls_central.import_history(&id, archive).await.unwrap();
+                    let messages = history.into_iter().map(|m| m.message).collect::<Vec<_>>();
+                    assert_eq!(messages, vec![b"2".to_vec(), b"3".to_vec()]);
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn import_should_fail_outside_of_the_archive_epoch(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .enable_history_sharing(&id, MlsHistorySharingLimits::default())
+                        .await
+                        .unwrap();
+                    alice_central.mls_central.encrypt_message(&id, b"hello").await.unwrap();
+                    let archive = alice_central.mls_central.export_history(&id).await.unwrap();
+
+                    // tampered with
+                    let mut tampered = archive.clone();
+                    *tampered.last_mut().unwrap() ^= 1;
+                    let import = bob_central.mls_central.import_history(&id, tampered).await;
+                    assert!(matches!(
+                        import.unwrap_err(),
+                        CryptoError::HistoryArchiveDecryptionError
+                    ));
+
+                    // Bob moved to the next epoch
+                    let commit = alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    let import = bob_central.mls_central.import_history(&id, archive).await;
+                    assert!(matches!(import.unwrap_err(), CryptoError::WrongEpoch));
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn export_should_fail_when_history_sharing_disabled(case: TestCase) {
+        run_test_with_client_ids(case.clone(), ["alice"], move |[mut alice_central]| {
+            Box::pin(async move {
+                let id = conversation_id();
+                alice_central
+                    .mls_central
+                    .new_conversation(&id, case.credential_type, case.cfg.clone())
+                    .await
+                    .unwrap();
+                let export = alice_central.mls_central.export_history(&id).await;
+                assert!(matches!(export.unwrap_err(), CryptoError::HistorySharingDisabled));
+
+                alice_central
+                    .mls_central
+                    .enable_history_sharing(&id, MlsHistorySharingLimits::default())
+                    .await
+                    .unwrap();
+                assert!(alice_central.mls_central.export_history(&id).await.is_ok());
+
+                alice_central.mls_central.disable_history_sharing(&id).await.unwrap();
+                let export = alice_central.mls_central.export_history(&id).await;
+                assert!(matches!(export.unwrap_err(), CryptoError::HistorySharingDisabled));
+            })
+        })
+        .await
+    }
+}
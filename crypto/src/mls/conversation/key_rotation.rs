@@ -0,0 +1,474 @@
+//! Conversations rotate the own leaf once their [MlsCustomConfiguration::key_rotation_span] has
+//! passed since its last update. This is what provides post-compromise security so the time of
+//! the last update is persisted in the keystore for each conversation. Conversations created before that get
+//! [MlsCustomConfiguration::DEFAULT_KEY_ROTATION_SPAN] when they are restored.
+//!
+//! [MlsCustomConfiguration::key_rotation_span]: crate::prelude::MlsCustomConfiguration::key_rotation_span
+//! [MlsCustomConfiguration::DEFAULT_KEY_ROTATION_SPAN]: crate::prelude::MlsCustomConfiguration::DEFAULT_KEY_ROTATION_SPAN
+
+use std::collections::HashMap;
+
+use core_crypto_keystore::entities::MlsGroupKeyRotation;
+use mls_crypto_provider::MlsCryptoProvider;
+use openmls_traits::OpenMlsCryptoProvider;
+
+use crate::prelude::{
+    ConversationId, CryptoResult, MlsCentral, MlsCommitBundle, MlsConversation, MlsCustomConfiguration,
+};
+
+impl MlsConversation {
+    /// Starts tracking the key rotation of a newly created or joined conversation
+    pub(crate) async fn init_key_rotation(&self, backend: &MlsCryptoProvider) -> CryptoResult<()> {
+        let rotation = MlsGroupKeyRotation {
+            id: self.id.clone(),
+            key_rotation_span: self.configuration.custom.key_rotation_span.map(|span| span.as_secs()),
+            last_update: now(),
+        };
+        backend.key_store().save(rotation).await?;
+        Ok(())
+    }
+
+    /// Starts tracking the key rotation of a restored conversation created before it was tracked, with
+    /// [MlsCustomConfiguration::DEFAULT_KEY_ROTATION_SPAN]. The time of its last update being unknown, it's
+    /// considered as just rotated.
+    pub(crate) async fn init_missing_key_rotation(&self, backend: &MlsCryptoProvider) -> CryptoResult<()> {
+        let keystore = backend.key_store();
+        if keystore.find::<MlsGroupKeyRotation>(&self.id).await?.is_some() {
+            return Ok(());
+        }
+        let rotation = MlsGroupKeyRotation {
+            id: self.id.clone(),
+            key_rotation_span: Some(MlsCustomConfiguration::DEFAULT_KEY_ROTATION_SPAN.as_secs()),
+            last_update: now(),
+        };
+        keystore.save(rotation).await?;
+        Ok(())
+    }
+
+    /// Records that the own leaf has just been updated
+    pub(crate) async fn key_rotated(&self, backend: &MlsCryptoProvider) -> CryptoResult<()> {
+        // The span is only known in memory until the conversation is restored from the keystore
+        let key_rotation_span = match backend.key_store().find::<MlsGroupKeyRotation>(&self.id).await? {
+            Some(rotation) => rotation.key_rotation_span,
+            None => self.configuration.custom.key_rotation_span.map(|span| span.as_secs()),
+        };
+        let rotation = MlsGroupKeyRotation {
+            id: self.id.clone(),
+            key_rotation_span,
+            last_update: now(),
+        };
+        backend.key_store().save(rotation).await?;
+        Ok(())
+    }
+}
+
+impl MlsCentral {
+    /// Returns the conversations for which the `key_rotation_span` of their
+    /// [crate::prelude::MlsCustomConfiguration] has passed since the own leaf was last updated.
+    /// Use [MlsCentral::update_keying_material] on them or [MlsCentral::update_keying_material_when_due]
+    #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
+    pub async fn conversations_due_for_key_rotation(&self) -> CryptoResult<Vec<ConversationId>> {
+        let now = now();
+        Ok(self
+            .mls_backend
+            .key_store()
+            .find_all::<MlsGroupKeyRotation>(Default::default())
+            .await?
+            .iter()
+            .filter(|rotation| {
+                rotation
+                    .key_rotation_span
+                    .map(|span| rotation.last_update.saturating_add(span) <= now)
+                    .unwrap_or_default()
+            })
+            .map(|rotation| rotation.id.clone())
+            .collect())
+    }
+
+    /// Generates an update commit for each conversation returned by [MlsCentral::conversations_due_for_key_rotation].
+    /// Conversations which already have a pending commit are skipped, they will be returned again
+    /// later if that commit does not update the own leaf.
+    ///
+    /// Every commit has to be sent to the Delivery Service and then merged with
+    /// [MlsCentral::commit_accepted] which marks the conversation as rotated.
+    ///
+    /// # Errors
+    /// see [MlsCentral::update_keying_material]. The commits already created for other conversations are then
+    /// cleared, so that they can be rotated again.
+    #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
+    pub async fn update_keying_material_when_due(&mut self) -> CryptoResult<HashMap<ConversationId, MlsCommitBundle>> {
+        let due = self.conversations_due_for_key_rotation().await?;
+        let mut commits = HashMap::with_capacity(due.len());
+        for id in due {
+            match self.update_keying_material_unless_pending(&id).await {
+                Ok(Some(commit)) => {
+                    commits.insert(id, commit);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    // the caller never gets the commits created so far, they must not stay pending
+                    for id in commits.keys() {
+                        if let Err(e) = self.clear_pending_commit(id).await {
+                            tracing::warn!(error = %e, "Failed clearing a key rotation commit");
+                        }
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(commits)
+    }
+
+    async fn update_keying_material_unless_pending(
+        &mut self,
+        id: &ConversationId,
+    ) -> CryptoResult<Option<MlsCommitBundle>> {
+        let has_pending_commit = self
+            .get_conversation(id)
+            .await?
+            .read()
+            .await
+            .group
+            .pending_commit()
+            .is_some();
+        if has_pending_commit {
+            return Ok(None);
+        }
+        self.update_keying_material(id).await.map(Some)
+    }
+
+    /// Changes the key rotation span of a conversation, e.g. for the conversations created before the key rotation
+    /// was tracked which get [MlsCustomConfiguration::DEFAULT_KEY_ROTATION_SPAN]
+    ///
+    /// # Arguments
+    /// * `id` - the conversation id
+    /// * `key_rotation_span` - after how long the own leaf has to be updated. `None` disables the key rotation
+    ///
+    /// # Errors
+    /// If the conversation can't be found or from the KeyStore
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self)))]
+    pub async fn set_key_rotation_span(
+        &mut self,
+        id: &ConversationId,
+        key_rotation_span: Option<std::time::Duration>,
+    ) -> CryptoResult<()> {
+        let conversation = self.get_conversation(id).await?;
+        let mut conversation = conversation.write().await;
+        conversation.configuration.custom.key_rotation_span = key_rotation_span;
+
+        let keystore = self.mls_backend.key_store();
+        let last_update = keystore
+            .find::<MlsGroupKeyRotation>(id)
+            .await?
+            .map(|rotation| rotation.last_update)
+            .unwrap_or_else(now);
+        let rotation = MlsGroupKeyRotation {
+            id: id.clone(),
+            key_rotation_span: key_rotation_span.map(|span| span.as_secs()),
+            last_update,
+        };
+        keystore.save(rotation).await?;
+        Ok(())
+    }
+}
+
+/// Unix timestamp in seconds, in a WASM compatible way
+pub(crate) fn now() -> u64 {
+    fluvio_wasm_timer::SystemTime::now()
+        .duration_since(fluvio_wasm_timer::UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::time::Duration;
+
+    use wasm_bindgen_test::*;
+
+    use crate::{test_utils::*, CryptoError};
+
+    use super::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    const KEY_ROTATION_SPAN: Duration = Duration::from_secs(3600);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_rotate_conversations_once_span_has_passed(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    let mut cfg = case.cfg.clone();
+                    cfg.custom.key_rotation_span = Some(KEY_ROTATION_SPAN);
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, cfg)
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    // without a span, a conversation is never rotated
+                    let other_id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&other_id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+
+                    assert!(alice_central
+                        .mls_central
+                        .conversations_due_for_key_rotation()
+                        .await
+                        .unwrap()
+                        .is_empty());
+
+                    // pretend the own leaf has been updated long ago
+                    for id in [&id, &other_id] {
+                        let mut rotation = alice_central
+                            .mls_central
+                            .mls_backend
+                            .key_store()
+                            .find::<MlsGroupKeyRotation>(id)
+                            .await
+                            .unwrap()
+                            .unwrap();
+                        rotation.last_update -= 2 * KEY_ROTATION_SPAN.as_secs();
+                        alice_central
+                            .mls_central
+                            .mls_backend
+                            .key_store()
+                            .save(rotation)
+                            .await
+                            .unwrap();
+                    }
+
+                    let due = alice_central
+                        .mls_central
+                        .conversations_due_for_key_rotation()
+                        .await
+                        .unwrap();
+                    assert_eq!(due, vec![id.clone()]);
+
+                    let mut commits = alice_central
+                        .mls_central
+                        .update_keying_material_when_due()
+                        .await
+                        .unwrap();
+                    assert_eq!(commits.len(), 1);
+                    let commit = commits.remove(&id).unwrap().commit;
+
+                    // not rotated until the commit is merged
+                    assert_eq!(
+                        alice_central
+                            .mls_central
+                            .conversations_due_for_key_rotation()
+                            .await
+                            .unwrap(),
+                        vec![id.clone()]
+                    );
+                    // a pending commit is not overwritten
+                    assert!(alice_central
+                        .mls_central
+                        .update_keying_material_when_due()
+                        .await
+                        .unwrap()
+                        .is_empty());
+
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    assert!(alice_central
+                        .mls_central
+                        .conversations_due_for_key_rotation()
+                        .await
+                        .unwrap()
+                        .is_empty());
+                    assert!(alice_central
+                        .mls_central
+                        .try_talk_to(&id, &mut bob_central.mls_central)
+                        .await
+                        .is_ok());
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_keep_span_when_changed_after_creation(case: TestCase) {
+        run_test_with_client_ids(case.clone(), ["alice"], move |[mut alice_central]| {
+            Box::pin(async move {
+                let id = conversation_id();
+                alice_central
+                    .mls_central
+                    .new_conversation(&id, case.credential_type, case.cfg.clone())
+                    .await
+                    .unwrap();
+
+                alice_central
+                    .mls_central
+                    .set_key_rotation_span(&id, Some(Duration::ZERO))
+                    .await
+                    .unwrap();
+                assert_eq!(
+                    alice_central
+                        .mls_central
+                        .conversations_due_for_key_rotation()
+                        .await
+                        .unwrap(),
+                    vec![id.clone()]
+                );
+
+                // the span survives updating the own leaf
+                alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                let rotation = alice_central
+                    .mls_central
+                    .mls_backend
+                    .key_store()
+                    .find::<MlsGroupKeyRotation>(&id)
+                    .await
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(rotation.key_rotation_span, Some(0));
+
+                alice_central.mls_central.wipe_conversation(&id).await.unwrap();
+                assert!(alice_central
+                    .mls_central
+                    .mls_backend
+                    .key_store()
+                    .find::<MlsGroupKeyRotation>(&id)
+                    .await
+                    .unwrap()
+                    .is_none());
+            })
+        })
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_rotate_conversations_created_before_rotation_was_tracked(case: TestCase) {
+        run_test_with_client_ids(case.clone(), ["alice"], move |[mut alice_central]| {
+            Box::pin(async move {
+                let id = conversation_id();
+                alice_central
+                    .mls_central
+                    .new_conversation(&id, case.credential_type, case.cfg.clone())
+                    .await
+                    .unwrap();
+
+                // such a conversation has nothing stored about its key rotation
+                alice_central
+                    .mls_central
+                    .mls_backend
+                    .key_store()
+                    .remove::<MlsGroupKeyRotation, _>(&id)
+                    .await
+                    .unwrap();
+                let central = &mut alice_central.mls_central;
+                central.mls_groups = MlsCentral::restore_groups(&central.mls_backend).await.unwrap();
+
+                let mut rotation = alice_central
+                    .mls_central
+                    .mls_backend
+                    .key_store()
+                    .find::<MlsGroupKeyRotation>(&id)
+                    .await
+                    .unwrap()
+                    .unwrap();
+                let span = MlsCustomConfiguration::DEFAULT_KEY_ROTATION_SPAN.as_secs();
+                assert_eq!(rotation.key_rotation_span, Some(span));
+                // it's considered as just rotated
+                assert!(alice_central
+                    .mls_central
+                    .conversations_due_for_key_rotation()
+                    .await
+                    .unwrap()
+                    .is_empty());
+
+                rotation.last_update -= span;
+                alice_central
+                    .mls_central
+                    .mls_backend
+                    .key_store()
+                    .save(rotation)
+                    .await
+                    .unwrap();
+                assert_eq!(
+                    alice_central
+                        .mls_central
+                        .conversations_due_for_key_rotation()
+                        .await
+                        .unwrap(),
+                    vec![id.clone()]
+                );
+
+                // restoring it again leaves its key rotation untouched
+                let central = &mut alice_central.mls_central;
+                central.mls_groups = MlsCentral::restore_groups(&central.mls_backend).await.unwrap();
+                assert_eq!(
+                    alice_central
+                        .mls_central
+                        .conversations_due_for_key_rotation()
+                        .await
+                        .unwrap(),
+                    vec![id.clone()]
+                );
+            })
+        })
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_clear_rotation_commits_when_one_fails(case: TestCase) {
+        run_test_with_client_ids(case.clone(), ["alice"], move |[mut alice_central]| {
+            Box::pin(async move {
+                let id = conversation_id();
+                alice_central
+                    .mls_central
+                    .new_conversation(&id, case.credential_type, case.cfg.clone())
+                    .await
+                    .unwrap();
+                alice_central
+                    .mls_central
+                    .set_key_rotation_span(&id, Some(Duration::ZERO))
+                    .await
+                    .unwrap();
+
+                // a conversation which cannot be rotated, listed after the other one whatever the platform
+                let unknown_id = vec![u8::MAX; 32];
+                alice_central
+                    .mls_central
+                    .mls_backend
+                    .key_store()
+                    .save(MlsGroupKeyRotation {
+                        id: unknown_id,
+                        key_rotation_span: Some(0),
+                        last_update: 0,
+                    })
+                    .await
+                    .unwrap();
+
+                let result = alice_central.mls_central.update_keying_material_when_due().await;
+                assert!(matches!(result.unwrap_err(), CryptoError::ConversationNotFound(_)));
+                assert!(alice_central.mls_central.pending_commit(&id).await.is_none());
+            })
+        })
+        .await
+    }
+}
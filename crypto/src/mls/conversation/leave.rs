@@ -0,0 +1,191 @@
+//! Leaving a conversation is a 2-step process: a proposal removing our own leaf is created and sent to
+//! the other members, who will commit it (they are hinted to do so by [MlsConversationDecryptMessage::delay]).
+//! Only once this proposal has been handed over to the Delivery Service is the conversation wiped locally.
+//!
+//! The SelfRemove proposal of the MLS extensions draft is not supported by openmls, so a regular Remove
+//! proposal naming our own leaf and signed by us is used instead.
+//!
+//! [MlsConversationDecryptMessage::delay]: crate::prelude::MlsConversationDecryptMessage::delay
+
+use openmls::prelude::Proposal;
+
+use mls_crypto_provider::MlsCryptoProvider;
+
+use crate::prelude::{
+    Client, ConversationId, CryptoError, CryptoResult, MlsCentral, MlsConversation, MlsProposalBundle,
+};
+
+impl MlsConversation {
+    /// Creates a Remove proposal of our own leaf
+    #[cfg_attr(test, crate::durable)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
+    pub async fn propose_self_remove(
+        &mut self,
+        client: &Client,
+        backend: &MlsCryptoProvider,
+    ) -> CryptoResult<MlsProposalBundle> {
+        let own_index = self.group.own_leaf_index();
+        self.propose_remove_member(client, backend, own_index).await
+    }
+
+    /// Whether we have a pending proposal removing ourselves from the group
+    fn is_leaving(&self) -> bool {
+        let own_index = self.group.own_leaf_index();
+        self.self_pending_proposals()
+            .any(|p| matches!(p.proposal(), Proposal::Remove(r) if r.removed() == own_index))
+    }
+}
+
+impl MlsCentral {
+    /// Creates a proposal removing ourselves from the conversation. It has to be sent to the other members,
+    /// then [MlsCentral::leave_proposal_sent] has to be called to wipe the conversation locally.
+    ///
+    /// Remaining members commit it like any other Remove proposal. We are never asked to commit it ourselves.
+    ///
+    /// # Arguments
+    /// * `id` - the group/conversation id
+    ///
+    /// # Errors
+    /// If the conversation can't be found, an error will be returned. Other errors are originating
+    /// from OpenMls and the KeyStore
+    #[cfg_attr(test, crate::idempotent)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
+    pub async fn leave_conversation(&mut self, id: &ConversationId) -> CryptoResult<MlsProposalBundle> {
+        self.get_conversation(id)
+            .await?
+            .write()
+            .await
+            .propose_self_remove(self.mls_client()?, &self.mls_backend)
+            .await
+    }
+
+    /// Wipes the conversation locally, along with its subconversations, once the proposal created by
+    /// [MlsCentral::leave_conversation] has been sent to the Delivery Service
+    ///
+    /// # Arguments
+    /// * `id` - the group/conversation id
+    ///
+    /// # Errors
+    /// [CryptoError::SelfRemoveProposalNotFound] when [MlsCentral::leave_conversation] was not called
+    /// beforehand or when its proposal was discarded since. KeyStore errors, such as IO
+    #[cfg_attr(test, crate::dispotent)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
+    pub async fn leave_proposal_sent(&mut self, id: &ConversationId) -> CryptoResult<()> {
+        if !self.get_conversation(id).await?.read().await.is_leaving() {
+            return Err(CryptoError::SelfRemoveProposalNotFound);
+        }
+        self.wipe_conversation(id).await
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::MlsCommitBundle, test_utils::*, CryptoError};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_leave_conversation(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob", "charlie"],
+            move |[mut alice_central, mut bob_central, mut charlie_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(
+                            &case,
+                            &id,
+                            [&mut bob_central.mls_central, &mut charlie_central.mls_central],
+                        )
+                        .await
+                        .unwrap();
+
+                    let proposal = bob_central.mls_central.leave_conversation(&id).await.unwrap();
+                    let proposal = proposal.proposal.to_bytes().unwrap();
+                    bob_central.mls_central.leave_proposal_sent(&id).await.unwrap();
+                    assert!(matches!(
+                        bob_central.mls_central.get_conversation(&id).await.unwrap_err(),
+                        CryptoError::ConversationNotFound(i) if i == id
+                    ));
+
+                    // remaining members are asked to commit the proposal
+                    let decrypted = alice_central.mls_central.decrypt_message(&id, &proposal).await.unwrap();
+                    assert!(decrypted.delay.is_some());
+                    charlie_central
+                        .mls_central
+                        .decrypt_message(&id, &proposal)
+                        .await
+                        .unwrap();
+
+                    let MlsCommitBundle { commit, .. } = alice_central
+                        .mls_central
+                        .commit_pending_proposals(&id)
+                        .await
+                        .unwrap()
+                        .unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    charlie_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+
+                    let members = alice_central
+                        .mls_central
+                        .get_conversation_unchecked(&id)
+                        .await
+                        .members();
+                    assert_eq!(members.len(), 2);
+                    assert!(!members.contains_key(bob_central.mls_central.client_id().unwrap().as_slice()));
+                    assert!(alice_central
+                        .mls_central
+                        .try_talk_to(&id, &mut charlie_central.mls_central)
+                        .await
+                        .is_ok());
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_not_wipe_without_self_remove_proposal(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    // a pending proposal which does not remove ourselves is not enough
+                    bob_central.mls_central.new_update_proposal(&id).await.unwrap();
+                    let leave = bob_central.mls_central.leave_proposal_sent(&id).await;
+                    assert!(matches!(leave.unwrap_err(), CryptoError::SelfRemoveProposalNotFound));
+                    assert!(bob_central.mls_central.get_conversation(&id).await.is_ok());
+                })
+            },
+        )
+        .await
+    }
+}
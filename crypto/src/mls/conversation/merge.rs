@@ -11,7 +11,7 @@
 //! | 1+ pend. Proposal | ❌              | ✅              |
 //!
 
-use core_crypto_keystore::entities::{MlsEncryptionKeyPair, MlsPendingMessage};
+use core_crypto_keystore::entities::MlsEncryptionKeyPair;
 use openmls::prelude::MlsGroupStateError;
 use openmls_traits::OpenMlsCryptoProvider;
 
@@ -31,9 +31,17 @@ impl MlsConversation {
     pub async fn commit_accepted(&mut self, backend: &MlsCryptoProvider) -> CryptoResult<()> {
         // openmls stores here all the encryption keypairs used for update proposals..
         let previous_own_leaf_nodes = self.group.own_leaf_nodes.clone();
+        let previous_encryption_key = self.group.own_leaf().map(|leaf| leaf.encryption_key().clone());
 
         self.group.merge_pending_commit(backend).await.map_err(MlsError::from)?;
         self.persist_group_when_changed(backend, false).await?;
+        self.record_epoch_authenticator(backend).await?;
+        self.record_member_epochs(backend).await?;
+
+        let encryption_key = self.group.own_leaf().map(|leaf| leaf.encryption_key());
+        if encryption_key != previous_encryption_key.as_ref() {
+            self.key_rotated(backend).await?;
+        }
 
         // ..so if there's any, we clear them after the commit is merged
         for oln in &previous_own_leaf_nodes {
@@ -103,11 +111,7 @@ impl MlsCentral {
         let mut conv = conv.write().await;
         conv.commit_accepted(&self.mls_backend).await?;
 
-        let pending_messages = self.restore_pending_messages(&mut conv, false).await?;
-        if pending_messages.is_some() {
-            self.mls_backend.key_store().remove::<MlsPendingMessage, _>(id).await?;
-        }
-        Ok(pending_messages)
+        self.restore_pending_messages(&mut conv, false).await
     }
 
     /// Allows to remove a pending (uncommitted) proposal. Use this when backend rejects the proposal
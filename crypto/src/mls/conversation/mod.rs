@@ -41,10 +41,10 @@ use config::MlsConversationConfiguration;
 use crate::{
     group_store::GroupStoreValue,
     mls::{client::Client, MlsCentral},
-    prelude::{CryptoError, CryptoResult, MlsCiphersuite, MlsCredentialType, MlsError},
+    prelude::{CryptoError, CryptoResult, MlsCiphersuite, MlsCredentialType, MlsError, MlsRatchetTreeType},
 };
 
-mod buffer_messages;
+pub(crate) mod buffer_messages;
 pub mod commit;
 mod commit_delay;
 pub mod config;
@@ -55,15 +55,25 @@ mod duplicate;
 #[cfg(test)]
 mod durability;
 pub mod encrypt;
+pub mod epoch_authenticator;
 pub mod export;
 pub(crate) mod external_sender;
+mod group_context_extensions;
 pub(crate) mod group_info;
+pub mod history_sharing;
+pub(crate) mod key_rotation;
 mod leaf_node_validation;
+mod leave;
 pub mod merge;
 mod orphan_welcome;
 pub mod proposal;
+mod psk;
+pub(crate) mod ratchet_tree_delta;
+pub mod reinit;
 mod renew;
+pub mod roster;
 mod self_commit;
+mod subconversation;
 pub(crate) mod welcome;
 mod wipe;
 /// A unique identifier for a group/conversation. The identifier must be unique within a client.
@@ -89,16 +99,18 @@ impl MlsConversation {
     /// * `author_client` - the client responsible for creating the group
     /// * `creator_credential_type` - kind of credential the creator wants to join the group with
     /// * `config` - group configuration
+    /// * `ratchet_tree_type` - representation of the ratchet tree in the GroupInfos of the group
     /// * `backend` - MLS Provider that will be used to persist the group
     ///
     /// # Errors
     /// Errors can happen from OpenMls or from the KeyStore
-    #[cfg_attr(not(test), tracing::instrument(err, skip(author_client, configuration, backend, creator_credential_type), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, &id))))]
+    #[cfg_attr(not(test), tracing::instrument(err, skip(author_client, configuration, ratchet_tree_type, backend, creator_credential_type), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, &id))))]
     pub async fn create(
         id: ConversationId,
         author_client: &mut Client,
         creator_credential_type: MlsCredentialType,
         configuration: MlsConversationConfiguration,
+        ratchet_tree_type: MlsRatchetTreeType,
         backend: &MlsCryptoProvider,
     ) -> CryptoResult<Self> {
         let (cs, ct) = (configuration.ciphersuite, creator_credential_type);
@@ -109,7 +121,7 @@ impl MlsConversation {
         let group = MlsGroup::new_with_group_id(
             backend,
             &cb.signature_key,
-            &configuration.as_openmls_default_configuration()?,
+            &configuration.as_openmls_configuration(ratchet_tree_type)?,
             openmls::prelude::GroupId::from_slice(id.as_slice()),
             cb.to_mls_credential_with_key(),
         )
@@ -124,6 +136,9 @@ impl MlsConversation {
         };
 
         conversation.persist_group_when_changed(backend, true).await?;
+        conversation.init_key_rotation(backend).await?;
+        conversation.record_epoch_authenticator(backend).await?;
+        conversation.init_member_epochs(backend).await?;
 
         Ok(conversation)
     }
@@ -145,6 +160,9 @@ impl MlsConversation {
         };
 
         conversation.persist_group_when_changed(backend, true).await?;
+        conversation.init_key_rotation(backend).await?;
+        conversation.record_epoch_authenticator(backend).await?;
+        conversation.init_member_epochs(backend).await?;
 
         Ok(conversation)
     }
@@ -5,7 +5,13 @@
 //! | 0 pend. Proposal       | ✅              | ❌              |
 //! | 1+ pend. Proposal      | ✅              | ❌              |
 
-use openmls::{binary_tree::LeafNodeIndex, framing::MlsMessageOut, key_packages::KeyPackageIn, prelude::LeafNode};
+use openmls::{
+    binary_tree::LeafNodeIndex,
+    framing::MlsMessageOut,
+    key_packages::KeyPackageIn,
+    prelude::{ExternalPsk, LeafNode, PreSharedKeyId, ProposalOrRefType, Propose, Psk},
+};
+use openmls_traits::OpenMlsCryptoProvider;
 
 use mls_crypto_provider::MlsCryptoProvider;
 
@@ -14,7 +20,7 @@ use crate::{
 };
 use crate::{
     mls::credential::crl::extract_crl_uris_from_credentials,
-    prelude::{Client, MlsConversation, MlsProposalRef},
+    prelude::{Client, MlsConversation, MlsProposalRef, MlsReInit},
     CryptoError, CryptoResult, MlsError,
 };
 
@@ -77,6 +83,67 @@ impl MlsConversation {
         Ok(proposal)
     }
 
+    /// see [openmls::group::MlsGroup::propose_external_psk]
+    ///
+    /// The PSK has to be registered beforehand with [crate::prelude::MlsCentral::register_external_psk], by this
+    /// client as well as by all the other members, for the commit including this proposal to be created and processed.
+    #[cfg_attr(test, crate::durable)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
+    pub async fn propose_external_psk(
+        &mut self,
+        client: &Client,
+        backend: &MlsCryptoProvider,
+        psk_id: Vec<u8>,
+    ) -> CryptoResult<MlsProposalBundle> {
+        let signer = &self
+            .find_current_credential_bundle(client)?
+            .ok_or(CryptoError::IdentityInitializationError)?
+            .signature_key;
+        let psk_id = PreSharedKeyId::new(
+            self.ciphersuite().into(),
+            backend.rand(),
+            Psk::External(ExternalPsk::new(psk_id)),
+        )
+        .map_err(MlsError::from)?;
+        let proposal = self
+            .group
+            .propose_external_psk(backend, signer, psk_id)
+            .map_err(MlsError::from)
+            .map_err(CryptoError::from)
+            .map(MlsProposalBundle::from)?;
+        self.persist_group_when_changed(backend, false).await?;
+        Ok(proposal)
+    }
+
+    /// Proposes to end the conversation for its members to move to a successor with the given parameters, see
+    /// [crate::prelude::MlsCentral::reinit_conversation]
+    #[cfg_attr(test, crate::durable)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
+    pub async fn propose_reinit(
+        &mut self,
+        client: &Client,
+        backend: &MlsCryptoProvider,
+        reinit: &MlsReInit,
+    ) -> CryptoResult<MlsProposalBundle> {
+        let signer = &self
+            .find_current_credential_bundle(client)?
+            .ok_or(CryptoError::IdentityInitializationError)?
+            .signature_key;
+        let proposal = self
+            .group
+            .propose(
+                backend,
+                signer,
+                Propose::ReInit(reinit.to_proposal()?),
+                ProposalOrRefType::Reference,
+            )
+            .map_err(MlsError::from)
+            .map_err(CryptoError::from)
+            .map(MlsProposalBundle::from)?;
+        self.persist_group_when_changed(backend, false).await?;
+        Ok(proposal)
+    }
+
     /// see [openmls::group::MlsGroup::propose_self_update]
     #[cfg_attr(test, crate::durable)]
     #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
@@ -0,0 +1,292 @@
+//! External pre-shared keys let a conversation be bound to secrets distributed out-of-band: once a
+//! PreSharedKey proposal referencing one has been committed, only the members knowing the secret
+//! can process the commit and derive the secrets of the new epoch.
+//!
+//! PSKs are kept in the keystore from which openmls looks them up on its own, be it when creating
+//! or when processing a commit.
+
+use openmls::prelude::{ExternalPsk, PreSharedKeyId, Psk};
+use openmls_traits::OpenMlsCryptoProvider;
+
+use core_crypto_keystore::entities::MlsPskBundle;
+use mls_crypto_provider::MlsCryptoProvider;
+
+use crate::prelude::{
+    Client, ConversationId, CryptoError, CryptoResult, MlsCentral, MlsCiphersuite, MlsCommitBundle, MlsConversation,
+    MlsError,
+};
+
+impl MlsCentral {
+    /// Registers an external pre-shared key in the keystore, making it available to be referenced by
+    /// PreSharedKey proposals in conversations using the given ciphersuite.
+    /// Registering again the same `psk_id` replaces the previous secret.
+    ///
+    /// # Arguments
+    /// * `ciphersuite` - of the conversations the PSK will be used in
+    /// * `psk_id` - identifier of the PSK, shared with the other parties alongside the secret
+    /// * `psk` - the secret itself
+    ///
+    /// # Errors
+    /// KeyStore errors
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self, psk_id, psk)))]
+    pub async fn register_external_psk(
+        &self,
+        ciphersuite: MlsCiphersuite,
+        psk_id: Vec<u8>,
+        psk: Vec<u8>,
+    ) -> CryptoResult<()> {
+        store_external_psk(&self.mls_backend, ciphersuite, psk_id, psk).await
+    }
+
+    /// Injects external pre-shared keys in the key schedule of a conversation by committing a PreSharedKey
+    /// proposal for each of them. Other pending proposals are committed as well.
+    ///
+    /// # Arguments
+    /// * `id` - group/conversation id
+    /// * `psk_ids` - ids of PSKs registered with [MlsCentral::register_external_psk]
+    ///
+    /// # Errors
+    /// If `psk_ids` is empty or if one of the PSKs has not been registered. Other errors are KeyStore and OpenMls errors
+    #[cfg_attr(test, crate::idempotent)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self, psk_ids), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
+    pub async fn commit_external_psks(
+        &mut self,
+        id: &ConversationId,
+        psk_ids: Vec<Vec<u8>>,
+    ) -> CryptoResult<MlsCommitBundle> {
+        if psk_ids.is_empty() {
+            return Err(CryptoError::ConsumerError);
+        }
+        let conversation = self.get_conversation(id).await?;
+        let mut conversation = conversation.write().await;
+        let mut commit = conversation
+            .commit_external_psks(self.mls_client()?, &self.mls_backend, psk_ids)
+            .await?;
+        commit.group_info = self.finalize_group_info(commit.group_info, Some(&*conversation))?;
+        Ok(commit)
+    }
+}
+
+/// see [MlsCentral::register_external_psk]
+pub(crate) async fn store_external_psk(
+    backend: &MlsCryptoProvider,
+    ciphersuite: MlsCiphersuite,
+    psk_id: Vec<u8>,
+    psk: Vec<u8>,
+) -> CryptoResult<()> {
+    // the nonce is irrelevant here since the PSK is stored under its id only
+    let psk_id = PreSharedKeyId::new(
+        ciphersuite.into(),
+        backend.rand(),
+        Psk::External(ExternalPsk::new(psk_id)),
+    )
+    .map_err(MlsError::from)?;
+    psk_id
+        .write_to_key_store(backend, ciphersuite.into(), &psk)
+        .await
+        .map_err(MlsError::from)?;
+    Ok(())
+}
+
+/// Deletes an external pre-shared key stored with [store_external_psk]
+pub(crate) async fn delete_external_psk(backend: &MlsCryptoProvider, psk_id: Vec<u8>) -> CryptoResult<()> {
+    use tls_codec::Serialize as _;
+
+    // PSKs are stored under their id only, see [store_external_psk]
+    let psk = Psk::External(ExternalPsk::new(psk_id))
+        .tls_serialize_detached()
+        .map_err(MlsError::from)?;
+    backend.key_store().remove::<MlsPskBundle, _>(psk).await?;
+    Ok(())
+}
+
+impl MlsConversation {
+    /// see [MlsCentral::commit_external_psks]
+    #[cfg_attr(test, crate::durable)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
+    pub(crate) async fn commit_external_psks(
+        &mut self,
+        client: &Client,
+        backend: &MlsCryptoProvider,
+        psk_ids: Vec<Vec<u8>>,
+    ) -> CryptoResult<MlsCommitBundle> {
+        let mut proposal_refs = Vec::with_capacity(psk_ids.len());
+        for psk_id in psk_ids {
+            let proposal = self.propose_external_psk(client, backend, psk_id).await?;
+            proposal_refs.push(proposal.proposal_ref);
+        }
+
+        match self.commit_pending_proposals(client, backend).await {
+            Ok(commit) => commit.ok_or(CryptoError::ImplementationError),
+            Err(e) => {
+                // do not leave proposals around that would fail the next commits as well
+                for proposal_ref in proposal_refs {
+                    self.group
+                        .remove_pending_proposal(backend.key_store(), &proposal_ref)
+                        .await
+                        .map_err(MlsError::from)?;
+                }
+                self.persist_group_when_changed(backend, true).await?;
+                Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::MlsConversationDecryptMessage, test_utils::*, CryptoError};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    const PSK_ID: &[u8] = b"tenant-psk";
+    const PSK: &[u8] = b"an out-of-band secret";
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_commit_external_psk(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    for central in [&alice_central.mls_central, &bob_central.mls_central] {
+                        central
+                            .register_external_psk(case.ciphersuite(), PSK_ID.to_vec(), PSK.to_vec())
+                            .await
+                            .unwrap();
+                    }
+
+                    let epoch = alice_central.mls_central.conversation_epoch(&id).await.unwrap();
+                    let commit = alice_central
+                        .mls_central
+                        .commit_external_psks(&id, vec![PSK_ID.to_vec()])
+                        .await
+                        .unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+
+                    let MlsConversationDecryptMessage { is_active, .. } = bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    assert!(is_active);
+                    assert_eq!(
+                        bob_central.mls_central.conversation_epoch(&id).await.unwrap(),
+                        epoch + 1
+                    );
+                    assert!(alice_central
+                        .mls_central
+                        .try_talk_to(&id, &mut bob_central.mls_central)
+                        .await
+                        .is_ok());
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_not_process_commit_without_external_psk(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    // only Alice knows the secret
+                    alice_central
+                        .mls_central
+                        .register_external_psk(case.ciphersuite(), PSK_ID.to_vec(), PSK.to_vec())
+                        .await
+                        .unwrap();
+
+                    let epoch = bob_central.mls_central.conversation_epoch(&id).await.unwrap();
+                    let commit = alice_central
+                        .mls_central
+                        .commit_external_psks(&id, vec![PSK_ID.to_vec()])
+                        .await
+                        .unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+
+                    let decrypted = bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.commit.to_bytes().unwrap())
+                        .await;
+                    assert!(decrypted.is_err());
+                    assert_eq!(bob_central.mls_central.conversation_epoch(&id).await.unwrap(), epoch);
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_fail_committing_unknown_external_psk(case: TestCase) {
+        run_test_with_client_ids(case.clone(), ["alice"], move |[mut alice_central]| {
+            Box::pin(async move {
+                let id = conversation_id();
+                alice_central
+                    .mls_central
+                    .new_conversation(&id, case.credential_type, case.cfg.clone())
+                    .await
+                    .unwrap();
+
+                assert!(matches!(
+                    alice_central
+                        .mls_central
+                        .commit_external_psks(&id, vec![])
+                        .await
+                        .unwrap_err(),
+                    CryptoError::ConsumerError
+                ));
+                assert!(alice_central
+                    .mls_central
+                    .commit_external_psks(&id, vec![PSK_ID.to_vec()])
+                    .await
+                    .is_err());
+
+                // the proposal has been discarded so the conversation is not stuck
+                assert_eq!(
+                    alice_central
+                        .mls_central
+                        .get_conversation_unchecked(&id)
+                        .await
+                        .group
+                        .pending_proposals()
+                        .count(),
+                    0
+                );
+                assert!(alice_central.mls_central.update_keying_material(&id).await.is_ok());
+            })
+        })
+        .await
+    }
+}
@@ -0,0 +1,328 @@
+//! A [GroupInfo] carrying only the ratchet tree changes since the previous epoch
+//! (see [draft](https://github.com/rohan-wire/ietf-drafts/blob/main/mahy-mls-ratchet-tree-delta/draft-mahy-mls-ratchet-tree-delta.md)).
+//!
+//! This encoding deviates from the draft. The draft has the committer sign a new [GroupInfo]
+//! carrying a delta extension in place of the ratchet tree extension, which OpenMLS cannot produce
+//! since it only signs [GroupInfo]s with the complete ratchet tree or none. Here the delta carries
+//! the signed [GroupInfo] without its ratchet tree extension along with the nodes which changed.
+//! Whoever holds the ratchet tree of the previous epoch can then rebuild the original [GroupInfo]
+//! byte for byte and verify it as usual.
+//!
+//! Since peers cannot interoperate with draft implementations, the encoding starts with a version
+//! ([RATCHET_TREE_DELTA_VERSION]) and a delta with any other version is rejected before anything
+//! else is read.
+//!
+//! ```text
+//! struct {
+//!     uint32 index;
+//!     optional<Node> node;
+//! } NodeUpdate;
+//!
+//! struct {
+//!     uint8 version = 1;
+//!     opaque group_info<V>;
+//!     uint32 extension_index;
+//!     uint32 tree_size;
+//!     NodeUpdate updates<V>;
+//! } RatchetTreeDelta;
+//! ```
+//!
+//! [GroupInfo]: openmls::prelude::group_info::GroupInfo
+
+use openmls::prelude::{Capabilities, Credential, Extensions, Lifetime};
+use tls_codec::{DeserializeBytes as _, Serialize as _, VLByteSlice, VLBytes};
+
+use crate::{CryptoError, CryptoResult};
+
+/// Version of the ratchet tree delta encoding produced and understood by this client
+pub const RATCHET_TREE_DELTA_VERSION: u8 = 1;
+
+const WIRE_FORMAT_GROUP_INFO: u16 = 0x0004;
+const RATCHET_TREE_EXTENSION_TYPE: u16 = 0x0002;
+const NODE_TYPE_LEAF: u8 = 1;
+const NODE_TYPE_PARENT: u8 = 2;
+const LEAF_NODE_SOURCE_KEY_PACKAGE: u8 = 1;
+const LEAF_NODE_SOURCE_UPDATE: u8 = 2;
+const LEAF_NODE_SOURCE_COMMIT: u8 = 3;
+const BLANK_NODE: &[u8] = &[0];
+
+/// Computes the delta between the ratchet tree of a [GroupInfo] wrapped in a MLS message and the
+/// ratchet tree of the previous epoch
+///
+/// [GroupInfo]: openmls::prelude::group_info::GroupInfo
+pub(crate) fn compute(group_info: &[u8], previous_ratchet_tree: &[u8]) -> CryptoResult<Vec<u8>> {
+    let parts = GroupInfoParts::parse(group_info)?;
+    let (extension_index, ratchet_tree, stripped) = parts.strip_ratchet_tree()?;
+    let entries = tree_entries(ratchet_tree)?;
+    let previous_entries = tree_entries(previous_ratchet_tree)?;
+
+    let mut updates = vec![];
+    for (index, entry) in entries.iter().enumerate() {
+        if previous_entries.get(index) != Some(entry) {
+            updates.extend_from_slice(&(index as u32).to_be_bytes());
+            updates.extend_from_slice(entry);
+        }
+    }
+
+    let mut delta = vec![RATCHET_TREE_DELTA_VERSION];
+    write_vec(&mut delta, &stripped)?;
+    delta.extend_from_slice(&extension_index.to_be_bytes());
+    delta.extend_from_slice(&(entries.len() as u32).to_be_bytes());
+    write_vec(&mut delta, &updates)?;
+    Ok(delta)
+}
+
+/// Rebuilds the complete [GroupInfo] wrapped in a MLS message from a delta and the complete
+/// [GroupInfo] of the previous epoch
+///
+/// [GroupInfo]: openmls::prelude::group_info::GroupInfo
+pub(crate) fn apply(delta: &[u8], previous_group_info: &[u8]) -> CryptoResult<Vec<u8>> {
+    let previous = GroupInfoParts::parse(previous_group_info)?;
+    let (_, previous_ratchet_tree, _) = previous.strip_ratchet_tree()?;
+    let mut entries = tree_entries(previous_ratchet_tree)?;
+
+    let (&version, mut cursor) = delta.split_first().ok_or(CryptoError::MalformedGroupInfo)?;
+    if version != RATCHET_TREE_DELTA_VERSION {
+        return Err(CryptoError::UnsupportedRatchetTreeDeltaVersion(version));
+    }
+    let stripped = read_vec(&mut cursor)?;
+    let extension_index = read::<u32>(&mut cursor)?;
+    let tree_size = read::<u32>(&mut cursor)? as usize;
+    let mut cursor_updates = read_vec(&mut cursor)?;
+    if !cursor.is_empty() {
+        return Err(CryptoError::MalformedGroupInfo);
+    }
+
+    let mut updates = vec![];
+    while !cursor_updates.is_empty() {
+        let index = read::<u32>(&mut cursor_updates)? as usize;
+        let start = cursor_updates;
+        skip_optional_node(&mut cursor_updates)?;
+        updates.push((index, &start[..start.len() - cursor_updates.len()]));
+    }
+    // every node past the previous tree is carried by the delta, even a blank one, so this bounds
+    // the size claimed by an untrusted delta by its actual length
+    if tree_size > entries.len() + updates.len() {
+        return Err(CryptoError::MalformedGroupInfo);
+    }
+
+    let parts = GroupInfoParts::parse(stripped)?;
+    if parts.group_id != previous.group_id || Some(parts.epoch) != previous.epoch.checked_add(1) {
+        return Err(CryptoError::RatchetTreeDeltaEpochMismatch);
+    }
+
+    entries.resize(tree_size, BLANK_NODE);
+    for (index, node) in updates {
+        let entry = entries.get_mut(index).ok_or(CryptoError::MalformedGroupInfo)?;
+        *entry = node;
+    }
+
+    parts.insert_ratchet_tree(extension_index, &entries.concat())
+}
+
+/// Extracts the content of a TLS serialized ratchet tree i.e. without its length prefix
+pub(crate) fn ratchet_tree_content(mut ratchet_tree: &[u8]) -> CryptoResult<&[u8]> {
+    let content = read_vec(&mut ratchet_tree)?;
+    if !ratchet_tree.is_empty() {
+        return Err(CryptoError::MalformedGroupInfo);
+    }
+    Ok(content)
+}
+
+/// Whether a [GroupInfo] wrapped in a MLS message carries the ratchet tree extension
+///
+/// [GroupInfo]: openmls::prelude::group_info::GroupInfo
+pub(crate) fn has_ratchet_tree(group_info: &[u8]) -> CryptoResult<bool> {
+    let mut cursor = GroupInfoParts::parse(group_info)?.extensions;
+    while !cursor.is_empty() {
+        let extension_type = read::<u16>(&mut cursor)?;
+        let _extension_data = read_vec(&mut cursor)?;
+        if extension_type == RATCHET_TREE_EXTENSION_TYPE {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Extracts the content of the ratchet tree extension from a [GroupInfo] wrapped in a MLS message
+///
+/// [GroupInfo]: openmls::prelude::group_info::GroupInfo
+#[cfg(test)]
+pub(crate) fn extract_ratchet_tree(group_info: &[u8]) -> CryptoResult<Vec<u8>> {
+    let (_, ratchet_tree, _) = GroupInfoParts::parse(group_info)?.strip_ratchet_tree()?;
+    Ok(ratchet_tree.to_vec())
+}
+
+/// A [GroupInfo] wrapped in a MLS message, split around its extensions
+///
+/// [GroupInfo]: openmls::prelude::group_info::GroupInfo
+struct GroupInfoParts<'a> {
+    /// Everything up to the [GroupInfo] extensions i.e. MLS message header and group context
+    ///
+    /// [GroupInfo]: openmls::prelude::group_info::GroupInfo
+    header: &'a [u8],
+    group_id: &'a [u8],
+    epoch: u64,
+    extensions: &'a [u8],
+    /// Confirmation tag, signer and signature
+    trailer: &'a [u8],
+}
+
+impl<'a> GroupInfoParts<'a> {
+    fn parse(message: &'a [u8]) -> CryptoResult<Self> {
+        let mut cursor = message;
+        let _version = read::<u16>(&mut cursor)?;
+        if read::<u16>(&mut cursor)? != WIRE_FORMAT_GROUP_INFO {
+            return Err(CryptoError::MalformedGroupInfo);
+        }
+        // group context
+        let _version = read::<u16>(&mut cursor)?;
+        let _ciphersuite = read::<u16>(&mut cursor)?;
+        let group_id = read_vec(&mut cursor)?;
+        let epoch = read::<u64>(&mut cursor)?;
+        let _tree_hash = read_vec(&mut cursor)?;
+        let _confirmed_transcript_hash = read_vec(&mut cursor)?;
+        let _group_context_extensions = read_vec(&mut cursor)?;
+
+        let header = &message[..message.len() - cursor.len()];
+        let extensions = read_vec(&mut cursor)?;
+        Ok(Self {
+            header,
+            group_id,
+            epoch,
+            extensions,
+            trailer: cursor,
+        })
+    }
+
+    /// Returns the index of the ratchet tree extension, the ratchet tree content and the MLS
+    /// message without this extension
+    fn strip_ratchet_tree(&self) -> CryptoResult<(u32, &'a [u8], Vec<u8>)> {
+        let mut ratchet_tree = None;
+        let mut other_extensions = vec![];
+        let mut cursor = self.extensions;
+        let mut index = 0u32;
+        while !cursor.is_empty() {
+            let start = cursor;
+            let extension_type = read::<u16>(&mut cursor)?;
+            let extension_data = read_vec(&mut cursor)?;
+            if extension_type == RATCHET_TREE_EXTENSION_TYPE && ratchet_tree.is_none() {
+                ratchet_tree = Some((index, ratchet_tree_content(extension_data)?));
+            } else {
+                other_extensions.extend_from_slice(&start[..start.len() - cursor.len()]);
+            }
+            index += 1;
+        }
+        let (index, ratchet_tree) = ratchet_tree.ok_or(CryptoError::MalformedGroupInfo)?;
+        Ok((index, ratchet_tree, self.assemble(&other_extensions)?))
+    }
+
+    /// Inserts back the ratchet tree extension at its original position
+    fn insert_ratchet_tree(&self, extension_index: u32, ratchet_tree: &[u8]) -> CryptoResult<Vec<u8>> {
+        let mut ratchet_tree_extension = RATCHET_TREE_EXTENSION_TYPE.to_be_bytes().to_vec();
+        let mut extension_data = vec![];
+        write_vec(&mut extension_data, ratchet_tree)?;
+        write_vec(&mut ratchet_tree_extension, &extension_data)?;
+
+        let mut extensions = vec![];
+        let mut cursor = self.extensions;
+        let mut index = 0u32;
+        loop {
+            if index == extension_index {
+                extensions.extend_from_slice(&ratchet_tree_extension);
+            }
+            if cursor.is_empty() {
+                break;
+            }
+            let start = cursor;
+            let _extension_type = read::<u16>(&mut cursor)?;
+            let _extension_data = read_vec(&mut cursor)?;
+            extensions.extend_from_slice(&start[..start.len() - cursor.len()]);
+            index += 1;
+        }
+        if index < extension_index {
+            return Err(CryptoError::MalformedGroupInfo);
+        }
+        self.assemble(&extensions)
+    }
+
+    fn assemble(&self, extensions: &[u8]) -> CryptoResult<Vec<u8>> {
+        let mut message = self.header.to_vec();
+        write_vec(&mut message, extensions)?;
+        message.extend_from_slice(self.trailer);
+        Ok(message)
+    }
+}
+
+/// Splits the content of a ratchet tree into its `optional<Node>` entries
+fn tree_entries(mut ratchet_tree: &[u8]) -> CryptoResult<Vec<&[u8]>> {
+    let mut entries = vec![];
+    while !ratchet_tree.is_empty() {
+        let start = ratchet_tree;
+        skip_optional_node(&mut ratchet_tree)?;
+        entries.push(&start[..start.len() - ratchet_tree.len()]);
+    }
+    Ok(entries)
+}
+
+/// Reads an `optional<Node>`, decoding each field of a node with its OpenMLS type
+fn skip_optional_node(cursor: &mut &[u8]) -> CryptoResult<()> {
+    match read::<u8>(cursor)? {
+        0 => Ok(()),
+        1 => skip_node(cursor),
+        _ => Err(CryptoError::MalformedGroupInfo),
+    }
+}
+
+fn skip_node(cursor: &mut &[u8]) -> CryptoResult<()> {
+    match read::<u8>(cursor)? {
+        NODE_TYPE_LEAF => {
+            let _encryption_key = read::<VLBytes>(cursor)?;
+            let _signature_key = read::<VLBytes>(cursor)?;
+            let _credential = read::<Credential>(cursor)?;
+            let _capabilities = read::<Capabilities>(cursor)?;
+            match read::<u8>(cursor)? {
+                LEAF_NODE_SOURCE_KEY_PACKAGE => {
+                    let _lifetime = read::<Lifetime>(cursor)?;
+                }
+                LEAF_NODE_SOURCE_UPDATE => {}
+                LEAF_NODE_SOURCE_COMMIT => {
+                    let _parent_hash = read::<VLBytes>(cursor)?;
+                }
+                _ => return Err(CryptoError::MalformedGroupInfo),
+            }
+            let _extensions = read::<Extensions>(cursor)?;
+            let _signature = read::<VLBytes>(cursor)?;
+        }
+        NODE_TYPE_PARENT => {
+            let _encryption_key = read::<VLBytes>(cursor)?;
+            let _parent_hash = read::<VLBytes>(cursor)?;
+            let _unmerged_leaves = read::<Vec<u32>>(cursor)?;
+        }
+        _ => return Err(CryptoError::MalformedGroupInfo),
+    }
+    Ok(())
+}
+
+/// TLS deserializes a value, advancing the cursor past it
+fn read<T: tls_codec::Deserialize>(cursor: &mut &[u8]) -> CryptoResult<T> {
+    T::tls_deserialize(cursor).map_err(|_| CryptoError::MalformedGroupInfo)
+}
+
+/// Reads a variable-length vector (see [RFC 9420 section 2.1.2](https://www.rfc-editor.org/rfc/rfc9420.html#section-2.1.2))
+/// and returns its content borrowed from the cursor
+fn read_vec<'a>(cursor: &mut &'a [u8]) -> CryptoResult<&'a [u8]> {
+    let start = *cursor;
+    let (content, rest) = VLBytes::tls_deserialize_bytes(start).map_err(|_| CryptoError::MalformedGroupInfo)?;
+    let end = start.len() - rest.len();
+    *cursor = rest;
+    Ok(&start[end - content.as_slice().len()..end])
+}
+
+/// Writes a variable-length vector with the shortest length encoding, as required by RFC 9420
+fn write_vec(out: &mut Vec<u8>, data: &[u8]) -> CryptoResult<()> {
+    VLByteSlice(data)
+        .tls_serialize(out)
+        .map_err(|_| CryptoError::MalformedGroupInfo)?;
+    Ok(())
+}
@@ -0,0 +1,454 @@
+//! A conversation is moved to another ciphersuite, e.g. a post-quantum one, by re-initializing it (see
+//! [RFC 9420 section 11.2](https://www.rfc-editor.org/rfc/rfc9420.html#section-11.2)). A commit carrying a ReInit
+//! proposal alone ends the conversation, its members are told to move to a successor group by
+//! [MlsConversationDecryptMessage::reinit]. One of them then creates the successor with the parameters of the
+//! proposal and welcomes the others.
+//!
+//! The successor is bound to the last epoch of the conversation. The MLS way would be a resumption PSK with the
+//! `reinit` usage but, as for subconversations, openmls can't resolve the resumption PSKs of another group: a secret
+//! exported from that epoch stands in for it, registered as an external PSK which is injected in the first commit
+//! of the successor. Only the members who merged the ReInit commit can derive it, hence joining the successor
+//! requires being at the same epoch of the conversation as its creator.
+//!
+//! [MlsConversationDecryptMessage::reinit]: crate::prelude::MlsConversationDecryptMessage::reinit
+
+use openmls::prelude::{KeyPackageIn, MlsMessageIn, Proposal, ReInitProposal, StagedCommit};
+use tls_codec::{Deserialize as _, Serialize as _, VLByteSlice, VLBytes};
+
+use mls_crypto_provider::MlsCryptoProvider;
+
+use crate::prelude::{
+    Client, ClientId, ConversationId, CryptoError, CryptoResult, MlsCentral, MlsCiphersuite, MlsCommitBundle,
+    MlsConversation, MlsConversationConfiguration, MlsConversationCreationMessage, MlsCredentialType,
+    MlsCustomConfiguration, MlsError, WelcomeBundle,
+};
+
+/// Parameters of the successor of a re-initialized conversation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MlsReInit {
+    /// Id of the successor group
+    pub group_id: ConversationId,
+    /// Ciphersuite of the successor group
+    pub ciphersuite: MlsCiphersuite,
+}
+
+impl MlsReInit {
+    /// Only MLS 1.0 is supported
+    const PROTOCOL_VERSION: u16 = 1;
+
+    /// openmls keeps the fields of a ReInit proposal private, hence it is built from its wire format
+    pub(crate) fn to_proposal(&self) -> CryptoResult<ReInitProposal> {
+        let mut bytes = vec![];
+        VLByteSlice(&self.group_id)
+            .tls_serialize(&mut bytes)
+            .map_err(MlsError::from)?;
+        Self::PROTOCOL_VERSION
+            .tls_serialize(&mut bytes)
+            .map_err(MlsError::from)?;
+        u16::from(self.ciphersuite)
+            .tls_serialize(&mut bytes)
+            .map_err(MlsError::from)?;
+        // no extensions
+        VLByteSlice(&[]).tls_serialize(&mut bytes).map_err(MlsError::from)?;
+        Ok(ReInitProposal::tls_deserialize_exact(bytes).map_err(MlsError::from)?)
+    }
+
+    /// Reads the parameters of a ReInit proposal from its wire format, see [MlsReInit::to_proposal]
+    pub(crate) fn try_from_proposal(proposal: &ReInitProposal) -> CryptoResult<Self> {
+        let bytes = proposal.tls_serialize_detached().map_err(MlsError::from)?;
+        let mut bytes = bytes.as_slice();
+        let group_id = VLBytes::tls_deserialize(&mut bytes).map_err(MlsError::from)?;
+        let _version = u16::tls_deserialize(&mut bytes).map_err(MlsError::from)?;
+        let ciphersuite = u16::tls_deserialize(&mut bytes).map_err(MlsError::from)?;
+        Ok(Self {
+            group_id: group_id.as_slice().to_vec(),
+            ciphersuite: MlsCiphersuite::try_from(ciphersuite)?,
+        })
+    }
+}
+
+/// Parameters of the successor if the given commit carries a ReInit proposal
+pub(crate) fn committed_reinit(commit: &StagedCommit) -> CryptoResult<Option<MlsReInit>> {
+    commit
+        .queued_proposals()
+        .find_map(|proposal| match proposal.proposal() {
+            Proposal::ReInit(reinit) => Some(reinit),
+            _ => None,
+        })
+        .map(MlsReInit::try_from_proposal)
+        .transpose()
+}
+
+impl MlsConversation {
+    const REINIT_LABEL: &'static str = "reinit";
+
+    /// Identifies the PSK binding the successor of this conversation to its current epoch
+    fn reinit_psk_id(&self) -> Vec<u8> {
+        [
+            Self::REINIT_LABEL.as_bytes(),
+            self.id.as_slice(),
+            &self.group.epoch().as_u64().to_be_bytes(),
+        ]
+        .concat()
+    }
+
+    /// Derives the PSK binding the successor of this conversation to its current epoch and registers it in the
+    /// keystore for the ciphersuite of the successor. Returns its id.
+    async fn register_reinit_psk(
+        &self,
+        backend: &MlsCryptoProvider,
+        ciphersuite: MlsCiphersuite,
+    ) -> CryptoResult<Vec<u8>> {
+        let psk = self
+            .group
+            .export_secret(backend, Self::REINIT_LABEL, &[], ciphersuite.hash_length())
+            .map_err(MlsError::from)?;
+        let psk_id = self.reinit_psk_id();
+        super::psk::store_external_psk(backend, ciphersuite, psk_id.clone(), psk).await?;
+        Ok(psk_id)
+    }
+
+    /// see [MlsCentral::reinit_conversation]
+    #[cfg_attr(test, crate::durable)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
+    async fn commit_reinit(
+        &mut self,
+        client: &Client,
+        backend: &MlsCryptoProvider,
+        reinit: &MlsReInit,
+    ) -> CryptoResult<MlsCommitBundle> {
+        // a commit carrying a ReInit proposal must not carry any other proposal
+        if self.group.pending_proposals().next().is_some() {
+            return Err(CryptoError::PendingProposalsPreventReInit);
+        }
+        let proposal = self.propose_reinit(client, backend, reinit).await?;
+
+        match self.commit_pending_proposals(client, backend).await {
+            Ok(commit) => commit.ok_or(CryptoError::ImplementationError),
+            Err(e) => {
+                // do not leave a proposal around that would end the conversation with the next commit
+                self.group
+                    .remove_pending_proposal(backend.key_store(), &proposal.proposal_ref)
+                    .await
+                    .map_err(MlsError::from)?;
+                self.persist_group_when_changed(backend, true).await?;
+                Err(e)
+            }
+        }
+    }
+}
+
+impl MlsCentral {
+    /// Ends a conversation by committing a ReInit proposal, for its members to move to a successor group under
+    /// another ciphersuite. They learn about it from [crate::prelude::MlsConversationDecryptMessage::reinit].
+    ///
+    /// As for any other commit, [MlsCentral::commit_accepted] has to be called once the Delivery Service
+    /// acknowledged it. Then the successor is created with [MlsCentral::create_reinit_successor].
+    ///
+    /// # Arguments
+    /// * `id` - group/conversation id
+    /// * `reinit` - parameters of the successor
+    ///
+    /// # Errors
+    /// [CryptoError::PendingProposalsPreventReInit] if the conversation has pending proposals, since a ReInit
+    /// proposal has to be committed alone. Other errors are KeyStore and OpenMls errors
+    #[cfg_attr(test, crate::idempotent)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self, reinit), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
+    pub async fn reinit_conversation(
+        &mut self,
+        id: &ConversationId,
+        reinit: MlsReInit,
+    ) -> CryptoResult<MlsCommitBundle> {
+        let conversation = self.get_conversation(id).await?;
+        let mut conversation = conversation.write().await;
+        let mut commit = conversation
+            .commit_reinit(self.mls_client()?, &self.mls_backend, &reinit)
+            .await?;
+        commit.group_info = self.finalize_group_info(commit.group_info, Some(&*conversation))?;
+        Ok(commit)
+    }
+
+    /// Creates the successor of a conversation whose ReInit commit has been merged, and adds the other members
+    /// to it. The first commit of the successor is bound to the last epoch of the conversation so that only its
+    /// members can join.
+    ///
+    /// The successor uses the configuration of the conversation, the ciphersuite aside, and the same credential
+    /// type. As for [MlsCentral::add_members_to_conversation], the commit has to be accepted once the Delivery
+    /// Service acknowledged it.
+    ///
+    /// # Arguments
+    /// * `id` - id of the re-initialized conversation
+    /// * `reinit` - parameters of the successor, as committed in the conversation
+    /// * `key_packages` - of the members to add, for the ciphersuite of the successor. They all have to be members
+    ///   of the conversation
+    ///
+    /// # Errors
+    /// If the conversation does not exist, if the successor already exists or if a KeyPackage belongs to a client
+    /// outside of the conversation. Other errors are KeyStore and OpenMls errors
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self, reinit, key_packages), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
+    pub async fn create_reinit_successor(
+        &mut self,
+        id: &ConversationId,
+        reinit: MlsReInit,
+        key_packages: Vec<KeyPackageIn>,
+    ) -> CryptoResult<MlsConversationCreationMessage> {
+        let successor_id = &reinit.group_id;
+        if self.conversation_exists(successor_id).await || self.pending_group_exists(successor_id).await {
+            return Err(CryptoError::ConversationAlreadyExists(successor_id.clone()));
+        }
+
+        let conversation = self.get_conversation(id).await?;
+        let conversation = conversation.read().await;
+        conversation.ensure_members(key_packages.iter().map(|kp| ClientId::from(kp.credential().identity())))?;
+        let psk_id = conversation
+            .register_reinit_psk(&self.mls_backend, reinit.ciphersuite)
+            .await?;
+        let credential_type = conversation.own_credential_type()?;
+        let configuration = MlsConversationConfiguration {
+            ciphersuite: reinit.ciphersuite,
+            ..conversation.configuration.clone()
+        };
+        drop(conversation);
+
+        let commit = self
+            .create_successor(
+                successor_id,
+                credential_type,
+                configuration,
+                psk_id.clone(),
+                key_packages,
+            )
+            .await;
+        // the PSK is only needed to create the first commit
+        self.delete_reinit_psk(psk_id).await;
+
+        // do not leave a half-baked successor behind
+        if commit.is_err() && self.conversation_exists(successor_id).await {
+            if let Err(e) = self.wipe_conversation(successor_id).await {
+                tracing::warn!(error = %e, "Failed to wipe a successor which could not be created");
+            }
+        }
+        commit
+    }
+
+    /// Creates the successor and its first commit. The successor is registered even when the commit fails, for it
+    /// to be wiped
+    async fn create_successor(
+        &mut self,
+        id: &ConversationId,
+        credential_type: MlsCredentialType,
+        configuration: MlsConversationConfiguration,
+        psk_id: Vec<u8>,
+        key_packages: Vec<KeyPackageIn>,
+    ) -> CryptoResult<MlsConversationCreationMessage> {
+        let mls_client = self.mls_client.as_mut().ok_or(CryptoError::MlsNotInitialized)?;
+        let mut conversation = MlsConversation::create(
+            id.clone(),
+            mls_client,
+            credential_type,
+            configuration,
+            self.group_info_ratchet_tree_type,
+            &self.mls_backend,
+        )
+        .await?;
+
+        let client = self.mls_client()?;
+        let commit = match conversation
+            .propose_external_psk(client, &self.mls_backend, psk_id)
+            .await
+        {
+            Ok(_) => conversation.add_members(client, key_packages, &self.mls_backend).await,
+            Err(e) => Err(e),
+        };
+        let commit = commit.and_then(|mut commit| {
+            commit.group_info = self.finalize_group_info(commit.group_info, Some(&conversation))?;
+            Ok(commit)
+        });
+        // inserted either way so that it can be wiped on error
+        self.mls_groups.insert(id.clone(), conversation);
+        commit
+    }
+
+    /// Deletes the PSK binding a successor to its conversation once it has been used
+    async fn delete_reinit_psk(&self, psk_id: Vec<u8>) {
+        if let Err(e) = super::psk::delete_external_psk(&self.mls_backend, psk_id).await {
+            tracing::warn!(error = %e, "Failed to delete the PSK of a successor");
+        }
+    }
+
+    /// Joins the successor of a re-initialized conversation from the Welcome generated by
+    /// [MlsCentral::create_reinit_successor]. The secret proving the membership to the conversation is derived from
+    /// its local epoch, which has to be the one following the ReInit commit.
+    ///
+    /// # Arguments
+    /// * `id` - id of the re-initialized conversation
+    /// * `welcome` - the Welcome message of the successor
+    /// * `custom_cfg` - configuration of the successor
+    ///
+    /// # Errors
+    /// If the conversation does not exist, if the local conversation is not at the epoch the successor was created
+    /// at or if the successor has members outside of the conversation. Other errors are the ones of
+    /// [MlsCentral::process_welcome_message]
+    #[cfg_attr(test, crate::dispotent)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self, welcome), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
+    pub async fn join_reinit_successor(
+        &mut self,
+        id: &ConversationId,
+        welcome: MlsMessageIn,
+        custom_cfg: MlsCustomConfiguration,
+    ) -> CryptoResult<WelcomeBundle> {
+        let welcome = super::welcome::extract_welcome(welcome)?;
+        let conversation = self.get_conversation(id).await?;
+        let psk_id = conversation
+            .read()
+            .await
+            .register_reinit_psk(&self.mls_backend, welcome.ciphersuite().into())
+            .await?;
+
+        let bundle = self.process_welcome(welcome, None, custom_cfg).await;
+        // the PSK is only needed to process the Welcome
+        self.delete_reinit_psk(psk_id).await;
+        let bundle = bundle?;
+
+        let successor = self.get_conversation(&bundle.id).await?;
+        let members = successor.read().await.get_client_ids();
+        if let Err(e) = conversation.read().await.ensure_members(members) {
+            self.wipe_conversation(&bundle.id).await?;
+            return Err(e);
+        }
+        Ok(bundle)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use openmls::prelude::Ciphersuite;
+    use openmls_traits::types::SignatureScheme;
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::MlsConversationDecryptMessage, test_utils::*, CryptoError};
+
+    use super::MlsReInit;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    const POST_QUANTUM_CIPHERSUITE: Ciphersuite = Ciphersuite::MLS_128_X25519KYBER768DRAFT00_AES128GCM_SHA256_Ed25519;
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_move_conversation_to_post_quantum_ciphersuite(case: TestCase) {
+        // the successor is signed with the same keys, so only Ed25519 conversations can move to it
+        if case.signature_scheme() != SignatureScheme::ED25519 {
+            return;
+        }
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let pq_case = TestCase::new(case.credential_type, POST_QUANTUM_CIPHERSUITE);
+                    let reinit = MlsReInit {
+                        group_id: conversation_id(),
+                        ciphersuite: pq_case.ciphersuite(),
+                    };
+                    let commit = alice_central
+                        .mls_central
+                        .reinit_conversation(&id, reinit.clone())
+                        .await
+                        .unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+
+                    let MlsConversationDecryptMessage { reinit: received, .. } = bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    assert_eq!(received, Some(reinit.clone()));
+
+                    let bob_kp = bob_central.mls_central.rand_key_package(&pq_case).await;
+                    let creation = alice_central
+                        .mls_central
+                        .create_reinit_successor(&id, reinit.clone(), vec![bob_kp])
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .commit_accepted(&reinit.group_id)
+                        .await
+                        .unwrap();
+                    bob_central
+                        .mls_central
+                        .join_reinit_successor(&id, creation.welcome.into(), case.custom_cfg())
+                        .await
+                        .unwrap();
+
+                    for central in [&mut alice_central.mls_central, &mut bob_central.mls_central] {
+                        assert_eq!(
+                            central.conversation_ciphersuite(&reinit.group_id).await.unwrap(),
+                            pq_case.ciphersuite()
+                        );
+                        // the PSK binding the successor to the conversation is not needed anymore
+                        assert_eq!(central.count_entities().await.psk_bundle, 0);
+                    }
+                    assert!(alice_central
+                        .mls_central
+                        .try_talk_to(&reinit.group_id, &mut bob_central.mls_central)
+                        .await
+                        .is_ok());
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_commit_reinit_alone(case: TestCase) {
+        run_test_with_client_ids(case.clone(), ["alice"], move |[mut alice_central]| {
+            Box::pin(async move {
+                let id = conversation_id();
+                alice_central
+                    .mls_central
+                    .new_conversation(&id, case.credential_type, case.cfg.clone())
+                    .await
+                    .unwrap();
+                alice_central.mls_central.new_update_proposal(&id).await.unwrap();
+
+                let reinit = MlsReInit {
+                    group_id: conversation_id(),
+                    ciphersuite: POST_QUANTUM_CIPHERSUITE.into(),
+                };
+                let result = alice_central.mls_central.reinit_conversation(&id, reinit).await;
+                assert!(matches!(
+                    result.unwrap_err(),
+                    CryptoError::PendingProposalsPreventReInit
+                ));
+                assert!(alice_central.mls_central.pending_commit(&id).await.is_none());
+            })
+        })
+        .await
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    pub fn should_read_back_reinit_proposal() {
+        let reinit = MlsReInit {
+            group_id: conversation_id(),
+            ciphersuite: POST_QUANTUM_CIPHERSUITE.into(),
+        };
+        let proposal = reinit.to_proposal().unwrap();
+        assert_eq!(MlsReInit::try_from_proposal(&proposal).unwrap(), reinit);
+    }
+}
@@ -0,0 +1,371 @@
+//! A detailed view of the members of a conversation, including the changes pending proposals will
+//! bring in the next epoch.
+//!
+//! MLS does not record when a member joined, so the epoch in which each leaf was added is tracked
+//! in the keystore as commits get merged. It is only known for members added after we joined.
+
+use std::collections::HashMap;
+
+use openmls::prelude::{KeyPackage, LeafNode, Node, Proposal};
+use serde::{Deserialize, Serialize};
+use tls_codec::{Deserialize as _, Serialize as _};
+
+use core_crypto_keystore::entities::MlsMemberEpochs;
+use mls_crypto_provider::MlsCryptoProvider;
+use openmls_traits::OpenMlsCryptoProvider;
+
+use crate::prelude::{
+    ClientId, ConversationId, CryptoResult, MlsCentral, MlsConversation, MlsCredentialType, MlsError,
+};
+
+/// Whether a member is part of the current epoch and whether pending proposals change that
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum MlsMemberStatus {
+    /// Member of the current epoch
+    Active,
+    /// Member of the current epoch whom a pending proposal removes
+    PendingRemoval,
+    /// Added by a pending proposal, not a member of the current epoch yet
+    PendingAddition,
+}
+
+/// A member of a conversation, see [MlsCentral::get_conversation_roster]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MlsConversationMember {
+    /// Identifier of the client
+    pub client_id: ClientId,
+    /// Position of the member in the ratchet tree. `None` for pending additions
+    pub leaf_index: Option<u32>,
+    /// Type of the member's Credential
+    pub credential_type: MlsCredentialType,
+    /// Public key the member signs its messages with
+    pub signature_key: Vec<u8>,
+    /// Ciphersuites supported by the member, as raw values
+    pub ciphersuites: Vec<u16>,
+    /// Extension types supported by the member on top of the default ones, as raw values
+    pub extensions: Vec<u16>,
+    /// Epoch in which the member was added. `None` when this happened before we joined the
+    /// conversation or for pending additions
+    pub added_in_epoch: Option<u64>,
+    /// Whether the member is part of the current epoch
+    pub status: MlsMemberStatus,
+}
+
+/// Capabilities of a leaf node shown in the roster, as raw values
+#[derive(Debug, Default)]
+struct LeafCapabilities {
+    ciphersuites: Vec<u16>,
+    extensions: Vec<u16>,
+}
+
+impl LeafCapabilities {
+    fn new(leaf_node: &LeafNode) -> CryptoResult<Self> {
+        let capabilities = leaf_node.capabilities();
+        let ciphersuites = capabilities
+            .ciphersuites()
+            .iter()
+            .map(|cs| {
+                // keeps the ciphersuites unknown to OpenMLS, e.g. GREASE values
+                let cs = cs.tls_serialize_detached().map_err(MlsError::from)?;
+                Ok(u16::tls_deserialize(&mut cs.as_slice()).map_err(MlsError::from)?)
+            })
+            .collect::<CryptoResult<_>>()?;
+        let extensions = capabilities.extensions().iter().map(|t| u16::from(*t)).collect();
+        Ok(Self {
+            ciphersuites,
+            extensions,
+        })
+    }
+}
+
+/// Epoch in which the member at a given leaf was added
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MemberEpoch {
+    leaf_index: u32,
+    client_id: Vec<u8>,
+    epoch: Option<u64>,
+}
+
+impl MlsConversation {
+    /// Builds the roster of this conversation: the members of the current epoch ordered by leaf
+    /// index followed by the pending additions
+    pub async fn roster(&self, backend: &MlsCryptoProvider) -> CryptoResult<Vec<MlsConversationMember>> {
+        let ratchet_tree = self.group.export_ratchet_tree();
+        let mut capabilities = HashMap::new();
+        // leaves are the even nodes of the tree
+        for (leaf_index, node) in ratchet_tree.iter().step_by(2).enumerate() {
+            if let Some(Node::LeafNode(leaf_node)) = node {
+                capabilities.insert(leaf_index as u32, LeafCapabilities::new(leaf_node)?);
+            }
+        }
+        let added_in_epochs = self
+            .load_member_epochs(backend)
+            .await?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|m| ((m.leaf_index, m.client_id), m.epoch))
+            .collect::<HashMap<_, _>>();
+        let pending_removals = self.pending_removals();
+
+        let mut roster = self
+            .group
+            .members()
+            .map(|member| {
+                let leaf_index = member.index.u32();
+                let client_id = member.credential.identity().to_vec();
+                let capabilities = capabilities.remove(&leaf_index).unwrap_or_default();
+                let status = if pending_removals.contains(&member.index) {
+                    MlsMemberStatus::PendingRemoval
+                } else {
+                    MlsMemberStatus::Active
+                };
+                MlsConversationMember {
+                    added_in_epoch: added_in_epochs.get(&(leaf_index, client_id.clone())).copied().flatten(),
+                    client_id: client_id.into(),
+                    leaf_index: Some(leaf_index),
+                    credential_type: member.credential.credential_type().into(),
+                    signature_key: member.signature_key,
+                    ciphersuites: capabilities.ciphersuites,
+                    extensions: capabilities.extensions,
+                    status,
+                }
+            })
+            .collect::<Vec<_>>();
+        roster.sort_by_key(|m| m.leaf_index);
+
+        for key_package in self.pending_additions() {
+            let leaf_node = key_package.leaf_node();
+            let capabilities = LeafCapabilities::new(leaf_node)?;
+            roster.push(MlsConversationMember {
+                client_id: leaf_node.credential().identity().into(),
+                leaf_index: None,
+                credential_type: leaf_node.credential().credential_type().into(),
+                signature_key: leaf_node.signature_key().as_slice().to_vec(),
+                ciphersuites: capabilities.ciphersuites,
+                extensions: capabilities.extensions,
+                added_in_epoch: None,
+                status: MlsMemberStatus::PendingAddition,
+            });
+        }
+
+        Ok(roster)
+    }
+
+    /// Gather the KeyPackages of pending add proposals
+    fn pending_additions(&self) -> impl Iterator<Item = &KeyPackage> {
+        self.group
+            .pending_proposals()
+            .filter_map(|proposal| match proposal.proposal() {
+                Proposal::Add(add) => Some(add.key_package()),
+                _ => None,
+            })
+    }
+
+    /// Starts tracking the epoch in which members are added in a newly created or joined
+    /// conversation. Only the own leaf is known to have been added in the current epoch
+    pub(crate) async fn init_member_epochs(&self, backend: &MlsCryptoProvider) -> CryptoResult<()> {
+        let own_index = self.group.own_leaf_index();
+        let epoch = self.group.epoch().as_u64();
+        let members = self
+            .group
+            .members()
+            .map(|member| MemberEpoch {
+                leaf_index: member.index.u32(),
+                client_id: member.credential.identity().to_vec(),
+                epoch: (member.index == own_index).then_some(epoch),
+            })
+            .collect::<Vec<_>>();
+        self.save_member_epochs(backend, &members).await
+    }
+
+    /// Records the current epoch for every leaf which was not known in the previous one
+    pub(crate) async fn record_member_epochs(&self, backend: &MlsCryptoProvider) -> CryptoResult<()> {
+        // for conversations created before this was tracked, nobody is known to have been added recently
+        let previous = self.load_member_epochs(backend).await?;
+        let epoch = previous.is_some().then(|| self.group.epoch().as_u64());
+        let previous = previous
+            .unwrap_or_default()
+            .into_iter()
+            .map(|m| ((m.leaf_index, m.client_id), m.epoch))
+            .collect::<HashMap<_, _>>();
+
+        let members = self
+            .group
+            .members()
+            .map(|member| {
+                let leaf_index = member.index.u32();
+                let client_id = member.credential.identity().to_vec();
+                let epoch = match previous.get(&(leaf_index, client_id.clone())) {
+                    Some(known) => *known,
+                    None => epoch,
+                };
+                MemberEpoch {
+                    leaf_index,
+                    client_id,
+                    epoch,
+                }
+            })
+            .collect::<Vec<_>>();
+        self.save_member_epochs(backend, &members).await
+    }
+
+    async fn load_member_epochs(&self, backend: &MlsCryptoProvider) -> CryptoResult<Option<Vec<MemberEpoch>>> {
+        match backend.key_store().find::<MlsMemberEpochs>(&self.id).await? {
+            Some(entity) => Ok(Some(
+                serde_json::from_slice(&entity.members).map_err(MlsError::MlsKeystoreSerializationError)?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    async fn save_member_epochs(&self, backend: &MlsCryptoProvider, members: &[MemberEpoch]) -> CryptoResult<()> {
+        let members = serde_json::to_vec(members).map_err(MlsError::MlsKeystoreSerializationError)?;
+        let entity = MlsMemberEpochs {
+            id: self.id.clone(),
+            members,
+        };
+        backend.key_store().save(entity).await?;
+        Ok(())
+    }
+}
+
+impl MlsCentral {
+    /// Returns a detailed view of the members of a conversation. Members which pending proposals
+    /// add or remove are included with the according [MlsMemberStatus]
+    ///
+    /// # Arguments
+    /// * `id` - the conversation id
+    ///
+    /// # Errors
+    /// If the conversation can't be found or from the KeyStore
+    #[cfg_attr(test, crate::idempotent)]
+    pub async fn get_conversation_roster(&mut self, id: &ConversationId) -> CryptoResult<Vec<MlsConversationMember>> {
+        self.get_conversation(id)
+            .await?
+            .read()
+            .await
+            .roster(&self.mls_backend)
+            .await
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use super::*;
+    use crate::test_utils::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_list_members(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let alice_id = alice_central.mls_central.client_id().unwrap();
+                    let bob_id = bob_central.mls_central.client_id().unwrap();
+
+                    let roster = alice_central.mls_central.get_conversation_roster(&id).await.unwrap();
+                    assert_eq!(roster.len(), 2);
+                    let (alice, bob) = (&roster[0], &roster[1]);
+                    assert_eq!(alice.client_id, alice_id);
+                    assert_eq!(alice.leaf_index, Some(0));
+                    assert_eq!(alice.added_in_epoch, Some(0));
+                    assert_eq!(bob.client_id, bob_id);
+                    assert_eq!(bob.leaf_index, Some(1));
+                    assert_eq!(bob.added_in_epoch, Some(1));
+                    for member in &roster {
+                        assert_eq!(member.status, MlsMemberStatus::Active);
+                        assert_eq!(member.credential_type, case.credential_type);
+                        assert!(member.ciphersuites.contains(&u16::from(case.ciphersuite())));
+                    }
+                    assert_eq!(
+                        alice.signature_key,
+                        alice_central.mls_central.client_signature_key(&case).as_slice()
+                    );
+
+                    // alice was added before bob joined
+                    let roster = bob_central.mls_central.get_conversation_roster(&id).await.unwrap();
+                    assert_eq!(roster[0].client_id, alice_id);
+                    assert_eq!(roster[0].added_in_epoch, None);
+                    assert_eq!(roster[1].client_id, bob_id);
+                    assert_eq!(roster[1].added_in_epoch, Some(1));
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_show_pending_changes(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob", "guest"],
+            move |[mut alice_central, mut bob_central, guest_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let bob_id = bob_central.mls_central.client_id().unwrap();
+                    let guest_id = guest_central.mls_central.client_id().unwrap();
+                    let guest_kp = guest_central.mls_central.get_one_key_package(&case).await;
+                    alice_central.mls_central.new_add_proposal(&id, guest_kp).await.unwrap();
+                    alice_central
+                        .mls_central
+                        .new_remove_proposal(&id, bob_id.clone())
+                        .await
+                        .unwrap();
+
+                    let roster = alice_central.mls_central.get_conversation_roster(&id).await.unwrap();
+                    assert_eq!(roster.len(), 3);
+                    assert_eq!(roster[1].client_id, bob_id);
+                    assert_eq!(roster[1].status, MlsMemberStatus::PendingRemoval);
+                    let guest = &roster[2];
+                    assert_eq!(guest.client_id, guest_id);
+                    assert_eq!(guest.status, MlsMemberStatus::PendingAddition);
+                    assert_eq!(guest.leaf_index, None);
+                    assert_eq!(guest.credential_type, case.credential_type);
+                    assert!(guest.ciphersuites.contains(&u16::from(case.ciphersuite())));
+
+                    alice_central.mls_central.commit_pending_proposals(&id).await.unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    let epoch = alice_central.mls_central.conversation_epoch(&id).await.unwrap();
+
+                    let roster = alice_central.mls_central.get_conversation_roster(&id).await.unwrap();
+                    assert_eq!(roster.len(), 2);
+                    assert!(roster.iter().all(|m| m.status == MlsMemberStatus::Active));
+                    let guest = roster.iter().find(|m| m.client_id == guest_id).unwrap();
+                    assert_eq!(guest.added_in_epoch, Some(epoch));
+                    assert!(guest.leaf_index.is_some());
+                })
+            },
+        )
+        .await
+    }
+}
@@ -77,6 +77,12 @@ impl MlsConversation {
         &mut self,
         backend: &MlsCryptoProvider,
     ) -> CryptoResult<MlsConversationDecryptMessage> {
+        let reinit = self
+            .group
+            .pending_commit()
+            .map(super::reinit::committed_reinit)
+            .transpose()?
+            .flatten();
         self.commit_accepted(backend).await?;
 
         let own_leaf = self.group.own_leaf().ok_or(CryptoError::InternalMlsError)?;
@@ -97,10 +103,12 @@ impl MlsConversation {
             is_active: self.group.is_active(),
             delay: self.compute_next_commit_delay(),
             sender_client_id: None,
+            aad: None,
             has_epoch_changed: true,
             identity,
             buffered_messages: None,
             crl_new_distribution_points,
+            reinit,
         })
     }
 }
@@ -0,0 +1,498 @@
+//! Subconversations are groups branched off a parent conversation with a subset of its members, e.g. for
+//! conference calls. Their link to the parent is recorded in [PersistedMlsGroup::parent_id] and they are wiped
+//! along with it.
+//!
+//! The MLS way of proving that the members of a subgroup belong to its parent would be a branch resumption PSK.
+//! openmls neither supports branching nor resolving the resumption PSKs of another group, so a secret exported
+//! from the key schedule of the parent stands in for it: it is registered as an external PSK which is injected in
+//! the first commit of the subconversation. Only the members of the parent at that epoch can derive it, hence
+//! joining a subconversation requires being at the same epoch of the parent as its creator.
+
+use core_crypto_keystore::{
+    entities::{EntityFindParams, PersistedMlsGroup},
+    CryptoKeystoreMls,
+};
+use mls_crypto_provider::MlsCryptoProvider;
+use openmls::prelude::{KeyPackageIn, MlsMessageIn};
+use openmls_traits::OpenMlsCryptoProvider;
+
+use crate::prelude::{
+    Client, ClientId, ConversationId, CryptoError, CryptoResult, MlsCentral, MlsConversation,
+    MlsConversationConfiguration, MlsConversationCreationMessage, MlsCredentialType, MlsCustomConfiguration, MlsError,
+    WelcomeBundle,
+};
+
+impl MlsConversation {
+    const SUBCONVERSATION_LABEL: &'static str = "subconversation";
+
+    /// Identifies the PSK binding subconversations to this conversation at its current epoch
+    fn subconversation_psk_id(&self) -> Vec<u8> {
+        [
+            Self::SUBCONVERSATION_LABEL.as_bytes(),
+            self.id.as_slice(),
+            &self.group.epoch().as_u64().to_be_bytes(),
+        ]
+        .concat()
+    }
+
+    /// Derives the PSK binding subconversations to this conversation at its current epoch and registers it
+    /// in the keystore. Returns its id.
+    async fn register_subconversation_psk(&self, backend: &MlsCryptoProvider) -> CryptoResult<Vec<u8>> {
+        let psk = self
+            .group
+            .export_secret(
+                backend,
+                Self::SUBCONVERSATION_LABEL,
+                &[],
+                self.ciphersuite().hash_length(),
+            )
+            .map_err(MlsError::from)?;
+        let psk_id = self.subconversation_psk_id();
+        super::psk::store_external_psk(backend, self.ciphersuite(), psk_id.clone(), psk).await?;
+        Ok(psk_id)
+    }
+
+    /// Makes a freshly created conversation a subconversation of `parent_id` by adding the first members
+    /// in a commit injecting the PSK derived from the parent
+    async fn branch_from(
+        &mut self,
+        client: &Client,
+        backend: &MlsCryptoProvider,
+        parent_id: &ConversationId,
+        psk_id: Vec<u8>,
+        key_packages: Vec<KeyPackageIn>,
+    ) -> CryptoResult<MlsConversationCreationMessage> {
+        self.mark_as_child_of(parent_id, backend).await?;
+        self.propose_external_psk(client, backend, psk_id).await?;
+        self.add_members(client, key_packages, backend).await
+    }
+
+    /// Fails when a client is not a member of this conversation
+    pub(crate) fn ensure_members(&self, clients: impl IntoIterator<Item = ClientId>) -> CryptoResult<()> {
+        let members = self.get_client_ids();
+        clients.into_iter().try_for_each(|client_id| {
+            if members.contains(&client_id) {
+                Ok(())
+            } else {
+                Err(CryptoError::ClientNotFound(client_id))
+            }
+        })
+    }
+}
+
+impl MlsCentral {
+    /// Creates a subconversation of an existing conversation and adds some of its members to it. The first commit
+    /// is bound to the current epoch of the parent so that only its members can join.
+    ///
+    /// The subconversation uses the configuration of its parent and the same credential type. As for
+    /// [MlsCentral::add_members_to_conversation], the commit has to be accepted once the Delivery Service
+    /// acknowledged it.
+    ///
+    /// # Arguments
+    /// * `parent_id` - id of the parent conversation
+    /// * `id` - id of the subconversation
+    /// * `key_packages` - of the members to add, who all have to be members of the parent
+    ///
+    /// # Errors
+    /// If the parent does not exist, if the subconversation already exists or if a KeyPackage belongs to a client
+    /// outside of the parent. Other errors are KeyStore and OpenMls errors
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self, key_packages), fields(parent_id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, parent_id), id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
+    pub async fn create_subconversation(
+        &mut self,
+        parent_id: &ConversationId,
+        id: &ConversationId,
+        key_packages: Vec<KeyPackageIn>,
+    ) -> CryptoResult<MlsConversationCreationMessage> {
+        if self.conversation_exists(id).await || self.pending_group_exists(id).await {
+            return Err(CryptoError::ConversationAlreadyExists(id.clone()));
+        }
+
+        let parent = self
+            .get_conversation(parent_id)
+            .await
+            .map_err(|_| CryptoError::ParentGroupNotFound)?;
+        let parent = parent.read().await;
+        parent.ensure_members(key_packages.iter().map(|kp| ClientId::from(kp.credential().identity())))?;
+        let psk_id = parent.register_subconversation_psk(&self.mls_backend).await?;
+        let credential_type = parent.own_credential_type()?;
+        let configuration = parent.configuration.clone();
+        drop(parent);
+
+        let commit = self
+            .branch_subconversation(
+                parent_id,
+                id,
+                credential_type,
+                configuration,
+                psk_id.clone(),
+                key_packages,
+            )
+            .await;
+        // the PSK is only needed to create the first commit
+        self.delete_subconversation_psk(psk_id).await;
+
+        // do not leave a half-baked subconversation behind
+        if commit.is_err() && self.conversation_exists(id).await {
+            if let Err(e) = self.wipe_conversation(id).await {
+                tracing::warn!(error = %e, "Failed to wipe a subconversation which could not be created");
+            }
+        }
+        commit
+    }
+
+    /// Creates the subconversation and its first commit. The subconversation is registered even when the commit
+    /// fails, for it to be wiped
+    async fn branch_subconversation(
+        &mut self,
+        parent_id: &ConversationId,
+        id: &ConversationId,
+        credential_type: MlsCredentialType,
+        configuration: MlsConversationConfiguration,
+        psk_id: Vec<u8>,
+        key_packages: Vec<KeyPackageIn>,
+    ) -> CryptoResult<MlsConversationCreationMessage> {
+        let mls_client = self.mls_client.as_mut().ok_or(CryptoError::MlsNotInitialized)?;
+        let mut conversation = MlsConversation::create(
+            id.clone(),
+            mls_client,
+            credential_type,
+            configuration,
+            self.group_info_ratchet_tree_type,
+            &self.mls_backend,
+        )
+        .await?;
+
+        let commit = conversation
+            .branch_from(self.mls_client()?, &self.mls_backend, parent_id, psk_id, key_packages)
+            .await;
+        let commit = commit.and_then(|mut commit| {
+            commit.group_info = self.finalize_group_info(commit.group_info, Some(&conversation))?;
+            Ok(commit)
+        });
+        // inserted either way so that it can be wiped on error
+        self.mls_groups.insert(id.clone(), conversation);
+        commit
+    }
+
+    /// Deletes the PSK binding a subconversation to its parent once it has been used
+    async fn delete_subconversation_psk(&self, psk_id: Vec<u8>) {
+        if let Err(e) = super::psk::delete_external_psk(&self.mls_backend, psk_id).await {
+            tracing::warn!(error = %e, "Failed to delete the PSK of a subconversation");
+        }
+    }
+
+    /// Joins a subconversation from the Welcome generated by [MlsCentral::create_subconversation]. The secret
+    /// proving the membership to the parent is derived from the current epoch of the local parent conversation.
+    ///
+    /// # Arguments
+    /// * `parent_id` - id of the parent conversation
+    /// * `welcome` - the Welcome message of the subconversation
+    /// * `custom_cfg` - configuration of the subconversation
+    ///
+    /// # Errors
+    /// If the parent does not exist, if the local parent is not at the epoch the subconversation was created at or
+    /// if the subconversation has members outside of the parent. Other errors are the ones of
+    /// [MlsCentral::process_welcome_message]
+    #[cfg_attr(test, crate::dispotent)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self, welcome), fields(parent_id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, parent_id))))]
+    pub async fn join_subconversation(
+        &mut self,
+        parent_id: &ConversationId,
+        welcome: MlsMessageIn,
+        custom_cfg: MlsCustomConfiguration,
+    ) -> CryptoResult<WelcomeBundle> {
+        let parent = self
+            .get_conversation(parent_id)
+            .await
+            .map_err(|_| CryptoError::ParentGroupNotFound)?;
+        let psk_id = parent
+            .read()
+            .await
+            .register_subconversation_psk(&self.mls_backend)
+            .await?;
+
+        let bundle = self.process_welcome_message(welcome, custom_cfg).await;
+        // the PSK is only needed to process the Welcome
+        self.delete_subconversation_psk(psk_id).await;
+        let bundle = bundle?;
+
+        let conversation = self.get_conversation(&bundle.id).await?;
+        let members = conversation.read().await.get_client_ids();
+        let joined = match parent.read().await.ensure_members(members) {
+            Ok(()) => {
+                conversation
+                    .write()
+                    .await
+                    .mark_as_child_of(parent_id, &self.mls_backend)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = joined {
+            self.wipe_conversation(&bundle.id).await?;
+            return Err(e);
+        }
+        Ok(bundle)
+    }
+
+    /// Lists the ids of the subconversations of a conversation
+    ///
+    /// # Errors
+    /// If the conversation does not exist. Other errors are KeyStore errors
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
+    pub async fn list_subconversations(&self, id: &ConversationId) -> CryptoResult<Vec<ConversationId>> {
+        let keystore = self.mls_backend.key_store();
+        if keystore.find::<PersistedMlsGroup>(id).await?.is_none() {
+            return Err(CryptoError::ConversationNotFound(id.clone()));
+        }
+        // goes through the keystore rather than its connection to see the subconversations staged in a transaction
+        Ok(keystore
+            .find_all::<PersistedMlsGroup>(EntityFindParams::default())
+            .await?
+            .into_iter()
+            .filter(|group| group.parent_id.as_deref() == Some(id.as_slice()))
+            .map(|child| child.id.clone())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::{prelude::MlsConversationDecryptMessage, test_utils::*, CryptoError};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_create_and_join_subconversation(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob", "charlie"],
+            move |[mut alice_central, mut bob_central, mut charlie_central]| {
+                Box::pin(async move {
+                    let parent_id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&parent_id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(
+                            &case,
+                            &parent_id,
+                            [&mut bob_central.mls_central, &mut charlie_central.mls_central],
+                        )
+                        .await
+                        .unwrap();
+
+                    // only Bob joins the call
+                    let id = conversation_id();
+                    let bob_kp = bob_central.mls_central.rand_key_package(&case).await;
+                    let creation = alice_central
+                        .mls_central
+                        .create_subconversation(&parent_id, &id, vec![bob_kp])
+                        .await
+                        .unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+
+                    bob_central
+                        .mls_central
+                        .join_subconversation(&parent_id, creation.welcome.into(), case.custom_cfg())
+                        .await
+                        .unwrap();
+                    assert_eq!(bob_central.mls_central.get_client_ids(&id).await.unwrap().len(), 2);
+                    // the PSK binding the subconversation to its parent is not needed anymore
+                    for central in [&alice_central.mls_central, &bob_central.mls_central] {
+                        assert_eq!(central.count_entities().await.psk_bundle, 0);
+                    }
+                    assert!(alice_central
+                        .mls_central
+                        .try_talk_to(&id, &mut bob_central.mls_central)
+                        .await
+                        .is_ok());
+
+                    for central in [&alice_central.mls_central, &bob_central.mls_central] {
+                        assert_eq!(
+                            central.list_subconversations(&parent_id).await.unwrap(),
+                            vec![id.clone()]
+                        );
+                    }
+                    assert!(charlie_central
+                        .mls_central
+                        .list_subconversations(&parent_id)
+                        .await
+                        .unwrap()
+                        .is_empty());
+
+                    // messages of the subconversation are not readable in the parent
+                    let msg = alice_central.mls_central.encrypt_message(&id, b"hello").await.unwrap();
+                    let MlsConversationDecryptMessage { app_msg, .. } =
+                        bob_central.mls_central.decrypt_message(&id, &msg).await.unwrap();
+                    assert_eq!(app_msg.unwrap(), b"hello");
+                    assert!(charlie_central
+                        .mls_central
+                        .decrypt_message(&parent_id, &msg)
+                        .await
+                        .is_err());
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_not_create_subconversation_with_outsiders(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob", "guest"],
+            move |[mut alice_central, mut bob_central, guest_central]| {
+                Box::pin(async move {
+                    let parent_id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&parent_id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &parent_id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let id = conversation_id();
+                    let guest_kp = guest_central.mls_central.rand_key_package(&case).await;
+                    assert!(matches!(
+                        alice_central
+                            .mls_central
+                            .create_subconversation(&parent_id, &id, vec![guest_kp])
+                            .await
+                            .unwrap_err(),
+                        CryptoError::ClientNotFound(_)
+                    ));
+                    assert!(!alice_central.mls_central.conversation_exists(&id).await);
+
+                    let bob_kp = bob_central.mls_central.rand_key_package(&case).await;
+                    assert!(matches!(
+                        alice_central
+                            .mls_central
+                            .create_subconversation(&conversation_id(), &id, vec![bob_kp])
+                            .await
+                            .unwrap_err(),
+                        CryptoError::ParentGroupNotFound
+                    ));
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_not_join_subconversation_from_another_parent_epoch(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let parent_id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&parent_id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &parent_id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let id = conversation_id();
+                    let bob_kp = bob_central.mls_central.rand_key_package(&case).await;
+                    let creation = alice_central
+                        .mls_central
+                        .create_subconversation(&parent_id, &id, vec![bob_kp])
+                        .await
+                        .unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+
+                    // Bob's parent has moved on so the secret can no longer be derived
+                    let commit = bob_central
+                        .mls_central
+                        .update_keying_material(&parent_id)
+                        .await
+                        .unwrap();
+                    bob_central.mls_central.commit_accepted(&parent_id).await.unwrap();
+                    alice_central
+                        .mls_central
+                        .decrypt_message(&parent_id, commit.commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+
+                    assert!(bob_central
+                        .mls_central
+                        .join_subconversation(&parent_id, creation.welcome.into(), case.custom_cfg())
+                        .await
+                        .is_err());
+                    assert!(!bob_central.mls_central.conversation_exists(&id).await);
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_wipe_subconversations_with_parent(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let parent_id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&parent_id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &parent_id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let id = conversation_id();
+                    let bob_kp = bob_central.mls_central.rand_key_package(&case).await;
+                    alice_central
+                        .mls_central
+                        .create_subconversation(&parent_id, &id, vec![bob_kp])
+                        .await
+                        .unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+
+                    // nested subconversation
+                    let nested_id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&nested_id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .mark_conversation_as_child_of(&nested_id, &id)
+                        .await
+                        .unwrap();
+
+                    alice_central.mls_central.wipe_conversation(&parent_id).await.unwrap();
+                    for id in [&parent_id, &id, &nested_id] {
+                        assert!(!alice_central.mls_central.conversation_exists(id).await);
+                    }
+                })
+            },
+        )
+        .await
+    }
+}
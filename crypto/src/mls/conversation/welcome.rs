@@ -3,13 +3,13 @@ use crate::{
     group_store::GroupStore,
     mls::credential::crl::{extract_crl_uris_from_group, get_new_crl_distribution_points},
     prelude::{
-        ConversationId, CryptoError, CryptoResult, MlsCentral, MlsConversation, MlsConversationConfiguration,
-        MlsCustomConfiguration, MlsError,
+        decrypt::MlsBufferedConversationDecryptMessage, ConversationId, CryptoError, CryptoResult, MlsCentral,
+        MlsConversation, MlsConversationConfiguration, MlsCustomConfiguration, MlsError, MlsRatchetTreeType,
     },
 };
 use core_crypto_keystore::entities::PersistedMlsPendingGroup;
 use mls_crypto_provider::MlsCryptoProvider;
-use openmls::prelude::{MlsGroup, MlsMessageIn, MlsMessageInBody, Welcome};
+use openmls::prelude::{MlsGroup, MlsMessageIn, MlsMessageInBody, RatchetTreeIn, Welcome};
 use openmls_traits::OpenMlsCryptoProvider;
 use tls_codec::Deserialize;
 
@@ -20,6 +20,8 @@ pub struct WelcomeBundle {
     pub id: ConversationId,
     /// New CRL distribution points that appeared by the introduction of a new credential
     pub crl_new_distribution_points: NewCrlDistributionPoint,
+    /// Messages of the conversation which were received before the Welcome and buffered, decrypted
+    pub buffered_messages: Option<Vec<MlsBufferedConversationDecryptMessage>>,
 }
 
 impl MlsCentral {
@@ -53,7 +55,8 @@ impl MlsCentral {
     /// * `configuration` - configuration of the group/conversation
     ///
     /// # Return type
-    /// This function will return the conversation/group id
+    /// This function will return the conversation/group id along with the messages of the conversation
+    /// which were received before the Welcome, if any
     ///
     /// # Errors
     /// Errors can be originating from the KeyStore of from OpenMls:
@@ -66,19 +69,57 @@ impl MlsCentral {
         welcome: MlsMessageIn,
         custom_cfg: MlsCustomConfiguration,
     ) -> CryptoResult<WelcomeBundle> {
-        let welcome = match welcome.extract() {
-            MlsMessageInBody::Welcome(welcome) => welcome,
-            _ => return Err(CryptoError::ConsumerError),
-        };
+        self.process_welcome(extract_welcome(welcome)?, None, custom_cfg).await
+    }
+
+    /// Create a conversation from a received MLS Welcome message whose GroupInfo does not carry the
+    /// ratchet tree e.g. when the committer uses [crate::prelude::MlsRatchetTreeType::ByRef]
+    ///
+    /// # Arguments
+    /// * `welcome` - a `Welcome` message received as a result of a commit adding new members to a group
+    /// * `ratchet_tree` - the TLS serialized ratchet tree of the group, served by the Delivery Service
+    /// * `configuration` - configuration of the group/conversation
+    ///
+    /// # Return type
+    /// This function will return the conversation/group id
+    ///
+    /// # Errors
+    /// see [MlsCentral::process_welcome_message]. Also fails when the ratchet tree does not match
+    /// the tree hash of the group
+    #[cfg_attr(test, crate::dispotent)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
+    pub async fn process_welcome_message_with_ratchet_tree(
+        &mut self,
+        welcome: MlsMessageIn,
+        ratchet_tree: &[u8],
+        custom_cfg: MlsCustomConfiguration,
+    ) -> CryptoResult<WelcomeBundle> {
+        let ratchet_tree = RatchetTreeIn::tls_deserialize(&mut &ratchet_tree[..]).map_err(MlsError::from)?;
+        self.process_welcome(extract_welcome(welcome)?, Some(ratchet_tree), custom_cfg)
+            .await
+    }
+
+    pub(crate) async fn process_welcome(
+        &mut self,
+        welcome: Welcome,
+        ratchet_tree: Option<RatchetTreeIn>,
+        custom_cfg: MlsCustomConfiguration,
+    ) -> CryptoResult<WelcomeBundle> {
         let cs = welcome.ciphersuite().into();
         let configuration = MlsConversationConfiguration {
             ciphersuite: cs,
             custom: custom_cfg,
             ..Default::default()
         };
-        let conversation =
-            MlsConversation::from_welcome_message(welcome, configuration, &mut self.mls_backend, &mut self.mls_groups)
-                .await?;
+        let mut conversation = MlsConversation::from_welcome_message(
+            welcome,
+            ratchet_tree,
+            configuration,
+            self.group_info_ratchet_tree_type,
+            &mut self.mls_backend,
+            &mut self.mls_groups,
+        )
+        .await?;
 
         // We wait for the group to be created then we iterate through all members
         let crl_new_distribution_points =
@@ -86,22 +127,45 @@ impl MlsCentral {
                 .await?;
 
         let id = conversation.id.clone();
+
+        // Replay the messages which were fanned out before the Welcome. The group has been persisted by now,
+        // so failing to do so must not fail joining it
+        let epoch = conversation.group.epoch().as_u64();
+        let buffered_messages = match self.discard_stale_pending_messages(&id, epoch).await {
+            Ok(()) => self.restore_pending_messages(&mut conversation, false).await,
+            Err(e) => Err(e),
+        }
+        .unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "Failed replaying the messages buffered before joining");
+            None
+        });
+
         self.mls_groups.insert(id.clone(), conversation);
 
         Ok(WelcomeBundle {
             id,
             crl_new_distribution_points,
+            buffered_messages,
         })
     }
 }
 
+/// Fails when the message is not a Welcome
+pub(crate) fn extract_welcome(message: MlsMessageIn) -> CryptoResult<Welcome> {
+    match message.extract() {
+        MlsMessageInBody::Welcome(welcome) => Ok(welcome),
+        _ => Err(CryptoError::ConsumerError),
+    }
+}
+
 impl MlsConversation {
-    // ? Do we need to provide the ratchet_tree to the MlsGroup? Does everything crumble down if we can't actually get it?
     /// Create the MLS conversation from an MLS Welcome message
     ///
     /// # Arguments
     /// * `welcome` - welcome message to create the group from
+    /// * `ratchet_tree` - the ratchet tree, when it is not carried by the GroupInfo in the Welcome
     /// * `config` - group configuration
+    /// * `ratchet_tree_type` - representation of the ratchet tree in the GroupInfos of the group
     /// * `backend` - the KeyStore to persist the group
     ///
     /// # Errors
@@ -109,13 +173,15 @@ impl MlsConversation {
     #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
     async fn from_welcome_message(
         welcome: Welcome,
+        ratchet_tree: Option<RatchetTreeIn>,
         configuration: MlsConversationConfiguration,
+        ratchet_tree_type: MlsRatchetTreeType,
         backend: &mut MlsCryptoProvider,
         mls_groups: &mut GroupStore<MlsConversation>,
     ) -> CryptoResult<Self> {
-        let mls_group_config = configuration.as_openmls_default_configuration()?;
+        let mls_group_config = configuration.as_openmls_configuration(ratchet_tree_type)?;
 
-        let group = MlsGroup::new_from_welcome(backend, &mls_group_config, welcome, None).await;
+        let group = MlsGroup::new_from_welcome(backend, &mls_group_config, welcome, ratchet_tree).await;
 
         let group = match group {
             Err(openmls::prelude::WelcomeError::NoMatchingKeyPackage)
@@ -231,4 +297,239 @@ pub mod tests {
         )
         .await;
     }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn process_welcome_should_replay_messages_received_before_it(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    let bob = bob_central.mls_central.rand_key_package(&case).await;
+                    let welcome = alice_central
+                        .mls_central
+                        .add_members_to_conversation(&id, vec![bob])
+                        .await
+                        .unwrap()
+                        .welcome;
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+
+                    // Alice talks then moves to the next epoch
+                    let msg = alice_central.mls_central.encrypt_message(&id, b"hello").await.unwrap();
+                    let commit = alice_central
+                        .mls_central
+                        .update_keying_material(&id)
+                        .await
+                        .unwrap()
+                        .commit;
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+
+                    // Bob receives all of it before the Welcome
+                    let decrypt = bob_central.mls_central.decrypt_message(&id, &msg).await;
+                    assert!(matches!(
+                        decrypt.unwrap_err(),
+                        CryptoError::BufferedMessageForUnknownConversation
+                    ));
+                    let decrypt = bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await;
+                    assert!(matches!(
+                        decrypt.unwrap_err(),
+                        CryptoError::BufferedMessageForUnknownConversation
+                    ));
+
+                    let bundle = bob_central
+                        .mls_central
+                        .process_welcome_message(welcome.into(), case.custom_cfg())
+                        .await
+                        .unwrap();
+                    let buffered = bundle.buffered_messages.unwrap();
+                    assert_eq!(buffered.len(), 2);
+                    assert_eq!(buffered[0].app_msg.as_deref(), Some(b"hello".as_slice()));
+                    assert!(buffered[1].has_epoch_changed);
+
+                    assert_eq!(
+                        bob_central.mls_central.conversation_epoch(&id).await.unwrap(),
+                        alice_central.mls_central.conversation_epoch(&id).await.unwrap()
+                    );
+                    assert!(bob_central
+                        .mls_central
+                        .list_pending_messages(&id)
+                        .await
+                        .unwrap()
+                        .is_empty());
+                    assert!(bob_central
+                        .mls_central
+                        .try_talk_to(&id, &mut alice_central.mls_central)
+                        .await
+                        .is_ok());
+                })
+            },
+        )
+        .await;
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn process_welcome_should_replay_messages_epoch_by_epoch(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    let bob = bob_central.mls_central.rand_key_package(&case).await;
+                    let welcome = alice_central
+                        .mls_central
+                        .add_members_to_conversation(&id, vec![bob])
+                        .await
+                        .unwrap()
+                        .welcome;
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+
+                    // Alice moves to the next epoch then talks in it
+                    let commit = alice_central
+                        .mls_central
+                        .update_keying_material(&id)
+                        .await
+                        .unwrap()
+                        .commit;
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    let msg = alice_central.mls_central.encrypt_message(&id, b"after").await.unwrap();
+
+                    // then moves on again, but Bob will not get this commit
+                    alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    let future_msg = alice_central.mls_central.encrypt_message(&id, b"future").await.unwrap();
+
+                    // Bob receives the messages before the commit and the Welcome
+                    for m in [msg, future_msg.clone(), commit.to_bytes().unwrap()] {
+                        let decrypt = bob_central.mls_central.decrypt_message(&id, m).await;
+                        assert!(matches!(
+                            decrypt.unwrap_err(),
+                            CryptoError::BufferedMessageForUnknownConversation
+                        ));
+                    }
+
+                    let bundle = bob_central
+                        .mls_central
+                        .process_welcome_message(welcome.into(), case.custom_cfg())
+                        .await
+                        .unwrap();
+                    let buffered = bundle.buffered_messages.unwrap();
+                    assert_eq!(buffered.len(), 2);
+                    assert!(buffered[0].has_epoch_changed);
+                    assert_eq!(buffered[1].app_msg.as_deref(), Some(b"after".as_slice()));
+
+                    // the message whose commit is missing is kept for later
+                    let pending = bob_central.mls_central.list_pending_messages(&id).await.unwrap();
+                    assert_eq!(pending.len(), 1);
+                    assert_eq!(pending[0].message, future_msg);
+                })
+            },
+        )
+        .await;
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn process_welcome_should_discard_buffered_messages_preceding_it(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+
+                    // this one is sent before Bob joins, he will never be able to decrypt it
+                    let msg = alice_central.mls_central.encrypt_message(&id, b"hello").await.unwrap();
+                    let decrypt = bob_central.mls_central.decrypt_message(&id, &msg).await;
+                    assert!(matches!(
+                        decrypt.unwrap_err(),
+                        CryptoError::BufferedMessageForUnknownConversation
+                    ));
+
+                    let bob = bob_central.mls_central.rand_key_package(&case).await;
+                    let welcome = alice_central
+                        .mls_central
+                        .add_members_to_conversation(&id, vec![bob])
+                        .await
+                        .unwrap()
+                        .welcome;
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+
+                    let bundle = bob_central
+                        .mls_central
+                        .process_welcome_message(welcome.into(), case.custom_cfg())
+                        .await
+                        .unwrap();
+                    assert!(bundle.buffered_messages.is_none());
+                    assert!(bob_central
+                        .mls_central
+                        .list_pending_messages(&id)
+                        .await
+                        .unwrap()
+                        .is_empty());
+                })
+            },
+        )
+        .await;
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn decrypting_a_welcome_for_an_unknown_conversation_should_not_buffer_it(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    let bob = bob_central.mls_central.rand_key_package(&case).await;
+                    let welcome = alice_central
+                        .mls_central
+                        .add_members_to_conversation(&id, vec![bob])
+                        .await
+                        .unwrap()
+                        .welcome;
+
+                    let decrypt = bob_central
+                        .mls_central
+                        .decrypt_message(&id, welcome.to_bytes().unwrap())
+                        .await;
+                    assert!(matches!(decrypt.unwrap_err(), CryptoError::ConversationNotFound(i) if i == id));
+                    assert!(bob_central
+                        .mls_central
+                        .list_pending_messages(&id)
+                        .await
+                        .unwrap()
+                        .is_empty());
+                })
+            },
+        )
+        .await;
+    }
 }
@@ -1,16 +1,36 @@
 use crate::prelude::{ConversationId, CryptoResult, MlsCentral, MlsConversation, MlsError};
-use core_crypto_keystore::CryptoKeystoreMls;
+use core_crypto_keystore::{
+    entities::{MlsEpochAuthenticators, MlsGroupKeyRotation, MlsHistory, MlsMemberEpochs},
+    CryptoKeystoreError, CryptoKeystoreMls,
+};
 use mls_crypto_provider::MlsCryptoProvider;
 use openmls_traits::OpenMlsCryptoProvider;
 
 impl MlsCentral {
-    /// Destroys a group locally
+    /// Destroys a group locally, along with its subconversations
     ///
     /// # Errors
     /// KeyStore errors, such as IO
     #[cfg_attr(test, crate::dispotent)]
     #[cfg_attr(not(test), tracing::instrument(err, skip(self), fields(id = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, id))))]
     pub async fn wipe_conversation(&mut self, id: &ConversationId) -> CryptoResult<()> {
+        self.get_conversation(id).await?;
+
+        // subconversations are of no use without their parent, nested ones included
+        let mut ids = vec![id.clone()];
+        let mut i = 0;
+        while let Some(parent_id) = ids.get(i).cloned() {
+            ids.extend(self.list_subconversations(&parent_id).await?);
+            i += 1;
+        }
+
+        for id in ids.iter().rev() {
+            self.wipe_single_conversation(id).await?;
+        }
+        Ok(())
+    }
+
+    async fn wipe_single_conversation(&mut self, id: &ConversationId) -> CryptoResult<()> {
         self.get_conversation(id)
             .await?
             .write()
@@ -18,11 +38,26 @@ impl MlsCentral {
             .wipe_associated_entities(&self.mls_backend)
             .await?;
         self.mls_backend.key_store().mls_group_delete(id).await?;
+        let keystore = self.mls_backend.key_store();
+        // conversations created before key rotation, epoch authenticators or member epochs were tracked have none
+        ignore_missing(keystore.remove::<MlsGroupKeyRotation, _>(id).await)?;
+        ignore_missing(keystore.remove::<MlsEpochAuthenticators, _>(id).await)?;
+        ignore_missing(keystore.remove::<MlsMemberEpochs, _>(id).await)?;
+        // only conversations sharing their history have one
+        ignore_missing(keystore.remove::<MlsHistory, _>(id).await)?;
         let _ = self.mls_groups.remove(id);
         Ok(())
     }
 }
 
+/// Removing an entity a conversation does not have is fine, any other KeyStore error is not
+fn ignore_missing(result: Result<(), CryptoKeystoreError>) -> CryptoResult<()> {
+    match result {
+        Ok(()) | Err(CryptoKeystoreError::MissingKeyInStore(_)) | Err(CryptoKeystoreError::NotFound(..)) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
 impl MlsConversation {
     #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
     async fn wipe_associated_entities(&mut self, backend: &MlsCryptoProvider) -> CryptoResult<()> {
@@ -119,4 +154,76 @@ pub mod tests {
         })
         .await
     }
+
+    // the keystore integrity check must not take what OpenMLS still uses for dangling
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_leave_nothing_dangling(case: TestCase) {
+        use core_crypto_keystore::connection::IntegrityCheckParams;
+
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob", "charlie"],
+            move |[mut alice_central, mut bob_central, mut charlie_central]| {
+                Box::pin(async move {
+                    // a group with a pending update proposal, joined by external commit by someone else
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+                    let group_info = alice_central.mls_central.get_group_info(&id).await;
+                    charlie_central
+                        .mls_central
+                        .join_by_external_commit(group_info, case.custom_cfg(), case.credential_type)
+                        .await
+                        .unwrap();
+                    alice_central.mls_central.new_update_proposal(&id).await.unwrap();
+
+                    // a group with a pending commit
+                    let other_id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&other_id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &other_id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .update_keying_material(&other_id)
+                        .await
+                        .unwrap();
+
+                    // and key packages nobody claimed
+                    alice_central
+                        .mls_central
+                        .get_or_create_client_keypackages(case.ciphersuite(), case.credential_type, 2)
+                        .await
+                        .unwrap();
+
+                    for central in [&alice_central, &bob_central, &charlie_central] {
+                        let report = central
+                            .mls_central
+                            .mls_backend
+                            .key_store()
+                            .check_integrity(IntegrityCheckParams::default())
+                            .await
+                            .unwrap();
+                        assert!(report.is_empty(), "{report:?}");
+                    }
+                })
+            },
+        )
+        .await
+    }
 }
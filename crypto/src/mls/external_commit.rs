@@ -16,16 +16,14 @@
 
 use mls_crypto_provider::MlsCryptoProvider;
 use openmls::prelude::{
-    group_info::VerifiableGroupInfo, CredentialType, MlsGroup, MlsMessageOut, Proposal, Sender, StagedCommit,
+    group_info::VerifiableGroupInfo, CredentialType, MlsGroup, MlsMessageOut, Proposal, RatchetTreeIn, Sender,
+    StagedCommit,
 };
 use openmls_traits::OpenMlsCryptoProvider;
-use tls_codec::Serialize;
+use tls_codec::{Deserialize, Serialize};
 use tracing::Instrument;
 
-use core_crypto_keystore::{
-    entities::{MlsPendingMessage, PersistedMlsPendingGroup},
-    CryptoKeystoreMls,
-};
+use core_crypto_keystore::{entities::PersistedMlsPendingGroup, CryptoKeystoreMls};
 
 use crate::{
     e2e_identity::{conversation_state::compute_state, init_certificates::NewCrlDistributionPoint},
@@ -34,7 +32,8 @@ use crate::{
     prelude::{
         decrypt::MlsBufferedConversationDecryptMessage, id::ClientId, ConversationId, CoreCryptoCallbacks, CryptoError,
         CryptoResult, E2eiConversationState, MlsCentral, MlsCiphersuite, MlsConversation, MlsConversationConfiguration,
-        MlsCredentialType, MlsCustomConfiguration, MlsError, MlsGroupInfoBundle,
+        MlsCredentialType, MlsCustomConfiguration, MlsError, MlsGroupInfoBundle, MlsGroupInfoEncryptionKey,
+        MlsGroupInfoIn,
     },
 };
 
@@ -76,8 +75,8 @@ impl MlsCentral {
     /// bad can happen if you forget to except some storage space wasted.
     ///
     /// # Arguments
-    /// * `group_info` - a GroupInfo wrapped in a MLS message. it can be obtained by deserializing a TLS
-    /// serialized `GroupInfo` object
+    /// * `group_info` - a GroupInfo wrapped in a MLS message, either deserialized or as found in the payload of a
+    /// [MlsGroupInfoBundle] i.e. possibly encrypted in a JWE. See [MlsGroupInfoIn]
     /// * `custom_cfg` - configuration of the MLS conversation fetched from the Delivery Service
     /// * `credential_type` - kind of [openmls::prelude::Credential] to use for joining this group.
     /// If [MlsCredentialType::Basic] is chosen and no Credential has been created yet for it,
@@ -92,8 +91,48 @@ impl MlsCentral {
     /// Errors resulting from OpenMls, the KeyStore calls and serialization
     #[cfg_attr(not(test), tracing::instrument(err, skip(self, group_info, custom_cfg)))]
     pub async fn join_by_external_commit(
+        &mut self,
+        group_info: impl Into<MlsGroupInfoIn>,
+        custom_cfg: MlsCustomConfiguration,
+        credential_type: MlsCredentialType,
+    ) -> CryptoResult<MlsConversationInitBundle> {
+        let group_info = self.resolve_group_info(group_info.into())?;
+        self.join_external(group_info, None, custom_cfg, credential_type).await
+    }
+
+    /// Issues an external commit from a [crate::prelude::MlsRatchetTreeType::ByRef] GroupInfo, which
+    /// does not carry the ratchet tree, and the ratchet tree served separately.
+    /// See [MlsCentral::join_by_external_commit]
+    ///
+    /// # Arguments
+    /// * `group_info` - the payload of a [MlsGroupInfoBundle] with [crate::prelude::MlsRatchetTreeType::ByRef], encrypted or not
+    /// * `ratchet_tree` - the TLS serialized ratchet tree, served by the Delivery Service
+    /// * `key` - key to decrypt the JWE with, if any. See [MlsCentral::open_group_info]
+    /// * `custom_cfg` - configuration of the MLS conversation fetched from the Delivery Service
+    /// * `credential_type` - kind of [openmls::prelude::Credential] to use for joining this group
+    ///
+    /// # Errors
+    /// see [MlsCentral::join_by_external_commit]. Also fails when the ratchet tree does not match
+    /// the tree hash of the GroupInfo
+    #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
+    pub async fn join_by_external_commit_with_ratchet_tree(
+        &mut self,
+        group_info: &[u8],
+        ratchet_tree: &[u8],
+        key: Option<MlsGroupInfoEncryptionKey>,
+        custom_cfg: MlsCustomConfiguration,
+        credential_type: MlsCredentialType,
+    ) -> CryptoResult<MlsConversationInitBundle> {
+        let group_info = self.open_group_info(group_info, key)?;
+        let ratchet_tree = RatchetTreeIn::tls_deserialize(&mut &ratchet_tree[..]).map_err(MlsError::from)?;
+        self.join_external(group_info, Some(ratchet_tree), custom_cfg, credential_type)
+            .await
+    }
+
+    async fn join_external(
         &mut self,
         group_info: VerifiableGroupInfo,
+        ratchet_tree: Option<RatchetTreeIn>,
         custom_cfg: MlsCustomConfiguration,
         credential_type: MlsCredentialType,
     ) -> CryptoResult<MlsConversationInitBundle> {
@@ -115,9 +154,9 @@ impl MlsCentral {
         let (group, commit, group_info) = MlsGroup::join_by_external_commit(
             &self.mls_backend,
             &cb.signature_key,
-            None,
+            ratchet_tree,
             group_info,
-            &configuration.as_openmls_default_configuration()?,
+            &configuration.as_openmls_configuration(self.group_info_ratchet_tree_type)?,
             &[],
             cb.to_mls_credential_with_key(),
         )
@@ -127,7 +166,7 @@ impl MlsCentral {
 
         // We should always have ratchet tree extension turned on hence GroupInfo should always be present
         let group_info = group_info.ok_or(CryptoError::ImplementationError)?;
-        let group_info = MlsGroupInfoBundle::try_new_full_plaintext(group_info)?;
+        let group_info = self.finalize_group_info(MlsGroupInfoBundle::try_new_full_plaintext(group_info)?, None)?;
 
         let crl_new_distribution_points =
             get_new_crl_distribution_points(&self.mls_backend, extract_crl_uris_from_group(&group)?).await?;
@@ -196,10 +235,6 @@ impl MlsCentral {
         // cleanup the pending group we no longer need
         self.mls_backend.key_store().mls_pending_groups_delete(id).await?;
 
-        if pending_messages.is_some() {
-            self.mls_backend.key_store().remove::<MlsPendingMessage, _>(id).await?;
-        }
-
         Ok(pending_messages)
     }
 
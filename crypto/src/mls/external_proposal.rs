@@ -79,7 +79,7 @@ impl MlsConversation {
     }
 
     /// Gather pending remove proposals
-    fn pending_removals(&self) -> Vec<LeafNodeIndex> {
+    pub(crate) fn pending_removals(&self) -> Vec<LeafNodeIndex> {
         self.group
             .pending_proposals()
             .filter_map(|proposal| match proposal.proposal() {
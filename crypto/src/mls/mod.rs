@@ -6,7 +6,7 @@ use openmls_traits::OpenMlsCryptoProvider;
 use crate::prelude::{
     identifier::ClientIdentifier, key_package::INITIAL_KEYING_MATERIAL_COUNT, Client, ClientId, ConversationId,
     CoreCryptoCallbacks, CryptoError, CryptoResult, MlsCentralConfiguration, MlsCiphersuite, MlsConversation,
-    MlsConversationConfiguration, MlsCredentialType, MlsError,
+    MlsConversationConfiguration, MlsCredentialType, MlsError, MlsRatchetTreeType,
 };
 
 pub(crate) mod buffer_external_commit;
@@ -18,6 +18,8 @@ pub(crate) mod external_commit;
 pub(crate) mod external_proposal;
 pub(crate) mod proposal;
 pub(crate) mod restore;
+pub(crate) mod snapshot;
+pub(crate) mod transaction;
 
 // Prevents direct instantiation of [MlsCentralConfiguration]
 pub(crate) mod config {
@@ -37,8 +39,7 @@ pub(crate) mod config {
         pub client_id: Option<ClientId>,
         /// Entropy pool seed for the internal PRNG
         pub external_entropy: Option<EntropySeed>,
-        /// All supported ciphersuites
-        /// TODO: pending wire-server API supports selecting a ciphersuite only the first item of this array will be used.
+        /// All supported ciphersuites. The client holds a credential and KeyPackages for each of them
         pub ciphersuites: Vec<ciphersuite::MlsCiphersuite>,
         /// Number of [openmls::prelude::KeyPackage] to create when creating a MLS client. Default to [INITIAL_KEYING_MATERIAL_COUNT]
         pub nb_init_key_packages: Option<usize>,
@@ -144,6 +145,9 @@ pub struct MlsCentral {
     pub(crate) mls_backend: MlsCryptoProvider,
     pub(crate) mls_groups: crate::group_store::GroupStore<MlsConversation>,
     pub(crate) callbacks: Option<std::sync::Arc<dyn CoreCryptoCallbacks + 'static>>,
+    pub(crate) group_info_encryption: Option<conversation::group_info::MlsGroupInfoEncryption>,
+    pub(crate) group_info_ratchet_tree_type: MlsRatchetTreeType,
+    pub(crate) pending_messages_limits: conversation::buffer_messages::MlsPendingMessagesLimits,
 }
 
 impl MlsCentral {
@@ -199,6 +203,9 @@ impl MlsCentral {
             mls_client,
             mls_groups,
             callbacks: None,
+            group_info_encryption: None,
+            group_info_ratchet_tree_type: MlsRatchetTreeType::Full,
+            pending_messages_limits: Default::default(),
         };
 
         central.init_pki_env().in_current_span().await?;
@@ -239,6 +246,9 @@ impl MlsCentral {
             mls_client,
             mls_groups,
             callbacks: None,
+            group_info_encryption: None,
+            group_info_ratchet_tree_type: MlsRatchetTreeType::Full,
+            pending_messages_limits: Default::default(),
         };
 
         central.init_pki_env().in_current_span().await?;
@@ -370,6 +380,7 @@ impl MlsCentral {
             mls_client,
             creator_credential_type,
             config,
+            self.group_info_ratchet_tree_type,
             &self.mls_backend,
         )
         .in_current_span()
@@ -426,6 +437,25 @@ impl MlsCentral {
         Ok(())
     }
 
+    /// Changes the key the KeyStore is encrypted with at rest, e.g. after the device master key has been
+    /// regenerated. Once this returns, CoreCrypto has to be instantiated with `new_key`.
+    ///
+    /// Keep both keys around until this returns. If this gets interrupted, the KeyStore is still under the former
+    /// key on native platforms. On WASM, instantiate CoreCrypto with `new_key` instead: it picks up where it
+    /// stopped while opening the KeyStore.
+    ///
+    /// # Errors
+    /// KeyStore errors, such as a transaction being in progress or IO
+    #[cfg_attr(test, crate::idempotent)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
+    pub async fn rekey(&self, new_key: &str) -> CryptoResult<()> {
+        if new_key.trim().is_empty() {
+            return Err(CryptoError::MalformedIdentifier("new_key"));
+        }
+        self.mls_backend.key_store().rekey(new_key).await?;
+        Ok(())
+    }
+
     /// Generates a random byte array of the specified size
     pub fn random_bytes(&self, len: usize) -> CryptoResult<Vec<u8>> {
         use openmls_traits::random::OpenMlsRand as _;
@@ -655,6 +685,63 @@ pub mod tests {
         .await
     }
 
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn can_reopen_central_after_rekey(case: TestCase) {
+        run_tests(move |[tmp_dir_argument]| {
+            Box::pin(async move {
+                let x509_test_chain = X509TestChain::init_empty(case.signature_scheme());
+                let mut configuration = MlsCentralConfiguration::try_new(
+                    tmp_dir_argument,
+                    "test".to_string(),
+                    None,
+                    vec![case.ciphersuite()],
+                    None,
+                    Some(INITIAL_KEYING_MATERIAL_COUNT),
+                )
+                .unwrap();
+                let mut central = MlsCentral::try_new(configuration.clone()).await.unwrap();
+                x509_test_chain.register_with_central(&central).await;
+                let identifier = match case.credential_type {
+                    MlsCredentialType::Basic => ClientIdentifier::Basic("alice".into()),
+                    MlsCredentialType::X509 => {
+                        CertificateBundle::rand_identifier("alice", &[x509_test_chain.find_local_intermediate_ca()])
+                    }
+                };
+                central
+                    .mls_init(
+                        identifier.clone(),
+                        vec![case.ciphersuite()],
+                        Some(INITIAL_KEYING_MATERIAL_COUNT),
+                    )
+                    .await
+                    .unwrap();
+                let id = conversation_id();
+                central
+                    .new_conversation(&id, case.credential_type, case.cfg.clone())
+                    .await
+                    .unwrap();
+
+                central.rekey("new key").await.unwrap();
+                central.close().await.unwrap();
+
+                configuration.identity_key = "new key".to_string();
+                let mut central = MlsCentral::try_new(configuration).await.unwrap();
+                central
+                    .mls_init(
+                        identifier,
+                        vec![case.ciphersuite()],
+                        Some(INITIAL_KEYING_MATERIAL_COUNT),
+                    )
+                    .await
+                    .unwrap();
+                assert!(central.conversation_exists(&id).await);
+                assert!(central.encrypt_message(&id, b"hello").await.is_ok());
+            })
+        })
+        .await
+    }
+
     #[apply(all_cred_cipher)]
     #[wasm_bindgen_test]
     pub async fn can_2_phase_init_central(case: TestCase) {
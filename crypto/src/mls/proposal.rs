@@ -20,7 +20,7 @@ use mls_crypto_provider::MlsCryptoProvider;
 
 use crate::{
     mls::{ClientId, ConversationId, MlsCentral, MlsConversation},
-    prelude::{Client, CryptoError, CryptoResult, MlsProposalBundle},
+    prelude::{Client, CryptoError, CryptoResult, MlsProposalBundle, MlsReInit},
 };
 
 /// Abstraction over a [openmls::prelude::hash_ref::ProposalRef] to deal with conversions
@@ -60,6 +60,10 @@ pub enum MlsProposal {
     Update,
     /// Requests that the member with LeafNodeRef removed be removed from the group
     Remove(ClientId),
+    /// Requests that the external PSK with the given id be injected in the key schedule of the next epoch
+    ExternalPsk(Vec<u8>),
+    /// Requests that the group be ended for its members to move to a successor with the given parameters
+    ReInit(MlsReInit),
 }
 
 impl MlsProposal {
@@ -86,6 +90,8 @@ impl MlsProposal {
                     .map(|kp| kp.index)?;
                 (*conversation).propose_remove_member(client, backend, index).await
             }
+            MlsProposal::ExternalPsk(psk_id) => (*conversation).propose_external_psk(client, backend, psk_id).await,
+            MlsProposal::ReInit(reinit) => (*conversation).propose_reinit(client, backend, &reinit).await,
         }?;
         Ok(proposal)
     }
@@ -112,6 +118,28 @@ impl MlsCentral {
         self.new_proposal(id, MlsProposal::Remove(client_id)).await
     }
 
+    /// Creates a new PreSharedKey proposal referencing an external PSK previously registered with
+    /// [MlsCentral::register_external_psk]
+    #[cfg_attr(test, crate::idempotent)]
+    pub async fn new_external_psk_proposal(
+        &mut self,
+        id: &ConversationId,
+        psk_id: Vec<u8>,
+    ) -> CryptoResult<MlsProposalBundle> {
+        self.new_proposal(id, MlsProposal::ExternalPsk(psk_id)).await
+    }
+
+    /// Creates a new ReInit proposal. It has to be committed alone, see [MlsCentral::reinit_conversation] which
+    /// proposes and commits it at once
+    #[cfg_attr(test, crate::idempotent)]
+    pub async fn new_reinit_proposal(
+        &mut self,
+        id: &ConversationId,
+        reinit: MlsReInit,
+    ) -> CryptoResult<MlsProposalBundle> {
+        self.new_proposal(id, MlsProposal::ReInit(reinit)).await
+    }
+
     /// Creates a new Add proposal
     #[cfg_attr(test, crate::dispotent)]
     pub async fn new_update_proposal(&mut self, id: &ConversationId) -> CryptoResult<MlsProposalBundle> {
@@ -37,6 +37,7 @@ impl MlsCentral {
 
         for (group_id, (parent_id, state)) in groups.into_iter() {
             let conversation = MlsConversation::from_serialized_state(state, parent_id)?;
+            conversation.init_missing_key_rotation(backend).await?;
             let encoded_id = BASE64_STANDARD.encode(&group_id);
             info!(group_id = encoded_id, "Restored group");
             if group_store.try_insert(group_id, conversation).is_err() {
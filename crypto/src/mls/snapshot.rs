@@ -0,0 +1,368 @@
+//! Export of the whole MLS state of a client, to move it to another device without rejoining every
+//! conversation. A snapshot holds the credentials, signature keys, conversations, pending
+//! conversations, KeyPackages and PKI environment of the client and is encrypted under a key
+//! derived from a passphrase.
+//!
+//! A snapshot is a [core_crypto_keystore::envelope] prefixed with its version (1 byte).
+
+use std::collections::HashSet;
+
+use openmls_traits::{types::SignatureScheme, OpenMlsCryptoProvider};
+use serde::{Deserialize, Serialize};
+use tls_codec::Deserialize as _;
+use zeroize::Zeroizing;
+
+use core_crypto_keystore::{
+    entities::{
+        E2eiAcmeCA, E2eiCrl, E2eiIntermediateCert, E2eiRefreshToken, EntityFindParams, MlsCredential,
+        MlsEncryptionKeyPair, MlsEpochAuthenticators, MlsEpochEncryptionKeyPair, MlsGroupKeyRotation, MlsHistory,
+        MlsHpkePrivateKey, MlsKeyPackage, MlsMemberEpochs, MlsPendingMessage, MlsPskBundle, MlsSignatureKeyPair,
+        PersistedMlsGroup, PersistedMlsPendingGroup,
+    },
+    envelope, CryptoKeystoreError,
+};
+use mls_crypto_provider::MlsCryptoProvider;
+
+use crate::prelude::{Client, ClientId, CryptoError, CryptoResult, MlsCentral, MlsError};
+
+const SNAPSHOT_VERSION: u8 = 1;
+
+/// Everything persisted by a client which is needed to carry on from another device
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    client_id: Vec<u8>,
+    credentials: Vec<MlsCredential>,
+    signature_keypairs: Vec<MlsSignatureKeyPair>,
+    key_packages: Vec<MlsKeyPackage>,
+    hpke_private_keys: Vec<MlsHpkePrivateKey>,
+    encryption_keypairs: Vec<MlsEncryptionKeyPair>,
+    epoch_encryption_keypairs: Vec<MlsEpochEncryptionKeyPair>,
+    psk_bundles: Vec<MlsPskBundle>,
+    groups: Vec<PersistedMlsGroup>,
+    pending_groups: Vec<PersistedMlsPendingGroup>,
+    pending_messages: Vec<MlsPendingMessage>,
+    group_key_rotations: Vec<MlsGroupKeyRotation>,
+    epoch_authenticators: Vec<MlsEpochAuthenticators>,
+    member_epochs: Vec<MlsMemberEpochs>,
+    history: Vec<MlsHistory>,
+    acme_ca: Option<E2eiAcmeCA>,
+    intermediate_certs: Vec<E2eiIntermediateCert>,
+    crls: Vec<E2eiCrl>,
+    refresh_token: Option<E2eiRefreshToken>,
+}
+
+impl Snapshot {
+    async fn collect(backend: &MlsCryptoProvider, client_id: &ClientId) -> CryptoResult<Self> {
+        let keystore = backend.key_store();
+        let params = EntityFindParams::default;
+        let acme_ca = keystore.find_unique::<E2eiAcmeCA>().await.ok();
+        let refresh_token = keystore.find_unique::<E2eiRefreshToken>().await.ok();
+        Ok(Self {
+            client_id: client_id.to_vec(),
+            credentials: keystore.find_all(params()).await?,
+            signature_keypairs: keystore.find_all(params()).await?,
+            key_packages: keystore.find_all(params()).await?,
+            hpke_private_keys: keystore.find_all(params()).await?,
+            encryption_keypairs: keystore.find_all(params()).await?,
+            epoch_encryption_keypairs: keystore.find_all(params()).await?,
+            psk_bundles: keystore.find_all(params()).await?,
+            groups: keystore.find_all(params()).await?,
+            pending_groups: keystore.find_all(params()).await?,
+            pending_messages: keystore.find_all(params()).await?,
+            group_key_rotations: keystore.find_all(params()).await?,
+            epoch_authenticators: keystore.find_all(params()).await?,
+            member_epochs: keystore.find_all(params()).await?,
+            history: keystore.find_all(params()).await?,
+            acme_ca,
+            intermediate_certs: keystore.find_all(params()).await?,
+            crls: keystore.find_all(params()).await?,
+            refresh_token,
+        })
+    }
+
+    /// Writes every entity of the snapshot in a single keystore transaction
+    async fn persist(self, backend: &MlsCryptoProvider) -> CryptoResult<()> {
+        let keystore = backend.key_store();
+        keystore.begin_transaction().await?;
+        if let Err(e) = self.stage(backend).await {
+            keystore.rollback_transaction().await?;
+            return Err(e);
+        }
+        keystore.commit_transaction().await?;
+        Ok(())
+    }
+
+    async fn stage(&self, backend: &MlsCryptoProvider) -> CryptoResult<()> {
+        let keystore = backend.key_store();
+        for entity in &self.credentials {
+            keystore.save(entity.clone()).await?;
+        }
+        for entity in &self.signature_keypairs {
+            keystore.save(entity.clone()).await?;
+        }
+        for entity in &self.key_packages {
+            keystore.save(entity.clone()).await?;
+        }
+        for entity in &self.hpke_private_keys {
+            keystore.save(entity.clone()).await?;
+        }
+        for entity in &self.encryption_keypairs {
+            keystore.save(entity.clone()).await?;
+        }
+        for entity in &self.epoch_encryption_keypairs {
+            keystore.save(entity.clone()).await?;
+        }
+        for entity in &self.psk_bundles {
+            keystore.save(entity.clone()).await?;
+        }
+        for entity in &self.groups {
+            keystore.save(entity.clone()).await?;
+        }
+        for entity in &self.pending_groups {
+            keystore.save(entity.clone()).await?;
+        }
+        for entity in &self.pending_messages {
+            keystore.save(entity.clone()).await?;
+        }
+        for entity in &self.group_key_rotations {
+            keystore.save(entity.clone()).await?;
+        }
+        for entity in &self.epoch_authenticators {
+            keystore.save(entity.clone()).await?;
+        }
+        for entity in &self.member_epochs {
+            keystore.save(entity.clone()).await?;
+        }
+        for entity in &self.history {
+            keystore.save(entity.clone()).await?;
+        }
+        for entity in &self.intermediate_certs {
+            keystore.save(entity.clone()).await?;
+        }
+        for entity in &self.crls {
+            keystore.save(entity.clone()).await?;
+        }
+        if let Some(acme_ca) = &self.acme_ca {
+            keystore.replace_unique(acme_ca.clone()).await?;
+        }
+        if let Some(refresh_token) = &self.refresh_token {
+            keystore.replace_unique(refresh_token.clone()).await?;
+        }
+        Ok(())
+    }
+}
+
+impl MlsCentral {
+    /// Exports the whole MLS state of this client in a snapshot encrypted under a key derived from
+    /// `passphrase`, to be imported on another device with [MlsCentral::import_snapshot].
+    ///
+    /// Once imported elsewhere, this device must not be used anymore: two devices sharing the same
+    /// keys and conversations would fork their state. Wiping it afterwards is advised.
+    ///
+    /// # Errors
+    /// If the passphrase is empty, if the client is not initialized or when reading the keystore fails
+    #[cfg_attr(test, crate::idempotent)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
+    pub async fn export_snapshot(&self, passphrase: &str) -> CryptoResult<Vec<u8>> {
+        if passphrase.is_empty() {
+            return Err(CryptoError::MalformedIdentifier("passphrase"));
+        }
+        let client_id = self.mls_client()?.id();
+        let snapshot = Snapshot::collect(&self.mls_backend, client_id).await?;
+        let plaintext = Zeroizing::new(serde_json::to_vec(&snapshot)?);
+
+        Ok(envelope::seal(passphrase, &[SNAPSHOT_VERSION], &plaintext)?)
+    }
+
+    /// Imports a snapshot created by [MlsCentral::export_snapshot] and initializes the MLS client
+    /// with it, restoring all of its conversations. This has to be done on a fresh keystore, in
+    /// place of [MlsCentral::mls_init].
+    ///
+    /// # Errors
+    /// * [CryptoError::SnapshotImportIntoNonEmptyKeystore] if the keystore already holds a client
+    /// * [CryptoError::UnsupportedSnapshotVersion] if the snapshot was created by a newer version
+    /// * [CryptoError::SnapshotDecryptionError] if the passphrase is wrong or the snapshot corrupted
+    #[cfg_attr(test, crate::dispotent)]
+    #[cfg_attr(not(test), tracing::instrument(err, skip_all))]
+    pub async fn import_snapshot(&mut self, snapshot: &[u8], passphrase: &str) -> CryptoResult<ClientId> {
+        let keystore = self.mls_backend.key_store();
+        if self.mls_client.is_some()
+            || keystore.count::<MlsCredential>().await? > 0
+            || keystore.count::<MlsSignatureKeyPair>().await? > 0
+        {
+            return Err(CryptoError::SnapshotImportIntoNonEmptyKeystore);
+        }
+
+        match snapshot.first() {
+            None => return Err(CryptoError::SnapshotDecryptionError),
+            Some(&version) if version != SNAPSHOT_VERSION => {
+                return Err(CryptoError::UnsupportedSnapshotVersion(version))
+            }
+            _ => {}
+        }
+        let plaintext = envelope::open(passphrase, 1, snapshot).map_err(|e| match e {
+            CryptoKeystoreError::BackupDecryptionError => CryptoError::SnapshotDecryptionError,
+            e => e.into(),
+        })?;
+        let snapshot: Snapshot = serde_json::from_slice(&plaintext)?;
+
+        let client_id = ClientId::from(snapshot.client_id.clone());
+        let credentials = snapshot
+            .credentials
+            .iter()
+            .filter(|c| c.id == snapshot.client_id)
+            .map(|c| {
+                let credential = openmls::prelude::Credential::tls_deserialize(&mut c.credential.as_slice())
+                    .map_err(MlsError::from)?;
+                CryptoResult::Ok((credential, c.created_at))
+            })
+            .collect::<CryptoResult<Vec<_>>>()?;
+        let signature_schemes = snapshot
+            .signature_keypairs
+            .iter()
+            .map(|kp| SignatureScheme::try_from(kp.signature_scheme).map_err(|_| CryptoError::ImplementationError))
+            .collect::<CryptoResult<HashSet<_>>>()?;
+
+        snapshot.persist(&self.mls_backend).await?;
+
+        let mls_client = Client::load(&self.mls_backend, &client_id, credentials, signature_schemes).await?;
+        self.mls_groups = Self::restore_groups(&self.mls_backend).await?;
+        self.init_pki_env().await?;
+        self.mls_client.replace(mls_client);
+
+        Ok(client_id)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use wasm_bindgen_test::*;
+
+    use crate::{
+        prelude::{CryptoError, MlsCentral, MlsCentralConfiguration},
+        test_utils::*,
+    };
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    async fn fresh_central(case: &TestCase) -> MlsCentral {
+        let configuration = MlsCentralConfiguration::try_new(
+            "new device".to_string(),
+            "test".to_string(),
+            None,
+            vec![case.ciphersuite()],
+            None,
+            None,
+        )
+        .unwrap();
+        MlsCentral::try_new_in_memory(configuration).await.unwrap()
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn new_device_should_carry_on_from_snapshot(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let mut alice_central = alice_central.mls_central;
+                    let id = conversation_id();
+                    alice_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let snapshot = alice_central.export_snapshot("correct horse").await.unwrap();
+                    let alice_id = alice_central.client_id().unwrap();
+                    let epoch = alice_central.conversation_epoch(&id).await.unwrap();
+                    alice_central.wipe().await.unwrap();
+
+                    let mut new_device = fresh_central(&case).await;
+                    let client_id = new_device.import_snapshot(&snapshot, "correct horse").await.unwrap();
+                    assert_eq!(client_id, alice_id);
+                    assert_eq!(new_device.client_id().unwrap(), alice_id);
+                    assert_eq!(new_device.conversation_epoch(&id).await.unwrap(), epoch);
+                    assert!(new_device.try_talk_to(&id, &mut bob_central.mls_central).await.is_ok());
+
+                    // still able to move the conversation forward
+                    let commit = new_device.update_keying_material(&id).await.unwrap().commit;
+                    new_device.commit_accepted(&id).await.unwrap();
+                    bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+                    assert!(new_device.try_talk_to(&id, &mut bob_central.mls_central).await.is_ok());
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn import_should_fail_with_wrong_passphrase(case: TestCase) {
+        run_test_with_central(case.clone(), move |[alice_central]| {
+            Box::pin(async move {
+                let snapshot = alice_central
+                    .mls_central
+                    .export_snapshot("correct horse")
+                    .await
+                    .unwrap();
+                let mut new_device = fresh_central(&case).await;
+
+                let import = new_device.import_snapshot(&snapshot, "battery staple").await;
+                assert!(matches!(import.unwrap_err(), CryptoError::SnapshotDecryptionError));
+
+                let mut tampered = snapshot.clone();
+                *tampered.last_mut().unwrap() ^= 1;
+                let import = new_device.import_snapshot(&tampered, "correct horse").await;
+                assert!(matches!(import.unwrap_err(), CryptoError::SnapshotDecryptionError));
+
+                let mut newer = snapshot.clone();
+                newer[0] = u8::MAX;
+                let import = new_device.import_snapshot(&newer, "correct horse").await;
+                assert!(matches!(
+                    import.unwrap_err(),
+                    CryptoError::UnsupportedSnapshotVersion(u8::MAX)
+                ));
+
+                // nothing was imported
+                assert!(new_device.client_id().is_err());
+            })
+        })
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn import_should_fail_when_keystore_holds_a_client(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let snapshot = alice_central
+                        .mls_central
+                        .export_snapshot("correct horse")
+                        .await
+                        .unwrap();
+                    let import = bob_central
+                        .mls_central
+                        .import_snapshot(&snapshot, "correct horse")
+                        .await;
+                    assert!(matches!(
+                        import.unwrap_err(),
+                        CryptoError::SnapshotImportIntoNonEmptyKeystore
+                    ));
+                })
+            },
+        )
+        .await
+    }
+}
@@ -0,0 +1,296 @@
+use openmls_traits::OpenMlsCryptoProvider;
+
+use crate::prelude::{CryptoResult, MlsCentral};
+
+impl MlsCentral {
+    /// Starts a keystore transaction. Until [MlsCentral::commit_transaction] is called, everything persisted by
+    /// the operations made in the meantime (decrypting, committing, merging, buffering messages...) is staged in
+    /// memory and later written to the keystore at once. This way, a crash in the middle of processing a batch of
+    /// messages cannot leave conversations, key material and buffered messages out of step.
+    ///
+    /// # Errors
+    /// If a transaction is already in progress
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self)))]
+    pub async fn begin_transaction(&self) -> CryptoResult<()> {
+        Ok(self.mls_backend.key_store().begin_transaction().await?)
+    }
+
+    /// Atomically persists everything staged since [MlsCentral::begin_transaction].
+    ///
+    /// # Errors
+    /// If no transaction is in progress or if writing to the keystore failed. In the latter case nothing has been
+    /// persisted and conversations and identities are reloaded from the keystore, as after a
+    /// [MlsCentral::rollback_transaction]
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self)))]
+    pub async fn commit_transaction(&mut self) -> CryptoResult<()> {
+        if let Err(e) = self.mls_backend.key_store().commit_transaction().await {
+            self.mls_groups = Self::restore_groups(&self.mls_backend).await?;
+            if let Some(client) = self.mls_client.as_mut() {
+                client.reload_identities(&self.mls_backend).await?;
+            }
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    /// Discards everything staged since [MlsCentral::begin_transaction]. Since conversations and identities kept in
+    /// memory might have moved on in the meantime, they are reloaded from the keystore.
+    ///
+    /// # Errors
+    /// If no transaction is in progress
+    #[cfg_attr(not(test), tracing::instrument(err, skip(self)))]
+    pub async fn rollback_transaction(&mut self) -> CryptoResult<()> {
+        self.mls_backend.key_store().rollback_transaction().await?;
+        self.mls_groups = Self::restore_groups(&self.mls_backend).await?;
+        if let Some(client) = self.mls_client.as_mut() {
+            client.reload_identities(&self.mls_backend).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use core_crypto_keystore::entities::{EntityBase, PersistedMlsGroup};
+    use openmls_traits::OpenMlsCryptoProvider;
+    use wasm_bindgen_test::*;
+
+    use crate::{
+        prelude::{Client, MlsCentral, MlsConversationCreationMessage},
+        test_utils::*,
+        CryptoError,
+    };
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    async fn persisted_state(central: &MlsCentral, id: &[u8]) -> Vec<u8> {
+        // read straight from the database, bypassing any transaction
+        let mut conn = central.mls_backend.key_store().borrow_conn().await.unwrap();
+        PersistedMlsGroup::find_one(&mut conn, &id.into())
+            .await
+            .unwrap()
+            .unwrap()
+            .state
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_persist_staged_writes_on_commit(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let before = persisted_state(&bob_central.mls_central, &id).await;
+                    bob_central.mls_central.begin_transaction().await.unwrap();
+                    assert!(matches!(
+                        bob_central.mls_central.begin_transaction().await.unwrap_err(),
+                        CryptoError::KeyStoreError(core_crypto_keystore::CryptoKeystoreError::TransactionInProgress)
+                    ));
+
+                    let commit = alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    bob_central
+                        .mls_central
+                        .decrypt_message(&id, commit.commit.to_bytes().unwrap())
+                        .await
+                        .unwrap();
+
+                    // nothing has reached the database yet but reads see the staged group
+                    assert_eq!(persisted_state(&bob_central.mls_central, &id).await, before);
+                    let staged = bob_central
+                        .mls_central
+                        .mls_backend
+                        .key_store()
+                        .find::<PersistedMlsGroup>(&id)
+                        .await
+                        .unwrap()
+                        .unwrap();
+                    assert_ne!(staged.state, before);
+
+                    bob_central.mls_central.commit_transaction().await.unwrap();
+                    assert_eq!(persisted_state(&bob_central.mls_central, &id).await, staged.state);
+
+                    bob_central.mls_central.drop_and_restore(&id).await;
+                    assert_eq!(
+                        bob_central.mls_central.conversation_epoch(&id).await.unwrap(),
+                        alice_central.mls_central.conversation_epoch(&id).await.unwrap()
+                    );
+                    assert!(bob_central
+                        .mls_central
+                        .try_talk_to(&id, &mut alice_central.mls_central)
+                        .await
+                        .is_ok());
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_discard_staged_writes_on_rollback(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    alice_central
+                        .mls_central
+                        .invite_all(&case, &id, [&mut bob_central.mls_central])
+                        .await
+                        .unwrap();
+
+                    let epoch = bob_central.mls_central.conversation_epoch(&id).await.unwrap();
+                    let commit = alice_central.mls_central.update_keying_material(&id).await.unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+                    let commit = commit.commit.to_bytes().unwrap();
+
+                    bob_central.mls_central.begin_transaction().await.unwrap();
+                    bob_central.mls_central.decrypt_message(&id, &commit).await.unwrap();
+                    assert_eq!(
+                        bob_central.mls_central.conversation_epoch(&id).await.unwrap(),
+                        epoch + 1
+                    );
+
+                    // as if the app had been killed before committing the transaction
+                    bob_central.mls_central.rollback_transaction().await.unwrap();
+                    assert_eq!(bob_central.mls_central.conversation_epoch(&id).await.unwrap(), epoch);
+                    assert!(matches!(
+                        bob_central.mls_central.rollback_transaction().await.unwrap_err(),
+                        CryptoError::KeyStoreError(core_crypto_keystore::CryptoKeystoreError::TransactionNotStarted)
+                    ));
+
+                    // the commit can be processed again
+                    bob_central.mls_central.decrypt_message(&id, &commit).await.unwrap();
+                    assert_eq!(
+                        bob_central.mls_central.conversation_epoch(&id).await.unwrap(),
+                        epoch + 1
+                    );
+                    assert!(bob_central
+                        .mls_central
+                        .try_talk_to(&id, &mut alice_central.mls_central)
+                        .await
+                        .is_ok());
+                })
+            },
+        )
+        .await
+    }
+
+    #[apply(all_cred_cipher)]
+    #[wasm_bindgen_test]
+    pub async fn should_discard_staged_welcome_and_credential_on_rollback(case: TestCase) {
+        run_test_with_client_ids(
+            case.clone(),
+            ["alice", "bob"],
+            move |[mut alice_central, mut bob_central]| {
+                Box::pin(async move {
+                    let id = conversation_id();
+                    alice_central
+                        .mls_central
+                        .new_conversation(&id, case.credential_type, case.cfg.clone())
+                        .await
+                        .unwrap();
+                    let bob = bob_central.mls_central.rand_key_package(&case).await;
+                    let MlsConversationCreationMessage { welcome, .. } = alice_central
+                        .mls_central
+                        .add_members_to_conversation(&id, vec![bob])
+                        .await
+                        .unwrap();
+                    alice_central.mls_central.commit_accepted(&id).await.unwrap();
+
+                    let before = bob_central.mls_central.count_entities().await;
+                    let credentials = bob_central.mls_central.count_credentials_in_keystore().await;
+
+                    bob_central.mls_central.begin_transaction().await.unwrap();
+                    bob_central
+                        .mls_central
+                        .process_welcome_message(welcome.clone().into(), case.custom_cfg())
+                        .await
+                        .unwrap();
+                    let client = bob_central.mls_central.mls_client.as_mut().unwrap();
+                    let cb = Client::new_basic_credential_bundle(
+                        client.id(),
+                        case.signature_scheme(),
+                        &bob_central.mls_central.mls_backend,
+                    )
+                    .unwrap();
+                    let cb = client
+                        .save_identity(&bob_central.mls_central.mls_backend, None, case.signature_scheme(), cb)
+                        .await
+                        .unwrap();
+
+                    // reads see the staged writes
+                    assert!(bob_central.mls_central.conversation_exists(&id).await);
+                    assert_eq!(
+                        bob_central.mls_central.count_entities().await.key_package,
+                        before.key_package - 1
+                    );
+                    assert_eq!(
+                        bob_central.mls_central.count_credentials_in_keystore().await,
+                        credentials + 1
+                    );
+                    assert!(bob_central
+                        .mls_central
+                        .find_credential_from_keystore(&cb)
+                        .await
+                        .is_some());
+
+                    bob_central.mls_central.rollback_transaction().await.unwrap();
+                    assert!(!bob_central.mls_central.conversation_exists(&id).await);
+                    assert_eq!(bob_central.mls_central.count_entities().await, before);
+                    assert_eq!(
+                        bob_central.mls_central.count_credentials_in_keystore().await,
+                        credentials
+                    );
+                    assert!(bob_central
+                        .mls_central
+                        .find_credential_from_keystore(&cb)
+                        .await
+                        .is_none());
+                    // identities kept in memory are reloaded too
+                    assert!(bob_central
+                        .mls_central
+                        .mls_client
+                        .as_ref()
+                        .unwrap()
+                        .identities
+                        .iter()
+                        .all(|(_, c)| c != &cb));
+
+                    // the Welcome can be processed again
+                    bob_central
+                        .mls_central
+                        .process_welcome_message(welcome.into(), case.custom_cfg())
+                        .await
+                        .unwrap();
+                    assert!(bob_central
+                        .mls_central
+                        .try_talk_to(&id, &mut alice_central.mls_central)
+                        .await
+                        .is_ok());
+                })
+            },
+        )
+        .await
+    }
+}
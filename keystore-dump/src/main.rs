@@ -8,27 +8,18 @@ fn main() -> Result<()> {
 #[cfg(not(target_family = "wasm"))]
 #[tokio::main]
 async fn main() -> Result<()> {
-    #[derive(Debug, clap::Parser)]
-    #[command(author, version, about, long_about = None)]
-    struct Args {
-        #[arg(short, long)]
-        key: String,
-
-        path: String,
-    }
-
-    use chrono::TimeZone;
     use clap::Parser as _;
     use color_eyre::eyre::eyre;
-    use core_crypto_keystore::{entities::*, Connection as Keystore};
-    use openmls::prelude::TlsDeserializeTrait;
-    use serde::ser::{SerializeMap, Serializer};
+    use core_crypto_keystore::Connection as Keystore;
 
     color_eyre::install()?;
 
     let args = Args::parse();
 
-    if !tokio::fs::try_exists(&args.path).await.unwrap_or_default() {
+    let command = args.command.unwrap_or(Command::Dump);
+
+    // Restoring a backup creates the keystore if it does not exist yet
+    if !matches!(command, Command::Import { .. }) && !tokio::fs::try_exists(&args.path).await.unwrap_or_default() {
         return Err(eyre!("File not found: {}", args.path));
     }
 
@@ -36,6 +27,69 @@ async fn main() -> Result<()> {
         .await
         .map_err(|e| eyre!("The passkey is probably wrong; [err: {e}]"))?;
 
+    match command {
+        Command::Dump => dump(&keystore).await?,
+        Command::Export { passphrase, output } => {
+            let backup = keystore.export_backup(passphrase).await?;
+            tokio::fs::write(&output, backup).await?;
+        }
+        Command::Import { passphrase, input } => {
+            let backup = tokio::fs::read(&input).await?;
+            keystore.import_backup(&backup, passphrase).await?;
+        }
+    }
+
+    keystore.close().await?;
+
+    Ok(())
+}
+
+#[cfg(not(target_family = "wasm"))]
+#[derive(Debug, clap::Parser)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[arg(short, long)]
+    key: String,
+
+    path: String,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[cfg(not(target_family = "wasm"))]
+#[derive(Debug, clap::Subcommand)]
+enum Command {
+    /// Dumps the keystore to JSON on stdout. This is the default
+    Dump,
+    /// Exports the keystore to an encrypted backup, which can be restored into a keystore on any platform
+    Export {
+        /// Passphrase the backup is encrypted with
+        #[arg(short, long)]
+        passphrase: String,
+
+        /// File the backup is written to
+        output: String,
+    },
+    /// Restores an encrypted backup into the keystore, which must be empty
+    Import {
+        /// Passphrase the backup was encrypted with
+        #[arg(short, long)]
+        passphrase: String,
+
+        /// File the backup is read from
+        input: String,
+    },
+}
+
+#[cfg(not(target_family = "wasm"))]
+async fn dump(keystore: &core_crypto_keystore::Connection) -> Result<()> {
+    use chrono::TimeZone;
+    use color_eyre::eyre::eyre;
+    use core_crypto_keystore::entities::*;
+    use openmls::prelude::TlsDeserializeTrait;
+    use serde::ser::{SerializeMap, Serializer};
+
     let mut json_serializer = serde_json::Serializer::pretty(std::io::stdout());
     let mut json_map = json_serializer.serialize_map(None)?;
 
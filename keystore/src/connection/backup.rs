@@ -0,0 +1,381 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+//! Portable, encrypted backups of a whole keystore. The same backup can be restored into a SQLCipher
+//! database or an IndexedDB one.
+//!
+//! A backup is a [crate::envelope] prefixed with `magic (4 bytes) | version (1 byte)`. The plaintext is the
+//! postcard serialization of a list of collections, each holding the postcard serialization of its entities.
+//!
+//! The same machinery copies a keystore from one storage to another, e.g. to persist an in-memory keystore.
+
+use zeroize::Zeroizing;
+
+use crate::{
+    connection::{Connection, DatabaseConnection, KeystoreDatabaseConnection},
+    entities::{Entity, EntityFindParams},
+    envelope, CryptoKeystoreError, CryptoKeystoreResult,
+};
+
+const BACKUP_MAGIC: &[u8; 4] = b"CCKB";
+const BACKUP_VERSION: u8 = 1;
+const PREFIX_LENGTH: usize = BACKUP_MAGIC.len() + 1;
+
+/// The entities of a collection, each serialized with postcard
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct BackupCollection {
+    name: String,
+    entities: Vec<Vec<u8>>,
+}
+
+/// Serializes every entity of a collection
+async fn export_collection<E>(
+    conn: &mut KeystoreDatabaseConnection,
+    name: &str,
+    collections: &mut Vec<BackupCollection>,
+) -> CryptoKeystoreResult<()>
+where
+    E: Entity<ConnectionType = KeystoreDatabaseConnection> + serde::Serialize,
+{
+    let entities = E::find_all(conn, EntityFindParams::default())
+        .await?
+        .iter()
+        .map(postcard::to_stdvec)
+        .collect::<Result<Vec<_>, _>>()?;
+    collections.push(BackupCollection {
+        name: name.into(),
+        entities,
+    });
+    Ok(())
+}
+
+/// Serializes the entity of a collection holding at most one
+#[cfg(feature = "mls-keystore")]
+async fn export_unique<E>(
+    conn: &mut KeystoreDatabaseConnection,
+    name: &str,
+    collections: &mut Vec<BackupCollection>,
+) -> CryptoKeystoreResult<()>
+where
+    E: crate::entities::UniqueEntity<ConnectionType = KeystoreDatabaseConnection> + serde::Serialize,
+{
+    let entities = match find_unique::<E>(conn).await? {
+        Some(entity) => vec![postcard::to_stdvec(&entity)?],
+        None => vec![],
+    };
+    collections.push(BackupCollection {
+        name: name.into(),
+        entities,
+    });
+    Ok(())
+}
+
+#[cfg(feature = "mls-keystore")]
+async fn find_unique<E>(conn: &mut KeystoreDatabaseConnection) -> CryptoKeystoreResult<Option<E>>
+where
+    E: crate::entities::UniqueEntity<ConnectionType = KeystoreDatabaseConnection>,
+{
+    match E::find_unique(conn).await {
+        Ok(entity) => Ok(Some(entity)),
+        Err(CryptoKeystoreError::NotFound(..) | CryptoKeystoreError::MissingKeyInStore(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+async fn import_collection<E>(
+    conn: &mut KeystoreDatabaseConnection,
+    collection: &BackupCollection,
+) -> CryptoKeystoreResult<()>
+where
+    E: Entity<ConnectionType = KeystoreDatabaseConnection> + serde::de::DeserializeOwned,
+{
+    for entity in &collection.entities {
+        let entity: E = postcard::from_bytes(entity).map_err(|_| CryptoKeystoreError::InvalidBackup)?;
+        entity.save(conn).await?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "mls-keystore")]
+async fn import_unique<E>(
+    conn: &mut KeystoreDatabaseConnection,
+    collection: &BackupCollection,
+) -> CryptoKeystoreResult<()>
+where
+    E: crate::entities::UniqueEntity<ConnectionType = KeystoreDatabaseConnection> + serde::de::DeserializeOwned,
+{
+    for entity in &collection.entities {
+        let entity: E = postcard::from_bytes(entity).map_err(|_| CryptoKeystoreError::InvalidBackup)?;
+        entity.replace(conn).await?;
+    }
+    Ok(())
+}
+
+async fn is_empty(conn: &mut KeystoreDatabaseConnection) -> CryptoKeystoreResult<bool> {
+    let mut collections = vec![];
+    export_all(conn, &mut collections).await?;
+    Ok(collections.iter().all(|collection| collection.entities.is_empty()))
+}
+
+async fn export_all(
+    conn: &mut KeystoreDatabaseConnection,
+    collections: &mut Vec<BackupCollection>,
+) -> CryptoKeystoreResult<()> {
+    #[cfg(feature = "mls-keystore")]
+    {
+        use crate::entities::*;
+        export_collection::<MlsCredential>(conn, "mls_credentials", collections).await?;
+        export_collection::<MlsSignatureKeyPair>(conn, "mls_signature_keypairs", collections).await?;
+        export_collection::<MlsHpkePrivateKey>(conn, "mls_hpke_private_keys", collections).await?;
+        export_collection::<MlsEncryptionKeyPair>(conn, "mls_encryption_keypairs", collections).await?;
+        export_collection::<MlsEpochEncryptionKeyPair>(conn, "mls_epoch_encryption_keypairs", collections).await?;
+        export_collection::<MlsPskBundle>(conn, "mls_psk_bundles", collections).await?;
+        export_collection::<MlsKeyPackage>(conn, "mls_keypackages", collections).await?;
+        export_collection::<PersistedMlsGroup>(conn, "mls_groups", collections).await?;
+        export_collection::<MlsGroupKeyRotation>(conn, "mls_group_key_rotations", collections).await?;
+        export_collection::<MlsEpochAuthenticators>(conn, "mls_epoch_authenticators", collections).await?;
+        export_collection::<MlsMemberEpochs>(conn, "mls_member_epochs", collections).await?;
+        export_collection::<MlsHistory>(conn, "mls_history", collections).await?;
+        export_collection::<PersistedMlsPendingGroup>(conn, "mls_pending_groups", collections).await?;
+        export_collection::<MlsPendingMessage>(conn, "mls_pending_messages", collections).await?;
+        export_collection::<E2eiEnrollment>(conn, "e2ei_enrollment", collections).await?;
+        export_unique::<E2eiRefreshToken>(conn, "e2ei_refresh_token", collections).await?;
+        export_unique::<E2eiAcmeCA>(conn, "e2ei_acme_ca", collections).await?;
+        export_collection::<E2eiIntermediateCert>(conn, "e2ei_intermediate_certs", collections).await?;
+        export_collection::<E2eiCrl>(conn, "e2ei_crls", collections).await?;
+    }
+
+    #[cfg(feature = "proteus-keystore")]
+    {
+        use crate::entities::{ProteusIdentity, ProteusPrekey, ProteusSession};
+        export_collection::<ProteusPrekey>(conn, "proteus_prekeys", collections).await?;
+        export_collection::<ProteusIdentity>(conn, "proteus_identities", collections).await?;
+        export_collection::<ProteusSession>(conn, "proteus_sessions", collections).await?;
+    }
+
+    Ok(())
+}
+
+async fn import_all(
+    conn: &mut KeystoreDatabaseConnection,
+    collections: &[BackupCollection],
+) -> CryptoKeystoreResult<()> {
+    for collection in collections {
+        match collection.name.as_str() {
+            #[cfg(feature = "mls-keystore")]
+            "mls_credentials" => import_collection::<crate::entities::MlsCredential>(conn, collection).await?,
+            #[cfg(feature = "mls-keystore")]
+            "mls_signature_keypairs" => {
+                import_collection::<crate::entities::MlsSignatureKeyPair>(conn, collection).await?
+            }
+            #[cfg(feature = "mls-keystore")]
+            "mls_hpke_private_keys" => {
+                import_collection::<crate::entities::MlsHpkePrivateKey>(conn, collection).await?
+            }
+            #[cfg(feature = "mls-keystore")]
+            "mls_encryption_keypairs" => {
+                import_collection::<crate::entities::MlsEncryptionKeyPair>(conn, collection).await?
+            }
+            #[cfg(feature = "mls-keystore")]
+            "mls_epoch_encryption_keypairs" => {
+                import_collection::<crate::entities::MlsEpochEncryptionKeyPair>(conn, collection).await?
+            }
+            #[cfg(feature = "mls-keystore")]
+            "mls_psk_bundles" => import_collection::<crate::entities::MlsPskBundle>(conn, collection).await?,
+            #[cfg(feature = "mls-keystore")]
+            "mls_keypackages" => import_collection::<crate::entities::MlsKeyPackage>(conn, collection).await?,
+            #[cfg(feature = "mls-keystore")]
+            "mls_groups" => import_collection::<crate::entities::PersistedMlsGroup>(conn, collection).await?,
+            #[cfg(feature = "mls-keystore")]
+            "mls_group_key_rotations" => {
+                import_collection::<crate::entities::MlsGroupKeyRotation>(conn, collection).await?
+            }
+            #[cfg(feature = "mls-keystore")]
+            "mls_epoch_authenticators" => {
+                import_collection::<crate::entities::MlsEpochAuthenticators>(conn, collection).await?
+            }
+            #[cfg(feature = "mls-keystore")]
+            "mls_member_epochs" => import_collection::<crate::entities::MlsMemberEpochs>(conn, collection).await?,
+            #[cfg(feature = "mls-keystore")]
+            "mls_history" => import_collection::<crate::entities::MlsHistory>(conn, collection).await?,
+            #[cfg(feature = "mls-keystore")]
+            "mls_pending_groups" => {
+                import_collection::<crate::entities::PersistedMlsPendingGroup>(conn, collection).await?
+            }
+            #[cfg(feature = "mls-keystore")]
+            "mls_pending_messages" => import_collection::<crate::entities::MlsPendingMessage>(conn, collection).await?,
+            #[cfg(feature = "mls-keystore")]
+            "e2ei_enrollment" => import_collection::<crate::entities::E2eiEnrollment>(conn, collection).await?,
+            #[cfg(feature = "mls-keystore")]
+            "e2ei_refresh_token" => import_unique::<crate::entities::E2eiRefreshToken>(conn, collection).await?,
+            #[cfg(feature = "mls-keystore")]
+            "e2ei_acme_ca" => import_unique::<crate::entities::E2eiAcmeCA>(conn, collection).await?,
+            #[cfg(feature = "mls-keystore")]
+            "e2ei_intermediate_certs" => {
+                import_collection::<crate::entities::E2eiIntermediateCert>(conn, collection).await?
+            }
+            #[cfg(feature = "mls-keystore")]
+            "e2ei_crls" => import_collection::<crate::entities::E2eiCrl>(conn, collection).await?,
+            #[cfg(feature = "proteus-keystore")]
+            "proteus_prekeys" => import_collection::<crate::entities::ProteusPrekey>(conn, collection).await?,
+            #[cfg(feature = "proteus-keystore")]
+            "proteus_identities" => import_collection::<crate::entities::ProteusIdentity>(conn, collection).await?,
+            #[cfg(feature = "proteus-keystore")]
+            "proteus_sessions" => import_collection::<crate::entities::ProteusSession>(conn, collection).await?,
+            name => return Err(CryptoKeystoreError::UnknownBackupCollection(name.into())),
+        }
+    }
+    Ok(())
+}
+
+/// Copies every entity of `source` into `target`, which has to be empty. Everything is written atomically.
+async fn copy_all(
+    source: &mut KeystoreDatabaseConnection,
+    target: &mut KeystoreDatabaseConnection,
+) -> CryptoKeystoreResult<()> {
+    if !is_empty(target).await? {
+        return Err(CryptoKeystoreError::CopyIntoNonEmptyStore);
+    }
+
+    let mut collections = vec![];
+    export_all(source, &mut collections).await?;
+
+    target.begin_batch().await?;
+    if let Err(e) = import_all(target, &collections).await {
+        target.rollback_batch().await?;
+        return Err(e);
+    }
+    target.commit_batch().await
+}
+
+impl Connection {
+    /// Exports every entity of the keystore, MLS, Proteus and E2EI alike, in a backup encrypted under a key
+    /// derived from `passphrase`. It can be restored with [Connection::import_backup] into a keystore backed by
+    /// any platform.
+    ///
+    /// # Errors
+    /// [CryptoKeystoreError::TransactionInProgress] if a transaction is in progress on this connection, since the
+    /// writes it staged would be left out
+    pub async fn export_backup(&self, passphrase: impl AsRef<str>) -> CryptoKeystoreResult<Vec<u8>> {
+        if passphrase.as_ref().is_empty() {
+            return Err(CryptoKeystoreError::IncorrectApiUsage(
+                "The backup passphrase cannot be empty",
+            ));
+        }
+        let transaction = self.transaction.lock().await;
+        if transaction.is_some() {
+            return Err(CryptoKeystoreError::TransactionInProgress);
+        }
+
+        let mut collections = vec![];
+        {
+            let mut conn = self.conn.lock().await;
+            export_all(&mut conn, &mut collections).await?;
+        }
+        let plaintext = Zeroizing::new(postcard::to_stdvec(&collections)?);
+
+        let mut prefix = Vec::with_capacity(PREFIX_LENGTH);
+        prefix.extend_from_slice(BACKUP_MAGIC);
+        prefix.push(BACKUP_VERSION);
+        envelope::seal(passphrase.as_ref(), &prefix, &plaintext)
+    }
+
+    /// Restores a backup created by [Connection::export_backup]. Everything is written atomically, so that a
+    /// failure leaves the keystore untouched.
+    ///
+    /// # Errors
+    /// * [CryptoKeystoreError::BackupRestoreIntoNonEmptyStore] if the keystore already holds entities
+    /// * [CryptoKeystoreError::UnsupportedBackupVersion] if the backup was created by a newer version
+    /// * [CryptoKeystoreError::BackupDecryptionError] if the passphrase is wrong or the backup was tampered with
+    /// * [CryptoKeystoreError::InvalidBackup] if this is not a backup
+    pub async fn import_backup(&self, backup: &[u8], passphrase: impl AsRef<str>) -> CryptoKeystoreResult<()> {
+        if backup.len() < PREFIX_LENGTH || &backup[..BACKUP_MAGIC.len()] != BACKUP_MAGIC {
+            return Err(CryptoKeystoreError::InvalidBackup);
+        }
+        let version = backup[BACKUP_MAGIC.len()];
+        if version != BACKUP_VERSION {
+            return Err(CryptoKeystoreError::UnsupportedBackupVersion(version));
+        }
+        let plaintext = envelope::open(passphrase.as_ref(), PREFIX_LENGTH, backup)?;
+        let collections: Vec<BackupCollection> =
+            postcard::from_bytes(&plaintext).map_err(|_| CryptoKeystoreError::InvalidBackup)?;
+
+        let transaction = self.transaction.lock().await;
+        if transaction.is_some() {
+            return Err(CryptoKeystoreError::TransactionInProgress);
+        }
+        let mut conn = self.conn.lock().await;
+        if !is_empty(&mut conn).await? {
+            return Err(CryptoKeystoreError::BackupRestoreIntoNonEmptyStore);
+        }
+
+        conn.begin_batch().await?;
+        if let Err(e) = import_all(&mut conn, &collections).await {
+            conn.rollback_batch().await?;
+            return Err(e);
+        }
+        conn.commit_batch().await
+    }
+
+    /// Writes a copy of this keystore, usually an in-memory one, to the encrypted keystore at `name` and returns it.
+    /// That is a SQLCipher database, or a persistent IndexedDB store on WASM. This keystore is left untouched.
+    ///
+    /// # Errors
+    /// * [CryptoKeystoreError::TransactionInProgress] if a transaction is in progress on this connection
+    /// * [CryptoKeystoreError::CopyIntoNonEmptyStore] if the keystore at `name` already holds entities
+    pub async fn persist_to_disk(&self, name: impl AsRef<str>, key: impl AsRef<str>) -> CryptoKeystoreResult<Self> {
+        let transaction = self.transaction.lock().await;
+        if transaction.is_some() {
+            return Err(CryptoKeystoreError::TransactionInProgress);
+        }
+
+        let target = Self::open_with_key(name, key).await?;
+        let result = {
+            let mut source = self.conn.lock().await;
+            let mut conn = target.conn.lock().await;
+            copy_all(&mut source, &mut conn).await
+        };
+        if let Err(e) = result {
+            target.close().await?;
+            return Err(e);
+        }
+        Ok(target)
+    }
+
+    /// Loads every entity of the encrypted keystore at `name` into a new in-memory keystore. The keystore at `name`
+    /// is left untouched, and none of the changes made to the returned one reach it.
+    ///
+    /// # Errors
+    /// [CryptoKeystoreError::NotFound] if there is no SQLCipher database at `name`
+    pub async fn load_into_memory(name: impl AsRef<str>, key: impl AsRef<str>) -> CryptoKeystoreResult<Self> {
+        // Opening a SQLCipher database creates it when it doesn't exist
+        #[cfg(not(target_family = "wasm"))]
+        if !std::path::Path::new(name.as_ref()).exists() {
+            return Err(CryptoKeystoreError::NotFound("keystore", name.as_ref().to_string()));
+        }
+
+        let source = Self::open_with_key(name.as_ref(), key.as_ref()).await?;
+        let target = Self::open_in_memory_with_key(name.as_ref(), key.as_ref()).await?;
+        let result = {
+            let mut conn = source.conn.lock().await;
+            let mut target = target.conn.lock().await;
+            copy_all(&mut conn, &mut target).await
+        };
+        source.close().await?;
+        result?;
+        Ok(target)
+    }
+}
@@ -0,0 +1,357 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+//! Detection and removal of the MLS entities left behind once what they belong to is gone.
+//!
+//! The keystore only holds opaque blobs, so a key is considered in use as long as its public key appears verbatim
+//! in the state of a group or in a key package, which is how OpenMLS serializes them.
+//! Since this relies on OpenMLS internals, [Connection::gc] only deletes the kinds of entities it is told to.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    connection::{Connection, DatabaseConnection, KeystoreDatabaseConnection},
+    entities::{
+        EntityBase, EntityFindParams, MlsCredential, MlsEncryptionKeyPair, MlsEpochEncryptionKeyPair,
+        MlsHpkePrivateKey, MlsKeyPackage, MlsPendingMessage, MlsSignatureKeyPair, PersistedMlsGroup,
+        PersistedMlsPendingGroup, StringEntityId,
+    },
+    CryptoKeystoreError, CryptoKeystoreResult,
+};
+
+/// What OpenMLS prepends to the public key of a leaf encryption keypair to identify it
+const ENCRYPTION_KEYPAIR_ID_PREFIX: &[u8] = b"leaf_encryption_key";
+
+/// Maximum size of the length OpenMLS prepends to the group id to identify epoch encryption keypairs, as a TLS
+/// variable-length integer
+const EPOCH_KEYPAIR_ID_MAX_PREFIX_LENGTH: usize = 4;
+
+/// Size of what OpenMLS appends to the group id to identify epoch encryption keypairs: the epoch (u64) and our leaf
+/// index (u32)
+const EPOCH_KEYPAIR_ID_SUFFIX_LENGTH: usize = 12;
+
+/// Default time a message buffered for a conversation missing from the keystore is kept before being dangling
+pub const DEFAULT_PENDING_MESSAGES_GRACE_PERIOD: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+/// Parameters of [Connection::check_integrity] and [Connection::gc]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntegrityCheckParams {
+    /// Messages are buffered for conversations which have not been joined yet, so messages of a conversation
+    /// missing from the keystore are only dangling once they have been buffered for longer than this
+    pub pending_messages_grace_period: std::time::Duration,
+}
+
+impl Default for IntegrityCheckParams {
+    fn default() -> Self {
+        Self {
+            pending_messages_grace_period: DEFAULT_PENDING_MESSAGES_GRACE_PERIOD,
+        }
+    }
+}
+
+/// Which kinds of dangling entities [Connection::gc] deletes. None by default: each kind has to be opted into once
+/// [Connection::check_integrity] has been seen to report it accurately with the OpenMLS version in use.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GcCategories {
+    pub encryption_keypairs: bool,
+    pub hpke_private_keys: bool,
+    pub epoch_encryption_keypairs: bool,
+    pub pending_messages: bool,
+    pub signature_keypairs: bool,
+}
+
+impl GcCategories {
+    /// Every kind of dangling entity
+    pub fn all() -> Self {
+        Self {
+            encryption_keypairs: true,
+            hpke_private_keys: true,
+            epoch_encryption_keypairs: true,
+            pending_messages: true,
+            signature_keypairs: true,
+        }
+    }
+}
+
+/// Dangling entities found in the keystore, by id
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// Leaf encryption keypairs used neither by a group nor by a key package
+    pub encryption_keypairs: Vec<Vec<u8>>,
+    /// Init keys whose key package is gone
+    pub hpke_private_keys: Vec<Vec<u8>>,
+    /// Epoch encryption keypairs of groups which are gone
+    pub epoch_encryption_keypairs: Vec<Vec<u8>>,
+    /// Conversations which are gone, but still have buffered messages
+    pub pending_messages: Vec<Vec<u8>>,
+    /// Signature keypairs whose credential is gone
+    pub signature_keypairs: Vec<Vec<u8>>,
+}
+
+impl IntegrityReport {
+    /// Whether no dangling entity was found
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of dangling entities found, counting the buffered messages of a conversation once
+    pub fn len(&self) -> usize {
+        self.encryption_keypairs.len()
+            + self.hpke_private_keys.len()
+            + self.epoch_encryption_keypairs.len()
+            + self.pending_messages.len()
+            + self.signature_keypairs.len()
+    }
+
+    /// Leaves out the kinds of entities which are not part of `categories`
+    fn retain(&mut self, categories: GcCategories) {
+        let GcCategories {
+            encryption_keypairs,
+            hpke_private_keys,
+            epoch_encryption_keypairs,
+            pending_messages,
+            signature_keypairs,
+        } = categories;
+        for (ids, retained) in [
+            (&mut self.encryption_keypairs, encryption_keypairs),
+            (&mut self.hpke_private_keys, hpke_private_keys),
+            (&mut self.epoch_encryption_keypairs, epoch_encryption_keypairs),
+            (&mut self.pending_messages, pending_messages),
+            (&mut self.signature_keypairs, signature_keypairs),
+        ] {
+            if !retained {
+                ids.clear();
+            }
+        }
+    }
+}
+
+/// Finds which of `keys` appear in any of `blobs`.
+///
+/// Keys are bucketed by length then by their first 8 bytes, so that each blob is scanned once per distinct key length.
+fn find_referenced<'k>(keys: &[&'k [u8]], blobs: &[&[u8]]) -> HashSet<&'k [u8]> {
+    fn prefix(bytes: &[u8]) -> u64 {
+        let mut prefix = [0u8; 8];
+        prefix.copy_from_slice(&bytes[..8]);
+        u64::from_le_bytes(prefix)
+    }
+
+    let mut referenced = HashSet::new();
+    let mut buckets: HashMap<usize, HashMap<u64, Vec<&'k [u8]>>> = HashMap::new();
+    for &key in keys {
+        if key.len() < 8 {
+            // Too short to be a public key, and to be searched for reliably: let's consider it used
+            referenced.insert(key);
+            continue;
+        }
+        buckets
+            .entry(key.len())
+            .or_default()
+            .entry(prefix(key))
+            .or_default()
+            .push(key);
+    }
+
+    for (len, bucket) in buckets {
+        for blob in blobs {
+            for window in blob.windows(len) {
+                if let Some(candidates) = bucket.get(&prefix(window)) {
+                    referenced.extend(candidates.iter().copied().filter(|&key| key == window));
+                }
+            }
+        }
+    }
+
+    referenced
+}
+
+fn encryption_public_key(kp: &MlsEncryptionKeyPair) -> &[u8] {
+    kp.pk.strip_prefix(ENCRYPTION_KEYPAIR_ID_PREFIX).unwrap_or(&kp.pk)
+}
+
+fn now() -> CryptoKeystoreResult<u64> {
+    #[cfg(target_family = "wasm")]
+    let now = fluvio_wasm_timer::SystemTime::now().duration_since(fluvio_wasm_timer::UNIX_EPOCH);
+    #[cfg(not(target_family = "wasm"))]
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH);
+    Ok(now.map_err(|_| CryptoKeystoreError::TimestampError)?.as_secs())
+}
+
+async fn find_all<E: EntityBase<ConnectionType = KeystoreDatabaseConnection>>(
+    conn: &mut KeystoreDatabaseConnection,
+) -> CryptoKeystoreResult<Vec<E>> {
+    E::find_all(conn, EntityFindParams::default()).await
+}
+
+async fn check(
+    conn: &mut KeystoreDatabaseConnection,
+    params: IntegrityCheckParams,
+) -> CryptoKeystoreResult<IntegrityReport> {
+    let groups = find_all::<PersistedMlsGroup>(conn).await?;
+    let pending_groups = find_all::<PersistedMlsPendingGroup>(conn).await?;
+    let keypackages = find_all::<MlsKeyPackage>(conn).await?;
+
+    let group_ids: HashSet<&[u8]> = groups
+        .iter()
+        .map(|g| g.id.as_slice())
+        .chain(pending_groups.iter().map(|g| g.id.as_slice()))
+        .collect();
+    let keypackage_blobs: Vec<&[u8]> = keypackages.iter().map(|kp| kp.keypackage.as_slice()).collect();
+    let all_blobs: Vec<&[u8]> = groups
+        .iter()
+        .map(|g| g.state.as_slice())
+        .chain(pending_groups.iter().map(|g| g.state.as_slice()))
+        .chain(keypackage_blobs.iter().copied())
+        .collect();
+
+    let mut report = IntegrityReport::default();
+
+    let encryption_keypairs = find_all::<MlsEncryptionKeyPair>(conn).await?;
+    let pks: Vec<&[u8]> = encryption_keypairs.iter().map(encryption_public_key).collect();
+    let referenced = find_referenced(&pks, &all_blobs);
+    report.encryption_keypairs = encryption_keypairs
+        .iter()
+        .filter(|kp| !referenced.contains(encryption_public_key(kp)))
+        .map(|kp| kp.pk.clone())
+        .collect();
+
+    let hpke_private_keys = find_all::<MlsHpkePrivateKey>(conn).await?;
+    let pks: Vec<&[u8]> = hpke_private_keys.iter().map(|kp| kp.pk.as_slice()).collect();
+    let referenced = find_referenced(&pks, &keypackage_blobs);
+    report.hpke_private_keys = pks
+        .into_iter()
+        .filter(|pk| !referenced.contains(pk))
+        .map(<[u8]>::to_vec)
+        .collect();
+
+    report.epoch_encryption_keypairs = find_all::<MlsEpochEncryptionKeyPair>(conn)
+        .await?
+        .iter()
+        .filter(|kp| {
+            let Some(group_id) = kp
+                .id
+                .len()
+                .checked_sub(EPOCH_KEYPAIR_ID_SUFFIX_LENGTH)
+                .map(|len| &kp.id[..len])
+            else {
+                return true;
+            };
+            // the group id may be prefixed with its length
+            !(0..=EPOCH_KEYPAIR_ID_MAX_PREFIX_LENGTH.min(group_id.len()))
+                .any(|prefix_len| group_ids.contains(&group_id[prefix_len..]))
+        })
+        .map(|kp| kp.id.clone())
+        .collect();
+
+    let expired_before = now()?.saturating_sub(params.pending_messages_grace_period.as_secs());
+    let mut pending_messages: HashMap<Vec<u8>, bool> = HashMap::new();
+    for message in find_all::<MlsPendingMessage>(conn).await? {
+        if group_ids.contains(message.id.as_slice()) {
+            continue;
+        }
+        let expired = pending_messages.entry(message.id.clone()).or_insert(true);
+        // messages buffered at an unknown time (0) are never considered expired
+        *expired &= message.received_at != 0 && message.received_at < expired_before;
+    }
+    report.pending_messages = pending_messages
+        .into_iter()
+        .filter_map(|(id, expired)| expired.then_some(id))
+        .collect();
+
+    // Before a client is initialized, its provisional signature keypairs have no credential yet
+    let credentials = find_all::<MlsCredential>(conn).await?;
+    if !credentials.is_empty() {
+        let credential_ids: HashSet<&[u8]> = credentials.iter().map(|c| c.id.as_slice()).collect();
+        report.signature_keypairs = find_all::<MlsSignatureKeyPair>(conn)
+            .await?
+            .iter()
+            // Keypairs stored through OpenMLS have no credential id, it is unknown whether they are in use
+            .filter(|kp| !kp.credential_id.is_empty() && !credential_ids.contains(kp.credential_id.as_slice()))
+            .map(|kp| kp.pk.clone())
+            .collect();
+    }
+
+    Ok(report)
+}
+
+async fn delete<E: EntityBase<ConnectionType = KeystoreDatabaseConnection>>(
+    conn: &mut KeystoreDatabaseConnection,
+    ids: &[Vec<u8>],
+) -> CryptoKeystoreResult<()> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+    let ids: Vec<StringEntityId> = ids.iter().map(|id| id.as_slice().into()).collect();
+    E::delete(conn, &ids).await
+}
+
+async fn delete_all(conn: &mut KeystoreDatabaseConnection, report: &IntegrityReport) -> CryptoKeystoreResult<()> {
+    delete::<MlsEncryptionKeyPair>(conn, &report.encryption_keypairs).await?;
+    delete::<MlsHpkePrivateKey>(conn, &report.hpke_private_keys).await?;
+    delete::<MlsEpochEncryptionKeyPair>(conn, &report.epoch_encryption_keypairs).await?;
+    delete::<MlsPendingMessage>(conn, &report.pending_messages).await?;
+    delete::<MlsSignatureKeyPair>(conn, &report.signature_keypairs).await?;
+    Ok(())
+}
+
+impl Connection {
+    /// Cross-checks the MLS entities against the groups, pending groups, key packages and credentials of the keystore,
+    /// and reports the ones which are dangling. Nothing is modified, see [Connection::gc] for that.
+    ///
+    /// This reads the whole MLS state, it's meant to be run occasionally.
+    ///
+    /// # Errors
+    /// [CryptoKeystoreError::TransactionInProgress] if a transaction is in progress on this connection, since the
+    /// writes it staged would be left out
+    pub async fn check_integrity(&self, params: IntegrityCheckParams) -> CryptoKeystoreResult<IntegrityReport> {
+        let transaction = self.transaction.lock().await;
+        if transaction.is_some() {
+            return Err(CryptoKeystoreError::TransactionInProgress);
+        }
+        let mut conn = self.conn.lock().await;
+        check(&mut conn, params).await
+    }
+
+    /// Deletes the dangling entities found by [Connection::check_integrity] which are part of `categories`, all at
+    /// once, and returns them.
+    ///
+    /// # Errors
+    /// [CryptoKeystoreError::TransactionInProgress] if a transaction is in progress on this connection
+    pub async fn gc(
+        &self,
+        params: IntegrityCheckParams,
+        categories: GcCategories,
+    ) -> CryptoKeystoreResult<IntegrityReport> {
+        let transaction = self.transaction.lock().await;
+        if transaction.is_some() {
+            return Err(CryptoKeystoreError::TransactionInProgress);
+        }
+        let mut conn = self.conn.lock().await;
+        let mut report = check(&mut conn, params).await?;
+        report.retain(categories);
+        if report.is_empty() {
+            return Ok(report);
+        }
+
+        conn.begin_batch().await?;
+        if let Err(e) = delete_all(&mut conn, &report).await {
+            conn.rollback_batch().await?;
+            return Err(e);
+        }
+        conn.commit_batch().await?;
+        Ok(report)
+    }
+}
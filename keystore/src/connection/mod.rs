@@ -22,13 +22,47 @@ pub mod platform {
             pub use wasm::storage;
         } else {
             mod generic;
-            pub use self::generic::SqlCipherConnection as KeystoreDatabaseConnection;
+            mod native;
+            pub use self::generic::SqlCipherConnection;
+            pub use self::native::NativeConnection as KeystoreDatabaseConnection;
+
+            #[cfg(feature = "custom-backend")]
+            mod backend;
+            #[cfg(feature = "custom-backend")]
+            pub use self::backend::{BackendConnection, FileBackend, KeystoreBackend};
         }
     }
 }
 
+/// Gets the SQLCipher connection out of a `KeystoreDatabaseConnection`, or returns early with `$on_backend` when the
+/// keystore is kept in a custom `KeystoreBackend`
+#[cfg(not(target_family = "wasm"))]
+macro_rules! sqlcipher_conn {
+    ($conn:expr, $backend:ident => $on_backend:expr) => {{
+        // Without the `custom-backend` feature, SQLCipher is the only variant
+        #[allow(clippy::infallible_destructuring_match)]
+        let conn = match $conn {
+            $crate::connection::KeystoreDatabaseConnection::SqlCipher(conn) => conn,
+            #[cfg(feature = "custom-backend")]
+            $crate::connection::KeystoreDatabaseConnection::Backend($backend) => return $on_backend,
+        };
+        conn
+    }};
+}
+
+#[cfg(any(target_family = "wasm", feature = "backup"))]
+mod backup;
+#[cfg(feature = "mls-keystore")]
+mod integrity;
+mod transaction;
+
+#[cfg(feature = "mls-keystore")]
+pub use self::integrity::{GcCategories, IntegrityCheckParams, IntegrityReport, DEFAULT_PENDING_MESSAGES_GRACE_PERIOD};
 pub use self::platform::*;
+use self::transaction::{paginate, KeystoreTransaction};
 use crate::entities::{Entity, EntityFindParams, StringEntityId};
+#[cfg(feature = "mls-keystore")]
+use crate::entities::{MlsCredential, MlsCredentialExt as _, UniqueEntity};
 
 use crate::{CryptoKeystoreError, CryptoKeystoreResult};
 use async_lock::{Mutex, MutexGuard};
@@ -63,6 +97,19 @@ pub trait DatabaseConnection: DatabaseConnectionRequirements {
         self.close().await
     }
 
+    /// Starts a batch of writes: everything written until [DatabaseConnection::commit_batch] is called
+    /// is applied as a single atomic unit, or not at all
+    async fn begin_batch(&mut self) -> CryptoKeystoreResult<()>;
+
+    /// Atomically applies the writes made since [DatabaseConnection::begin_batch]
+    async fn commit_batch(&mut self) -> CryptoKeystoreResult<()>;
+
+    /// Discards the writes made since [DatabaseConnection::begin_batch]
+    async fn rollback_batch(&mut self) -> CryptoKeystoreResult<()>;
+
+    /// Re-encrypts the whole database under a new key. From now on, it has to be opened with `new_key`
+    async fn rekey(&mut self, new_key: &str) -> CryptoKeystoreResult<()>;
+
     fn check_buffer_size(size: usize) -> CryptoKeystoreResult<()> {
         #[cfg(not(target_family = "wasm"))]
         if size > i32::MAX as usize {
@@ -80,6 +127,7 @@ pub trait DatabaseConnection: DatabaseConnectionRequirements {
 #[derive(Debug)]
 pub struct Connection {
     pub(crate) conn: Arc<Mutex<KeystoreDatabaseConnection>>,
+    transaction: Mutex<Option<KeystoreTransaction>>,
 }
 
 // * SAFETY: this has mutexes and atomics protecting underlying data so this is safe to share between threads
@@ -93,7 +141,10 @@ impl Connection {
             .into();
         #[allow(clippy::arc_with_non_send_sync)] // see https://github.com/rustwasm/wasm-bindgen/pull/955
         let conn = Arc::new(conn);
-        Ok(Self { conn })
+        Ok(Self {
+            conn,
+            transaction: Default::default(),
+        })
     }
 
     pub async fn open_in_memory_with_key(name: impl AsRef<str>, key: impl AsRef<str>) -> CryptoKeystoreResult<Self> {
@@ -102,35 +153,175 @@ impl Connection {
             .into();
         #[allow(clippy::arc_with_non_send_sync)] // see https://github.com/rustwasm/wasm-bindgen/pull/955
         let conn = Arc::new(conn);
-        Ok(Self { conn })
+        Ok(Self {
+            conn,
+            transaction: Default::default(),
+        })
+    }
+
+    /// Opens a keystore kept in a custom storage backend instead of a SQLCipher database.
+    /// Everything is encrypted under `key` before reaching the backend.
+    #[cfg(all(not(target_family = "wasm"), feature = "custom-backend"))]
+    pub async fn open_with_backend(
+        backend: impl KeystoreBackend + 'static,
+        key: impl AsRef<str>,
+    ) -> CryptoKeystoreResult<Self> {
+        let conn = KeystoreDatabaseConnection::Backend(BackendConnection::new(Box::new(backend), key.as_ref()));
+        Ok(Self {
+            conn: Arc::new(conn.into()),
+            transaction: Default::default(),
+        })
     }
 
     pub async fn borrow_conn(&self) -> CryptoKeystoreResult<MutexGuard<'_, KeystoreDatabaseConnection>> {
         Ok(self.conn.lock().await)
     }
 
+    /// Starts a transaction: from now on, every entity written or removed through this connection is staged in memory
+    /// and only reaches the database once [Connection::commit_transaction] is called, all at once.
+    /// Reads made through this connection in the meantime take the staged writes into account.
+    ///
+    /// Note that only the writes made through this type are staged, writing through [Connection::borrow_conn] bypasses
+    /// the transaction.
+    pub async fn begin_transaction(&self) -> CryptoKeystoreResult<()> {
+        let mut transaction = self.transaction.lock().await;
+        if transaction.is_some() {
+            return Err(CryptoKeystoreError::TransactionInProgress);
+        }
+        transaction.replace(KeystoreTransaction::default());
+        Ok(())
+    }
+
+    /// Atomically flushes every write staged since [Connection::begin_transaction].
+    /// If anything fails, none of the staged writes are persisted and the transaction is over.
+    pub async fn commit_transaction(&self) -> CryptoKeystoreResult<()> {
+        let mut transaction = self.transaction.lock().await;
+        let staged = transaction.take().ok_or(CryptoKeystoreError::TransactionNotStarted)?;
+        let mut conn = self.conn.lock().await;
+        staged.flush(&mut conn).await
+    }
+
+    /// Discards every write staged since [Connection::begin_transaction]
+    pub async fn rollback_transaction(&self) -> CryptoKeystoreResult<()> {
+        self.transaction
+            .lock()
+            .await
+            .take()
+            .ok_or(CryptoKeystoreError::TransactionNotStarted)?;
+        Ok(())
+    }
+
+    /// Changes the key the keystore is encrypted with at rest. Once this returns, the keystore has to be opened
+    /// with `new_key`.
+    ///
+    /// SQLCipher rekeys the database in a single transaction: if interrupted, the database is still under the
+    /// former key. IndexedDB stores are re-encrypted one collection at a time, each in its own transaction,
+    /// and the progress is persisted along the way. Until the run completes, every collection keeps being read
+    /// and written with the key it is currently under. If interrupted, either call this again with the same
+    /// `new_key` on this connection, or open the store with `new_key`: it picks up where it stopped before
+    /// returning. Opening it with the former key fails with [CryptoKeystoreError::RekeyInterrupted].
+    ///
+    /// # Errors
+    /// * [CryptoKeystoreError::TransactionInProgress] if a transaction is in progress on this connection
+    /// * [CryptoKeystoreError::RekeyKeyMismatch] if an interrupted rekeying towards another key is pending
+    pub async fn rekey(&self, new_key: impl AsRef<str>) -> CryptoKeystoreResult<()> {
+        let transaction = self.transaction.lock().await;
+        if transaction.is_some() {
+            return Err(CryptoKeystoreError::TransactionInProgress);
+        }
+        let mut conn = self.conn.lock().await;
+        conn.rekey(new_key.as_ref()).await
+    }
+
+    /// Whether a transaction is in progress on this connection
+    pub async fn is_in_transaction(&self) -> bool {
+        self.transaction.lock().await.is_some()
+    }
+
     pub async fn save<E: Entity<ConnectionType = KeystoreDatabaseConnection>>(
         &self,
         entity: E,
     ) -> CryptoKeystoreResult<E> {
+        if let Some(transaction) = self.transaction.lock().await.as_mut() {
+            transaction.stage_save(entity.clone());
+            return Ok(entity);
+        }
+
         let mut conn = self.conn.lock().await;
         entity.save(&mut conn).await?;
         Ok(entity)
     }
 
+    /// Inserts an entity and returns its database-generated fields.
+    /// In a transaction, those fields are generated right away (see [EntityBase::generate_fields]) and the entity is
+    /// staged with them.
+    ///
+    /// [EntityBase::generate_fields]: crate::entities::EntityBase::generate_fields
     pub async fn insert<E: Entity<ConnectionType = KeystoreDatabaseConnection> + std::marker::Sync>(
         &self,
-        entity: E,
+        mut entity: E,
     ) -> CryptoKeystoreResult<E::AutoGeneratedFields> {
+        if let Some(transaction) = self.transaction.lock().await.as_mut() {
+            let fields = entity.generate_fields()?;
+            transaction.stage_save(entity);
+            return Ok(fields);
+        }
+
         let mut conn = self.conn.lock().await;
         let fields = entity.insert(&mut conn).await?;
         Ok(fields)
     }
 
+    /// Finds the single entity of a [UniqueEntity] collection, taking a staged replacement into account
+    #[cfg(feature = "mls-keystore")]
+    pub async fn find_unique<U: UniqueEntity<ConnectionType = KeystoreDatabaseConnection>>(
+        &self,
+    ) -> CryptoKeystoreResult<U> {
+        let transaction = self.transaction.lock().await;
+        if let Some(staged) = transaction.as_ref().and_then(|t| t.find_unique::<U>()) {
+            return Ok(staged);
+        }
+
+        let mut conn = self.conn.lock().await;
+        U::find_unique(&mut conn).await
+    }
+
+    /// Replaces the single entity of a [UniqueEntity] collection
+    #[cfg(feature = "mls-keystore")]
+    pub async fn replace_unique<U: UniqueEntity<ConnectionType = KeystoreDatabaseConnection>>(
+        &self,
+        entity: U,
+    ) -> CryptoKeystoreResult<()> {
+        if let Some(transaction) = self.transaction.lock().await.as_mut() {
+            transaction.stage_replace(entity);
+            return Ok(());
+        }
+
+        let mut conn = self.conn.lock().await;
+        entity.replace(&mut conn).await
+    }
+
+    /// Removes every credential with the given content, whatever the client it belongs to
+    #[cfg(feature = "mls-keystore")]
+    pub async fn remove_credential(&self, credential: &[u8]) -> CryptoKeystoreResult<()> {
+        if let Some(transaction) = self.transaction.lock().await.as_mut() {
+            transaction.stage_credential_delete(credential);
+            return Ok(());
+        }
+
+        let mut conn = self.conn.lock().await;
+        MlsCredential::delete_by_credential(&mut conn, credential.to_vec()).await
+    }
+
     pub async fn find<E: Entity<ConnectionType = KeystoreDatabaseConnection>>(
         &self,
         id: impl AsRef<[u8]>,
     ) -> CryptoKeystoreResult<Option<E>> {
+        let transaction = self.transaction.lock().await;
+        if let Some(staged) = transaction.as_ref().and_then(|t| t.find::<E>(id.as_ref())) {
+            return Ok(staged);
+        }
+
         let mut conn = self.conn.lock().await;
         E::find_one(&mut conn, &id.as_ref().into()).await
     }
@@ -139,8 +330,16 @@ impl Connection {
         &self,
         params: EntityFindParams,
     ) -> CryptoKeystoreResult<Vec<E>> {
+        let transaction = self.transaction.lock().await;
         let mut conn = self.conn.lock().await;
-        E::find_all(&mut conn, params).await
+        match transaction.as_ref().filter(|t| t.has_writes_of::<E>()) {
+            Some(transaction) => {
+                let entities = E::find_all(&mut conn, EntityFindParams::default()).await?;
+                let entities = transaction.merge(entities, None);
+                Ok(paginate(entities, &params))
+            }
+            None => E::find_all(&mut conn, params).await,
+        }
     }
 
     pub async fn find_many<E: Entity<ConnectionType = KeystoreDatabaseConnection>, S: AsRef<[u8]>>(
@@ -148,22 +347,48 @@ impl Connection {
         ids: &[S],
     ) -> CryptoKeystoreResult<Vec<E>> {
         let entity_ids: Vec<StringEntityId> = ids.iter().map(|id| id.as_ref().into()).collect();
+        let transaction = self.transaction.lock().await;
         let mut conn = self.conn.lock().await;
-        E::find_many(&mut conn, &entity_ids).await
+        let entities = E::find_many(&mut conn, &entity_ids).await?;
+        Ok(match transaction.as_ref() {
+            Some(transaction) => transaction.merge(entities, Some(&entity_ids)),
+            None => entities,
+        })
     }
 
     pub async fn remove<E: Entity<ConnectionType = KeystoreDatabaseConnection>, S: AsRef<[u8]>>(
         &self,
         id: S,
     ) -> CryptoKeystoreResult<()> {
+        let mut transaction = self.transaction.lock().await;
         let mut conn = self.conn.lock().await;
+        if let Some(transaction) = transaction.as_mut() {
+            // Mirror the database, which complains when deleting something that isn't there
+            let exists = match transaction.find::<E>(id.as_ref()) {
+                Some(staged) => staged.is_some(),
+                None => E::find_one(&mut conn, &id.as_ref().into()).await?.is_some(),
+            };
+            if !exists {
+                return Err(E::to_missing_key_err_kind().into());
+            }
+            transaction.stage_delete::<E>(id.as_ref());
+            return Ok(());
+        }
+
         E::delete(&mut conn, &[id.as_ref().into()]).await?;
         Ok(())
     }
 
     pub async fn count<E: Entity<ConnectionType = KeystoreDatabaseConnection>>(&self) -> CryptoKeystoreResult<usize> {
+        let transaction = self.transaction.lock().await;
         let mut conn = self.conn.lock().await;
-        E::count(&mut conn).await
+        match transaction.as_ref().filter(|t| t.has_writes_of::<E>()) {
+            Some(transaction) => {
+                let entities = E::find_all(&mut conn, EntityFindParams::default()).await?;
+                Ok(transaction.merge(entities, None).len())
+            }
+            None => E::count(&mut conn).await,
+        }
     }
 
     pub async fn wipe(self) -> CryptoKeystoreResult<()> {
@@ -0,0 +1,196 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use blocking::unblock;
+
+use super::KeystoreBackend;
+use crate::CryptoKeystoreResult;
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+struct FileContents {
+    /// Incremented on every insertion, to return the values in the order they were first inserted
+    next_seq: u64,
+    collections: HashMap<String, HashMap<Vec<u8>, (u64, Vec<u8>)>>,
+}
+
+/// A [KeystoreBackend] keeping the whole keystore in a single file, loaded in memory when opened.
+///
+/// The file is rewritten on every write, or once per batch, by writing a temporary file next to it, syncing it and
+/// renaming it over the former one: a crash leaves either the former or the new contents, never a half-written file.
+///
+/// Since the whole store is serialized each time, every write outside a batch costs O(n) in the size of the store.
+/// This backend is meant for small stores; writes that come together should be grouped in a batch (which is what
+/// keystore transactions do) to pay this cost once.
+#[derive(Debug)]
+pub struct FileBackend {
+    path: PathBuf,
+    contents: FileContents,
+    /// The contents as they were when the batch in progress began, if any
+    batch_snapshot: Option<FileContents>,
+}
+
+impl FileBackend {
+    /// Opens the store kept at `path`, creating it if it doesn't exist
+    pub async fn open(path: impl Into<PathBuf>) -> CryptoKeystoreResult<Self> {
+        let path = path.into();
+        let contents = match async_fs::read(&path).await {
+            Ok(bytes) => postcard::from_bytes(&bytes)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => FileContents::default(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut backend = Self {
+            path,
+            contents,
+            batch_snapshot: None,
+        };
+        backend.persist().await?;
+        Ok(backend)
+    }
+
+    async fn persist(&mut self) -> CryptoKeystoreResult<()> {
+        let bytes = postcard::to_stdvec(&self.contents)?;
+        let path = self.path.clone();
+        unblock(move || -> std::io::Result<()> {
+            use std::io::Write as _;
+
+            let mut tmp_path = path.clone().into_os_string();
+            tmp_path.push(".tmp");
+            let mut file = std::fs::File::create(&tmp_path)?;
+            file.write_all(&bytes)?;
+            file.sync_all()?;
+            std::fs::rename(&tmp_path, &path)?;
+            // the rename itself is only durable once the directory holding the file is synced
+            #[cfg(unix)]
+            if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                std::fs::File::open(dir)?.sync_all()?;
+            }
+            Ok(())
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Applies `write` to the contents and persists them, unless a batch is in progress in which case it's done when
+    /// it's committed. If they can't be persisted, the contents are restored to what they were before.
+    async fn write<T>(&mut self, write: impl FnOnce(&mut FileContents) -> T) -> CryptoKeystoreResult<T> {
+        if self.batch_snapshot.is_some() {
+            return Ok(write(&mut self.contents));
+        }
+        let snapshot = self.contents.clone();
+        let result = write(&mut self.contents);
+        if let Err(e) = self.persist().await {
+            self.contents = snapshot;
+            return Err(e);
+        }
+        Ok(result)
+    }
+}
+
+#[async_trait::async_trait]
+impl KeystoreBackend for FileBackend {
+    async fn get(&mut self, collection: &str, key: &[u8]) -> CryptoKeystoreResult<Option<Vec<u8>>> {
+        Ok(self
+            .contents
+            .collections
+            .get(collection)
+            .and_then(|values| values.get(key))
+            .map(|(_, value)| value.clone()))
+    }
+
+    async fn get_all(&mut self, collection: &str) -> CryptoKeystoreResult<Vec<(Vec<u8>, Vec<u8>)>> {
+        let Some(values) = self.contents.collections.get(collection) else {
+            return Ok(vec![]);
+        };
+        let mut values: Vec<_> = values.iter().collect();
+        values.sort_by_key(|(_, (seq, _))| *seq);
+        Ok(values
+            .into_iter()
+            .map(|(key, (_, value))| (key.clone(), value.clone()))
+            .collect())
+    }
+
+    async fn count(&mut self, collection: &str) -> CryptoKeystoreResult<usize> {
+        Ok(self.contents.collections.get(collection).map_or(0, HashMap::len))
+    }
+
+    async fn put(&mut self, collection: &str, key: &[u8], value: Vec<u8>) -> CryptoKeystoreResult<()> {
+        self.write(|contents| {
+            let next_seq = &mut contents.next_seq;
+            contents
+                .collections
+                .entry(collection.to_string())
+                .or_default()
+                .entry(key.to_vec())
+                .and_modify(|(_, former)| *former = value.clone())
+                .or_insert_with(|| {
+                    *next_seq += 1;
+                    (*next_seq, value.clone())
+                });
+        })
+        .await
+    }
+
+    async fn delete(&mut self, collection: &str, key: &[u8]) -> CryptoKeystoreResult<bool> {
+        let deleted = self
+            .contents
+            .collections
+            .get(collection)
+            .is_some_and(|values| values.contains_key(key));
+        if deleted {
+            self.write(|contents| {
+                if let Some(values) = contents.collections.get_mut(collection) {
+                    values.remove(key);
+                }
+            })
+            .await?;
+        }
+        Ok(deleted)
+    }
+
+    async fn begin_batch(&mut self) -> CryptoKeystoreResult<()> {
+        self.batch_snapshot = Some(self.contents.clone());
+        Ok(())
+    }
+
+    async fn commit_batch(&mut self) -> CryptoKeystoreResult<()> {
+        if let Err(e) = self.persist().await {
+            self.rollback_batch().await?;
+            return Err(e);
+        }
+        self.batch_snapshot = None;
+        Ok(())
+    }
+
+    async fn rollback_batch(&mut self) -> CryptoKeystoreResult<()> {
+        if let Some(snapshot) = self.batch_snapshot.take() {
+            self.contents = snapshot;
+        }
+        Ok(())
+    }
+
+    async fn close(self: Box<Self>) -> CryptoKeystoreResult<()> {
+        Ok(())
+    }
+
+    async fn wipe(self: Box<Self>) -> CryptoKeystoreResult<()> {
+        async_fs::remove_file(&self.path).await?;
+        Ok(())
+    }
+}
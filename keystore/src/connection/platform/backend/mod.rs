@@ -0,0 +1,365 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+//! Storage backends the keystore can be plugged into instead of SQLCipher.
+//!
+//! A backend only has to keep opaque values in named collections: serializing the entities and encrypting them is
+//! taken care of by [BackendConnection], the same way the IndexedDB store does it on WASM.
+
+mod file;
+
+pub use self::file::FileBackend;
+
+use aes_gcm::aead::{Aead as _, Payload};
+
+use crate::{
+    connection::{transaction::paginate, DatabaseConnection as _, KeystoreDatabaseConnection},
+    entities::{Entity, EntityFindParams, StringEntityId},
+    CryptoKeystoreError, CryptoKeystoreResult,
+};
+
+const AES_GCM_256_NONCE_SIZE: usize = 12;
+
+/// A storage for the keystore, made of collections mapping keys to values.
+///
+/// Values are encrypted by the keystore before reaching the backend, which never sees any secret in the clear.
+/// Keys are the ids of the entities, like the primary keys of the SQLCipher tables.
+///
+/// Reads made while a batch is in progress have to see the writes made in that batch.
+#[async_trait::async_trait]
+pub trait KeystoreBackend: std::fmt::Debug + Send + Sync {
+    /// Fetches the value stored under `key` in `collection`
+    async fn get(&mut self, collection: &str, key: &[u8]) -> CryptoKeystoreResult<Option<Vec<u8>>>;
+
+    /// Fetches all the keys and values of `collection`, in the order they were first inserted
+    async fn get_all(&mut self, collection: &str) -> CryptoKeystoreResult<Vec<(Vec<u8>, Vec<u8>)>>;
+
+    /// Counts the values stored in `collection`
+    async fn count(&mut self, collection: &str) -> CryptoKeystoreResult<usize>;
+
+    /// Stores `value` under `key` in `collection`, replacing the value already stored under `key` if any
+    async fn put(&mut self, collection: &str, key: &[u8], value: Vec<u8>) -> CryptoKeystoreResult<()>;
+
+    /// Removes the value stored under `key` in `collection`. Returns whether there was one
+    async fn delete(&mut self, collection: &str, key: &[u8]) -> CryptoKeystoreResult<bool>;
+
+    /// Starts a batch: everything written until [KeystoreBackend::commit_batch] is called has to be applied as a
+    /// single atomic unit, or not at all
+    async fn begin_batch(&mut self) -> CryptoKeystoreResult<()>;
+
+    /// Atomically applies the writes made since [KeystoreBackend::begin_batch]
+    async fn commit_batch(&mut self) -> CryptoKeystoreResult<()>;
+
+    /// Discards the writes made since [KeystoreBackend::begin_batch]
+    async fn rollback_batch(&mut self) -> CryptoKeystoreResult<()>;
+
+    async fn close(self: Box<Self>) -> CryptoKeystoreResult<()>;
+
+    /// Deletes everything the backend holds
+    async fn wipe(self: Box<Self>) -> CryptoKeystoreResult<()>;
+}
+
+/// A keystore kept in a [KeystoreBackend].
+///
+/// Every id maps to the list of entities saved under it, which holds a single entity unless
+/// [EntityBase::UNIQUE_ID](crate::entities::EntityBase::UNIQUE_ID) is `false`. The list is serialized with postcard
+/// and encrypted with AES-256-GCM, using the collection and the id as additional authenticated data.
+pub struct BackendConnection {
+    backend: Box<dyn KeystoreBackend>,
+    cipher: aes_gcm::Aes256Gcm,
+}
+
+impl std::fmt::Debug for BackendConnection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BackendConnection")
+            .field("backend", &self.backend)
+            .field("cipher", &"[REDACTED]")
+            .finish()
+    }
+}
+
+fn cipher_from_key(key: &str) -> aes_gcm::Aes256Gcm {
+    let hashed_key: aes_gcm::Key<aes_gcm::Aes256Gcm> = {
+        use sha2::Digest as _;
+        let mut hasher = sha2::Sha256::new();
+        hasher.update(key.as_bytes());
+        hasher.finalize()
+    };
+
+    use aes_gcm::KeyInit as _;
+
+    aes_gcm::Aes256Gcm::new(&hashed_key)
+}
+
+fn aad(collection: &str, key: &[u8]) -> Vec<u8> {
+    [collection.as_bytes(), b"/", key].concat()
+}
+
+fn encrypt(cipher: &aes_gcm::Aes256Gcm, collection: &str, key: &[u8], data: &[u8]) -> CryptoKeystoreResult<Vec<u8>> {
+    let nonce: [u8; AES_GCM_256_NONCE_SIZE] = rand::random();
+    let encrypted = cipher
+        .encrypt(
+            aes_gcm::Nonce::from_slice(&nonce),
+            Payload {
+                msg: data,
+                aad: &aad(collection, key),
+            },
+        )
+        .map_err(|_| CryptoKeystoreError::AesGcmError)?;
+    Ok([&nonce[..], &encrypted[..]].concat())
+}
+
+fn decrypt(cipher: &aes_gcm::Aes256Gcm, collection: &str, key: &[u8], data: &[u8]) -> CryptoKeystoreResult<Vec<u8>> {
+    if data.len() < AES_GCM_256_NONCE_SIZE {
+        return Err(CryptoKeystoreError::AesGcmError);
+    }
+    let (nonce, encrypted) = data.split_at(AES_GCM_256_NONCE_SIZE);
+    cipher
+        .decrypt(
+            aes_gcm::Nonce::from_slice(nonce),
+            Payload {
+                msg: encrypted,
+                aad: &aad(collection, key),
+            },
+        )
+        .map_err(|_| CryptoKeystoreError::AesGcmError)
+}
+
+/// All the collections an entity can be saved in
+fn collections() -> Vec<&'static str> {
+    let mut collections = vec![];
+
+    #[cfg(feature = "mls-keystore")]
+    {
+        use crate::entities::*;
+        collections.extend([
+            MlsCredential::COLLECTION_NAME,
+            MlsSignatureKeyPair::COLLECTION_NAME,
+            MlsHpkePrivateKey::COLLECTION_NAME,
+            MlsEncryptionKeyPair::COLLECTION_NAME,
+            MlsEpochEncryptionKeyPair::COLLECTION_NAME,
+            MlsPskBundle::COLLECTION_NAME,
+            MlsKeyPackage::COLLECTION_NAME,
+            PersistedMlsGroup::COLLECTION_NAME,
+            MlsGroupKeyRotation::COLLECTION_NAME,
+            MlsEpochAuthenticators::COLLECTION_NAME,
+            MlsMemberEpochs::COLLECTION_NAME,
+            MlsHistory::COLLECTION_NAME,
+            PersistedMlsPendingGroup::COLLECTION_NAME,
+            MlsPendingMessage::COLLECTION_NAME,
+            E2eiEnrollment::COLLECTION_NAME,
+            E2eiRefreshToken::COLLECTION_NAME,
+            E2eiAcmeCA::COLLECTION_NAME,
+            E2eiIntermediateCert::COLLECTION_NAME,
+            E2eiCrl::COLLECTION_NAME,
+        ]);
+    }
+
+    #[cfg(feature = "proteus-keystore")]
+    {
+        use crate::entities::{EntityBase as _, ProteusIdentity, ProteusPrekey, ProteusSession};
+        collections.extend([
+            ProteusPrekey::COLLECTION_NAME,
+            ProteusIdentity::COLLECTION_NAME,
+            ProteusSession::COLLECTION_NAME,
+        ]);
+    }
+
+    collections
+}
+
+impl BackendConnection {
+    pub fn new(backend: Box<dyn KeystoreBackend>, key: &str) -> Self {
+        Self {
+            backend,
+            cipher: cipher_from_key(key),
+        }
+    }
+
+    fn decode<E>(&self, key: &[u8], value: &[u8]) -> CryptoKeystoreResult<Vec<E>>
+    where
+        E: Entity<ConnectionType = KeystoreDatabaseConnection> + serde::de::DeserializeOwned,
+    {
+        let value = zeroize::Zeroizing::new(decrypt(&self.cipher, E::COLLECTION_NAME, key, value)?);
+        Ok(postcard::from_bytes(&value)?)
+    }
+
+    async fn read<E>(&mut self, key: &[u8]) -> CryptoKeystoreResult<Vec<E>>
+    where
+        E: Entity<ConnectionType = KeystoreDatabaseConnection> + serde::de::DeserializeOwned,
+    {
+        match self.backend.get(E::COLLECTION_NAME, key).await? {
+            Some(value) => self.decode(key, &value),
+            None => Ok(vec![]),
+        }
+    }
+
+    async fn write<E>(&mut self, key: &[u8], entities: &[E]) -> CryptoKeystoreResult<()>
+    where
+        E: Entity<ConnectionType = KeystoreDatabaseConnection> + serde::Serialize,
+    {
+        let value = zeroize::Zeroizing::new(postcard::to_stdvec(entities)?);
+        let value = encrypt(&self.cipher, E::COLLECTION_NAME, key, &value)?;
+        KeystoreDatabaseConnection::check_buffer_size(value.len())?;
+        self.backend.put(E::COLLECTION_NAME, key, value).await
+    }
+
+    pub(crate) async fn save<E>(&mut self, entity: &E) -> CryptoKeystoreResult<()>
+    where
+        E: Entity<ConnectionType = KeystoreDatabaseConnection> + serde::Serialize + serde::de::DeserializeOwned,
+    {
+        let key = entity.id_raw();
+        let mut entities = if E::UNIQUE_ID {
+            vec![]
+        } else {
+            self.read::<E>(key).await?
+        };
+        entities.push(entity.clone());
+        self.write(key, &entities).await
+    }
+
+    pub(crate) async fn find_one<E>(&mut self, id: &StringEntityId<'_>) -> CryptoKeystoreResult<Option<E>>
+    where
+        E: Entity<ConnectionType = KeystoreDatabaseConnection> + serde::de::DeserializeOwned,
+    {
+        Ok(self.read(id.as_slice()).await?.into_iter().next())
+    }
+
+    pub(crate) async fn find_many<E>(&mut self, ids: &[StringEntityId<'_>]) -> CryptoKeystoreResult<Vec<E>>
+    where
+        E: Entity<ConnectionType = KeystoreDatabaseConnection> + serde::de::DeserializeOwned,
+    {
+        let mut entities = vec![];
+        for id in ids {
+            entities.extend(self.read(id.as_slice()).await?);
+        }
+        Ok(entities)
+    }
+
+    pub(crate) async fn find_all<E>(&mut self, params: EntityFindParams) -> CryptoKeystoreResult<Vec<E>>
+    where
+        E: Entity<ConnectionType = KeystoreDatabaseConnection> + serde::de::DeserializeOwned,
+    {
+        let mut entities = vec![];
+        for (key, value) in self.backend.get_all(E::COLLECTION_NAME).await? {
+            entities.extend(self.decode(&key, &value)?);
+        }
+        Ok(paginate(entities, &params))
+    }
+
+    /// Finds the only entity of a collection which holds at most one
+    pub(crate) async fn find_unique<E>(&mut self) -> CryptoKeystoreResult<Option<E>>
+    where
+        E: Entity<ConnectionType = KeystoreDatabaseConnection> + serde::de::DeserializeOwned,
+    {
+        Ok(self.find_all(EntityFindParams::default()).await?.into_iter().next())
+    }
+
+    pub(crate) async fn count<E>(&mut self) -> CryptoKeystoreResult<usize>
+    where
+        E: Entity<ConnectionType = KeystoreDatabaseConnection> + serde::de::DeserializeOwned,
+    {
+        if E::UNIQUE_ID {
+            self.backend.count(E::COLLECTION_NAME).await
+        } else {
+            Ok(self.find_all::<E>(EntityFindParams::default()).await?.len())
+        }
+    }
+
+    /// Deletes the entities saved under `ids`. Like SQLCipher, nothing is deleted if one of them is missing
+    pub(crate) async fn delete<E>(&mut self, ids: &[StringEntityId<'_>]) -> CryptoKeystoreResult<()>
+    where
+        E: Entity<ConnectionType = KeystoreDatabaseConnection>,
+    {
+        for id in ids {
+            if self.backend.get(E::COLLECTION_NAME, id.as_slice()).await?.is_none() {
+                return Err(E::to_missing_key_err_kind().into());
+            }
+        }
+        for id in ids {
+            self.backend.delete(E::COLLECTION_NAME, id.as_slice()).await?;
+        }
+        Ok(())
+    }
+
+    /// Deletes the entities matching `predicate`, leaving the ones saved under the same ids untouched
+    pub(crate) async fn delete_where<E>(&mut self, predicate: impl Fn(&E) -> bool + Send) -> CryptoKeystoreResult<()>
+    where
+        E: Entity<ConnectionType = KeystoreDatabaseConnection> + serde::Serialize + serde::de::DeserializeOwned,
+    {
+        for (key, value) in self.backend.get_all(E::COLLECTION_NAME).await? {
+            let entities: Vec<E> = self.decode(&key, &value)?;
+            let len = entities.len();
+            let entities: Vec<E> = entities.into_iter().filter(|entity| !predicate(entity)).collect();
+            if entities.is_empty() {
+                self.backend.delete(E::COLLECTION_NAME, &key).await?;
+            } else if entities.len() != len {
+                self.write(&key, &entities).await?;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn close(self) -> CryptoKeystoreResult<()> {
+        self.backend.close().await
+    }
+
+    pub(crate) async fn wipe(self) -> CryptoKeystoreResult<()> {
+        self.backend.wipe().await
+    }
+
+    pub(crate) async fn begin_batch(&mut self) -> CryptoKeystoreResult<()> {
+        self.backend.begin_batch().await
+    }
+
+    pub(crate) async fn commit_batch(&mut self) -> CryptoKeystoreResult<()> {
+        self.backend.commit_batch().await
+    }
+
+    pub(crate) async fn rollback_batch(&mut self) -> CryptoKeystoreResult<()> {
+        self.backend.rollback_batch().await
+    }
+
+    /// Re-encrypts every value under `new_key`, in a single batch
+    pub(crate) async fn rekey(&mut self, new_key: &str) -> CryptoKeystoreResult<()> {
+        let new_cipher = cipher_from_key(new_key);
+
+        self.backend.begin_batch().await?;
+        let result: CryptoKeystoreResult<()> = async {
+            for collection in collections() {
+                for (key, value) in self.backend.get_all(collection).await? {
+                    let value = zeroize::Zeroizing::new(decrypt(&self.cipher, collection, &key, &value)?);
+                    let value = encrypt(&new_cipher, collection, &key, &value)?;
+                    self.backend.put(collection, &key, value).await?;
+                }
+            }
+            Ok(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                self.backend.commit_batch().await?;
+                self.cipher = new_cipher;
+                Ok(())
+            }
+            Err(e) => {
+                self.backend.rollback_batch().await?;
+                Err(e)
+            }
+        }
+    }
+}
@@ -0,0 +1,5 @@
+CREATE TABLE mls_group_key_rotations (
+    id BLOB UNIQUE,
+    key_rotation_span INTEGER,
+    last_update INTEGER NOT NULL
+);
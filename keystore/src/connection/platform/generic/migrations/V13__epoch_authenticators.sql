@@ -0,0 +1,4 @@
+CREATE TABLE mls_epoch_authenticators (
+    id BLOB UNIQUE,
+    authenticators BLOB NOT NULL
+);
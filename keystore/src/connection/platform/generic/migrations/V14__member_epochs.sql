@@ -0,0 +1,4 @@
+CREATE TABLE mls_member_epochs (
+    id BLOB UNIQUE,
+    members BLOB NOT NULL
+);
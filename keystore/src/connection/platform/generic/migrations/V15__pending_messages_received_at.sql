@@ -0,0 +1,2 @@
+ALTER TABLE mls_pending_messages ADD COLUMN received_at INTEGER NOT NULL DEFAULT 0;
+UPDATE mls_pending_messages SET received_at = unixepoch();
@@ -0,0 +1,4 @@
+CREATE TABLE mls_history (
+    id BLOB UNIQUE,
+    messages BLOB NOT NULL
+);
@@ -43,7 +43,36 @@ impl std::ops::DerefMut for SqlCipherConnection {
     }
 }
 
+/// A transaction which, unlike [rusqlite::Transaction], can be opened while a batch is in progress on the connection.
+/// It is backed by a savepoint, which behaves like a regular deferred transaction when no batch is in progress
+/// and becomes a nested transaction otherwise.
+pub struct SqlCipherTransaction<'conn>(rusqlite::Savepoint<'conn>);
+
+impl std::ops::Deref for SqlCipherTransaction<'_> {
+    type Target = rusqlite::Connection;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl SqlCipherTransaction<'_> {
+    pub fn commit(self) -> rusqlite::Result<()> {
+        self.0.commit()
+    }
+
+    pub fn rollback(mut self) -> rusqlite::Result<()> {
+        self.0.rollback()
+    }
+}
+
 impl SqlCipherConnection {
+    /// Begins a transaction on the connection. This shadows [rusqlite::Connection::transaction] so that entities
+    /// can be persisted the same way whether or not they are part of a batch.
+    pub fn transaction(&mut self) -> rusqlite::Result<SqlCipherTransaction<'_>> {
+        self.conn.savepoint().map(SqlCipherTransaction)
+    }
+
     #[allow(unused_mut)]
     fn init_with_connection(mut conn: rusqlite::Connection, path: &str, key: &str) -> CryptoKeystoreResult<Self> {
         cfg_if::cfg_if! {
@@ -214,6 +243,20 @@ impl SqlCipherConnection {
         Ok(())
     }
 
+    fn rekey(&mut self, new_key: &str) -> CryptoKeystoreResult<()> {
+        // Rekeying rewrites every page of the database within a single transaction. Switching to a rollback journal
+        // first checkpoints the WAL, so that no page is left behind under the former key
+        let is_persistent = !self.path.is_empty();
+        if is_persistent {
+            self.conn.pragma_update(None, "journal_mode", "delete")?;
+        }
+        let result = self.conn.pragma_update(None, "rekey", new_key);
+        if is_persistent {
+            self.conn.pragma_update(None, "journal_mode", "wal")?;
+        }
+        Ok(result?)
+    }
+
     fn run_migrations(&mut self) -> CryptoKeystoreResult<()> {
         let report = migrations::runner().run(&mut self.conn).map_err(Box::new)?;
         if let Some(version) = report.applied_migrations().iter().map(|m| m.version()).max() {
@@ -249,4 +292,23 @@ impl DatabaseConnection for SqlCipherConnection {
         self.wipe().await?;
         Ok(())
     }
+
+    async fn begin_batch(&mut self) -> CryptoKeystoreResult<()> {
+        self.conn.execute_batch("BEGIN IMMEDIATE")?;
+        Ok(())
+    }
+
+    async fn commit_batch(&mut self) -> CryptoKeystoreResult<()> {
+        self.conn.execute_batch("COMMIT")?;
+        Ok(())
+    }
+
+    async fn rollback_batch(&mut self) -> CryptoKeystoreResult<()> {
+        self.conn.execute_batch("ROLLBACK")?;
+        Ok(())
+    }
+
+    async fn rekey(&mut self, new_key: &str) -> CryptoKeystoreResult<()> {
+        SqlCipherConnection::rekey(self, new_key)
+    }
 }
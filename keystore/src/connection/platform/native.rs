@@ -0,0 +1,93 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+use crate::connection::{DatabaseConnection, DatabaseConnectionRequirements};
+use crate::CryptoKeystoreResult;
+
+#[cfg(feature = "custom-backend")]
+use super::BackendConnection;
+use super::SqlCipherConnection;
+
+/// Connection to a keystore on native platforms: a SQLCipher database by default, or any storage plugged in
+/// through a [KeystoreBackend](super::KeystoreBackend) when the `custom-backend` feature is enabled
+#[derive(Debug)]
+pub enum NativeConnection {
+    SqlCipher(SqlCipherConnection),
+    #[cfg(feature = "custom-backend")]
+    Backend(BackendConnection),
+}
+
+impl DatabaseConnectionRequirements for NativeConnection {}
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl DatabaseConnection for NativeConnection {
+    async fn open(name: &str, key: &str) -> CryptoKeystoreResult<Self> {
+        Ok(Self::SqlCipher(SqlCipherConnection::open(name, key).await?))
+    }
+
+    async fn open_in_memory(name: &str, key: &str) -> CryptoKeystoreResult<Self> {
+        Ok(Self::SqlCipher(SqlCipherConnection::open_in_memory(name, key).await?))
+    }
+
+    async fn close(self) -> CryptoKeystoreResult<()> {
+        match self {
+            Self::SqlCipher(conn) => DatabaseConnection::close(conn).await,
+            #[cfg(feature = "custom-backend")]
+            Self::Backend(backend) => backend.close().await,
+        }
+    }
+
+    async fn wipe(self) -> CryptoKeystoreResult<()> {
+        match self {
+            Self::SqlCipher(conn) => DatabaseConnection::wipe(conn).await,
+            #[cfg(feature = "custom-backend")]
+            Self::Backend(backend) => backend.wipe().await,
+        }
+    }
+
+    async fn begin_batch(&mut self) -> CryptoKeystoreResult<()> {
+        match self {
+            Self::SqlCipher(conn) => conn.begin_batch().await,
+            #[cfg(feature = "custom-backend")]
+            Self::Backend(backend) => backend.begin_batch().await,
+        }
+    }
+
+    async fn commit_batch(&mut self) -> CryptoKeystoreResult<()> {
+        match self {
+            Self::SqlCipher(conn) => conn.commit_batch().await,
+            #[cfg(feature = "custom-backend")]
+            Self::Backend(backend) => backend.commit_batch().await,
+        }
+    }
+
+    async fn rollback_batch(&mut self) -> CryptoKeystoreResult<()> {
+        match self {
+            Self::SqlCipher(conn) => conn.rollback_batch().await,
+            #[cfg(feature = "custom-backend")]
+            Self::Backend(backend) => backend.rollback_batch().await,
+        }
+    }
+
+    async fn rekey(&mut self, new_key: &str) -> CryptoKeystoreResult<()> {
+        match self {
+            Self::SqlCipher(conn) => DatabaseConnection::rekey(conn, new_key).await,
+            #[cfg(feature = "custom-backend")]
+            Self::Backend(backend) => backend.rekey(new_key).await,
+        }
+    }
+}
@@ -21,7 +21,7 @@ use crate::{
 use rexie::{Index, ObjectStore};
 
 pub mod storage;
-use self::storage::{WasmEncryptedStorage, WasmStorageWrapper};
+use self::storage::{WasmEncryptedStorage, WasmStorageWrapper, REKEY_COLLECTION};
 
 #[derive(Debug)]
 pub struct WasmConnection {
@@ -138,6 +138,26 @@ impl DatabaseConnection for WasmConnection {
                     .auto_increment(false)
                     .add_index(Index::new("id", "id").unique(true)),
             )
+            .add_object_store(
+                ObjectStore::new("mls_group_key_rotations")
+                    .auto_increment(false)
+                    .add_index(Index::new("id", "id").unique(true)),
+            )
+            .add_object_store(
+                ObjectStore::new("mls_epoch_authenticators")
+                    .auto_increment(false)
+                    .add_index(Index::new("id", "id").unique(true)),
+            )
+            .add_object_store(
+                ObjectStore::new("mls_member_epochs")
+                    .auto_increment(false)
+                    .add_index(Index::new("id", "id").unique(true)),
+            )
+            .add_object_store(
+                ObjectStore::new("mls_history")
+                    .auto_increment(false)
+                    .add_index(Index::new("id", "id").unique(true)),
+            )
             .add_object_store(
                 ObjectStore::new("mls_pending_groups")
                     .auto_increment(false)
@@ -187,7 +207,8 @@ impl DatabaseConnection for WasmConnection {
                 ObjectStore::new("proteus_sessions")
                     .auto_increment(false)
                     .add_index(Index::new("id", "id").unique(true)),
-            );
+            )
+            .add_object_store(ObjectStore::new(REKEY_COLLECTION).auto_increment(false));
 
         #[cfg(feature = "idb-regression-test")]
         let rexie_builder = rexie_builder.add_object_store(ObjectStore::new("regression_check").auto_increment(false));
@@ -196,8 +217,15 @@ impl DatabaseConnection for WasmConnection {
 
         let storage = WasmStorageWrapper::Persistent(rexie);
         let conn = WasmEncryptedStorage::new(key, storage);
+        let mut conn = Self { name, conn };
 
-        Ok(Self { name, conn })
+        if conn.conn.load_pending_rekey().await? {
+            conn.rekey_collections().await?;
+        }
+        #[cfg(feature = "mls-keystore")]
+        conn.conn.migrate_pending_messages().await?;
+
+        Ok(conn)
     }
 
     async fn open_in_memory(name: &str, key: &str) -> CryptoKeystoreResult<Self> {
@@ -223,4 +251,86 @@ impl DatabaseConnection for WasmConnection {
 
         Ok(())
     }
+
+    async fn begin_batch(&mut self) -> CryptoKeystoreResult<()> {
+        self.conn.begin_batch();
+        Ok(())
+    }
+
+    async fn commit_batch(&mut self) -> CryptoKeystoreResult<()> {
+        self.conn.commit_batch().await
+    }
+
+    async fn rollback_batch(&mut self) -> CryptoKeystoreResult<()> {
+        self.conn.rollback_batch();
+        Ok(())
+    }
+
+    async fn rekey(&mut self, new_key: &str) -> CryptoKeystoreResult<()> {
+        self.conn.begin_rekey(new_key).await?;
+        self.rekey_collections().await
+    }
+}
+
+impl WasmConnection {
+    /// Re-encrypts every collection not done yet under the key of the rekeying in progress, then finishes it
+    async fn rekey_collections(&mut self) -> CryptoKeystoreResult<()> {
+        #[cfg(feature = "mls-keystore")]
+        {
+            use crate::entities::*;
+            let storage = &mut self.conn;
+            storage.rekey_collection::<MlsCredential>("mls_credentials").await?;
+            storage
+                .rekey_collection::<MlsSignatureKeyPair>("mls_signature_keypairs")
+                .await?;
+            storage
+                .rekey_collection::<MlsHpkePrivateKey>("mls_hpke_private_keys")
+                .await?;
+            storage
+                .rekey_collection::<MlsEncryptionKeyPair>("mls_encryption_keypairs")
+                .await?;
+            storage
+                .rekey_collection::<MlsEpochEncryptionKeyPair>("mls_epoch_encryption_keypairs")
+                .await?;
+            storage.rekey_collection::<MlsPskBundle>("mls_psk_bundles").await?;
+            storage.rekey_collection::<MlsKeyPackage>("mls_keypackages").await?;
+            storage.rekey_collection::<PersistedMlsGroup>("mls_groups").await?;
+            storage
+                .rekey_collection::<MlsGroupKeyRotation>("mls_group_key_rotations")
+                .await?;
+            storage
+                .rekey_collection::<MlsEpochAuthenticators>("mls_epoch_authenticators")
+                .await?;
+            storage.rekey_collection::<MlsMemberEpochs>("mls_member_epochs").await?;
+            storage.rekey_collection::<MlsHistory>("mls_history").await?;
+            storage
+                .rekey_collection::<PersistedMlsPendingGroup>("mls_pending_groups")
+                .await?;
+            storage
+                .rekey_collection::<MlsPendingMessage>("mls_pending_messages")
+                .await?;
+            storage.rekey_collection::<E2eiEnrollment>("e2ei_enrollment").await?;
+            storage
+                .rekey_collection::<E2eiRefreshToken>("e2ei_refresh_token")
+                .await?;
+            storage.rekey_collection::<E2eiAcmeCA>("e2ei_acme_ca").await?;
+            storage
+                .rekey_collection::<E2eiIntermediateCert>("e2ei_intermediate_certs")
+                .await?;
+            storage.rekey_collection::<E2eiCrl>("e2ei_crls").await?;
+        }
+
+        #[cfg(feature = "proteus-keystore")]
+        {
+            use crate::entities::{ProteusIdentity, ProteusPrekey, ProteusSession};
+            let storage = &mut self.conn;
+            storage.rekey_collection::<ProteusPrekey>("proteus_prekeys").await?;
+            storage
+                .rekey_collection::<ProteusIdentity>("proteus_identities")
+                .await?;
+            storage.rekey_collection::<ProteusSession>("proteus_sessions").await?;
+        }
+
+        self.conn.finish_rekey().await
+    }
 }
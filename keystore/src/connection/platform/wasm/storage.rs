@@ -21,11 +21,35 @@ use wasm_bindgen::JsValue;
 
 use crate::{
     entities::{Entity, EntityFindParams},
-    CryptoKeystoreResult,
+    CryptoKeystoreError, CryptoKeystoreResult,
 };
 
 use super::WasmConnection;
 
+/// Object store keeping track of a rekeying in progress, see [WasmEncryptedStorage::begin_rekey]
+pub(crate) const REKEY_COLLECTION: &str = "keystore_rekey";
+const REKEY_PROGRESS_KEY: &str = "progress";
+const REKEY_NONCE_SIZE: usize = 12;
+
+/// State of a rekeying, persisted along with every collection re-encrypted so that an interrupted
+/// rekeying can pick up where it stopped
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct WasmRekeyProgress {
+    /// The former key encrypted under the new one. It lets the storage, once opened with the new key, finish
+    /// re-encrypting the collections still under the former key, and makes sure it is the right new key.
+    former_key: Vec<u8>,
+    /// Collections already re-encrypted under the new key
+    done: Vec<String>,
+}
+
+/// A rekeying which has been started on this storage but not finished yet
+struct WasmPendingRekey {
+    /// SHA256 of the new key
+    key: zeroize::Zeroizing<Vec<u8>>,
+    cipher: aes_gcm::Aes256Gcm,
+    progress: WasmRekeyProgress,
+}
+
 pub enum WasmStorageWrapper {
     Persistent(rexie::Rexie),
     InMemory(HashMap<String, HashMap<Vec<u8>, JsValue>>),
@@ -43,9 +67,35 @@ impl std::fmt::Debug for WasmStorageWrapper {
     }
 }
 
+/// A write buffered while a batch is in progress on a persistent storage
+#[derive(Debug)]
+enum WasmBatchWrite {
+    Put {
+        collection: String,
+        key: JsValue,
+        value: JsValue,
+    },
+    Delete {
+        collection: String,
+        key: JsValue,
+    },
+}
+
+impl WasmBatchWrite {
+    fn collection(&self) -> &str {
+        match self {
+            Self::Put { collection, .. } | Self::Delete { collection, .. } => collection,
+        }
+    }
+}
+
 pub struct WasmEncryptedStorage {
     pub(crate) storage: WasmStorageWrapper,
-    pub(crate) cipher: aes_gcm::Aes256Gcm,
+    /// SHA256 of the key the storage has been opened with
+    key: zeroize::Zeroizing<Vec<u8>>,
+    cipher: aes_gcm::Aes256Gcm,
+    rekey: Option<WasmPendingRekey>,
+    batch: Option<Vec<WasmBatchWrite>>,
 }
 
 impl std::fmt::Debug for WasmEncryptedStorage {
@@ -53,23 +103,44 @@ impl std::fmt::Debug for WasmEncryptedStorage {
         f.debug_struct("WasmEncryptedStorage")
             .field("storage", &self.storage)
             .field("cipher", &"[REDACTED]")
+            .field("rekey", &self.rekey.as_ref().map(|rekey| &rekey.progress.done))
+            .field("batch", &self.batch)
             .finish()
     }
 }
 
 impl WasmEncryptedStorage {
     pub fn new(key: impl AsRef<str>, storage: WasmStorageWrapper) -> Self {
-        let hashed_key: aes_gcm::Key<aes_gcm::Aes256Gcm> = {
-            use sha2::Digest as _;
-            let mut hasher = sha2::Sha256::new();
-            hasher.update(key.as_ref().as_bytes());
-            hasher.finalize()
-        };
+        let key = Self::hash_key(key);
+        Self {
+            cipher: Self::cipher_from_hashed_key(&key),
+            key,
+            storage,
+            rekey: None,
+            batch: None,
+        }
+    }
+
+    fn hash_key(key: impl AsRef<str>) -> zeroize::Zeroizing<Vec<u8>> {
+        use sha2::Digest as _;
+        let mut hasher = sha2::Sha256::new();
+        hasher.update(key.as_ref().as_bytes());
+        zeroize::Zeroizing::new(hasher.finalize().to_vec())
+    }
 
+    fn cipher_from_hashed_key(hashed_key: &[u8]) -> aes_gcm::Aes256Gcm {
         use aes_gcm::KeyInit as _;
 
-        let cipher = aes_gcm::Aes256Gcm::new(&hashed_key);
-        Self { cipher, storage }
+        aes_gcm::Aes256Gcm::new(aes_gcm::Key::<aes_gcm::Aes256Gcm>::from_slice(hashed_key))
+    }
+
+    /// The cipher the entities of `collection` are encrypted with. It only differs from one collection to another
+    /// while a rekeying is in progress.
+    pub(crate) fn cipher_for(&self, collection: &str) -> &aes_gcm::Aes256Gcm {
+        match &self.rekey {
+            Some(rekey) if rekey.progress.done.iter().any(|done| done == collection) => &rekey.cipher,
+            _ => &self.cipher,
+        }
     }
 
     pub fn is_persistent(&self) -> bool {
@@ -109,7 +180,7 @@ impl WasmEncryptedStorage {
 
                 if let Some(entity_raw) = store.get(&js_key).await? {
                     let mut entity: R = serde_wasm_bindgen::from_value(entity_raw)?;
-                    entity.decrypt(&self.cipher)?;
+                    entity.decrypt(self.cipher_for(collection))?;
 
                     Ok(Some(entity))
                 } else {
@@ -120,7 +191,7 @@ impl WasmEncryptedStorage {
                 if let Some(store) = map.get(collection) {
                     if let Some(js_value) = store.get(id.as_ref()).cloned() {
                         if let Some(mut entity) = serde_wasm_bindgen::from_value::<Option<R>>(js_value)? {
-                            entity.decrypt(&self.cipher)?;
+                            entity.decrypt(self.cipher_for(collection))?;
                             return Ok(Some(entity));
                         }
                     }
@@ -136,6 +207,7 @@ impl WasmEncryptedStorage {
         collection: &str,
         params: Option<EntityFindParams>,
     ) -> CryptoKeystoreResult<Vec<R>> {
+        let cipher = self.cipher_for(collection);
         match &self.storage {
             WasmStorageWrapper::Persistent(rexie) => {
                 let transaction = rexie.transaction(&[collection], TransactionMode::ReadOnly)?;
@@ -161,7 +233,7 @@ impl WasmEncryptedStorage {
                         if v.is_null() || v.is_undefined() {
                             None
                         } else if let Ok(mut entity) = serde_wasm_bindgen::from_value::<R>(v) {
-                            entity.decrypt(&self.cipher).ok()?;
+                            entity.decrypt(cipher).ok()?;
                             Some(entity)
                         } else {
                             None
@@ -178,7 +250,7 @@ impl WasmEncryptedStorage {
                         .cloned()
                         .filter_map(|v| {
                             if let Some(mut entity) = serde_wasm_bindgen::from_value::<Option<R>>(v).ok().flatten() {
-                                entity.decrypt(&self.cipher).ok()?;
+                                entity.decrypt(cipher).ok()?;
                                 Some(entity)
                             } else {
                                 None
@@ -209,6 +281,26 @@ impl WasmEncryptedStorage {
         values: &mut [R],
     ) -> CryptoKeystoreResult<()> {
         let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+        // not using Self::cipher_for, which would borrow the whole storage
+        let cipher = match &self.rekey {
+            Some(rekey) if rekey.progress.done.iter().any(|done| done == collection) => &rekey.cipher,
+            _ => &self.cipher,
+        };
+        if let Some(batch) = self.batch.as_mut() {
+            for value in values {
+                let key = value.id()?;
+                value.encrypt(cipher)?;
+                let value = value.serialize(&serializer)?;
+                batch.push(WasmBatchWrite::Put {
+                    collection: collection.into(),
+                    key,
+                    value,
+                });
+            }
+
+            return Ok(());
+        }
+
         match &mut self.storage {
             WasmStorageWrapper::Persistent(rexie) => {
                 let transaction = rexie.transaction(&[collection], TransactionMode::ReadWrite)?;
@@ -216,7 +308,7 @@ impl WasmEncryptedStorage {
 
                 for value in values {
                     let key = value.id()?;
-                    value.encrypt(&self.cipher)?;
+                    value.encrypt(cipher)?;
                     let js_value = value.serialize(&serializer)?;
                     store.put(&js_value, Some(&key)).await?;
                 }
@@ -230,7 +322,7 @@ impl WasmEncryptedStorage {
                         .map(|s| CryptoKeystoreResult::Ok(s.as_bytes().into()))
                         .unwrap_or_else(|| Ok(serde_wasm_bindgen::from_value(js_id)?))?;
 
-                    v.encrypt(&self.cipher)?;
+                    v.encrypt(cipher)?;
                     let js_value = v.serialize(&serializer)?;
                     entry.insert(id, js_value);
                 }
@@ -241,6 +333,17 @@ impl WasmEncryptedStorage {
     }
 
     pub async fn delete(&mut self, collection: &str, ids: &[impl AsRef<[u8]>]) -> CryptoKeystoreResult<()> {
+        if let Some(batch) = self.batch.as_mut() {
+            for k in ids {
+                batch.push(WasmBatchWrite::Delete {
+                    collection: collection.into(),
+                    key: Uint8Array::from(k.as_ref()).into(),
+                });
+            }
+
+            return Ok(());
+        }
+
         match &mut self.storage {
             WasmStorageWrapper::Persistent(rexie) => {
                 let transaction = rexie.transaction(&[collection], TransactionMode::ReadWrite)?;
@@ -262,4 +365,297 @@ impl WasmEncryptedStorage {
 
         Ok(())
     }
+
+    /// Starts buffering writes until [Self::commit_batch] or [Self::rollback_batch] is called.
+    /// The in-memory storage has no durability to protect so its writes are applied right away.
+    pub fn begin_batch(&mut self) {
+        if self.is_persistent() {
+            self.batch.replace(vec![]);
+        }
+    }
+
+    /// Applies the buffered writes in a single IndexedDB transaction spanning all the collections involved
+    pub async fn commit_batch(&mut self) -> CryptoKeystoreResult<()> {
+        let Some(batch) = self.batch.take() else {
+            return Ok(());
+        };
+
+        let WasmStorageWrapper::Persistent(rexie) = &self.storage else {
+            return Ok(());
+        };
+
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let mut collections: Vec<&str> = batch.iter().map(WasmBatchWrite::collection).collect();
+        collections.sort_unstable();
+        collections.dedup();
+
+        let transaction = rexie.transaction(&collections, TransactionMode::ReadWrite)?;
+        let result: CryptoKeystoreResult<()> = async {
+            for write in &batch {
+                let store = transaction.store(write.collection())?;
+                match write {
+                    WasmBatchWrite::Put { key, value, .. } => {
+                        store.put(value, Some(key)).await?;
+                    }
+                    WasmBatchWrite::Delete { key, .. } => {
+                        store.delete(key).await?;
+                    }
+                }
+            }
+            Ok(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                transaction.done().await?;
+                Ok(())
+            }
+            Err(e) => {
+                let _ = transaction.abort().await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Discards the buffered writes
+    pub fn rollback_batch(&mut self) {
+        self.batch = None;
+    }
+
+    /// Starts re-encrypting the storage under `new_key`, or resumes a run interrupted on this very storage. Every
+    /// collection is then re-encrypted with [Self::rekey_collection] before calling [Self::finish_rekey]. In the
+    /// meantime, each collection is read and written with the key it is currently under.
+    ///
+    /// # Errors
+    /// [CryptoKeystoreError::RekeyKeyMismatch] if an interrupted run towards another key has to be resumed first
+    pub(crate) async fn begin_rekey(&mut self, new_key: &str) -> CryptoKeystoreResult<()> {
+        use aes_gcm::aead::Aead as _;
+
+        let new_key = Self::hash_key(new_key);
+        if let Some(rekey) = &self.rekey {
+            if rekey.key != new_key {
+                return Err(CryptoKeystoreError::RekeyKeyMismatch);
+            }
+            return Ok(());
+        }
+
+        let cipher = Self::cipher_from_hashed_key(&new_key);
+        let nonce: [u8; REKEY_NONCE_SIZE] = rand::random();
+        let former_key = cipher
+            .encrypt(aes_gcm::Nonce::from_slice(&nonce), self.key.as_slice())
+            .map_err(|_| CryptoKeystoreError::AesGcmError)?;
+        let progress = WasmRekeyProgress {
+            former_key: [&nonce[..], &former_key[..]].concat(),
+            done: vec![],
+        };
+
+        if let WasmStorageWrapper::Persistent(rexie) = &self.storage {
+            let transaction = rexie.transaction(&[REKEY_COLLECTION], TransactionMode::ReadWrite)?;
+            let store = transaction.store(REKEY_COLLECTION)?;
+            store
+                .put(
+                    &serde_wasm_bindgen::to_value(&progress)?,
+                    Some(&JsValue::from_str(REKEY_PROGRESS_KEY)),
+                )
+                .await?;
+            transaction.done().await?;
+        }
+
+        self.rekey = Some(WasmPendingRekey {
+            key: new_key,
+            cipher,
+            progress,
+        });
+        Ok(())
+    }
+
+    /// Looks for a rekeying interrupted before this storage got opened. Since the collections already re-encrypted
+    /// cannot be decrypted with the former key, the storage has to be opened with the new key to finish it: the
+    /// former key is then recovered from the progress record. Returns whether a rekeying has to be finished, by
+    /// calling [Self::rekey_collection] for every collection then [Self::finish_rekey].
+    ///
+    /// # Errors
+    /// [CryptoKeystoreError::RekeyInterrupted] if the storage has been opened with another key than the new one
+    pub(crate) async fn load_pending_rekey(&mut self) -> CryptoKeystoreResult<bool> {
+        use aes_gcm::aead::Aead as _;
+
+        let WasmStorageWrapper::Persistent(rexie) = &self.storage else {
+            return Ok(false);
+        };
+
+        let transaction = rexie.transaction(&[REKEY_COLLECTION], TransactionMode::ReadOnly)?;
+        let store = transaction.store(REKEY_COLLECTION)?;
+        let Some(progress) = store.get(&JsValue::from_str(REKEY_PROGRESS_KEY)).await? else {
+            return Ok(false);
+        };
+        let progress: WasmRekeyProgress = serde_wasm_bindgen::from_value(progress)?;
+
+        if progress.former_key.len() < REKEY_NONCE_SIZE {
+            return Err(CryptoKeystoreError::RekeyInterrupted);
+        }
+        let (nonce, former_key) = progress.former_key.split_at(REKEY_NONCE_SIZE);
+        let former_key = zeroize::Zeroizing::new(
+            self.cipher
+                .decrypt(aes_gcm::Nonce::from_slice(nonce), former_key)
+                .map_err(|_| CryptoKeystoreError::RekeyInterrupted)?,
+        );
+
+        let former_cipher = Self::cipher_from_hashed_key(&former_key);
+        let new_cipher = std::mem::replace(&mut self.cipher, former_cipher);
+        let new_key = std::mem::replace(&mut self.key, former_key);
+        self.rekey = Some(WasmPendingRekey {
+            key: new_key,
+            cipher: new_cipher,
+            progress,
+        });
+        Ok(true)
+    }
+
+    /// Re-encrypts all the entities of a collection under the new key. On a persistent storage, this happens in a
+    /// single IndexedDB transaction which also records the collection as done, so that a collection is either
+    /// entirely under the former key or entirely under the new one.
+    pub(crate) async fn rekey_collection<R: Entity<ConnectionType = WasmConnection> + 'static>(
+        &mut self,
+        collection: &str,
+    ) -> CryptoKeystoreResult<()> {
+        let Some(rekey) = self.rekey.as_mut() else {
+            return Err(CryptoKeystoreError::IncorrectApiUsage("No rekeying is in progress"));
+        };
+        if rekey.progress.done.iter().any(|done| done == collection) {
+            return Ok(());
+        }
+
+        let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+        let former_cipher = &self.cipher;
+        let new_cipher = &rekey.cipher;
+        match &mut self.storage {
+            WasmStorageWrapper::Persistent(rexie) => {
+                let transaction = rexie.transaction(&[collection, REKEY_COLLECTION], TransactionMode::ReadWrite)?;
+                let mut next_progress = rekey.progress.clone();
+                next_progress.done.push(collection.into());
+
+                let result: CryptoKeystoreResult<()> = async {
+                    let store = transaction.store(collection)?;
+                    for (key, value) in store.get_all(None, None, None, None).await? {
+                        if value.is_null() || value.is_undefined() {
+                            continue;
+                        }
+                        let mut entity: R = serde_wasm_bindgen::from_value(value)?;
+                        entity.decrypt(former_cipher)?;
+                        entity.encrypt(new_cipher)?;
+                        store.put(&entity.serialize(&serializer)?, Some(&key)).await?;
+                    }
+
+                    let progress_store = transaction.store(REKEY_COLLECTION)?;
+                    progress_store
+                        .put(
+                            &serde_wasm_bindgen::to_value(&next_progress)?,
+                            Some(&JsValue::from_str(REKEY_PROGRESS_KEY)),
+                        )
+                        .await?;
+                    Ok(())
+                }
+                .await;
+
+                match result {
+                    Ok(()) => {
+                        transaction.done().await?;
+                        rekey.progress = next_progress;
+                        Ok(())
+                    }
+                    Err(e) => {
+                        let _ = transaction.abort().await;
+                        Err(e)
+                    }
+                }
+            }
+            WasmStorageWrapper::InMemory(map) => {
+                if let Some(store) = map.get_mut(collection) {
+                    // re-encrypt everything before touching the map so that a failure leaves it as it was
+                    let mut rekeyed = Vec::with_capacity(store.len());
+                    for (key, value) in store.iter() {
+                        let Some(mut entity) = serde_wasm_bindgen::from_value::<Option<R>>(value.clone())? else {
+                            continue;
+                        };
+                        entity.decrypt(former_cipher)?;
+                        entity.encrypt(new_cipher)?;
+                        rekeyed.push((key.clone(), entity.serialize(&serializer)?));
+                    }
+                    store.extend(rekeyed);
+                }
+                rekey.progress.done.push(collection.into());
+                Ok(())
+            }
+        }
+    }
+
+    /// Ends a rekeying once every collection has been re-encrypted: from now on, the storage uses the new key
+    pub(crate) async fn finish_rekey(&mut self) -> CryptoKeystoreResult<()> {
+        let Some(rekey) = self.rekey.take() else {
+            return Err(CryptoKeystoreError::IncorrectApiUsage("No rekeying is in progress"));
+        };
+        if let WasmStorageWrapper::Persistent(rexie) = &self.storage {
+            let result: CryptoKeystoreResult<()> = async {
+                let transaction = rexie.transaction(&[REKEY_COLLECTION], TransactionMode::ReadWrite)?;
+                let store = transaction.store(REKEY_COLLECTION)?;
+                store.delete(&JsValue::from_str(REKEY_PROGRESS_KEY)).await?;
+                transaction.done().await?;
+                Ok(())
+            }
+            .await;
+            if let Err(e) = result {
+                self.rekey = Some(rekey);
+                return Err(e);
+            }
+        }
+        self.cipher = rekey.cipher;
+        self.key = rekey.key;
+        Ok(())
+    }
+
+    /// Moves the buffered messages stored under the id of their conversation, which only left room for one message
+    /// per conversation, to their own key (see [crate::entities::MlsPendingMessage::primary_key]). Their content is
+    /// left as is.
+    #[cfg(feature = "mls-keystore")]
+    pub(crate) async fn migrate_pending_messages(&mut self) -> CryptoKeystoreResult<()> {
+        const COLLECTION: &str = "mls_pending_messages";
+
+        let WasmStorageWrapper::Persistent(rexie) = &self.storage else {
+            return Ok(());
+        };
+        let cipher = self.cipher_for(COLLECTION);
+        let transaction = rexie.transaction(&[COLLECTION], TransactionMode::ReadWrite)?;
+        let result: CryptoKeystoreResult<()> = async {
+            let store = transaction.store(COLLECTION)?;
+            for (key, value) in store.get_all(None, None, None, None).await? {
+                if value.is_null() || value.is_undefined() {
+                    continue;
+                }
+                let mut message: crate::entities::MlsPendingMessage = serde_wasm_bindgen::from_value(value.clone())?;
+                if Uint8Array::new(&key).to_vec() != message.id {
+                    continue;
+                }
+                message.decrypt(cipher)?;
+                store.delete(&key).await?;
+                store.put(&value, Some(&message.id()?)).await?;
+            }
+            Ok(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                transaction.done().await?;
+                Ok(())
+            }
+            Err(e) => {
+                let _ = transaction.abort().await;
+                Err(e)
+            }
+        }
+    }
 }
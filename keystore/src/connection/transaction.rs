@@ -0,0 +1,280 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+use std::any::{Any, TypeId};
+use std::marker::PhantomData;
+
+#[cfg(feature = "mls-keystore")]
+use crate::entities::{MlsCredential, MlsCredentialExt as _, UniqueEntity};
+use crate::{
+    connection::{DatabaseConnection, KeystoreDatabaseConnection},
+    entities::{Entity, EntityFindParams, StringEntityId},
+    CryptoKeystoreResult,
+};
+
+/// A write waiting for its transaction to be committed
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+trait StagedWrite: Send + Sync + std::fmt::Debug {
+    fn entity_type(&self) -> TypeId;
+
+    fn id(&self) -> &[u8];
+
+    /// The staged entity, or `None` when the entity is being deleted
+    fn entity(&self) -> Option<&dyn Any>;
+
+    /// Whether this write deletes the given entity
+    fn removes(&self, entity: &dyn Any) -> bool;
+
+    async fn flush(&self, conn: &mut KeystoreDatabaseConnection) -> CryptoKeystoreResult<()>;
+}
+
+#[derive(Debug)]
+struct StagedSave<E>(E);
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl<E: Entity<ConnectionType = KeystoreDatabaseConnection>> StagedWrite for StagedSave<E> {
+    fn entity_type(&self) -> TypeId {
+        TypeId::of::<E>()
+    }
+
+    fn id(&self) -> &[u8] {
+        self.0.id_raw()
+    }
+
+    fn entity(&self) -> Option<&dyn Any> {
+        Some(&self.0)
+    }
+
+    fn removes(&self, _entity: &dyn Any) -> bool {
+        false
+    }
+
+    async fn flush(&self, conn: &mut KeystoreDatabaseConnection) -> CryptoKeystoreResult<()> {
+        self.0.save(conn).await
+    }
+}
+
+/// Replaces the single entity of a [UniqueEntity] collection
+#[cfg(feature = "mls-keystore")]
+#[derive(Debug)]
+struct StagedReplace<E>(E);
+
+#[cfg(feature = "mls-keystore")]
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl<E: UniqueEntity<ConnectionType = KeystoreDatabaseConnection>> StagedWrite for StagedReplace<E> {
+    fn entity_type(&self) -> TypeId {
+        TypeId::of::<E>()
+    }
+
+    fn id(&self) -> &[u8] {
+        self.0.id_raw()
+    }
+
+    fn entity(&self) -> Option<&dyn Any> {
+        Some(&self.0)
+    }
+
+    fn removes(&self, _entity: &dyn Any) -> bool {
+        false
+    }
+
+    async fn flush(&self, conn: &mut KeystoreDatabaseConnection) -> CryptoKeystoreResult<()> {
+        self.0.replace(conn).await
+    }
+}
+
+#[derive(Debug)]
+struct StagedDelete<E> {
+    id: Vec<u8>,
+    entity: PhantomData<fn() -> E>,
+}
+
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl<E: Entity<ConnectionType = KeystoreDatabaseConnection>> StagedWrite for StagedDelete<E> {
+    fn entity_type(&self) -> TypeId {
+        TypeId::of::<E>()
+    }
+
+    fn id(&self) -> &[u8] {
+        self.id.as_slice()
+    }
+
+    fn entity(&self) -> Option<&dyn Any> {
+        None
+    }
+
+    fn removes(&self, entity: &dyn Any) -> bool {
+        entity.downcast_ref::<E>().is_some_and(|e| e.id_raw() == self.id)
+    }
+
+    async fn flush(&self, conn: &mut KeystoreDatabaseConnection) -> CryptoKeystoreResult<()> {
+        E::delete(conn, &[self.id.as_slice().into()]).await
+    }
+}
+
+/// Deletes every credential with the given content, whatever the client it belongs to
+#[cfg(feature = "mls-keystore")]
+#[derive(Debug)]
+struct StagedCredentialDelete(Vec<u8>);
+
+#[cfg(feature = "mls-keystore")]
+#[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
+impl StagedWrite for StagedCredentialDelete {
+    fn entity_type(&self) -> TypeId {
+        TypeId::of::<MlsCredential>()
+    }
+
+    /// Credentials are not deleted by id
+    fn id(&self) -> &[u8] {
+        &[]
+    }
+
+    fn entity(&self) -> Option<&dyn Any> {
+        None
+    }
+
+    fn removes(&self, entity: &dyn Any) -> bool {
+        entity
+            .downcast_ref::<MlsCredential>()
+            .is_some_and(|credential| credential.credential == self.0)
+    }
+
+    async fn flush(&self, conn: &mut KeystoreDatabaseConnection) -> CryptoKeystoreResult<()> {
+        MlsCredential::delete_by_credential(conn, self.0.clone()).await
+    }
+}
+
+/// Writes staged on a [crate::Connection] between a call to [crate::Connection::begin_transaction] and either
+/// [crate::Connection::commit_transaction] or [crate::Connection::rollback_transaction].
+///
+/// Nothing reaches the database until the transaction is committed, at which point all the staged writes are flushed
+/// in order within a single database transaction. Reads made in the meantime see the staged writes.
+#[derive(Debug, Default)]
+pub(crate) struct KeystoreTransaction {
+    writes: Vec<Box<dyn StagedWrite>>,
+}
+
+impl KeystoreTransaction {
+    pub(crate) fn stage_save<E: Entity<ConnectionType = KeystoreDatabaseConnection>>(&mut self, entity: E) {
+        self.writes.push(Box::new(StagedSave(entity)));
+    }
+
+    pub(crate) fn stage_delete<E: Entity<ConnectionType = KeystoreDatabaseConnection>>(&mut self, id: &[u8]) {
+        self.writes.push(Box::new(StagedDelete::<E> {
+            id: id.to_vec(),
+            entity: PhantomData,
+        }));
+    }
+
+    #[cfg(feature = "mls-keystore")]
+    pub(crate) fn stage_replace<E: UniqueEntity<ConnectionType = KeystoreDatabaseConnection>>(&mut self, entity: E) {
+        self.writes.push(Box::new(StagedReplace(entity)));
+    }
+
+    #[cfg(feature = "mls-keystore")]
+    pub(crate) fn stage_credential_delete(&mut self, credential: &[u8]) {
+        self.writes.push(Box::new(StagedCredentialDelete(credential.to_vec())));
+    }
+
+    fn writes_of<E: 'static>(&self) -> impl DoubleEndedIterator<Item = &dyn StagedWrite> + '_ {
+        self.writes
+            .iter()
+            .map(|write| &**write)
+            .filter(|write| write.entity_type() == TypeId::of::<E>())
+    }
+
+    pub(crate) fn has_writes_of<E: 'static>(&self) -> bool {
+        self.writes_of::<E>().next().is_some()
+    }
+
+    /// Looks up the latest staged write for the entity with the given id.
+    /// Returns `None` when the entity has not been touched by this transaction, `Some(None)` when it has been deleted
+    pub(crate) fn find<E: Entity>(&self, id: &[u8]) -> Option<Option<E>> {
+        self.writes_of::<E>()
+            .rev()
+            .find(|write| write.id() == id)
+            .map(|write| write.entity().and_then(|entity| entity.downcast_ref::<E>()).cloned())
+    }
+
+    /// Looks up the latest entity staged in a [UniqueEntity] collection
+    #[cfg(feature = "mls-keystore")]
+    pub(crate) fn find_unique<E: UniqueEntity>(&self) -> Option<E> {
+        self.writes_of::<E>()
+            .next_back()
+            .and_then(|write| write.entity())
+            .and_then(|entity| entity.downcast_ref::<E>())
+            .cloned()
+    }
+
+    /// Applies the staged writes to a set of entities read from the database.
+    /// When `ids` is provided, staged entities outside of it are left out.
+    pub(crate) fn merge<E: Entity>(&self, mut entities: Vec<E>, ids: Option<&[StringEntityId]>) -> Vec<E> {
+        for write in self.writes_of::<E>() {
+            match write.entity().and_then(|entity| entity.downcast_ref::<E>()) {
+                Some(entity) => {
+                    if ids.is_some_and(|ids| !ids.iter().any(|id| id.as_slice() == entity.id_raw())) {
+                        continue;
+                    }
+
+                    match entities
+                        .iter_mut()
+                        .find(|e| E::UNIQUE_ID && e.id_raw() == entity.id_raw())
+                    {
+                        Some(existing) => *existing = entity.clone(),
+                        None => entities.push(entity.clone()),
+                    }
+                }
+                None => entities.retain(|e| !write.removes(e)),
+            }
+        }
+
+        entities
+    }
+
+    /// Writes everything that has been staged in a single database transaction
+    pub(crate) async fn flush(self, conn: &mut KeystoreDatabaseConnection) -> CryptoKeystoreResult<()> {
+        if self.writes.is_empty() {
+            return Ok(());
+        }
+
+        conn.begin_batch().await?;
+        for write in &self.writes {
+            if let Err(e) = write.flush(conn).await {
+                conn.rollback_batch().await?;
+                return Err(e);
+            }
+        }
+        conn.commit_batch().await
+    }
+}
+
+/// Paginates entities the way the database would have done it with the same [EntityFindParams]
+pub(crate) fn paginate<E>(mut entities: Vec<E>, params: &EntityFindParams) -> Vec<E> {
+    if params.reverse {
+        entities.reverse();
+    }
+
+    entities
+        .into_iter()
+        .skip(params.offset.unwrap_or_default() as usize)
+        .take(params.limit.map(|limit| limit as usize).unwrap_or(usize::MAX))
+        .collect()
+}
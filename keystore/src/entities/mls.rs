@@ -77,6 +77,61 @@ pub struct PersistedMlsPendingGroup {
     pub custom_configuration: Vec<u8>,
 }
 
+/// Entity representing the key rotation state of a persisted `MlsGroup`
+#[derive(Debug, Clone, PartialEq, Eq, Zeroize)]
+#[zeroize(drop)]
+#[cfg_attr(
+    any(target_family = "wasm", feature = "serde"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct MlsGroupKeyRotation {
+    pub id: Vec<u8>,
+    /// Seconds after which the own leaf has to be updated, if any
+    pub key_rotation_span: Option<u64>,
+    /// Unix timestamp (in seconds) of the last update of the own leaf
+    pub last_update: u64,
+}
+
+/// Entity representing the most recent epoch authenticators of a persisted `MlsGroup`
+#[derive(Debug, Clone, PartialEq, Eq, Zeroize)]
+#[zeroize(drop)]
+#[cfg_attr(
+    any(target_family = "wasm", feature = "serde"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct MlsEpochAuthenticators {
+    pub id: Vec<u8>,
+    /// Serialized list of (epoch, epoch authenticator)
+    pub authenticators: Vec<u8>,
+}
+
+/// Entity representing the epoch in which each member of a persisted `MlsGroup` was added
+#[derive(Debug, Clone, PartialEq, Eq, Zeroize)]
+#[zeroize(drop)]
+#[cfg_attr(
+    any(target_family = "wasm", feature = "serde"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct MlsMemberEpochs {
+    pub id: Vec<u8>,
+    /// Serialized list of (leaf index, client id, epoch)
+    pub members: Vec<u8>,
+}
+
+/// Entity representing the application messages of a persisted `MlsGroup` recorded to be shared
+/// with devices joining it later
+#[derive(Debug, Clone, PartialEq, Eq, Zeroize)]
+#[zeroize(drop)]
+#[cfg_attr(
+    any(target_family = "wasm", feature = "serde"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct MlsHistory {
+    pub id: Vec<u8>,
+    /// Serialized sharing limits and list of (epoch, sender, timestamp, message)
+    pub messages: Vec<u8>,
+}
+
 /// Entity representing a buffered message
 #[derive(Debug, Clone, PartialEq, Eq, Zeroize)]
 #[zeroize(drop)]
@@ -87,6 +142,10 @@ pub struct PersistedMlsPendingGroup {
 pub struct MlsPendingMessage {
     pub id: Vec<u8>,
     pub message: Vec<u8>,
+    /// Unix timestamp in seconds at which the message was buffered. Messages buffered before this
+    /// was recorded have the time of the migration, or 0 on WASM which stands for an unknown time
+    #[cfg_attr(any(target_family = "wasm", feature = "serde"), serde(default))]
+    pub received_at: u64,
 }
 
 /// Entity representing a persisted `Credential`
@@ -160,7 +219,10 @@ pub struct MlsEncryptionKeyPair {
 /// Entity representing a list of [MlsEncryptionKeyPair]
 #[derive(Debug, Clone, PartialEq, Eq, Zeroize)]
 #[zeroize(drop)]
-#[cfg_attr(target_family = "wasm", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    any(target_family = "wasm", feature = "serde"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub struct MlsEpochEncryptionKeyPair {
     pub id: Vec<u8>,
     pub keypairs: Vec<u8>,
@@ -124,10 +124,17 @@ impl EntityFindParams {
 
 #[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
 #[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
-pub trait EntityBase: Send + Sized + Clone + PartialEq + Eq + std::fmt::Debug {
+pub trait EntityBase: Send + Sync + Sized + Clone + PartialEq + Eq + std::fmt::Debug + 'static {
     type ConnectionType: DatabaseConnection;
     type AutoGeneratedFields;
 
+    /// Name of the table, or object store, the entities are persisted in
+    const COLLECTION_NAME: &'static str;
+
+    /// Whether saving an entity replaces the one already stored under the same id.
+    /// Collections holding several rows per id (i.e. where saving appends) must set this to `false`
+    const UNIQUE_ID: bool = true;
+
     fn to_missing_key_err_kind() -> MissingKeyErrorKind;
 
     async fn save(&self, conn: &mut Self::ConnectionType) -> CryptoKeystoreResult<()>;
@@ -135,6 +142,12 @@ pub trait EntityBase: Send + Sized + Clone + PartialEq + Eq + std::fmt::Debug {
     async fn insert(&self, _conn: &mut Self::ConnectionType) -> CryptoKeystoreResult<Self::AutoGeneratedFields> {
         return Err(CryptoKeystoreError::NotImplemented);
     }
+
+    /// Fills in the fields the database would generate on [EntityBase::insert], so that the entity can be staged in
+    /// a transaction and inserted later on with the same values
+    fn generate_fields(&mut self) -> CryptoKeystoreResult<Self::AutoGeneratedFields> {
+        Err(CryptoKeystoreError::NotImplemented)
+    }
     async fn find_all(conn: &mut Self::ConnectionType, params: EntityFindParams) -> CryptoKeystoreResult<Vec<Self>>;
     async fn find_one(conn: &mut Self::ConnectionType, id: &StringEntityId) -> CryptoKeystoreResult<Option<Self>>;
     async fn find_many(conn: &mut Self::ConnectionType, ids: &[StringEntityId]) -> CryptoKeystoreResult<Vec<Self>> {
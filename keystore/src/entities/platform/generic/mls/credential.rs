@@ -33,6 +33,8 @@ impl Entity for MlsCredential {
 impl EntityBase for MlsCredential {
     type ConnectionType = KeystoreDatabaseConnection;
     type AutoGeneratedFields = u64;
+    const COLLECTION_NAME: &'static str = "mls_credentials";
+    const UNIQUE_ID: bool = false;
 
     fn to_missing_key_err_kind() -> MissingKeyErrorKind {
         MissingKeyErrorKind::MlsCredential
@@ -42,6 +44,7 @@ impl EntityBase for MlsCredential {
         conn: &mut Self::ConnectionType,
         params: EntityFindParams,
     ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let conn = sqlcipher_conn!(conn, backend => backend.find_all(params).await);
         let transaction = conn.transaction()?;
         let query: String = format!(
             "SELECT rowid, unixepoch(created_at) FROM mls_credentials {}",
@@ -93,6 +96,7 @@ impl EntityBase for MlsCredential {
         conn: &mut Self::ConnectionType,
         id: &StringEntityId,
     ) -> crate::CryptoKeystoreResult<Option<Self>> {
+        let conn = sqlcipher_conn!(conn, backend => backend.find_one(id).await);
         let transaction = conn.transaction()?;
         use rusqlite::OptionalExtension as _;
         let maybe_rowid = transaction
@@ -127,10 +131,12 @@ impl EntityBase for MlsCredential {
     }
 
     async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
+        let conn = sqlcipher_conn!(conn, backend => backend.count::<Self>().await);
         Ok(conn.query_row("SELECT COUNT(*) FROM mls_credentials", [], |r| r.get(0))?)
     }
 
     async fn delete(conn: &mut Self::ConnectionType, ids: &[StringEntityId]) -> crate::CryptoKeystoreResult<()> {
+        let conn = sqlcipher_conn!(conn, backend => backend.delete::<Self>(ids).await);
         let transaction = conn.transaction()?;
         let len = ids.len();
         let mut updated = 0;
@@ -148,6 +154,14 @@ impl EntityBase for MlsCredential {
     }
 
     async fn insert(&self, conn: &mut Self::ConnectionType) -> CryptoKeystoreResult<Self::AutoGeneratedFields> {
+        let conn = sqlcipher_conn!(conn, backend => {
+            let mut credential = self.clone();
+            let created_at = match self.created_at {
+                0 => credential.generate_fields()?,
+                created_at => created_at,
+            };
+            backend.save(&credential).await.map(|_| created_at)
+        });
         Self::ConnectionType::check_buffer_size(self.id.len())?;
         Self::ConnectionType::check_buffer_size(self.credential.len())?;
 
@@ -157,10 +171,21 @@ impl EntityBase for MlsCredential {
         let transaction = conn.transaction()?;
 
         use rusqlite::ToSql as _;
-        let params: [rusqlite::types::ToSqlOutput; 2] = [zb_id.to_sql()?, zb_cred.to_sql()?];
-
-        let sql = "INSERT INTO mls_credentials (id, credential) VALUES (?, ?) RETURNING unixepoch(created_at)";
-        let created_at = transaction.query_row(sql, params, |r| r.get(0))?;
+        // keep the timestamp of a credential staged in a transaction, otherwise let the database set it
+        let created_at = match self.created_at {
+            0 => {
+                let params: [rusqlite::types::ToSqlOutput; 2] = [zb_id.to_sql()?, zb_cred.to_sql()?];
+                let sql = "INSERT INTO mls_credentials (id, credential) VALUES (?, ?) RETURNING unixepoch(created_at)";
+                transaction.query_row(sql, params, |r| r.get(0))?
+            }
+            created_at => {
+                let created_at = created_at as i64;
+                let params: [rusqlite::types::ToSqlOutput; 3] =
+                    [zb_id.to_sql()?, zb_cred.to_sql()?, created_at.to_sql()?];
+                let sql = "INSERT INTO mls_credentials (id, credential, created_at) VALUES (?, ?, datetime(?, 'unixepoch')) RETURNING unixepoch(created_at)";
+                transaction.query_row(sql, params, |r| r.get(0))?
+            }
+        };
         let row_id = transaction.last_insert_rowid();
 
         let mut blob = transaction.blob_open(rusqlite::DatabaseName::Main, "mls_credentials", "id", row_id, false)?;
@@ -183,11 +208,19 @@ impl EntityBase for MlsCredential {
 
         Ok(created_at)
     }
+
+    fn generate_fields(&mut self) -> CryptoKeystoreResult<Self::AutoGeneratedFields> {
+        self.created_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Ok(self.created_at)
+    }
 }
 
 #[async_trait::async_trait]
 impl MlsCredentialExt for MlsCredential {
     async fn delete_by_credential(conn: &mut Self::ConnectionType, credential: Vec<u8>) -> CryptoKeystoreResult<()> {
+        let conn = sqlcipher_conn!(conn, backend => backend.delete_where(|c: &Self| c.credential == credential).await);
         let transaction = conn.transaction()?;
         // we do not have an index on this since we'll never have more than a handful of credentials in the sotre (~40 max)
         transaction.execute("DELETE FROM mls_credentials WHERE credential = ?", [&credential[..]])?;
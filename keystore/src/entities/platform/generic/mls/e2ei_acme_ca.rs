@@ -37,6 +37,10 @@ impl Entity for E2eiAcmeCA {
 #[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
 impl UniqueEntity for E2eiAcmeCA {
     async fn find_unique(conn: &mut Self::ConnectionType) -> CryptoKeystoreResult<Self> {
+        let conn = sqlcipher_conn!(conn, backend => backend
+            .find_unique()
+            .await?
+            .ok_or(CryptoKeystoreError::NotFound("E2EI ACME root CA", "".to_string())));
         let transaction = conn.transaction()?;
         use rusqlite::OptionalExtension as _;
 
@@ -54,6 +58,7 @@ impl UniqueEntity for E2eiAcmeCA {
     }
 
     async fn replace(&self, conn: &mut Self::ConnectionType) -> CryptoKeystoreResult<()> {
+        let conn = sqlcipher_conn!(conn, backend => backend.save(self).await);
         Self::ConnectionType::check_buffer_size(self.content.len())?;
         let zb_content = rusqlite::blob::ZeroBlob(self.content.len() as i32);
 
@@ -80,6 +85,7 @@ impl UniqueEntity for E2eiAcmeCA {
 impl EntityBase for E2eiAcmeCA {
     type ConnectionType = KeystoreDatabaseConnection;
     type AutoGeneratedFields = ();
+    const COLLECTION_NAME: &'static str = "e2ei_acme_ca";
 
     fn to_missing_key_err_kind() -> MissingKeyErrorKind {
         MissingKeyErrorKind::E2eiAcmeCA
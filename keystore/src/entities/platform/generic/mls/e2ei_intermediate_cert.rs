@@ -31,6 +31,7 @@ impl Entity for E2eiIntermediateCert {
 impl EntityBase for E2eiIntermediateCert {
     type ConnectionType = KeystoreDatabaseConnection;
     type AutoGeneratedFields = ();
+    const COLLECTION_NAME: &'static str = "e2ei_intermediate_certs";
 
     fn to_missing_key_err_kind() -> MissingKeyErrorKind {
         MissingKeyErrorKind::E2eiIntermediateCert
@@ -40,6 +41,7 @@ impl EntityBase for E2eiIntermediateCert {
         conn: &mut Self::ConnectionType,
         params: EntityFindParams,
     ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let conn = sqlcipher_conn!(conn, backend => backend.find_all(params).await);
         let transaction = conn.transaction()?;
         let query: String = format!(
             "SELECT rowid, ski_aki_pair FROM e2ei_intermediate_certs {}",
@@ -73,6 +75,7 @@ impl EntityBase for E2eiIntermediateCert {
     }
 
     async fn save(&self, conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<()> {
+        let conn = sqlcipher_conn!(conn, backend => backend.save(self).await);
         use rusqlite::OptionalExtension as _;
         use rusqlite::ToSql as _;
 
@@ -129,6 +132,7 @@ impl EntityBase for E2eiIntermediateCert {
         conn: &mut Self::ConnectionType,
         id: &StringEntityId,
     ) -> crate::CryptoKeystoreResult<Option<Self>> {
+        let conn = sqlcipher_conn!(conn, backend => backend.find_one(id).await);
         let transaction = conn.transaction()?;
         use rusqlite::OptionalExtension as _;
         let ski_aki_pair: String = id.try_into()?;
@@ -165,10 +169,12 @@ impl EntityBase for E2eiIntermediateCert {
     }
 
     async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
+        let conn = sqlcipher_conn!(conn, backend => backend.count::<Self>().await);
         Ok(conn.query_row("SELECT COUNT(*) FROM e2ei_intermediate_certs", [], |r| r.get(0))?)
     }
 
     async fn delete(conn: &mut Self::ConnectionType, ids: &[StringEntityId]) -> crate::CryptoKeystoreResult<()> {
+        let conn = sqlcipher_conn!(conn, backend => backend.delete::<Self>(ids).await);
         let transaction = conn.transaction()?;
         let len = ids.len();
         let mut updated = 0;
@@ -32,6 +32,7 @@ impl Entity for MlsEncryptionKeyPair {
 impl EntityBase for MlsEncryptionKeyPair {
     type ConnectionType = KeystoreDatabaseConnection;
     type AutoGeneratedFields = ();
+    const COLLECTION_NAME: &'static str = "mls_encryption_keypairs";
 
     fn to_missing_key_err_kind() -> MissingKeyErrorKind {
         MissingKeyErrorKind::MlsEncryptionKeyPair
@@ -41,6 +42,7 @@ impl EntityBase for MlsEncryptionKeyPair {
         conn: &mut Self::ConnectionType,
         params: EntityFindParams,
     ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let conn = sqlcipher_conn!(conn, backend => backend.find_all(params).await);
         let transaction = conn.transaction()?;
         let query: String = format!("SELECT rowid FROM mls_encryption_keypairs {}", params.to_sql());
 
@@ -83,6 +85,7 @@ impl EntityBase for MlsEncryptionKeyPair {
     }
 
     async fn save(&self, conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<()> {
+        let conn = sqlcipher_conn!(conn, backend => backend.save(self).await);
         use rusqlite::OptionalExtension as _;
 
         Self::ConnectionType::check_buffer_size(self.sk.len())?;
@@ -146,6 +149,7 @@ impl EntityBase for MlsEncryptionKeyPair {
         conn: &mut Self::ConnectionType,
         id: &StringEntityId,
     ) -> crate::CryptoKeystoreResult<Option<Self>> {
+        let conn = sqlcipher_conn!(conn, backend => backend.find_one(id).await);
         let transaction = conn.transaction()?;
         use rusqlite::OptionalExtension as _;
         let maybe_rowid = transaction
@@ -188,10 +192,12 @@ impl EntityBase for MlsEncryptionKeyPair {
     }
 
     async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
+        let conn = sqlcipher_conn!(conn, backend => backend.count::<Self>().await);
         Ok(conn.query_row("SELECT COUNT(*) FROM mls_encryption_keypairs", [], |r| r.get(0))?)
     }
 
     async fn delete(conn: &mut Self::ConnectionType, ids: &[StringEntityId]) -> crate::CryptoKeystoreResult<()> {
+        let conn = sqlcipher_conn!(conn, backend => backend.delete::<Self>(ids).await);
         let transaction = conn.transaction()?;
         let len = ids.len();
         let mut updated = 0;
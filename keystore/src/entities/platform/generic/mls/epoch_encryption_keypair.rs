@@ -33,6 +33,7 @@ impl Entity for MlsEpochEncryptionKeyPair {
 impl EntityBase for MlsEpochEncryptionKeyPair {
     type ConnectionType = KeystoreDatabaseConnection;
     type AutoGeneratedFields = ();
+    const COLLECTION_NAME: &'static str = "mls_epoch_encryption_keypairs";
 
     fn to_missing_key_err_kind() -> MissingKeyErrorKind {
         MissingKeyErrorKind::MlsEpochEncryptionKeyPair
@@ -42,6 +43,7 @@ impl EntityBase for MlsEpochEncryptionKeyPair {
         conn: &mut Self::ConnectionType,
         params: EntityFindParams,
     ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let conn = sqlcipher_conn!(conn, backend => backend.find_all(params).await);
         let transaction = conn.transaction()?;
         let query: String = format!("SELECT rowid FROM mls_epoch_encryption_keypairs {}", params.to_sql());
 
@@ -84,6 +86,7 @@ impl EntityBase for MlsEpochEncryptionKeyPair {
     }
 
     async fn save(&self, conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<()> {
+        let conn = sqlcipher_conn!(conn, backend => backend.save(self).await);
         use rusqlite::OptionalExtension as _;
 
         Self::ConnectionType::check_buffer_size(self.id.len())?;
@@ -150,6 +153,7 @@ impl EntityBase for MlsEpochEncryptionKeyPair {
         conn: &mut Self::ConnectionType,
         id: &StringEntityId,
     ) -> crate::CryptoKeystoreResult<Option<Self>> {
+        let conn = sqlcipher_conn!(conn, backend => backend.find_one(id).await);
         let transaction = conn.transaction()?;
         use rusqlite::OptionalExtension as _;
         let maybe_rowid = transaction
@@ -192,10 +196,12 @@ impl EntityBase for MlsEpochEncryptionKeyPair {
     }
 
     async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
+        let conn = sqlcipher_conn!(conn, backend => backend.count::<Self>().await);
         Ok(conn.query_row("SELECT COUNT(*) FROM mls_epoch_encryption_keypairs", [], |r| r.get(0))?)
     }
 
     async fn delete(conn: &mut Self::ConnectionType, ids: &[StringEntityId]) -> crate::CryptoKeystoreResult<()> {
+        let conn = sqlcipher_conn!(conn, backend => backend.delete::<Self>(ids).await);
         let transaction = conn.transaction()?;
         let len = ids.len();
         let mut updated = 0;
@@ -31,6 +31,7 @@ impl Entity for PersistedMlsGroup {
 impl EntityBase for PersistedMlsGroup {
     type ConnectionType = KeystoreDatabaseConnection;
     type AutoGeneratedFields = ();
+    const COLLECTION_NAME: &'static str = "mls_groups";
 
     fn to_missing_key_err_kind() -> MissingKeyErrorKind {
         MissingKeyErrorKind::MlsGroup
@@ -40,6 +41,7 @@ impl EntityBase for PersistedMlsGroup {
         conn: &mut Self::ConnectionType,
         params: EntityFindParams,
     ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let conn = sqlcipher_conn!(conn, backend => backend.find_all(params).await);
         let transaction = conn.transaction()?;
         let query: String = format!("SELECT rowid FROM mls_groups {}", params.to_sql());
 
@@ -79,6 +81,7 @@ impl EntityBase for PersistedMlsGroup {
     }
 
     async fn save(&self, conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<()> {
+        let conn = sqlcipher_conn!(conn, backend => backend.save(self).await);
         use rusqlite::OptionalExtension as _;
         use rusqlite::ToSql as _;
 
@@ -144,6 +147,7 @@ impl EntityBase for PersistedMlsGroup {
         conn: &mut Self::ConnectionType,
         id: &StringEntityId,
     ) -> crate::CryptoKeystoreResult<Option<Self>> {
+        let conn = sqlcipher_conn!(conn, backend => backend.find_one(id).await);
         use rusqlite::OptionalExtension as _;
         let transaction = conn.transaction()?;
         let mut rowid: Option<i64> = transaction
@@ -187,6 +191,7 @@ impl EntityBase for PersistedMlsGroup {
         conn: &mut Self::ConnectionType,
         _ids: &[StringEntityId],
     ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let conn = sqlcipher_conn!(conn, backend => backend.find_all(Default::default()).await);
         // Plot twist: we always select ALL the persisted groups. Unsure if we want to make it a real API with selection
         let mut stmt = conn.prepare_cached("SELECT rowid FROM mls_groups ORDER BY rowid ASC")?;
         let rowids: Vec<i64> = stmt
@@ -237,10 +242,12 @@ impl EntityBase for PersistedMlsGroup {
     }
 
     async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
+        let conn = sqlcipher_conn!(conn, backend => backend.count::<Self>().await);
         Ok(conn.query_row("SELECT COUNT(*) FROM mls_groups", [], |r| r.get(0))?)
     }
 
     async fn delete(conn: &mut Self::ConnectionType, ids: &[StringEntityId]) -> crate::CryptoKeystoreResult<()> {
+        let conn = sqlcipher_conn!(conn, backend => backend.delete::<Self>(ids).await);
         let transaction = conn.transaction()?;
         let len = ids.len();
         let mut updated = 0;
@@ -266,6 +273,12 @@ impl PersistedMlsGroupExt for PersistedMlsGroup {
     }
 
     async fn child_groups(&self, conn: &mut <Self as EntityBase>::ConnectionType) -> CryptoKeystoreResult<Vec<Self>> {
+        let conn = sqlcipher_conn!(conn, backend => Ok(backend
+            .find_all::<Self>(Default::default())
+            .await?
+            .into_iter()
+            .filter(|group| group.parent_id.as_deref() == Some(self.id_raw()))
+            .collect()));
         let id = self.id_raw();
         let transaction = conn.transaction()?;
         let mut query = transaction.prepare_cached("SELECT rowid FROM mls_groups WHERE parent_id = ?")?;
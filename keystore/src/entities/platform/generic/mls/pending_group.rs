@@ -32,12 +32,14 @@ impl Entity for PersistedMlsPendingGroup {
 impl EntityBase for PersistedMlsPendingGroup {
     type ConnectionType = KeystoreDatabaseConnection;
     type AutoGeneratedFields = ();
+    const COLLECTION_NAME: &'static str = "mls_pending_groups";
 
     fn to_missing_key_err_kind() -> MissingKeyErrorKind {
         MissingKeyErrorKind::MlsPendingGroup
     }
 
     async fn save(&self, conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<()> {
+        let conn = sqlcipher_conn!(conn, backend => backend.save(self).await);
         let parent_id = self.parent_id.as_ref();
 
         let transaction = conn.transaction()?;
@@ -122,6 +124,7 @@ impl EntityBase for PersistedMlsPendingGroup {
         conn: &mut Self::ConnectionType,
         id: &StringEntityId,
     ) -> crate::CryptoKeystoreResult<Option<Self>> {
+        let conn = sqlcipher_conn!(conn, backend => backend.find_one(id).await);
         use rusqlite::OptionalExtension as _;
         use std::io::Read as _;
 
@@ -183,6 +186,7 @@ impl EntityBase for PersistedMlsPendingGroup {
         conn: &mut Self::ConnectionType,
         params: EntityFindParams,
     ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let conn = sqlcipher_conn!(conn, backend => backend.find_all(params).await);
         let transaction = conn.transaction()?;
         let query: String = format!("SELECT rowid FROM mls_pending_groups {}", params.to_sql());
 
@@ -241,6 +245,7 @@ impl EntityBase for PersistedMlsPendingGroup {
         conn: &mut Self::ConnectionType,
         _ids: &[StringEntityId],
     ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let conn = sqlcipher_conn!(conn, backend => backend.find_all(Default::default()).await);
         // Plot twist: we always select ALL the persisted groups. Unsure if we want to make it a real API with selection
         let mut stmt = conn.prepare_cached("SELECT rowid FROM mls_pending_groups ORDER BY rowid ASC")?;
         let rowids: Vec<i64> = stmt
@@ -307,10 +312,12 @@ impl EntityBase for PersistedMlsPendingGroup {
     }
 
     async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
+        let conn = sqlcipher_conn!(conn, backend => backend.count::<Self>().await);
         Ok(conn.query_row("SELECT COUNT(*) FROM mls_pending_groups", [], |r| r.get(0))?)
     }
 
     async fn delete(conn: &mut Self::ConnectionType, ids: &[StringEntityId]) -> crate::CryptoKeystoreResult<()> {
+        let conn = sqlcipher_conn!(conn, backend => backend.delete::<Self>(ids).await);
         let transaction = conn.transaction()?;
         let len = ids.len();
         let mut updated = 0;
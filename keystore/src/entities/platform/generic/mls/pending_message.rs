@@ -31,12 +31,15 @@ impl Entity for MlsPendingMessage {
 impl EntityBase for MlsPendingMessage {
     type ConnectionType = KeystoreDatabaseConnection;
     type AutoGeneratedFields = ();
+    const COLLECTION_NAME: &'static str = "mls_pending_messages";
+    const UNIQUE_ID: bool = false;
 
     fn to_missing_key_err_kind() -> MissingKeyErrorKind {
         MissingKeyErrorKind::MlsPendingMessages
     }
 
     async fn save(&self, conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<()> {
+        let conn = sqlcipher_conn!(conn, backend => backend.save(self).await);
         let transaction = conn.transaction()?;
 
         Self::ConnectionType::check_buffer_size(self.id.len())?;
@@ -49,8 +52,8 @@ impl EntityBase for MlsPendingMessage {
 
         use rusqlite::ToSql as _;
         transaction.execute(
-            "INSERT INTO mls_pending_messages (id, message) VALUES(?, ?)",
-            [&zid.to_sql()?, &zmsg.to_sql()?],
+            "INSERT INTO mls_pending_messages (id, message, received_at) VALUES(?, ?, ?)",
+            rusqlite::params![&zid.to_sql()?, &zmsg.to_sql()?, self.received_at],
         )?;
         let rowid = transaction.last_insert_rowid();
 
@@ -79,19 +82,20 @@ impl EntityBase for MlsPendingMessage {
         conn: &mut Self::ConnectionType,
         id: &StringEntityId,
     ) -> crate::CryptoKeystoreResult<Option<Self>> {
+        let conn = sqlcipher_conn!(conn, backend => backend.find_one(id).await);
         use rusqlite::OptionalExtension as _;
         use std::io::Read as _;
 
         let transaction = conn.transaction()?;
-        let rowid: Option<i64> = transaction
+        let row: Option<(i64, u64)> = transaction
             .query_row(
-                "SELECT rowid FROM mls_pending_messages WHERE id = ?",
+                "SELECT rowid, received_at FROM mls_pending_messages WHERE id = ?",
                 [&id.as_slice()],
-                |r| r.get(0),
+                |r| Ok((r.get(0)?, r.get(1)?)),
             )
             .optional()?;
-        match rowid {
-            Some(rowid) => {
+        match row {
+            Some((rowid, received_at)) => {
                 let mut blob =
                     transaction.blob_open(rusqlite::DatabaseName::Main, "mls_pending_messages", "id", rowid, true)?;
                 let mut id = vec![];
@@ -109,7 +113,11 @@ impl EntityBase for MlsPendingMessage {
                 blob.read_to_end(&mut message)?;
                 blob.close()?;
 
-                Ok(Some(Self { id, message }))
+                Ok(Some(Self {
+                    id,
+                    message,
+                    received_at,
+                }))
             }
             None => Ok(None),
         }
@@ -119,51 +127,54 @@ impl EntityBase for MlsPendingMessage {
         conn: &mut Self::ConnectionType,
         params: EntityFindParams,
     ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let conn = sqlcipher_conn!(conn, backend => backend.find_all(params).await);
         let transaction = conn.transaction()?;
-        let query: String = format!("SELECT rowid FROM mls_pending_messages {}", params.to_sql());
+        let query: String = format!(
+            "SELECT rowid, received_at FROM mls_pending_messages {}",
+            params.to_sql()
+        );
 
         let mut stmt = transaction.prepare_cached(&query)?;
-        let mut rows = stmt.query_map([], |r| r.get(0))?;
-        let entities = rows.try_fold(Vec::new(), |mut acc, rowid_result| {
-            use std::io::Read as _;
-            let rowid = rowid_result?;
-
-            let mut blob =
-                transaction.blob_open(rusqlite::DatabaseName::Main, "mls_pending_messages", "id", rowid, true)?;
-            let mut id = vec![];
-            blob.read_to_end(&mut id)?;
-            blob.close()?;
-
-            let mut blob = transaction.blob_open(
-                rusqlite::DatabaseName::Main,
-                "mls_pending_messages",
-                "message",
-                rowid,
-                true,
-            )?;
-            let mut message = vec![];
-            blob.read_to_end(&mut message)?;
-            blob.close()?;
-
-            acc.push(Self { id, message });
-            crate::CryptoKeystoreResult::Ok(acc)
-        })?;
+        let entities =
+            stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
+                .try_fold(Vec::new(), |mut acc, row_result| {
+                    let (rowid, received_at) = row_result?;
+                    acc.push(Self::read(&transaction, rowid, received_at)?);
+                    crate::CryptoKeystoreResult::Ok(acc)
+                })?;
 
         Ok(entities)
     }
 
     async fn find_many(
-        _conn: &mut Self::ConnectionType,
-        _ids: &[StringEntityId],
+        conn: &mut Self::ConnectionType,
+        ids: &[StringEntityId],
     ) -> crate::CryptoKeystoreResult<Vec<Self>> {
-        unreachable!()
+        let conn = sqlcipher_conn!(conn, backend => backend.find_many(ids).await);
+        let transaction = conn.transaction()?;
+        let mut stmt = transaction
+            .prepare_cached("SELECT rowid, received_at FROM mls_pending_messages WHERE id = ? ORDER BY rowid")?;
+
+        let mut entities = vec![];
+        for id in ids {
+            let rows = stmt
+                .query_map([id.as_slice()], |r| Ok((r.get(0)?, r.get(1)?)))?
+                .collect::<Result<Vec<(i64, u64)>, _>>()?;
+            for (rowid, received_at) in rows {
+                entities.push(Self::read(&transaction, rowid, received_at)?);
+            }
+        }
+
+        Ok(entities)
     }
 
     async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
+        let conn = sqlcipher_conn!(conn, backend => backend.count::<Self>().await);
         Ok(conn.query_row("SELECT COUNT(*) FROM mls_pending_messages", [], |r| r.get(0))?)
     }
 
     async fn delete(conn: &mut Self::ConnectionType, ids: &[StringEntityId]) -> crate::CryptoKeystoreResult<()> {
+        let conn = sqlcipher_conn!(conn, backend => backend.delete::<Self>(ids).await);
         let transaction = conn.transaction()?;
         let mut updated = 0;
         for id in ids {
@@ -179,3 +190,32 @@ impl EntityBase for MlsPendingMessage {
         }
     }
 }
+
+impl MlsPendingMessage {
+    fn read(transaction: &rusqlite::Transaction, rowid: i64, received_at: u64) -> crate::CryptoKeystoreResult<Self> {
+        use std::io::Read as _;
+
+        let mut blob =
+            transaction.blob_open(rusqlite::DatabaseName::Main, "mls_pending_messages", "id", rowid, true)?;
+        let mut id = vec![];
+        blob.read_to_end(&mut id)?;
+        blob.close()?;
+
+        let mut blob = transaction.blob_open(
+            rusqlite::DatabaseName::Main,
+            "mls_pending_messages",
+            "message",
+            rowid,
+            true,
+        )?;
+        let mut message = vec![];
+        blob.read_to_end(&mut message)?;
+        blob.close()?;
+
+        Ok(Self {
+            id,
+            message,
+            received_at,
+        })
+    }
+}
@@ -37,6 +37,10 @@ impl Entity for E2eiRefreshToken {
 #[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
 impl UniqueEntity for E2eiRefreshToken {
     async fn find_unique(conn: &mut Self::ConnectionType) -> CryptoKeystoreResult<Self> {
+        let conn = sqlcipher_conn!(conn, backend => backend
+            .find_unique()
+            .await?
+            .ok_or(CryptoKeystoreError::NotFound("refresh token", "".to_string())));
         let transaction = conn.transaction()?;
         use rusqlite::OptionalExtension as _;
 
@@ -54,6 +58,7 @@ impl UniqueEntity for E2eiRefreshToken {
     }
 
     async fn replace(&self, conn: &mut Self::ConnectionType) -> CryptoKeystoreResult<()> {
+        let conn = sqlcipher_conn!(conn, backend => backend.save(self).await);
         Self::ConnectionType::check_buffer_size(self.content.len())?;
         let zb_content = rusqlite::blob::ZeroBlob(self.content.len() as i32);
 
@@ -89,6 +94,7 @@ impl UniqueEntity for E2eiRefreshToken {
 impl EntityBase for E2eiRefreshToken {
     type ConnectionType = KeystoreDatabaseConnection;
     type AutoGeneratedFields = ();
+    const COLLECTION_NAME: &'static str = "e2ei_refresh_token";
 
     fn to_missing_key_err_kind() -> MissingKeyErrorKind {
         MissingKeyErrorKind::E2eiRefreshToken
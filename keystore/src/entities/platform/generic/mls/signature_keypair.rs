@@ -32,6 +32,7 @@ impl Entity for MlsSignatureKeyPair {
 impl EntityBase for MlsSignatureKeyPair {
     type ConnectionType = KeystoreDatabaseConnection;
     type AutoGeneratedFields = ();
+    const COLLECTION_NAME: &'static str = "mls_signature_keypairs";
 
     fn to_missing_key_err_kind() -> MissingKeyErrorKind {
         MissingKeyErrorKind::MlsSignatureKeyPair
@@ -41,6 +42,7 @@ impl EntityBase for MlsSignatureKeyPair {
         conn: &mut Self::ConnectionType,
         params: EntityFindParams,
     ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let conn = sqlcipher_conn!(conn, backend => backend.find_all(params).await);
         let transaction = conn.transaction()?;
         let query: String = format!(
             "SELECT rowid, signature_scheme FROM mls_signature_keypairs {}",
@@ -111,6 +113,7 @@ impl EntityBase for MlsSignatureKeyPair {
         conn: &mut Self::ConnectionType,
         id: &StringEntityId,
     ) -> crate::CryptoKeystoreResult<Option<Self>> {
+        let conn = sqlcipher_conn!(conn, backend => backend.find_one(id).await);
         let transaction = conn.transaction()?;
         use rusqlite::OptionalExtension as _;
         let maybe_rowid = transaction
@@ -170,10 +173,12 @@ impl EntityBase for MlsSignatureKeyPair {
     }
 
     async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
+        let conn = sqlcipher_conn!(conn, backend => backend.count::<Self>().await);
         Ok(conn.query_row("SELECT COUNT(*) FROM mls_signature_keypairs", [], |r| r.get(0))?)
     }
 
     async fn delete(conn: &mut Self::ConnectionType, ids: &[StringEntityId]) -> crate::CryptoKeystoreResult<()> {
+        let conn = sqlcipher_conn!(conn, backend => backend.delete::<Self>(ids).await);
         let transaction = conn.transaction()?;
         let len = ids.len();
         let mut updated = 0;
@@ -191,6 +196,7 @@ impl EntityBase for MlsSignatureKeyPair {
     }
 
     async fn insert(&self, conn: &mut Self::ConnectionType) -> CryptoKeystoreResult<Self::AutoGeneratedFields> {
+        let conn = sqlcipher_conn!(conn, backend => backend.save(self).await);
         Self::ConnectionType::check_buffer_size(self.keypair.len())?;
         Self::ConnectionType::check_buffer_size(self.pk.len())?;
         Self::ConnectionType::check_buffer_size(self.credential_id.len())?;
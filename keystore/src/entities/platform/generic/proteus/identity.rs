@@ -34,6 +34,7 @@ impl Entity for ProteusIdentity {
 impl EntityBase for ProteusIdentity {
     type ConnectionType = KeystoreDatabaseConnection;
     type AutoGeneratedFields = ();
+    const COLLECTION_NAME: &'static str = "proteus_identities";
 
     fn to_missing_key_err_kind() -> MissingKeyErrorKind {
         MissingKeyErrorKind::ProteusIdentity
@@ -55,6 +56,7 @@ impl EntityBase for ProteusIdentity {
         conn: &mut Self::ConnectionType,
         _id: &StringEntityId,
     ) -> crate::CryptoKeystoreResult<Option<Self>> {
+        let conn = sqlcipher_conn!(conn, backend => backend.find_unique().await);
         let transaction = conn.transaction()?;
 
         let mut row_id: Option<i64> = transaction
@@ -100,6 +102,7 @@ impl EntityBase for ProteusIdentity {
     }
 
     async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
+        let conn = sqlcipher_conn!(conn, backend => backend.count::<Self>().await);
         let count = conn.query_row("SELECT COUNT(*) FROM proteus_identities", [], |r| r.get(0))?;
         // This should always be less or equal 1
         debug_assert!(count <= 1);
@@ -111,6 +114,8 @@ impl EntityBase for ProteusIdentity {
             return Ok(());
         }
 
+        let conn = sqlcipher_conn!(conn, backend => backend.save(self).await);
+
         let transaction = conn.transaction()?;
 
         use rusqlite::ToSql as _;
@@ -144,6 +149,7 @@ impl EntityBase for ProteusIdentity {
         conn: &mut Self::ConnectionType,
         _ids: &[crate::entities::StringEntityId],
     ) -> crate::CryptoKeystoreResult<()> {
+        let conn = sqlcipher_conn!(conn, backend => backend.delete::<Self>(&[b"1".as_slice().into()]).await);
         let transaction = conn.transaction()?;
         let row_id = transaction.query_row(
             "SELECT rowid FROM proteus_identities ORDER BY rowid ASC LIMIT 1",
@@ -33,6 +33,7 @@ impl Entity for ProteusPrekey {
 impl EntityBase for ProteusPrekey {
     type ConnectionType = KeystoreDatabaseConnection;
     type AutoGeneratedFields = ();
+    const COLLECTION_NAME: &'static str = "proteus_prekeys";
 
     fn to_missing_key_err_kind() -> MissingKeyErrorKind {
         MissingKeyErrorKind::ProteusPrekey
@@ -42,6 +43,7 @@ impl EntityBase for ProteusPrekey {
         conn: &mut Self::ConnectionType,
         params: EntityFindParams,
     ) -> crate::CryptoKeystoreResult<Vec<Self>> {
+        let conn = sqlcipher_conn!(conn, backend => backend.find_all(params).await);
         let transaction = conn.transaction()?;
         let query: String = format!("SELECT rowid, id FROM proteus_prekeys {}", params.to_sql());
 
@@ -69,6 +71,7 @@ impl EntityBase for ProteusPrekey {
         conn: &mut Self::ConnectionType,
         id: &StringEntityId,
     ) -> crate::CryptoKeystoreResult<Option<Self>> {
+        let conn = sqlcipher_conn!(conn, backend => backend.find_one(id).await);
         let id = ProteusPrekey::id_from_slice(id.as_slice());
 
         let transaction = conn.transaction()?;
@@ -96,10 +99,12 @@ impl EntityBase for ProteusPrekey {
     }
 
     async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
+        let conn = sqlcipher_conn!(conn, backend => backend.count::<Self>().await);
         Ok(conn.query_row("SELECT COUNT(*) FROM proteus_prekeys", [], |r| r.get(0))?)
     }
 
     async fn save(&self, conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<()> {
+        let conn = sqlcipher_conn!(conn, backend => backend.save(self).await);
         let transaction = conn.transaction()?;
 
         use rusqlite::ToSql as _;
@@ -143,6 +148,7 @@ impl EntityBase for ProteusPrekey {
         conn: &mut Self::ConnectionType,
         ids: &[crate::entities::StringEntityId],
     ) -> crate::CryptoKeystoreResult<()> {
+        let conn = sqlcipher_conn!(conn, backend => backend.delete::<Self>(ids).await);
         let transaction = conn.transaction()?;
         let len = ids.len();
 
@@ -15,17 +15,17 @@
 // along with this program. If not, see http://www.gnu.org/licenses/.
 
 use crate::{
-    connection::{storage::WasmStorageWrapper, DatabaseConnection, KeystoreDatabaseConnection},
+    connection::{DatabaseConnection, KeystoreDatabaseConnection},
     entities::{Entity, EntityBase, EntityFindParams, MlsCredential, MlsCredentialExt, StringEntityId},
     CryptoKeystoreError, CryptoKeystoreResult, MissingKeyErrorKind,
 };
-use rexie::TransactionMode;
 
 #[cfg_attr(target_family = "wasm", async_trait::async_trait(?Send))]
 #[cfg_attr(not(target_family = "wasm"), async_trait::async_trait)]
 impl EntityBase for MlsCredential {
     type ConnectionType = KeystoreDatabaseConnection;
     type AutoGeneratedFields = u64;
+    const COLLECTION_NAME: &'static str = "mls_credentials";
 
     fn to_missing_key_err_kind() -> MissingKeyErrorKind {
         MissingKeyErrorKind::MlsCredential
@@ -44,20 +44,27 @@ impl EntityBase for MlsCredential {
     async fn insert(&self, conn: &mut Self::ConnectionType) -> CryptoKeystoreResult<Self::AutoGeneratedFields> {
         let storage = conn.storage_mut();
 
-        let now = fluvio_wasm_timer::SystemTime::now();
-        let created_at = now
-            .duration_since(fluvio_wasm_timer::UNIX_EPOCH)
-            .map_err(|_| CryptoKeystoreError::TimestampError)?
-            .as_secs();
-
+        // keep the timestamp of a credential staged in a transaction
         let mut to_insert = self.clone();
-        to_insert.created_at = created_at;
+        let created_at = match self.created_at {
+            0 => to_insert.generate_fields()?,
+            created_at => created_at,
+        };
 
         storage.save("mls_credentials", &mut [to_insert]).await?;
 
         Ok(created_at)
     }
 
+    fn generate_fields(&mut self) -> CryptoKeystoreResult<Self::AutoGeneratedFields> {
+        let now = fluvio_wasm_timer::SystemTime::now();
+        self.created_at = now
+            .duration_since(fluvio_wasm_timer::UNIX_EPOCH)
+            .map_err(|_| CryptoKeystoreError::TimestampError)?
+            .as_secs();
+        Ok(self.created_at)
+    }
+
     async fn find_one(
         conn: &mut Self::ConnectionType,
         id: &StringEntityId,
@@ -98,31 +105,22 @@ impl Entity for MlsCredential {
 #[async_trait::async_trait(?Send)]
 impl MlsCredentialExt for MlsCredential {
     async fn delete_by_credential(conn: &mut Self::ConnectionType, credential: Vec<u8>) -> CryptoKeystoreResult<()> {
-        let storage = conn.storage_mut();
-        let (collection, index) = ("mls_credentials", "credential");
-        match &mut storage.storage {
-            WasmStorageWrapper::Persistent(rexie) => {
-                let transaction = rexie.transaction(&[collection], TransactionMode::ReadWrite)?;
-                let store = transaction.store(collection)?;
-                let store_index = store.index(index)?;
-                let credential_js: wasm_bindgen::JsValue = js_sys::Uint8Array::from(&credential[..]).into();
-                let Some(entity_raw) = store_index.get(&credential_js).await? else {
-                    let reason = "'credential' in 'mls_credentials' collection";
-                    let value = hex::encode(&credential);
-                    return Err(CryptoKeystoreError::NotFound(reason, value));
-                };
-
-                let mut credential = serde_wasm_bindgen::from_value::<MlsCredential>(entity_raw)?;
-                credential.decrypt(&storage.cipher)?;
-
-                let id = js_sys::Uint8Array::from(credential.id.as_slice());
-                store.delete(&id.into()).await?;
-            }
-            WasmStorageWrapper::InMemory(_) => {
-                // TODO: current table model does not fit in a hashmap (no more primary key)
-            }
+        // Goes through the storage rather than the `credential` index, so that the deletion joins the batch in
+        // progress if any and also applies to in-memory stores
+        let collection = "mls_credentials";
+        let ids = conn
+            .storage()
+            .get_all::<Self>(collection, None)
+            .await?
+            .into_iter()
+            .filter(|c| c.credential == credential)
+            .map(|c| c.id.clone())
+            .collect::<Vec<_>>();
+        if ids.is_empty() {
+            let reason = "'credential' in 'mls_credentials' collection";
+            return Err(CryptoKeystoreError::NotFound(reason, hex::encode(&credential)));
         }
 
-        Ok(())
+        conn.storage_mut().delete(collection, &ids).await
     }
 }
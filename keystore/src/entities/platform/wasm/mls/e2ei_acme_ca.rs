@@ -27,6 +27,7 @@ const ID: [u8; 1] = [0];
 impl EntityBase for E2eiAcmeCA {
     type ConnectionType = KeystoreDatabaseConnection;
     type AutoGeneratedFields = ();
+    const COLLECTION_NAME: &'static str = "e2ei_acme_ca";
 
     fn to_missing_key_err_kind() -> MissingKeyErrorKind {
         MissingKeyErrorKind::E2eiAcmeCA
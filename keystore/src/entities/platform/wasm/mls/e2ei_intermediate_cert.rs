@@ -25,6 +25,7 @@ use crate::{
 impl EntityBase for E2eiIntermediateCert {
     type ConnectionType = KeystoreDatabaseConnection;
     type AutoGeneratedFields = ();
+    const COLLECTION_NAME: &'static str = "e2ei_intermediate_certs";
 
     fn to_missing_key_err_kind() -> MissingKeyErrorKind {
         MissingKeyErrorKind::E2eiIntermediateCert
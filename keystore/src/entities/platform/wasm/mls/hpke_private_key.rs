@@ -25,6 +25,7 @@ use crate::{
 impl EntityBase for MlsHpkePrivateKey {
     type ConnectionType = KeystoreDatabaseConnection;
     type AutoGeneratedFields = ();
+    const COLLECTION_NAME: &'static str = "mls_hpke_private_keys";
 
     fn to_missing_key_err_kind() -> MissingKeyErrorKind {
         MissingKeyErrorKind::MlsHpkePrivateKey
@@ -25,6 +25,7 @@ use crate::{
 impl EntityBase for MlsKeyPackage {
     type ConnectionType = KeystoreDatabaseConnection;
     type AutoGeneratedFields = ();
+    const COLLECTION_NAME: &'static str = "mls_keypackages";
 
     fn to_missing_key_err_kind() -> MissingKeyErrorKind {
         MissingKeyErrorKind::MlsKeyPackageBundle
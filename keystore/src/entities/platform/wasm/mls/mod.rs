@@ -20,10 +20,14 @@ pub mod e2ei_crl;
 pub mod e2ei_intermediate_cert;
 pub mod encryption_keypair;
 pub mod enrollment;
+pub mod epoch_authenticators;
 pub mod epoch_encryption_keypair;
 pub mod group;
+pub mod group_key_rotation;
+pub mod history;
 pub mod hpke_private_key;
 pub mod keypackage;
+pub mod member_epochs;
 pub mod pending_message;
 pub mod psk_bundle;
 pub mod refresh_token;
@@ -9,6 +9,8 @@ use crate::{
 impl EntityBase for MlsPendingMessage {
     type ConnectionType = KeystoreDatabaseConnection;
     type AutoGeneratedFields = ();
+    const COLLECTION_NAME: &'static str = "mls_pending_messages";
+    const UNIQUE_ID: bool = false;
 
     fn to_missing_key_err_kind() -> MissingKeyErrorKind {
         MissingKeyErrorKind::MlsPendingMessages
@@ -31,15 +33,17 @@ impl EntityBase for MlsPendingMessage {
         conn: &mut Self::ConnectionType,
         id: &StringEntityId,
     ) -> crate::CryptoKeystoreResult<Option<Self>> {
-        conn.storage().get("mls_pending_messages", id.as_slice()).await
+        let messages = Self::find_many(conn, std::slice::from_ref(id)).await?;
+        Ok(messages.into_iter().next())
     }
 
     async fn find_many(
         conn: &mut Self::ConnectionType,
-        _ids: &[StringEntityId],
+        ids: &[StringEntityId],
     ) -> crate::CryptoKeystoreResult<Vec<Self>> {
-        // Plot twist: we always select ALL the persisted groups. Unsure if we want to make it a real API with selection
-        conn.storage().get_all("mls_pending_messages", None).await
+        let mut messages: Vec<Self> = conn.storage().get_all("mls_pending_messages", None).await?;
+        messages.retain(|m| ids.iter().any(|id| id.as_slice() == m.id.as_slice()));
+        Ok(messages)
     }
 
     async fn count(conn: &mut Self::ConnectionType) -> crate::CryptoKeystoreResult<usize> {
@@ -47,9 +51,25 @@ impl EntityBase for MlsPendingMessage {
     }
 
     async fn delete(conn: &mut Self::ConnectionType, ids: &[StringEntityId]) -> crate::CryptoKeystoreResult<()> {
-        let ids: Vec<Vec<u8>> = ids.iter().map(StringEntityId::to_bytes).collect();
-        let _ = conn.storage_mut().delete("mls_pending_messages", &ids).await?;
-        Ok(())
+        let keys: Vec<Vec<u8>> = Self::find_many(conn, ids)
+            .await?
+            .iter()
+            .map(Self::primary_key)
+            .collect();
+        conn.storage_mut().delete("mls_pending_messages", &keys).await
+    }
+}
+
+impl MlsPendingMessage {
+    /// Several messages are buffered per conversation, so they are stored under the id of their conversation
+    /// followed by the time they were received at, which keeps them sorted by age, and a digest of the message
+    pub(crate) fn primary_key(&self) -> Vec<u8> {
+        use sha2::Digest as _;
+
+        let mut key = self.id.clone();
+        key.extend_from_slice(&self.received_at.to_be_bytes());
+        key.extend_from_slice(&sha2::Sha256::digest(&self.message));
+        key
     }
 }
 
@@ -59,7 +79,7 @@ impl Entity for MlsPendingMessage {
     }
 
     fn id(&self) -> CryptoKeystoreResult<wasm_bindgen::JsValue> {
-        Ok(js_sys::Uint8Array::from(self.id.as_slice()).into())
+        Ok(js_sys::Uint8Array::from(self.primary_key().as_slice()).into())
     }
 
     fn aad(&self) -> &[u8] {
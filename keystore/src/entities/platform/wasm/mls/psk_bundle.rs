@@ -25,6 +25,7 @@ use crate::{
 impl EntityBase for MlsPskBundle {
     type ConnectionType = KeystoreDatabaseConnection;
     type AutoGeneratedFields = ();
+    const COLLECTION_NAME: &'static str = "mls_psk_bundles";
 
     fn to_missing_key_err_kind() -> MissingKeyErrorKind {
         MissingKeyErrorKind::MlsPskBundle
@@ -27,6 +27,7 @@ const ID: [u8; 1] = [0];
 impl EntityBase for E2eiRefreshToken {
     type ConnectionType = KeystoreDatabaseConnection;
     type AutoGeneratedFields = ();
+    const COLLECTION_NAME: &'static str = "e2ei_refresh_token";
 
     fn to_missing_key_err_kind() -> MissingKeyErrorKind {
         MissingKeyErrorKind::E2eiRefreshToken
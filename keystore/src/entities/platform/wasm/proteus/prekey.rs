@@ -25,6 +25,7 @@ use crate::{
 impl EntityBase for ProteusPrekey {
     type ConnectionType = KeystoreDatabaseConnection;
     type AutoGeneratedFields = ();
+    const COLLECTION_NAME: &'static str = "proteus_prekeys";
 
     fn to_missing_key_err_kind() -> MissingKeyErrorKind {
         MissingKeyErrorKind::ProteusPrekey
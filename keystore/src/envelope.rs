@@ -0,0 +1,100 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+//! Passphrase-encrypted envelopes, shared by keystore backups and MLS snapshots.
+//!
+//! Layout: `prefix | salt (16 bytes) | nonce (12 bytes) | AES-256-GCM ciphertext`. The prefix is chosen by the
+//! caller, usually a magic and a version. The key is derived from the passphrase with Argon2id and the whole
+//! header, prefix included, is used as additional authenticated data.
+
+use aes_gcm::aead::{Aead as _, KeyInit as _, Payload};
+use zeroize::Zeroizing;
+
+use crate::{CryptoKeystoreError, CryptoKeystoreResult};
+
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+const KEY_LENGTH: usize = 32;
+
+// Argon2id parameters: 19 MiB of memory, 2 iterations, 1 degree of parallelism
+const ARGON2_MEMORY_COST: u32 = 19 * 1024;
+const ARGON2_TIME_COST: u32 = 2;
+const ARGON2_PARALLELISM: u32 = 1;
+
+fn cipher(passphrase: &str, salt: &[u8]) -> CryptoKeystoreResult<aes_gcm::Aes256Gcm> {
+    let params = argon2::Params::new(
+        ARGON2_MEMORY_COST,
+        ARGON2_TIME_COST,
+        ARGON2_PARALLELISM,
+        Some(KEY_LENGTH),
+    )
+    .map_err(|_| CryptoKeystoreError::ImplementationError)?;
+    let argon2 = argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params);
+    let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+    argon2
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key[..])
+        .map_err(|_| CryptoKeystoreError::ImplementationError)?;
+    Ok(aes_gcm::Aes256Gcm::new(aes_gcm::Key::<aes_gcm::Aes256Gcm>::from_slice(
+        &key[..],
+    )))
+}
+
+/// Encrypts `plaintext` under a key derived from `passphrase` with a fresh salt and nonce, and prepends `prefix`
+/// to the result
+pub fn seal(passphrase: &str, prefix: &[u8], plaintext: &[u8]) -> CryptoKeystoreResult<Vec<u8>> {
+    let mut header = Vec::with_capacity(prefix.len() + SALT_LENGTH + NONCE_LENGTH);
+    header.extend_from_slice(prefix);
+    header.extend(rand::random::<[u8; SALT_LENGTH]>());
+    header.extend(rand::random::<[u8; NONCE_LENGTH]>());
+    let (salt, nonce) = header[prefix.len()..].split_at(SALT_LENGTH);
+    let ciphertext = cipher(passphrase, salt)?
+        .encrypt(
+            aes_gcm::Nonce::from_slice(nonce),
+            Payload {
+                msg: plaintext,
+                aad: &header,
+            },
+        )
+        .map_err(|_| CryptoKeystoreError::ImplementationError)?;
+
+    Ok([header, ciphertext].concat())
+}
+
+/// Decrypts an envelope created by [seal] whose prefix is `prefix_length` bytes long. Checking the prefix itself is
+/// left to the caller.
+///
+/// # Errors
+/// [CryptoKeystoreError::BackupDecryptionError] if the envelope is truncated, the passphrase is wrong or the envelope
+/// was tampered with
+pub fn open(passphrase: &str, prefix_length: usize, envelope: &[u8]) -> CryptoKeystoreResult<Zeroizing<Vec<u8>>> {
+    let header_length = prefix_length + SALT_LENGTH + NONCE_LENGTH;
+    if envelope.len() < header_length {
+        return Err(CryptoKeystoreError::BackupDecryptionError);
+    }
+    let (header, ciphertext) = envelope.split_at(header_length);
+    let (salt, nonce) = header[prefix_length..].split_at(SALT_LENGTH);
+    let plaintext = cipher(passphrase, salt)?
+        .decrypt(
+            aes_gcm::Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|_| CryptoKeystoreError::BackupDecryptionError)?;
+
+    Ok(Zeroizing::new(plaintext))
+}
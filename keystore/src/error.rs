@@ -37,6 +37,14 @@ pub enum MissingKeyErrorKind {
     MlsPendingGroup,
     #[error("MLS Pending Messages")]
     MlsPendingMessages,
+    #[error("MLS Group Key Rotation")]
+    MlsGroupKeyRotation,
+    #[error("MLS Epoch Authenticators")]
+    MlsEpochAuthenticators,
+    #[error("MLS Member Epochs")]
+    MlsMemberEpochs,
+    #[error("MLS History")]
+    MlsHistory,
     #[error("End-to-end identity enrollment")]
     E2eiEnrollment,
     #[error("OIDC refresh token")]
@@ -81,7 +89,26 @@ pub enum CryptoKeystoreError {
     AlreadyExists,
     #[error("The provided buffer is too big to be persisted in the store")]
     BlobTooBig,
-    #[cfg(feature = "mls-keystore")]
+    #[error("A transaction is already in progress on this keystore")]
+    TransactionInProgress,
+    #[error("No transaction is in progress on this keystore")]
+    TransactionNotStarted,
+    #[error("An interrupted rekeying towards another key has to be resumed first")]
+    RekeyKeyMismatch,
+    #[error("This keystore is being rekeyed and has to be opened with the new key to finish")]
+    RekeyInterrupted,
+    #[error("The provided data is not a keystore backup")]
+    InvalidBackup,
+    #[error("This keystore backup has an unsupported version: {0}")]
+    UnsupportedBackupVersion(u8),
+    #[error("The keystore backup could not be decrypted, the passphrase is probably wrong")]
+    BackupDecryptionError,
+    #[error("The keystore backup contains an unknown collection: {0}")]
+    UnknownBackupCollection(String),
+    #[error("A keystore backup can only be restored into an empty keystore")]
+    BackupRestoreIntoNonEmptyStore,
+    #[error("A keystore can only be copied into an empty keystore")]
+    CopyIntoNonEmptyStore,
     #[error(transparent)]
     KeyStoreValueTransformError(#[from] postcard::Error),
     #[error(transparent)]
@@ -101,7 +128,7 @@ pub enum CryptoKeystoreError {
     #[cfg(target_family = "wasm")]
     #[error("An IndexedDB timeout has occured")]
     RexieTimeoutError,
-    #[cfg(target_family = "wasm")]
+    #[cfg(any(target_family = "wasm", feature = "custom-backend"))]
     #[error("aead::Error")]
     AesGcmError,
     #[cfg(target_family = "wasm")]
@@ -113,6 +140,9 @@ pub enum CryptoKeystoreError {
     #[cfg(not(target_family = "wasm"))]
     #[error(transparent)]
     DbMigrationError(#[from] Box<refinery::Error>),
+    #[cfg(all(not(target_family = "wasm"), feature = "custom-backend"))]
+    #[error(transparent)]
+    BackendError(Box<dyn std::error::Error + Send + Sync>),
     #[cfg(test)]
     #[error(transparent)]
     MlsKeyPackageIdError(#[from] openmls::prelude::KeyPackageIdError),
@@ -20,8 +20,11 @@
 mod error;
 pub use error::*;
 
+#[macro_use]
 pub mod connection;
 pub mod entities;
+#[cfg(any(target_family = "wasm", feature = "backup"))]
+pub mod envelope;
 
 cfg_if::cfg_if! {
     if #[cfg(feature = "mls-keystore")] {
@@ -54,6 +57,7 @@ pub mod dummy_entity {
     impl EntityBase for DummyStoreValue {
         type ConnectionType = crate::connection::KeystoreDatabaseConnection;
         type AutoGeneratedFields = ();
+        const COLLECTION_NAME: &'static str = "dummy";
 
         fn to_missing_key_err_kind() -> MissingKeyErrorKind {
             MissingKeyErrorKind::MlsGroup
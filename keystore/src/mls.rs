@@ -139,7 +139,7 @@ impl CryptoKeystoreMls for crate::connection::Connection {
     async fn mls_fetch_keypackages<V: MlsEntity>(&self, count: u32) -> CryptoKeystoreResult<Vec<V>> {
         use crate::{connection::storage::WasmStorageWrapper, entities::Entity};
         let conn = self.conn.lock_arc().await;
-        let cipher = &conn.storage().cipher;
+        let cipher = conn.storage().cipher_for("mls_keypackages");
         let storage = &conn.storage().storage;
 
         let raw_kps: Vec<MlsKeyPackage> = match storage {
@@ -193,6 +193,16 @@ impl CryptoKeystoreMls for crate::connection::Connection {
     #[cfg(not(target_family = "wasm"))]
     async fn mls_fetch_keypackages<V: MlsEntity>(&self, count: u32) -> CryptoKeystoreResult<Vec<V>> {
         let mut db = self.conn.lock().await;
+        let db = sqlcipher_conn!(&mut *db, backend => Ok(backend
+            .find_all::<MlsKeyPackage>(EntityFindParams {
+                limit: Some(count),
+                offset: None,
+                reverse: true,
+            })
+            .await?
+            .into_iter()
+            .filter_map(|kpb| postcard::from_bytes(&kpb.keypackage).ok())
+            .collect()));
 
         let transaction = db.transaction()?;
 
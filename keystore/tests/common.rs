@@ -49,6 +49,31 @@ pub async fn setup(name: impl AsRef<str>, in_memory: bool) -> core_crypto_keysto
     .unwrap()
 }
 
+#[cfg(not(target_family = "wasm"))]
+#[fixture(name = store_name())]
+pub async fn setup_file_backend(name: impl AsRef<str>) -> core_crypto_keystore::Connection {
+    let backend = core_crypto_keystore::connection::FileBackend::open(name.as_ref())
+        .await
+        .unwrap();
+    core_crypto_keystore::Connection::open_with_backend(backend, TEST_ENCRYPTION_KEY)
+        .await
+        .unwrap()
+}
+
+#[cfg(not(target_family = "wasm"))]
+#[template]
+#[rstest]
+#[case::persistent(setup(store_name(), false))]
+#[case::in_memory(setup(store_name(), true))]
+#[case::file_backend(setup_file_backend(store_name()))]
+pub async fn all_storage_types(
+    #[case]
+    #[future]
+    store: core_crypto_keystore::Connection,
+) {
+}
+
+#[cfg(target_family = "wasm")]
 #[template]
 #[rstest]
 #[case::persistent(setup(store_name(), false))]
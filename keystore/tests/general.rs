@@ -34,6 +34,329 @@ pub mod tests {
         teardown(store).await;
     }
 
+    #[apply(all_storage_types)]
+    #[wasm_bindgen_test]
+    pub async fn can_rekey_store(store: CryptoKeystore) {
+        use core_crypto_keystore::entities::PersistedMlsGroup;
+
+        let store = store.await;
+        let group = PersistedMlsGroup {
+            id: b"group".to_vec(),
+            state: b"state".to_vec(),
+            parent_id: None,
+        };
+        store.save(group.clone()).await.unwrap();
+
+        store.rekey("new key").await.unwrap();
+        let found = store.find::<PersistedMlsGroup>(b"group").await.unwrap();
+        assert_eq!(found, Some(group));
+
+        // rekeying is not allowed while writes are being staged
+        store.begin_transaction().await.unwrap();
+        assert!(matches!(
+            store.rekey("another key").await.unwrap_err(),
+            core_crypto_keystore::CryptoKeystoreError::TransactionInProgress
+        ));
+        store.rollback_transaction().await.unwrap();
+
+        teardown(store).await;
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), async_std::test)]
+    #[wasm_bindgen_test]
+    pub async fn can_reopen_rekeyed_store() {
+        use core_crypto_keystore::entities::PersistedMlsGroup;
+
+        let name = store_name();
+        let store = setup(&name, false).await;
+        let group = PersistedMlsGroup {
+            id: b"group".to_vec(),
+            state: b"state".to_vec(),
+            parent_id: None,
+        };
+        store.save(group.clone()).await.unwrap();
+        store.rekey("new key").await.unwrap();
+        store.close().await.unwrap();
+
+        #[cfg(not(target_family = "wasm"))]
+        assert!(CryptoKeystore::open_with_key(&name, TEST_ENCRYPTION_KEY).await.is_err());
+
+        let store = CryptoKeystore::open_with_key(&name, "new key").await.unwrap();
+        let found = store.find::<PersistedMlsGroup>(b"group").await.unwrap();
+        assert_eq!(found, Some(group));
+        teardown(store).await;
+    }
+
+    #[cfg(target_family = "wasm")]
+    #[wasm_bindgen_test]
+    pub async fn can_resume_interrupted_rekey() {
+        use core_crypto_keystore::{
+            entities::{PersistedMlsGroup, PersistedMlsPendingGroup},
+            CryptoKeystoreError,
+        };
+
+        let name = store_name();
+        let store = setup(&name, false).await;
+        let group = PersistedMlsGroup {
+            id: b"group".to_vec(),
+            state: b"state".to_vec(),
+            parent_id: None,
+        };
+        store.save(group.clone()).await.unwrap();
+        let pending_group = PersistedMlsPendingGroup {
+            id: b"pending group".to_vec(),
+            state: b"state".to_vec(),
+            parent_id: None,
+            custom_configuration: vec![],
+        };
+        store.save(pending_group.clone()).await.unwrap();
+
+        // an entity the current key cannot decrypt interrupts the rekeying once "mls_groups" has been re-encrypted
+        let other_store = CryptoKeystore::open_with_key(&name, "other key").await.unwrap();
+        other_store
+            .save(PersistedMlsPendingGroup {
+                id: b"poison".to_vec(),
+                state: b"state".to_vec(),
+                parent_id: None,
+                custom_configuration: vec![],
+            })
+            .await
+            .unwrap();
+        other_store.close().await.unwrap();
+        assert!(store.rekey("new key").await.is_err());
+
+        // every collection keeps being used with the key it is under
+        assert_eq!(
+            store.find::<PersistedMlsGroup>(b"group").await.unwrap(),
+            Some(group.clone())
+        );
+        let other_group = PersistedMlsGroup {
+            id: b"other group".to_vec(),
+            state: b"state".to_vec(),
+            parent_id: None,
+        };
+        store.save(other_group.clone()).await.unwrap();
+        assert_eq!(
+            store.find::<PersistedMlsGroup>(b"other group").await.unwrap(),
+            Some(other_group.clone())
+        );
+        assert!(matches!(
+            store.rekey("another key").await.unwrap_err(),
+            CryptoKeystoreError::RekeyKeyMismatch
+        ));
+        store.remove::<PersistedMlsPendingGroup, _>(b"poison").await.unwrap();
+        store.close().await.unwrap();
+
+        assert!(matches!(
+            CryptoKeystore::open_with_key(&name, TEST_ENCRYPTION_KEY)
+                .await
+                .unwrap_err(),
+            CryptoKeystoreError::RekeyInterrupted
+        ));
+
+        // opening the store with the new key finishes the rekeying
+        let store = CryptoKeystore::open_with_key(&name, "new key").await.unwrap();
+        store.close().await.unwrap();
+        let store = CryptoKeystore::open_with_key(&name, "new key").await.unwrap();
+        assert_eq!(store.find::<PersistedMlsGroup>(b"group").await.unwrap(), Some(group));
+        assert_eq!(
+            store.find::<PersistedMlsGroup>(b"other group").await.unwrap(),
+            Some(other_group)
+        );
+        assert_eq!(
+            store.find::<PersistedMlsPendingGroup>(b"pending group").await.unwrap(),
+            Some(pending_group)
+        );
+        teardown(store).await;
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    #[async_std::test]
+    pub async fn can_reopen_file_backend() {
+        use core_crypto_keystore::{connection::FileBackend, entities::PersistedMlsGroup};
+
+        let name = store_name();
+        let store = setup_file_backend(&name).await;
+        let group = PersistedMlsGroup {
+            id: b"group".to_vec(),
+            state: b"state".to_vec(),
+            parent_id: None,
+        };
+        store.save(group.clone()).await.unwrap();
+        store.rekey("new key").await.unwrap();
+        store.close().await.unwrap();
+
+        // nothing is readable under the former key
+        let backend = FileBackend::open(&name).await.unwrap();
+        let store = CryptoKeystore::open_with_backend(backend, TEST_ENCRYPTION_KEY)
+            .await
+            .unwrap();
+        assert!(store.find::<PersistedMlsGroup>(b"group").await.is_err());
+        store.close().await.unwrap();
+
+        let backend = FileBackend::open(&name).await.unwrap();
+        let store = CryptoKeystore::open_with_backend(backend, "new key").await.unwrap();
+        let found = store.find::<PersistedMlsGroup>(b"group").await.unwrap();
+        assert_eq!(found, Some(group));
+        teardown(store).await;
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    #[async_std::test]
+    pub async fn should_ignore_leftover_file_backend_temp_file() {
+        use core_crypto_keystore::{connection::FileBackend, entities::PersistedMlsGroup};
+
+        let name = store_name();
+        let store = setup_file_backend(&name).await;
+        let group = PersistedMlsGroup {
+            id: b"group".to_vec(),
+            state: b"state".to_vec(),
+            parent_id: None,
+        };
+        store.save(group.clone()).await.unwrap();
+        store.close().await.unwrap();
+
+        // a crash in the middle of a write leaves a half-written temporary file next to the store
+        let tmp_name = format!("{name}.tmp");
+        std::fs::write(&tmp_name, b"half-written").unwrap();
+
+        let backend = FileBackend::open(&name).await.unwrap();
+        let store = CryptoKeystore::open_with_backend(backend, TEST_ENCRYPTION_KEY)
+            .await
+            .unwrap();
+        assert_eq!(store.find::<PersistedMlsGroup>(b"group").await.unwrap(), Some(group));
+        // and it's renamed over the store on the next write
+        assert!(!std::path::Path::new(&tmp_name).exists());
+        teardown(store).await;
+    }
+
+    #[apply(all_storage_types)]
+    #[wasm_bindgen_test]
+    pub async fn can_restore_backup_into_any_storage_type(store: CryptoKeystore) {
+        use core_crypto_keystore::entities::{E2eiAcmeCA, PersistedMlsGroup, ProteusSession, UniqueEntity as _};
+
+        let store = store.await;
+        let group = PersistedMlsGroup {
+            id: b"group".to_vec(),
+            state: b"state".to_vec(),
+            parent_id: None,
+        };
+        let session = ProteusSession {
+            id: "session".into(),
+            session: b"session".to_vec(),
+        };
+        let acme_ca = E2eiAcmeCA {
+            content: b"acme ca".to_vec(),
+        };
+        store.save(group.clone()).await.unwrap();
+        store.save(session.clone()).await.unwrap();
+        acme_ca.replace(&mut store.borrow_conn().await.unwrap()).await.unwrap();
+
+        let backup = store.export_backup("passphrase").await.unwrap();
+
+        // the backup can be restored into a persistent keystore as well as an in-memory one
+        for in_memory in [false, true] {
+            let restored = setup(store_name(), in_memory).await;
+            restored.import_backup(&backup, "passphrase").await.unwrap();
+
+            assert_eq!(
+                restored.find::<PersistedMlsGroup>(b"group").await.unwrap(),
+                Some(group.clone())
+            );
+            assert_eq!(
+                restored.find::<ProteusSession>(b"session").await.unwrap(),
+                Some(session.clone())
+            );
+            let found = E2eiAcmeCA::find_unique(&mut restored.borrow_conn().await.unwrap())
+                .await
+                .unwrap();
+            assert_eq!(found, acme_ca);
+            teardown(restored).await;
+        }
+
+        // the source keystore is left untouched
+        assert_eq!(store.count::<PersistedMlsGroup>().await.unwrap(), 1);
+
+        teardown(store).await;
+    }
+
+    #[apply(all_storage_types)]
+    #[wasm_bindgen_test]
+    pub async fn cannot_restore_backup_with_wrong_passphrase_or_into_non_empty_store(store: CryptoKeystore) {
+        use core_crypto_keystore::{entities::PersistedMlsGroup, CryptoKeystoreError};
+
+        let store = store.await;
+        let group = PersistedMlsGroup {
+            id: b"group".to_vec(),
+            state: b"state".to_vec(),
+            parent_id: None,
+        };
+        store.save(group).await.unwrap();
+        let backup = store.export_backup("passphrase").await.unwrap();
+
+        let restored = setup(store_name(), true).await;
+        assert!(matches!(
+            restored.import_backup(&backup, "wrong passphrase").await.unwrap_err(),
+            CryptoKeystoreError::BackupDecryptionError
+        ));
+        assert!(matches!(
+            restored.import_backup(b"not a backup", "passphrase").await.unwrap_err(),
+            CryptoKeystoreError::InvalidBackup
+        ));
+        assert_eq!(restored.count::<PersistedMlsGroup>().await.unwrap(), 0);
+
+        assert!(matches!(
+            store.import_backup(&backup, "passphrase").await.unwrap_err(),
+            CryptoKeystoreError::BackupRestoreIntoNonEmptyStore
+        ));
+
+        teardown(restored).await;
+        teardown(store).await;
+    }
+
+    #[cfg_attr(not(target_family = "wasm"), async_std::test)]
+    #[wasm_bindgen_test]
+    pub async fn can_persist_in_memory_store_and_load_it_back() {
+        use core_crypto_keystore::{entities::PersistedMlsGroup, CryptoKeystoreError};
+
+        let in_memory = setup(store_name(), true).await;
+        let group = PersistedMlsGroup {
+            id: b"group".to_vec(),
+            state: b"state".to_vec(),
+            parent_id: None,
+        };
+        in_memory.save(group.clone()).await.unwrap();
+
+        let name = store_name();
+        let persisted = in_memory.persist_to_disk(&name, TEST_ENCRYPTION_KEY).await.unwrap();
+        assert_eq!(
+            persisted.find::<PersistedMlsGroup>(b"group").await.unwrap(),
+            Some(group.clone())
+        );
+        persisted.close().await.unwrap();
+
+        // the keystore on disk is not overwritten
+        assert!(matches!(
+            in_memory.persist_to_disk(&name, TEST_ENCRYPTION_KEY).await.unwrap_err(),
+            CryptoKeystoreError::CopyIntoNonEmptyStore
+        ));
+        assert_eq!(in_memory.count::<PersistedMlsGroup>().await.unwrap(), 1);
+
+        let loaded = CryptoKeystore::load_into_memory(&name, TEST_ENCRYPTION_KEY)
+            .await
+            .unwrap();
+        assert_eq!(loaded.find::<PersistedMlsGroup>(b"group").await.unwrap(), Some(group));
+
+        // changes made in memory don't reach the disk
+        loaded.remove::<PersistedMlsGroup, _>(b"group").await.unwrap();
+        let persisted = setup(&name, false).await;
+        assert_eq!(persisted.count::<PersistedMlsGroup>().await.unwrap(), 1);
+
+        teardown(persisted).await;
+        teardown(loaded).await;
+        teardown(in_memory).await;
+    }
+
     #[cfg(feature = "ios-wal-compat")]
     #[cfg_attr(not(target_family = "wasm"), async_std::test)]
     async fn can_preserve_wal_compat_for_ios() {
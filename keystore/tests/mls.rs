@@ -142,6 +142,125 @@ pub mod tests {
         teardown(backend.unwrap_keystore()).await;
     }
 
+    #[apply(all_storage_types)]
+    #[wasm_bindgen_test]
+    pub async fn can_find_and_collect_dangling_entities(store: Connection) {
+        use core_crypto_keystore::{
+            connection::{GcCategories, IntegrityCheckParams},
+            entities::{MlsEncryptionKeyPair, MlsEpochEncryptionKeyPair, MlsPendingMessage},
+        };
+
+        let store = store.await;
+
+        let pk = |byte: u8| vec![byte; 32];
+        let group_id = b"group".to_vec();
+        let epoch_keypair_id = |group_id: &[u8]| [group_id, &[0; 12]].concat();
+
+        // a group referencing a leaf encryption key, and a key package referencing its init and encryption keys
+        store
+            .save(PersistedMlsGroup {
+                id: group_id.clone(),
+                state: [b"state".as_slice(), &pk(1), b"state"].concat(),
+                parent_id: None,
+            })
+            .await
+            .unwrap();
+        store
+            .save(MlsKeyPackage {
+                keypackage_ref: b"kp".to_vec(),
+                keypackage: [pk(2), pk(3)].concat(),
+            })
+            .await
+            .unwrap();
+        // OpenMLS identifies leaf encryption keypairs by their public key, prefixed with a label
+        let leaf_pk = |byte: u8| [b"leaf_encryption_key".as_slice(), &pk(byte)].concat();
+        for pk in [pk(1), leaf_pk(3), pk(4)] {
+            store
+                .save(MlsEncryptionKeyPair { pk, sk: b"sk".to_vec() })
+                .await
+                .unwrap();
+        }
+        for pk in [pk(2), pk(5)] {
+            store.save(MlsHpkePrivateKey { pk, sk: b"sk".to_vec() }).await.unwrap();
+        }
+        // and epoch encryption keypairs by the group id prefixed with its length
+        let prefixed_group_id = [&[group_id.len() as u8], group_id.as_slice()].concat();
+        for id in [epoch_keypair_id(&prefixed_group_id), epoch_keypair_id(b"wiped group")] {
+            store
+                .save(MlsEpochEncryptionKeyPair {
+                    id,
+                    keypairs: b"keypairs".to_vec(),
+                })
+                .await
+                .unwrap();
+        }
+        // messages of a wiped group, and fresh ones of a group not joined yet
+        for (id, received_at) in [(b"wiped group".to_vec(), 1), (b"future group".to_vec(), u64::MAX)] {
+            store
+                .save(MlsPendingMessage {
+                    id,
+                    message: b"message".to_vec(),
+                    received_at,
+                })
+                .await
+                .unwrap();
+        }
+        store
+            .save(MlsCredential {
+                id: b"client".to_vec(),
+                credential: b"credential".to_vec(),
+                created_at: 0,
+            })
+            .await
+            .unwrap();
+        for credential_id in [b"client".to_vec(), b"former client".to_vec()] {
+            store
+                .save(MlsSignatureKeyPair::new(
+                    openmls::prelude::SignatureScheme::ED25519,
+                    credential_id.clone(),
+                    b"keypair".to_vec(),
+                    credential_id,
+                ))
+                .await
+                .unwrap();
+        }
+
+        let report = store.check_integrity(IntegrityCheckParams::default()).await.unwrap();
+        assert_eq!(report.encryption_keypairs, vec![pk(4)]);
+        assert_eq!(report.hpke_private_keys, vec![pk(5)]);
+        assert_eq!(report.epoch_encryption_keypairs, vec![epoch_keypair_id(b"wiped group")]);
+        assert_eq!(report.pending_messages, vec![b"wiped group".to_vec()]);
+        assert_eq!(report.signature_keypairs, vec![b"former client".to_vec()]);
+        // checking doesn't delete anything
+        assert_eq!(store.count::<MlsEncryptionKeyPair>().await.unwrap(), 3);
+
+        // nothing is deleted unless opted into
+        assert!(store
+            .gc(IntegrityCheckParams::default(), GcCategories::default())
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            store
+                .gc(IntegrityCheckParams::default(), GcCategories::all())
+                .await
+                .unwrap(),
+            report
+        );
+        assert!(store
+            .check_integrity(IntegrityCheckParams::default())
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(store.count::<MlsEncryptionKeyPair>().await.unwrap(), 2);
+        assert_eq!(store.count::<MlsHpkePrivateKey>().await.unwrap(), 1);
+        assert_eq!(store.count::<MlsEpochEncryptionKeyPair>().await.unwrap(), 1);
+        assert_eq!(store.count::<MlsPendingMessage>().await.unwrap(), 1);
+        assert_eq!(store.count::<MlsSignatureKeyPair>().await.unwrap(), 1);
+
+        teardown(store).await;
+    }
+
     // FIXME: rewrite the tests using the new OpenMLS apis
     // #[apply(all_storage_types)]
     // #[wasm_bindgen_test]
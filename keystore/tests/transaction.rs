@@ -0,0 +1,242 @@
+// Wire
+// Copyright (C) 2022 Wire Swiss GmbH
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see http://www.gnu.org/licenses/.
+
+pub use rstest::*;
+pub use rstest_reuse::{self, *};
+
+mod common;
+
+#[cfg(feature = "mls-keystore")]
+pub mod tests {
+    use crate::common::*;
+    use wasm_bindgen_test::*;
+
+    use core_crypto_keystore::{
+        entities::{
+            E2eiRefreshToken, EntityBase, EntityFindParams, MlsCredential, MlsPendingMessage, PersistedMlsGroup,
+            StringEntityId, UniqueEntity,
+        },
+        CryptoKeystoreError,
+    };
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn group(id: &[u8], state: &[u8]) -> PersistedMlsGroup {
+        PersistedMlsGroup {
+            id: id.to_vec(),
+            state: state.to_vec(),
+            parent_id: None,
+        }
+    }
+
+    /// Reads straight from the database, ignoring whatever is staged
+    async fn find_persisted_group(store: &CryptoKeystore, id: &[u8]) -> Option<PersistedMlsGroup> {
+        let mut conn = store.borrow_conn().await.unwrap();
+        PersistedMlsGroup::find_one(&mut conn, &StringEntityId::new(id))
+            .await
+            .unwrap()
+    }
+
+    #[apply(all_storage_types)]
+    #[wasm_bindgen_test]
+    pub async fn should_only_persist_staged_writes_on_commit(store: CryptoKeystore) {
+        let store = store.await;
+        store.save(group(b"a", b"a-0")).await.unwrap();
+        store.save(group(b"b", b"b-0")).await.unwrap();
+
+        store.begin_transaction().await.unwrap();
+        assert!(store.is_in_transaction().await);
+        store.save(group(b"a", b"a-1")).await.unwrap();
+        store.save(group(b"c", b"c-0")).await.unwrap();
+        store.remove::<PersistedMlsGroup, _>(b"b").await.unwrap();
+
+        // reads see the staged writes...
+        let a = store.find::<PersistedMlsGroup>(b"a").await.unwrap().unwrap();
+        assert_eq!(a.state, b"a-1");
+        assert!(store.find::<PersistedMlsGroup>(b"b").await.unwrap().is_none());
+        assert_eq!(store.count::<PersistedMlsGroup>().await.unwrap(), 2);
+        let mut all = store
+            .find_all::<PersistedMlsGroup>(EntityFindParams::default())
+            .await
+            .unwrap();
+        all.sort_by(|a, b| a.id.cmp(&b.id));
+        assert_eq!(all, vec![group(b"a", b"a-1"), group(b"c", b"c-0")]);
+
+        // ...while the database is left untouched
+        assert_eq!(find_persisted_group(&store, b"a").await.unwrap().state, b"a-0");
+        assert!(find_persisted_group(&store, b"b").await.is_some());
+        assert!(find_persisted_group(&store, b"c").await.is_none());
+
+        store.commit_transaction().await.unwrap();
+        assert!(!store.is_in_transaction().await);
+        assert_eq!(find_persisted_group(&store, b"a").await.unwrap().state, b"a-1");
+        assert!(find_persisted_group(&store, b"b").await.is_none());
+        assert_eq!(find_persisted_group(&store, b"c").await.unwrap().state, b"c-0");
+
+        teardown(store).await;
+    }
+
+    #[apply(all_storage_types)]
+    #[wasm_bindgen_test]
+    pub async fn should_discard_staged_writes_on_rollback(store: CryptoKeystore) {
+        let store = store.await;
+        store.save(group(b"a", b"a-0")).await.unwrap();
+
+        store.begin_transaction().await.unwrap();
+        store.save(group(b"a", b"a-1")).await.unwrap();
+        store.save(group(b"b", b"b-0")).await.unwrap();
+        store.rollback_transaction().await.unwrap();
+
+        assert_eq!(
+            store.find::<PersistedMlsGroup>(b"a").await.unwrap().unwrap().state,
+            b"a-0"
+        );
+        assert!(store.find::<PersistedMlsGroup>(b"b").await.unwrap().is_none());
+        assert_eq!(store.count::<PersistedMlsGroup>().await.unwrap(), 1);
+
+        teardown(store).await;
+    }
+
+    #[apply(all_storage_types)]
+    #[wasm_bindgen_test]
+    pub async fn should_stage_deletion_of_staged_entities(store: CryptoKeystore) {
+        let store = store.await;
+
+        store.begin_transaction().await.unwrap();
+        store.save(group(b"a", b"a-0")).await.unwrap();
+        store.remove::<PersistedMlsGroup, _>(b"a").await.unwrap();
+        assert!(matches!(
+            store.remove::<PersistedMlsGroup, _>(b"a").await.unwrap_err(),
+            CryptoKeystoreError::MissingKeyInStore(_)
+        ));
+        store.commit_transaction().await.unwrap();
+
+        assert!(store.find::<PersistedMlsGroup>(b"a").await.unwrap().is_none());
+
+        teardown(store).await;
+    }
+
+    #[apply(all_storage_types)]
+    #[wasm_bindgen_test]
+    pub async fn should_append_non_unique_entities(store: CryptoKeystore) {
+        let store = store.await;
+        let message = |content: &[u8], received_at: u64| MlsPendingMessage {
+            id: b"group".to_vec(),
+            message: content.to_vec(),
+            received_at,
+        };
+        store.save(message(b"first", 1)).await.unwrap();
+
+        store.begin_transaction().await.unwrap();
+        store.save(message(b"second", 2)).await.unwrap();
+        let mut messages = store
+            .find_all::<MlsPendingMessage>(EntityFindParams::default())
+            .await
+            .unwrap();
+        // the in-memory storage on WASM is not ordered
+        messages.sort_by_key(|m| m.received_at);
+        assert_eq!(messages, vec![message(b"first", 1), message(b"second", 2)]);
+        store.commit_transaction().await.unwrap();
+
+        assert_eq!(store.count::<MlsPendingMessage>().await.unwrap(), 2);
+        let mut messages = store.find_many::<MlsPendingMessage, _>(&[b"group"]).await.unwrap();
+        messages.sort_by_key(|m| m.received_at);
+        assert_eq!(messages, vec![message(b"first", 1), message(b"second", 2)]);
+
+        store.remove::<MlsPendingMessage, _>(b"group").await.unwrap();
+        assert_eq!(store.count::<MlsPendingMessage>().await.unwrap(), 0);
+
+        teardown(store).await;
+    }
+
+    #[apply(all_storage_types)]
+    #[wasm_bindgen_test]
+    pub async fn should_reject_unbalanced_transactions(store: CryptoKeystore) {
+        let store = store.await;
+
+        assert!(matches!(
+            store.commit_transaction().await.unwrap_err(),
+            CryptoKeystoreError::TransactionNotStarted
+        ));
+        assert!(matches!(
+            store.rollback_transaction().await.unwrap_err(),
+            CryptoKeystoreError::TransactionNotStarted
+        ));
+
+        store.begin_transaction().await.unwrap();
+        assert!(matches!(
+            store.begin_transaction().await.unwrap_err(),
+            CryptoKeystoreError::TransactionInProgress
+        ));
+        store.rollback_transaction().await.unwrap();
+
+        teardown(store).await;
+    }
+
+    #[apply(all_storage_types)]
+    #[wasm_bindgen_test]
+    pub async fn should_stage_credentials_and_unique_entities(store: CryptoKeystore) {
+        let store = store.await;
+        let credential = |credential: &[u8]| MlsCredential {
+            id: [b"client-", credential].concat(),
+            credential: credential.to_vec(),
+            created_at: 0,
+        };
+        store.insert(credential(b"old")).await.unwrap();
+
+        store.begin_transaction().await.unwrap();
+        // the timestamp is generated when staging and kept when committing
+        let created_at = store.insert(credential(b"new")).await.unwrap();
+        assert_ne!(created_at, 0);
+        store.remove_credential(b"old").await.unwrap();
+        store
+            .replace_unique(E2eiRefreshToken {
+                content: b"token".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        let credentials = store
+            .find_all::<MlsCredential>(EntityFindParams::default())
+            .await
+            .unwrap();
+        assert_eq!(credentials.len(), 1);
+        assert_eq!(credentials[0].credential, b"new");
+        assert_eq!(credentials[0].created_at, created_at);
+        assert_eq!(store.find_unique::<E2eiRefreshToken>().await.unwrap().content, b"token");
+        {
+            let mut conn = store.borrow_conn().await.unwrap();
+            assert_eq!(MlsCredential::count(&mut conn).await.unwrap(), 1);
+            assert!(E2eiRefreshToken::find_unique(&mut conn).await.is_err());
+        }
+
+        store.commit_transaction().await.unwrap();
+        let mut conn = store.borrow_conn().await.unwrap();
+        let credentials = MlsCredential::find_all(&mut conn, EntityFindParams::default())
+            .await
+            .unwrap();
+        assert_eq!(credentials.len(), 1);
+        assert_eq!(credentials[0].credential, b"new");
+        assert_eq!(credentials[0].created_at, created_at);
+        assert_eq!(
+            E2eiRefreshToken::find_unique(&mut conn).await.unwrap().content,
+            b"token"
+        );
+        drop(conn);
+
+        teardown(store).await;
+    }
+}
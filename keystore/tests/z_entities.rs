@@ -154,6 +154,10 @@ pub mod tests {
         if #[cfg(feature = "mls-keystore")] {
             test_for_entity!(test_persisted_mls_group, PersistedMlsGroup);
             test_for_entity!(test_persisted_mls_pending_group, PersistedMlsPendingGroup);
+            test_for_entity!(test_mls_group_key_rotation, MlsGroupKeyRotation);
+            test_for_entity!(test_mls_epoch_authenticators, MlsEpochAuthenticators);
+            test_for_entity!(test_mls_member_epochs, MlsMemberEpochs);
+            test_for_entity!(test_mls_history, MlsHistory);
             test_for_entity!(test_mls_pending_message, MlsPendingMessage ignore_update:true ignore_find_many:true);
             test_for_entity!(test_mls_credential, MlsCredential ignore_update:true);
             test_for_entity!(test_mls_keypackage, MlsKeyPackage);
@@ -403,6 +407,7 @@ pub mod utils {
                     Self {
                         id: id.into(),
                         message,
+                        received_at: rng.gen_range(0..u32::MAX as u64),
                     }
                 }
 
@@ -513,6 +518,79 @@ pub mod utils {
                 }
             }
 
+            impl EntityTestExt for core_crypto_keystore::entities::MlsGroupKeyRotation {
+                fn random() -> Self {
+                    let mut rng = rand::thread_rng();
+
+                    let id = uuid::Uuid::new_v4().hyphenated().to_string().into_bytes();
+
+                    Self {
+                        id,
+                        key_rotation_span: rng.gen_bool(0.5).then(|| rng.gen_range(1..u32::MAX as u64)),
+                        last_update: rng.gen_range(0..u32::MAX as u64),
+                    }
+                }
+
+                fn random_update(&mut self) {
+                    let mut rng = rand::thread_rng();
+                    self.last_update = rng.gen_range(0..u32::MAX as u64);
+                }
+            }
+
+            impl EntityTestExt for core_crypto_keystore::entities::MlsEpochAuthenticators {
+                fn random() -> Self {
+                    let mut rng = rand::thread_rng();
+
+                    let id = uuid::Uuid::new_v4().hyphenated().to_string().into_bytes();
+                    let mut authenticators = vec![0; rng.gen_range(MAX_BLOB_SIZE)];
+                    rng.fill(&mut authenticators[..]);
+
+                    Self { id, authenticators }
+                }
+
+                fn random_update(&mut self) {
+                    let mut rng = rand::thread_rng();
+                    self.authenticators = vec![0; rng.gen_range(MAX_BLOB_SIZE)];
+                    rng.fill(&mut self.authenticators[..]);
+                }
+            }
+
+            impl EntityTestExt for core_crypto_keystore::entities::MlsMemberEpochs {
+                fn random() -> Self {
+                    let mut rng = rand::thread_rng();
+
+                    let id = uuid::Uuid::new_v4().hyphenated().to_string().into_bytes();
+                    let mut members = vec![0; rng.gen_range(MAX_BLOB_SIZE)];
+                    rng.fill(&mut members[..]);
+
+                    Self { id, members }
+                }
+
+                fn random_update(&mut self) {
+                    let mut rng = rand::thread_rng();
+                    self.members = vec![0; rng.gen_range(MAX_BLOB_SIZE)];
+                    rng.fill(&mut self.members[..]);
+                }
+            }
+
+            impl EntityTestExt for core_crypto_keystore::entities::MlsHistory {
+                fn random() -> Self {
+                    let mut rng = rand::thread_rng();
+
+                    let id = uuid::Uuid::new_v4().hyphenated().to_string().into_bytes();
+                    let mut messages = vec![0; rng.gen_range(MAX_BLOB_SIZE)];
+                    rng.fill(&mut messages[..]);
+
+                    Self { id, messages }
+                }
+
+                fn random_update(&mut self) {
+                    let mut rng = rand::thread_rng();
+                    self.messages = vec![0; rng.gen_range(MAX_BLOB_SIZE)];
+                    rng.fill(&mut self.messages[..]);
+                }
+            }
+
             impl EntityTestExt for core_crypto_keystore::entities::E2eiCrl {
                 fn random() -> Self {
                     let mut rng = rand::thread_rng();